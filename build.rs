@@ -1,7 +1,266 @@
-﻿use std::fs;
+﻿use serde_json::json;
+use std::fs;
 use std::path::Path;
 
+/// Split on commas that aren't nested inside `<>` or `()`.
+fn split_top_level(params: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut current = String::new();
+    for c in params.chars() {
+        match c {
+            '<' | '(' => depth += 1,
+            '>' | ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        parts.push(current.trim().to_string());
+    }
+    parts
+}
+
+/// Collect every `#[tauri::command]` signature under src/ into a JSON list
+/// for `get_api_description`. Arguments injected by Tauri (app handle,
+/// managed state, window) are left out since callers never pass them.
+fn write_command_manifest() {
+    let mut files: Vec<_> = fs::read_dir("src")
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    files.retain(|path: &std::path::PathBuf| path.extension().is_some_and(|ext| ext == "rs"));
+    files.sort();
+
+    let mut commands = Vec::new();
+    for path in files {
+        let Ok(source) = fs::read_to_string(&path) else {
+            continue;
+        };
+        for chunk in source.split("\n#[tauri::command]").skip(1) {
+            let Some(fn_pos) = chunk.find("fn ") else {
+                continue;
+            };
+            let is_async = chunk[..fn_pos].contains("async");
+            let signature = &chunk[fn_pos + 3..];
+            let Some(open) = signature.find('(') else {
+                continue;
+            };
+            let name = signature[..open].trim();
+            let mut depth = 0i32;
+            let mut close = open;
+            for (i, c) in signature[open..].char_indices() {
+                match c {
+                    '(' => depth += 1,
+                    ')' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            close = open + i;
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            let args: Vec<serde_json::Value> = split_top_level(&signature[open + 1..close])
+                .into_iter()
+                .filter_map(|param| {
+                    let (arg, ty) = param.split_once(':')?;
+                    let ty = ty.trim();
+                    let injected = ty.starts_with("AppHandle")
+                        || ty.starts_with("State<")
+                        || ty.starts_with("tauri::Window")
+                        || ty.starts_with("tauri::WebviewWindow");
+                    (!injected).then(|| json!({ "name": arg.trim(), "type": ty }))
+                })
+                .collect();
+            let rest = &signature[close + 1..];
+            let body = rest.find('{').unwrap_or(rest.len());
+            let returns = rest[..body].trim().trim_start_matches("->").trim();
+            commands.push(json!({
+                "name": name,
+                "async": is_async,
+                "args": args,
+                "returns": if returns.is_empty() { "()" } else { returns },
+            }));
+        }
+    }
+
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    let _ = fs::write(
+        Path::new(&out_dir).join("api_commands.json"),
+        serde_json::Value::Array(commands).to_string(),
+    );
+    println!("cargo:rerun-if-changed=src");
+}
+
+/// Short commit hash of the checkout being built, or "unknown" for builds
+/// from a source archive.
+fn set_build_hash() {
+    let hash = std::process::Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .map(|hash| hash.trim().to_string())
+        .filter(|hash| !hash.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=UPSTAND_BUILD_HASH={}", hash);
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/index");
+    // A commit on the current branch only moves the ref HEAD points to,
+    // which lives in packed-refs once git has packed it. Watching a missing
+    // file would rerun this script on every build.
+    if let Some(head_ref) = fs::read_to_string(".git/HEAD")
+        .ok()
+        .and_then(|head| Some(head.strip_prefix("ref: ")?.trim().to_string()))
+    {
+        for path in [format!(".git/{}", head_ref), ".git/packed-refs".to_string()] {
+            if Path::new(&path).exists() {
+                println!("cargo:rerun-if-changed={}", path);
+            }
+        }
+    }
+}
+
+/// License files a crate ships next to its manifest, in name order.
+fn license_files(package: &serde_json::Value) -> Vec<std::path::PathBuf> {
+    let Some(dir) = package["manifest_path"]
+        .as_str()
+        .and_then(|p| Path::new(p).parent())
+    else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    files.retain(|path: &std::path::PathBuf| {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_uppercase())
+            .unwrap_or_default();
+        path.is_file()
+            && ["LICENSE", "LICENCE", "COPYING", "NOTICE"]
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+    });
+    if let Some(file) = package["license_file"].as_str() {
+        let file = dir.join(file);
+        if file.is_file() && !files.contains(&file) {
+            files.push(file);
+        }
+    }
+    files.sort();
+    files
+}
+
+/// License notices for every crate linked into the app, from `cargo
+/// metadata`: a list for the About panel, and the license texts the crates
+/// ship, each text once with the crates it covers.
+fn write_third_party_notices() {
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    println!("cargo:rerun-if-changed=Cargo.lock");
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let mut command = std::process::Command::new(cargo);
+    command.args(["metadata", "--format-version", "1", "--offline"]);
+    if let Ok(target) = std::env::var("TARGET") {
+        command.args(["--filter-platform", &target]);
+    }
+    let metadata: serde_json::Value = command
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .and_then(|out| serde_json::from_slice(&out.stdout).ok())
+        .unwrap_or_else(|| {
+            println!("cargo:warning=cargo metadata failed; third-party notices are empty");
+            json!({})
+        });
+
+    // Walk normal dependencies from the app; build and dev dependencies
+    // aren't shipped.
+    let nodes = metadata["resolve"]["nodes"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let root = metadata["resolve"]["root"].clone();
+    let mut linked = std::collections::BTreeSet::new();
+    let mut pending = vec![root];
+    while let Some(id) = pending.pop() {
+        let Some(node) = nodes.iter().find(|node| node["id"] == id) else {
+            continue;
+        };
+        for dep in node["deps"].as_array().into_iter().flatten() {
+            let normal = dep["dep_kinds"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .any(|kind| kind["kind"].is_null());
+            if normal && linked.insert(dep["pkg"].to_string()) {
+                pending.push(dep["pkg"].clone());
+            }
+        }
+    }
+
+    let mut packages: Vec<&serde_json::Value> = metadata["packages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|package| linked.contains(&package["id"].to_string()))
+        .collect();
+    packages.sort_by_key(|package| (package["name"].to_string(), package["version"].to_string()));
+
+    let mut notices = Vec::new();
+    // Each distinct text with the crates that ship it, in first-seen order.
+    let mut texts: Vec<(String, Vec<String>)> = Vec::new();
+    for package in packages {
+        let (Some(name), Some(version)) = (package["name"].as_str(), package["version"].as_str())
+        else {
+            continue;
+        };
+        notices.push(json!({
+            "name": name,
+            "version": version,
+            "license": package["license"],
+            "repository": package["repository"],
+        }));
+        for file in license_files(package) {
+            let Ok(text) = fs::read_to_string(&file) else {
+                continue;
+            };
+            let text = text.trim().to_string();
+            let label = format!("{} {}", name, version);
+            match texts.iter_mut().find(|(known, _)| *known == text) {
+                Some((_, crates)) if !crates.contains(&label) => crates.push(label),
+                Some(_) => {}
+                None => texts.push((text, vec![label])),
+            }
+        }
+    }
+    let document: Vec<String> = texts
+        .into_iter()
+        .map(|(text, crates)| format!("{}\n\n{}", crates.join(", "), text))
+        .collect();
+
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    let _ = fs::write(
+        Path::new(&out_dir).join("third_party.json"),
+        serde_json::Value::Array(notices).to_string(),
+    );
+    let _ = fs::write(
+        Path::new(&out_dir).join("third_party_licenses.txt"),
+        document.join("\n\n----------------------------------------\n\n"),
+    );
+}
+
 fn main() {
+    write_command_manifest();
+    set_build_hash();
+    write_third_party_notices();
     // Keep reminder header icon in sync with icon assets.
     let src_small = Path::new("icons/icon-32.png");
     let src_fallback = Path::new("icons/icon.png");
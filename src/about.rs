@@ -0,0 +1,73 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::AppHandle;
+
+use crate::{analytics_path, api, config_path, data_dir, journal, tip_packs};
+
+/// License notices for the bundled crates, generated by build.rs.
+const THIRD_PARTY_JSON: &str = include_str!(concat!(env!("OUT_DIR"), "/third_party.json"));
+/// The license texts those crates ship, generated alongside.
+pub const THIRD_PARTY_LICENSES: &str =
+    include_str!(concat!(env!("OUT_DIR"), "/third_party_licenses.txt"));
+
+/// One third-party crate compiled into the app.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ThirdPartyNotice {
+    pub name: String,
+    pub version: String,
+    /// SPDX expression; `None` when the crate only ships a license file.
+    pub license: Option<String>,
+    pub repository: Option<String>,
+}
+
+/// Where this install keeps its files. `None` where the OS gave no app
+/// data directory.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct DataPaths {
+    pub data_dir: Option<String>,
+    pub config: Option<String>,
+    pub analytics: Option<String>,
+    pub journal: Option<String>,
+    pub tip_packs: Option<String>,
+}
+
+/// Everything the About panel shows and a bug report should include.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct AboutInfo {
+    pub app_version: String,
+    pub api_version: String,
+    /// Short commit hash, or "unknown" for builds outside a git checkout.
+    pub build_hash: String,
+    /// `std::env::consts::OS` and `ARCH`, e.g. "windows" and "x86_64".
+    pub os: String,
+    pub arch: String,
+    pub paths: DataPaths,
+    pub third_party: Vec<ThirdPartyNotice>,
+}
+
+pub fn third_party_notices() -> Vec<ThirdPartyNotice> {
+    serde_json::from_str(THIRD_PARTY_JSON).unwrap_or_default()
+}
+
+fn display(path: Option<impl AsRef<Path>>) -> Option<String> {
+    path.map(|path| path.as_ref().display().to_string())
+}
+
+pub fn info(app: &AppHandle) -> AboutInfo {
+    AboutInfo {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        api_version: api::version().api_version,
+        build_hash: env!("UPSTAND_BUILD_HASH").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        paths: DataPaths {
+            data_dir: display(data_dir::dir(app)),
+            config: display(config_path(app)),
+            analytics: display(analytics_path(app)),
+            journal: display(journal::journal_path(app)),
+            tip_packs: display(tip_packs::packs_dir(app)),
+        },
+        third_party: third_party_notices(),
+    }
+}
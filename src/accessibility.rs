@@ -0,0 +1,101 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+#[cfg(not(target_os = "windows"))]
+use std::process::Command as ProcessCommand;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct AccessibilityPrefs {
+    pub high_contrast: bool,
+    pub reduced_motion: bool,
+}
+
+#[cfg(target_os = "windows")]
+pub fn detect() -> AccessibilityPrefs {
+    use windows_sys::Win32::UI::Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        SystemParametersInfoW, SPI_GETCLIENTAREAANIMATION, SPI_GETHIGHCONTRAST,
+    };
+
+    let mut contrast = HIGHCONTRASTW {
+        cbSize: std::mem::size_of::<HIGHCONTRASTW>() as u32,
+        dwFlags: 0,
+        lpszDefaultScheme: std::ptr::null_mut(),
+    };
+    let high_contrast = unsafe {
+        SystemParametersInfoW(
+            SPI_GETHIGHCONTRAST,
+            contrast.cbSize,
+            &mut contrast as *mut HIGHCONTRASTW as *mut core::ffi::c_void,
+            0,
+        )
+    } != 0
+        && contrast.dwFlags & HCF_HIGHCONTRASTON != 0;
+
+    // Windows exposes "Show animations in Windows" as client area animation.
+    let mut animations: i32 = 1;
+    let ok = unsafe {
+        SystemParametersInfoW(
+            SPI_GETCLIENTAREAANIMATION,
+            0,
+            &mut animations as *mut i32 as *mut core::ffi::c_void,
+            0,
+        )
+    } != 0;
+
+    AccessibilityPrefs {
+        high_contrast,
+        reduced_motion: ok && animations == 0,
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn detect() -> AccessibilityPrefs {
+    AccessibilityPrefs {
+        high_contrast: read_defaults_flag("increaseContrast"),
+        reduced_motion: read_defaults_flag("reduceMotion"),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn read_defaults_flag(key: &str) -> bool {
+    ProcessCommand::new("defaults")
+        .args(["read", "com.apple.universalaccess", key])
+        .output()
+        .ok()
+        .map(|out| String::from_utf8_lossy(&out.stdout).trim() == "1")
+        .unwrap_or(false)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn detect() -> AccessibilityPrefs {
+    let high_contrast = read_gsettings("org.gnome.desktop.a11y.interface", "high-contrast")
+        .map(|v| v == "true")
+        .unwrap_or(false)
+        || read_gsettings("org.gnome.desktop.interface", "gtk-theme")
+            .map(|v| v.to_lowercase().contains("highcontrast"))
+            .unwrap_or(false);
+    let reduced_motion = read_gsettings("org.gnome.desktop.interface", "enable-animations")
+        .map(|v| v == "false")
+        .unwrap_or(false);
+    AccessibilityPrefs {
+        high_contrast,
+        reduced_motion,
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn read_gsettings(schema: &str, key: &str) -> Option<String> {
+    let out = ProcessCommand::new("gsettings")
+        .args(["get", schema, key])
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    Some(
+        String::from_utf8_lossy(&out.stdout)
+            .trim()
+            .trim_matches('\'')
+            .to_string(),
+    )
+}
@@ -0,0 +1,310 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+use std::process::Command as ProcessCommand;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{now_ts, save_analytics, timeline, timers, AppState};
+
+/// How often the sampler checks for input. A sample counts as active when
+/// there was any keyboard or mouse input during it.
+pub const SAMPLE_SECS: u64 = 15;
+pub const MIN_BLOCKING_POLL_SECS: u64 = 5;
+pub const MAX_BLOCKING_POLL_SECS: u64 = 5 * 60;
+/// How often the microphone is checked while `mute_when_mic_in_use` is on.
+pub const MIC_POLL_SECS: u64 = 10;
+const MAX_BLOCKING_APPS: usize = 32;
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// Processes whose presence pauses reminders, e.g. a game, OBS or a call.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BlockingApps {
+    /// Process names as the OS lists them ("obs64.exe", "zoom.us"); matched
+    /// without regard to case, and on Windows with or without ".exe".
+    #[serde(default)]
+    pub processes: Vec<String>,
+    #[serde(default = "default_blocking_poll_secs")]
+    pub poll_secs: u64,
+}
+
+impl Default for BlockingApps {
+    fn default() -> Self {
+        Self {
+            processes: Vec::new(),
+            poll_secs: default_blocking_poll_secs(),
+        }
+    }
+}
+
+fn default_blocking_poll_secs() -> u64 {
+    15
+}
+
+pub fn sanitize_blocking_apps(mut cfg: BlockingApps) -> BlockingApps {
+    let mut processes: Vec<String> = Vec::new();
+    for name in cfg.processes.iter().map(|name| name.trim()) {
+        if !name.is_empty() && !processes.iter().any(|p| p.eq_ignore_ascii_case(name)) {
+            processes.push(name.to_string());
+        }
+    }
+    processes.truncate(MAX_BLOCKING_APPS);
+    cfg.processes = processes;
+    cfg.poll_secs = cfg
+        .poll_secs
+        .clamp(MIN_BLOCKING_POLL_SECS, MAX_BLOCKING_POLL_SECS);
+    cfg
+}
+
+/// Names of the running processes, lowercased.
+#[cfg(target_os = "windows")]
+fn running_processes() -> Vec<String> {
+    let Ok(out) = ProcessCommand::new("tasklist")
+        .args(["/FO", "CSV", "/NH"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+    else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .filter_map(|line| line.split(',').next())
+        .map(|name| name.trim_matches('"').to_lowercase())
+        .collect()
+}
+
+/// `ps` prints full executable paths on macOS; the name is the last part.
+#[cfg(target_os = "macos")]
+fn running_processes() -> Vec<String> {
+    let Ok(out) = ProcessCommand::new("ps").args(["-axo", "comm="]).output() else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .filter_map(|line| line.trim().rsplit('/').next())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Straight from /proc, so no process is spawned per poll.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn running_processes() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().parse::<u32>().is_ok())
+        .filter_map(|entry| std::fs::read_to_string(entry.path().join("comm")).ok())
+        .map(|name| name.trim().to_lowercase())
+        .collect()
+}
+
+#[cfg(not(any(unix, target_os = "windows")))]
+fn running_processes() -> Vec<String> {
+    Vec::new()
+}
+
+/// The first listed app that is running, as the user spelled it.
+pub fn find_blocking(listed: &[String], running: &[String]) -> Option<String> {
+    listed
+        .iter()
+        .find(|name| {
+            let name = name.to_lowercase();
+            let bare = name.strip_suffix(".exe").unwrap_or(&name);
+            running
+                .iter()
+                .any(|proc| proc == &name || proc.strip_suffix(".exe").unwrap_or(proc) == bare)
+        })
+        .cloned()
+}
+
+/// Watch for the configured blocking apps and hold the countdown while one
+/// runs. Nothing is scanned while the list is empty.
+pub async fn run_blocking_scan(app: AppHandle) {
+    loop {
+        let (listed, poll_secs) = {
+            let cfg = app
+                .state::<AppState>()
+                .blocking_apps
+                .lock()
+                .unwrap()
+                .clone();
+            (cfg.processes, cfg.poll_secs)
+        };
+        let found = if listed.is_empty() {
+            None
+        } else {
+            tauri::async_runtime::spawn_blocking(running_processes)
+                .await
+                .ok()
+                .and_then(|running| find_blocking(&listed, &running))
+        };
+        let state = app.state::<AppState>();
+        let changed = {
+            let mut current = state.blocking_app.lock().unwrap();
+            let changed = *current != found;
+            *current = found.clone();
+            changed
+        };
+        if changed {
+            timers::wake(&state);
+            let _ = app.emit("blocking-app-changed", found);
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(poll_secs)).await;
+    }
+}
+
+/// Whether any app is recording from a microphone (a call, most likely);
+/// `None` where the platform can't tell. Windows lists apps with an open
+/// capture session in the privacy consent store: a zero stop time means
+/// still recording.
+#[cfg(target_os = "windows")]
+pub fn mic_in_use() -> Option<bool> {
+    let out = ProcessCommand::new("reg")
+        .args([
+            "query",
+            r"HKCU\Software\Microsoft\Windows\CurrentVersion\CapabilityAccessManager\ConsentStore\microphone",
+            "/s",
+            "/v",
+            "LastUsedTimeStop",
+        ])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&out.stdout);
+    Some(text.lines().any(|line| {
+        line.contains("LastUsedTimeStop") && line.split_whitespace().last() == Some("0x0")
+    }))
+}
+
+/// Reading whether an input device is running needs CoreAudio, which the
+/// app doesn't link yet.
+#[cfg(target_os = "macos")]
+pub fn mic_in_use() -> Option<bool> {
+    None
+}
+
+/// PulseAudio and PipeWire both list open capture streams as source outputs.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn mic_in_use() -> Option<bool> {
+    let out = ProcessCommand::new("pactl")
+        .args(["list", "short", "source-outputs"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())?;
+    Some(!String::from_utf8_lossy(&out.stdout).trim().is_empty())
+}
+
+/// Track the microphone while sound is to be held back for calls. Reminders
+/// still show; only their sound and notification chimes are dropped.
+pub async fn run_mic_scan(app: AppHandle) {
+    loop {
+        let enabled = *app.state::<AppState>().mute_when_mic_in_use.lock().unwrap();
+        let in_use = enabled
+            && tauri::async_runtime::spawn_blocking(mic_in_use)
+                .await
+                .ok()
+                .flatten()
+                .unwrap_or(false);
+        let state = app.state::<AppState>();
+        if std::mem::replace(&mut *state.mic_in_use.lock().unwrap(), in_use) != in_use {
+            let _ = app.emit("mic-in-use-changed", in_use);
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(MIC_POLL_SECS)).await;
+    }
+}
+
+const HOUR_SECS: i64 = 60 * 60;
+
+/// Active vs idle sitting seconds for one clock hour. Only totals are kept:
+/// the sampler never sees which keys were pressed or where the mouse went.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ActivityBucket {
+    /// Start of the hour (unix seconds).
+    pub hour_ts: i64,
+    pub active_secs: u64,
+    pub idle_secs: u64,
+}
+
+/// Seconds since the last keyboard or mouse input, if the platform can tell.
+#[cfg(target_os = "windows")]
+pub fn seconds_since_input() -> Option<u64> {
+    use windows_sys::Win32::System::SystemInformation::GetTickCount;
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+    if unsafe { GetLastInputInfo(&mut info) } == 0 {
+        return None;
+    }
+    let now = unsafe { GetTickCount() };
+    Some(u64::from(now.wrapping_sub(info.dwTime)) / 1000)
+}
+
+#[cfg(target_os = "macos")]
+pub fn seconds_since_input() -> Option<u64> {
+    let out = ProcessCommand::new("ioreg")
+        .args(["-c", "IOHIDSystem", "-d", "4"])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&out.stdout);
+    let line = text.lines().find(|line| line.contains("\"HIDIdleTime\""))?;
+    let nanos: u64 = line.rsplit('=').next()?.trim().parse().ok()?;
+    Some(nanos / 1_000_000_000)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn seconds_since_input() -> Option<u64> {
+    let out = ProcessCommand::new("xprintidle").output().ok()?;
+    let millis: u64 = String::from_utf8_lossy(&out.stdout).trim().parse().ok()?;
+    Some(millis / 1000)
+}
+
+/// Record one sample into the current hour's bucket. Returns true when a new
+/// hour was started, which is when the caller persists analytics.
+fn record_sample(state: &AppState, now: i64, active: bool) -> bool {
+    let hour_ts = now - now.rem_euclid(HOUR_SECS);
+    let mut buckets = state.activity_buckets.lock().unwrap();
+    let started_hour = buckets.last().map(|b| b.hour_ts) != Some(hour_ts);
+    if started_hour {
+        buckets.push(ActivityBucket {
+            hour_ts,
+            active_secs: 0,
+            idle_secs: 0,
+        });
+    }
+    if let Some(bucket) = buckets.last_mut() {
+        if active {
+            bucket.active_secs += SAMPLE_SECS;
+        } else {
+            bucket.idle_secs += SAMPLE_SECS;
+        }
+    }
+    started_hour
+}
+
+/// Opt-in sampler loop. Only sitting time is sampled: nothing is recorded
+/// while the countdown is held (reminder on screen, displays off, day off).
+pub async fn run(app: AppHandle) {
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(SAMPLE_SECS)).await;
+        let state = app.state::<AppState>();
+        if !*state.input_activity_enabled.lock().unwrap() || timers::countdown_paused(&state) {
+            *state.input_idle.lock().unwrap() = false;
+            continue;
+        }
+        let Ok(Some(idle)) = tauri::async_runtime::spawn_blocking(seconds_since_input).await else {
+            continue;
+        };
+        let away = idle >= timeline::IDLE_AFTER_SECS;
+        if std::mem::replace(&mut *state.input_idle.lock().unwrap(), away) != away {
+            timers::wake(&state);
+        }
+        if record_sample(&state, now_ts(), idle < SAMPLE_SECS) {
+            let _ = save_analytics(&app, &state);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use chrono::{Local, TimeZone, Timelike};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::network_profiles::{self, ProfileChange};
+use crate::tags::{self, TagChange};
+
+/// The families of events analytics are built from.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EventType {
+    /// Standups, sedentary sessions and skips.
+    Reminder,
+    /// Sampled keyboard and mouse activity.
+    Activity,
+    /// Desk mode time.
+    Desk,
+}
+
+/// How a reminder turned out, as far as the analytics record it.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OutcomeClass {
+    /// A standup, answered or not.
+    StoodUp,
+    /// Left open past the sedentary threshold.
+    Ignored,
+    /// Dismissed while still sitting.
+    Skipped,
+}
+
+/// Local hours `from` up to but not including `to`, wrapping past midnight
+/// when `to` is the smaller.
+#[derive(Clone, Copy, Serialize, Deserialize, JsonSchema)]
+pub struct HourRange {
+    pub from: u32,
+    pub to: u32,
+}
+
+/// Narrows an analytics query. Every field left empty matches everything;
+/// the fields are combined with "and", the values within one with "or".
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct AnalyticsFilter {
+    #[serde(default)]
+    pub event_types: Vec<EventType>,
+    /// Only narrows the reminder events.
+    #[serde(default)]
+    pub outcomes: Vec<OutcomeClass>,
+    /// Session tags; `null` matches untagged time.
+    #[serde(default)]
+    pub tags: Vec<Option<String>>,
+    /// Network profile names; `null` matches time under no profile. Profiles
+    /// are only known from when they were first configured.
+    #[serde(default)]
+    pub profiles: Vec<Option<String>>,
+    #[serde(default)]
+    pub hours: Option<HourRange>,
+}
+
+impl AnalyticsFilter {
+    pub fn validate(&self) -> Result<(), String> {
+        match self.hours {
+            Some(range) if range.from > 23 || range.to > 24 || range.from == range.to => {
+                Err("hours must run from 0-23 to a different hour up to 24".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn wants(&self, event_type: EventType) -> bool {
+        self.event_types.is_empty() || self.event_types.contains(&event_type)
+    }
+
+    pub fn wants_outcome(&self, outcome: OutcomeClass) -> bool {
+        self.wants(EventType::Reminder)
+            && (self.outcomes.is_empty() || self.outcomes.contains(&outcome))
+    }
+
+    /// Whether something at `ts` falls within the hours, tags and profiles.
+    pub fn matches(&self, ts: i64, tag_log: &[TagChange], profile_log: &[ProfileChange]) -> bool {
+        if let Some(range) = self.hours {
+            let Some(hour) = Local.timestamp_opt(ts, 0).single().map(|dt| dt.hour()) else {
+                return false;
+            };
+            let inside = if range.from < range.to {
+                hour >= range.from && hour < range.to
+            } else {
+                hour >= range.from || hour < range.to
+            };
+            if !inside {
+                return false;
+            }
+        }
+        let tag = tags::tag_at(tag_log, ts);
+        if !self.tags.is_empty() && !self.tags.iter().any(|t| t.as_deref() == tag) {
+            return false;
+        }
+        let profile = network_profiles::profile_at(profile_log, ts);
+        self.profiles.is_empty() || self.profiles.iter().any(|p| p.as_deref() == profile)
+    }
+}
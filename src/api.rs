@@ -0,0 +1,202 @@
+use schemars::{schema::RootSchema, schema_for};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::about::AboutInfo;
+use crate::accessibility::AccessibilityPrefs;
+use crate::activity::BlockingApps;
+use crate::analytics_filter::AnalyticsFilter;
+use crate::api_tokens::{ApiToken, NewApiToken};
+use crate::budget::BudgetExceeded;
+use crate::challenge::{ChallengeConfig, ChallengeStatus};
+use crate::changelog::{ChangelogEntry, WhatsNew};
+use crate::config_history::ConfigChange;
+use crate::desk::{DeskChange, DeskModeConfig, DeskPosition};
+use crate::events::{EventSeq, ReminderFired, ReminderIgnored};
+use crate::goals::MonthlyGoalProgress;
+use crate::handoff::{HandoffConfig, HandoffPeer};
+use crate::health::Health;
+use crate::hooks::{HookConfig, HookRun};
+use crate::interactions::InteractionStats;
+use crate::languages::LanguageInfo;
+use crate::marked_days::MarkedDay;
+use crate::network_profiles::{NetworkProfile, NetworkStatus};
+use crate::overlay::PresentationConfig;
+use crate::plugins::{PluginInfo, PluginInsight};
+use crate::power::PowerState;
+use crate::ratio::SitStandRatio;
+use crate::reminder_reason::ReminderHistoryEntry;
+use crate::repeats::RepeatConfig;
+use crate::reports::DaySummary;
+use crate::rollover::DayRollover;
+use crate::scheduled_export::ScheduledExportResult;
+use crate::skips::SkipBudget;
+use crate::sleep_drift::{SleepPolicy, SystemResumed};
+use crate::themes::ThemeTokens;
+use crate::timeline::TimelineSegment;
+use crate::timers::{FocusRecord, FocusSession, TickPayload, TickResolution, TimerStatus};
+use crate::tip_packs::{TipLanguageMatch, TipPackCoverage};
+use crate::tip_ratings::{TipRating, TipRatingSummary};
+use crate::tray_icons::TrayIconConfig;
+use crate::window_focus::{WindowFocusInfo, WindowFocusNudge};
+use crate::{ActiveReminderPayload, AnalyticsData};
+
+/// Bump the minor version for additive changes (new commands, events or
+/// fields) and the major version for anything that breaks existing callers.
+pub const API_VERSION_MAJOR: u32 = 2;
+pub const API_VERSION_MINOR: u32 = 28;
+
+/// Every `#[tauri::command]` signature, collected by build.rs.
+const COMMANDS_JSON: &str = include_str!(concat!(env!("OUT_DIR"), "/api_commands.json"));
+
+#[derive(Clone, Serialize)]
+pub struct ApiVersion {
+    pub api_version: String,
+    pub major: u32,
+    pub minor: u32,
+    pub app_version: String,
+}
+
+#[derive(Serialize)]
+pub struct EventDescription {
+    pub name: &'static str,
+    /// Key into `types`, or a JSON primitive name; `None` for payload-less events.
+    pub payload: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+pub struct ApiDescription {
+    pub version: ApiVersion,
+    pub commands: Value,
+    pub events: Vec<EventDescription>,
+    pub types: Vec<(&'static str, RootSchema)>,
+}
+
+pub fn version() -> ApiVersion {
+    ApiVersion {
+        api_version: format!("{}.{}", API_VERSION_MAJOR, API_VERSION_MINOR),
+        major: API_VERSION_MAJOR,
+        minor: API_VERSION_MINOR,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+    }
+}
+
+fn events() -> Vec<EventDescription> {
+    [
+        ("tick", Some("TickPayload")),
+        ("reminder-fired", Some("ReminderFired")),
+        ("reminder-ignored", Some("ReminderIgnored")),
+        ("refresh_tip", Some("integer")),
+        // Enter, for the page to submit its challenge answer with.
+        ("reminder-key", Some("string")),
+        ("pre-reminder-warning", Some("integer")),
+        ("standup-logged", None),
+        ("analytics-updated", Some("EventSeq")),
+        ("language-changed", Some("string")),
+        ("reminder-language-changed", Some("string")),
+        // The language no installed tip pack is written in.
+        ("reminder-language-unsupported", Some("string")),
+        ("theme-changed", Some("string")),
+        ("accessibility-changed", Some("AccessibilityPrefs")),
+        ("power-state-changed", Some("PowerState")),
+        ("screen-sharing-changed", Some("boolean")),
+        ("display-changed", Some("integer")),
+        ("skip-budget-exhausted", Some("SkipBudget")),
+        ("desk-position-changed", Some("DeskChange")),
+        ("desk-move-due", Some("DeskPosition")),
+        ("mic-in-use-changed", Some("boolean")),
+        ("focus-session-started", Some("FocusSession")),
+        ("focus-session-ended", Some("FocusRecord")),
+        ("scheduled-export-finished", Some("ScheduledExportResult")),
+        ("day-summary", Some("DaySummary")),
+        ("health-changed", Some("Health")),
+        ("hook-finished", Some("HookRun")),
+        ("handoff-received", Some("HandoffPeer")),
+        ("network-profile-changed", Some("NetworkStatus")),
+        ("day-rolled-over", Some("DayRollover")),
+        // null when the tag was cleared.
+        ("session-tag-changed", Some("string")),
+        ("whats-new", Some("WhatsNew")),
+        ("sitting-budget-exceeded", Some("BudgetExceeded")),
+        ("sit-stand-below-target", Some("SitStandRatio")),
+        ("window-focus-nudge", Some("WindowFocusNudge")),
+        ("system-resumed", Some("SystemResumed")),
+        // null once no listed app is running.
+        ("blocking-app-changed", Some("string")),
+    ]
+    .into_iter()
+    .map(|(name, payload)| EventDescription { name, payload })
+    .collect()
+}
+
+fn types() -> Vec<(&'static str, RootSchema)> {
+    vec![
+        ("TickPayload", schema_for!(TickPayload)),
+        ("TimerStatus", schema_for!(TimerStatus)),
+        ("TickResolution", schema_for!(TickResolution)),
+        ("AnalyticsData", schema_for!(AnalyticsData)),
+        ("ActiveReminderPayload", schema_for!(ActiveReminderPayload)),
+        ("ReminderFired", schema_for!(ReminderFired)),
+        ("ReminderIgnored", schema_for!(ReminderIgnored)),
+        ("ReminderHistoryEntry", schema_for!(ReminderHistoryEntry)),
+        ("EventSeq", schema_for!(EventSeq)),
+        ("AccessibilityPrefs", schema_for!(AccessibilityPrefs)),
+        ("PowerState", schema_for!(PowerState)),
+        ("FocusSession", schema_for!(FocusSession)),
+        ("FocusRecord", schema_for!(FocusRecord)),
+        ("ScheduledExportResult", schema_for!(ScheduledExportResult)),
+        ("DaySummary", schema_for!(DaySummary)),
+        ("MonthlyGoalProgress", schema_for!(MonthlyGoalProgress)),
+        ("Health", schema_for!(Health)),
+        ("DayRollover", schema_for!(DayRollover)),
+        ("ChangelogEntry", schema_for!(ChangelogEntry)),
+        ("WhatsNew", schema_for!(WhatsNew)),
+        ("AboutInfo", schema_for!(AboutInfo)),
+        ("LanguageInfo", schema_for!(LanguageInfo)),
+        ("TimelineSegment", schema_for!(TimelineSegment)),
+        ("BudgetExceeded", schema_for!(BudgetExceeded)),
+        ("SitStandRatio", schema_for!(SitStandRatio)),
+        ("WindowFocusInfo", schema_for!(WindowFocusInfo)),
+        ("WindowFocusNudge", schema_for!(WindowFocusNudge)),
+        ("ConfigChange", schema_for!(ConfigChange)),
+        ("MarkedDay", schema_for!(MarkedDay)),
+        ("BlockingApps", schema_for!(BlockingApps)),
+        ("ThemeTokens", schema_for!(ThemeTokens)),
+        ("InteractionStats", schema_for!(InteractionStats)),
+        ("SkipBudget", schema_for!(SkipBudget)),
+        ("RepeatConfig", schema_for!(RepeatConfig)),
+        ("PresentationConfig", schema_for!(PresentationConfig)),
+        ("ChallengeConfig", schema_for!(ChallengeConfig)),
+        ("ChallengeStatus", schema_for!(ChallengeStatus)),
+        ("DeskModeConfig", schema_for!(DeskModeConfig)),
+        ("DeskPosition", schema_for!(DeskPosition)),
+        ("DeskChange", schema_for!(DeskChange)),
+        ("TrayIconConfig", schema_for!(TrayIconConfig)),
+        ("TipRating", schema_for!(TipRating)),
+        ("TipRatingSummary", schema_for!(TipRatingSummary)),
+        ("TipPackCoverage", schema_for!(TipPackCoverage)),
+        ("TipLanguageMatch", schema_for!(TipLanguageMatch)),
+        ("HookConfig", schema_for!(HookConfig)),
+        ("HookRun", schema_for!(HookRun)),
+        ("HandoffConfig", schema_for!(HandoffConfig)),
+        ("HandoffPeer", schema_for!(HandoffPeer)),
+        ("NetworkProfile", schema_for!(NetworkProfile)),
+        ("NetworkStatus", schema_for!(NetworkStatus)),
+        ("AnalyticsFilter", schema_for!(AnalyticsFilter)),
+        ("SleepPolicy", schema_for!(SleepPolicy)),
+        ("SystemResumed", schema_for!(SystemResumed)),
+        ("PluginInfo", schema_for!(PluginInfo)),
+        ("PluginInsight", schema_for!(PluginInsight)),
+        ("ApiToken", schema_for!(ApiToken)),
+        ("NewApiToken", schema_for!(NewApiToken)),
+    ]
+}
+
+pub fn describe() -> ApiDescription {
+    ApiDescription {
+        version: version(),
+        commands: serde_json::from_str(COMMANDS_JSON).unwrap_or(Value::Null),
+        events: events(),
+        types: types(),
+    }
+}
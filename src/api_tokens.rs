@@ -0,0 +1,170 @@
+use base64::Engine;
+use rand::RngCore;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{fs, path::PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::{data_dir, now_ts, read_only, AppState};
+
+/// Prefix on every token so it is recognisable in a plugin's settings.
+const TOKEN_PREFIX: &str = "ups_";
+const MAX_NAME_CHARS: usize = 40;
+/// Live tokens at once; revoked ones don't count.
+pub const MAX_TOKENS: usize = 20;
+
+/// What the UI sees of a token. The token itself is shown once, by
+/// `generate_api_token`, and only its SHA-256 is kept.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ApiToken {
+    pub id: String,
+    /// Who it was made for, e.g. "Obsidian".
+    pub name: String,
+    /// Last four characters, to tell tokens apart.
+    pub hint: String,
+    pub created_ts: i64,
+    /// Updated on every request, saved with the next generate or revoke.
+    #[serde(default)]
+    pub last_used_ts: Option<i64>,
+    /// Revoked tokens stay listed and are refused for good.
+    #[serde(default)]
+    pub revoked_ts: Option<i64>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct StoredToken {
+    #[serde(flatten)]
+    pub info: ApiToken,
+    sha256: String,
+}
+
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct NewApiToken {
+    /// Copy it now; it can't be shown again.
+    pub token: String,
+    pub info: ApiToken,
+}
+
+/// Kept out of config.json so settings exports never carry them.
+fn path(app: &AppHandle) -> Option<PathBuf> {
+    data_dir::dir(app).map(|dir| dir.join("api_tokens.json"))
+}
+
+fn digest(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+pub fn load(app: &AppHandle) {
+    let Some(contents) = path(app).and_then(|p| fs::read_to_string(p).ok()) else {
+        return;
+    };
+    match serde_json::from_str::<Vec<StoredToken>>(&contents) {
+        Ok(tokens) => *app.state::<AppState>().api_tokens.lock().unwrap() = tokens,
+        Err(e) => eprintln!("ignoring unreadable api_tokens.json: {}", e),
+    }
+}
+
+fn save(app: &AppHandle, state: &AppState) {
+    if state.safe_mode || read_only::enabled(state) {
+        return;
+    }
+    let Some(path) = path(app) else {
+        return;
+    };
+    let tokens = state.api_tokens.lock().unwrap().clone();
+    if let Ok(json) = serde_json::to_string_pretty(&tokens) {
+        if let Err(e) = data_dir::write(state, &path, json) {
+            eprintln!("saving api tokens failed: {}", e);
+        }
+    }
+}
+
+pub fn list(state: &AppState) -> Vec<ApiToken> {
+    state
+        .api_tokens
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|t| t.info.clone())
+        .collect()
+}
+
+/// Make a token for `name` and return it in the clear, the only time it is.
+pub fn generate(state: &AppState, name: &str) -> Result<NewApiToken, String> {
+    let name: String = name.trim().chars().take(MAX_NAME_CHARS).collect();
+    if name.is_empty() {
+        return Err("token name is empty".to_string());
+    }
+    let mut tokens = state.api_tokens.lock().unwrap();
+    if tokens
+        .iter()
+        .filter(|t| t.info.revoked_ts.is_none())
+        .count()
+        >= MAX_TOKENS
+    {
+        return Err(format!("at most {} tokens", MAX_TOKENS));
+    }
+    let mut secret = [0u8; 32];
+    rand::rngs::OsRng.fill_bytes(&mut secret);
+    let token = format!(
+        "{}{}",
+        TOKEN_PREFIX,
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(secret)
+    );
+    let mut id_bytes = [0u8; 6];
+    rand::rngs::OsRng.fill_bytes(&mut id_bytes);
+    let info = ApiToken {
+        id: id_bytes.iter().map(|b| format!("{:02x}", b)).collect(),
+        name,
+        hint: token[token.len() - 4..].to_string(),
+        created_ts: now_ts(),
+        last_used_ts: None,
+        revoked_ts: None,
+    };
+    tokens.push(StoredToken {
+        info: info.clone(),
+        sha256: digest(&token),
+    });
+    Ok(NewApiToken { token, info })
+}
+
+pub fn revoke(state: &AppState, id: &str) -> Result<ApiToken, String> {
+    let mut tokens = state.api_tokens.lock().unwrap();
+    let token = tokens
+        .iter_mut()
+        .find(|t| t.info.id == id)
+        .ok_or_else(|| format!("unknown token: {}", id))?;
+    token.info.revoked_ts.get_or_insert(now_ts());
+    Ok(token.info.clone())
+}
+
+/// Whether `token` is live, recording the use if so. The comparison runs
+/// over the whole digest so timing says nothing about near misses.
+pub fn authorize(state: &AppState, token: &str) -> bool {
+    let wanted = digest(token.trim());
+    let mut tokens = state.api_tokens.lock().unwrap();
+    let found = tokens.iter_mut().find(|t| {
+        t.sha256.len() == wanted.len()
+            && t.sha256
+                .bytes()
+                .zip(wanted.bytes())
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                == 0
+    });
+    match found {
+        Some(t) if t.info.revoked_ts.is_none() => {
+            t.info.last_used_ts = Some(now_ts());
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Persist after `generate` or `revoke`.
+pub fn changed(app: &AppHandle) {
+    save(app, &app.state::<AppState>());
+}
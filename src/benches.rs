@@ -0,0 +1,132 @@
+//! Aggregation and export timings on synthetic histories. Analytics prune
+//! reminders and standups to `RETENTION_SECS` before aggregating, so those
+//! benches measure the 180 days the app ever keeps; pruning itself runs on
+//! unpruned one- and two-year histories. The crate is a binary, so criterion
+//! runs from ignored tests instead of a `benches/` target:
+//!
+//!     cargo test --release benches -- --ignored --nocapture --test-threads=1
+//!
+//! Budgets for any replacement of the JSON store (the planned SQLite backend
+//! included), measured on the same machine as a baseline run of these
+//! benches:
+//! - daily, weekly and monthly analytics over the retained 180 days: no
+//!   slower than the baseline, and under 5 ms each
+//! - loading and pruning an unpruned two-year history: under 50 ms
+//! - monthly CSV export and the full raw event dump of the retained 180
+//!   days: under 50 ms each
+
+use criterion::{BatchSize, Criterion};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::hint::black_box;
+use std::time::Duration as StdDuration;
+
+use super::*;
+
+/// Unpruned history lengths for the prune bench.
+const HISTORY_DAYS: [i64; 2] = [365, 730];
+/// Everything analytics and exports ever see once pruning has run.
+const RETAINED_DAYS: i64 = RETENTION_SECS / WINDOW_24H_SECS;
+
+/// A plausible desk worker: 9:00–18:00 on weekdays, 8–12 standups, a few
+/// ignored reminders, a focus session or two and hourly activity buckets.
+/// Seeded, so every run measures the same data.
+fn synthetic_state(days: i64) -> AppState {
+    let state = AppState::new(false);
+    let mut rng = StdRng::seed_from_u64(days as u64);
+    let today = clock::now().date_naive();
+    let mut reminders = Vec::new();
+    let mut standups = Vec::new();
+    let mut focus = Vec::new();
+    let mut activity = Vec::new();
+    for offset in (0..days).rev() {
+        let date = today - ChronoDuration::days(offset);
+        if schedule::is_weekend(date) {
+            continue;
+        }
+        let start = day_start_ts(date, 9);
+        let span = 9 * 3600;
+        for _ in 0..rng.gen_range(8..=12) {
+            standups.push(start + rng.gen_range(0..span));
+        }
+        for _ in 0..rng.gen_range(2..=5) {
+            reminders.push(ReminderRecord {
+                ts: start + rng.gen_range(0..span),
+                duration_secs: DEFAULT_INTERVAL_MINUTES * 60,
+            });
+        }
+        for _ in 0..rng.gen_range(1..=2) {
+            focus.push(FocusRecord {
+                ts: start + rng.gen_range(0..span),
+                duration_secs: 25 * 60,
+                completed: rng.gen_bool(0.8),
+            });
+        }
+        for hour in 0..9 {
+            let active_secs = rng.gen_range(1200..3600);
+            activity.push(ActivityBucket {
+                hour_ts: start + hour * 3600,
+                active_secs,
+                idle_secs: 3600 - active_secs,
+            });
+        }
+    }
+    reminders.sort_by_key(|r| r.ts);
+    standups.sort_unstable();
+    focus.sort_by_key(|r| r.ts);
+    *state.reminder_events.lock().unwrap() = reminders;
+    *state.standup_events.lock().unwrap() = standups;
+    *state.focus_events.lock().unwrap() = focus;
+    *state.activity_buckets.lock().unwrap() = activity;
+    state
+}
+
+fn criterion() -> Criterion {
+    Criterion::default()
+        .sample_size(30)
+        .measurement_time(StdDuration::from_secs(5))
+}
+
+#[test]
+#[ignore]
+fn bench_analytics_periods() {
+    let mut c = criterion();
+    let state = synthetic_state(RETAINED_DAYS);
+    for period in ["daily", "weekly", "monthly"] {
+        c.bench_function(&format!("analytics/{}/{}d", period, RETAINED_DAYS), |b| {
+            b.iter(|| build_analytics_for_period(black_box(&state), period))
+        });
+    }
+    c.final_summary();
+}
+
+#[test]
+#[ignore]
+fn bench_load_and_prune() {
+    let mut c = criterion();
+    for days in HISTORY_DAYS {
+        c.bench_function(&format!("prune/{}d", days), |b| {
+            b.iter_batched(
+                || synthetic_state(days),
+                |state| build_analytics(&state),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    c.final_summary();
+}
+
+#[test]
+#[ignore]
+fn bench_exports() {
+    let mut c = criterion();
+    let fmt = Formatter::new("en", false);
+    let state = synthetic_state(RETAINED_DAYS);
+    c.bench_function(&format!("export/csv_monthly/{}d", RETAINED_DAYS), |b| {
+        b.iter(|| analytics_csv(&build_analytics_for_period(&state, "monthly"), &fmt))
+    });
+    c.bench_function(&format!("export/raw_dump/{}d", RETAINED_DAYS), |b| {
+        b.iter(|| serde_json::to_string(&raw_events::dump(black_box(&state))))
+    });
+    c.final_summary();
+}
@@ -0,0 +1,144 @@
+use rand::Rng;
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::formatting::Formatter;
+use crate::timeline::{self, SegmentKind};
+use crate::{analytics_date, clock, notify, timers, AppState, TRAY_ID};
+
+/// Upper bound for `set_sitting_budget_minutes`; a whole day.
+pub const MAX_BUDGET_MINUTES: u64 = 24 * 60;
+
+/// Tips for the budget alert, kept apart from the interval reminders: this
+/// one is about the day as a whole, not the last stretch.
+const BUDGET_TIPS_EN: [&str; 4] = [
+    "That's your sitting budget for today. Anything else, do it standing.",
+    "Budget spent. Take the next call on your feet.",
+    "You've sat all you planned to today. Time for a walk.",
+    "Over budget! Raise the desk or take the rest outside.",
+];
+const BUDGET_TIPS_ZH: [&str; 4] = [
+    "今天的久坐额度用完了，剩下的事站着做吧。",
+    "额度已用完，下一个电话站着接。",
+    "今天计划坐的时间已经坐满了，出去走走吧。",
+    "超出额度了！把桌子升起来，或者出去活动一下。",
+];
+
+/// Payload of `sitting-budget-exceeded`.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct BudgetExceeded {
+    pub budget_secs: u64,
+    pub used_secs: u64,
+    pub tip: String,
+}
+
+/// The daily budget in seconds; `None` when it is off.
+pub fn budget_secs(state: &AppState) -> Option<u64> {
+    let minutes = *state.sitting_budget_minutes.lock().unwrap();
+    (minutes > 0).then_some(minutes * 60)
+}
+
+/// Sitting time so far today, as the day timeline draws it.
+pub fn used_secs(state: &AppState) -> u64 {
+    timeline::today(state)
+        .iter()
+        .filter(|segment| segment.kind == SegmentKind::Sitting)
+        .map(|segment| (segment.end_ts - segment.start_ts).max(0) as u64)
+        .sum()
+}
+
+/// What is left of today's budget, zero once it is spent.
+pub fn remaining_secs(state: &AppState) -> Option<u64> {
+    budget_secs(state).map(|budget| budget.saturating_sub(used_secs(state)))
+}
+
+fn tooltip(state: &AppState) -> String {
+    let fmt = Formatter::for_reports(state);
+    let zh = *state.language.lock().unwrap() == "zh-CN";
+    let budget_line = budget_secs(state).map(|budget| budget_text(state, &fmt, zh, budget));
+    // Today's interval override is easy to forget about by the afternoon.
+    let override_line = timers::interval_today(state).map(|secs| {
+        if zh {
+            format!("今天每 {} 提醒一次", fmt.duration(secs))
+        } else {
+            format!("Every {} today", fmt.duration(secs))
+        }
+    });
+    match (budget_line, override_line) {
+        (None, None) => "Upstand".to_string(),
+        (Some(budget), None) => budget,
+        (None, Some(interval)) => format!("Upstand — {}", interval),
+        (Some(budget), Some(interval)) => format!("{}\n{}", budget, interval),
+    }
+}
+
+fn budget_text(state: &AppState, fmt: &Formatter, zh: bool, budget: u64) -> String {
+    let used = used_secs(state);
+    match (used < budget, zh) {
+        (true, false) => format!(
+            "Upstand — {} of sitting budget left",
+            fmt.duration(budget - used)
+        ),
+        (true, true) => format!("Upstand — 久坐额度还剩 {}", fmt.duration(budget - used)),
+        (false, false) => format!(
+            "Upstand — sitting budget exceeded by {}",
+            fmt.duration(used - budget)
+        ),
+        (false, true) => format!("Upstand — 久坐额度已超出 {}", fmt.duration(used - budget)),
+    }
+}
+
+/// Refresh the tray tooltip, and post the alert the first time today's
+/// budget runs out.
+pub fn tick(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let text = tooltip(&state);
+    let changed = {
+        let mut last = state.tray_tooltip.lock().unwrap();
+        let changed = *last != text;
+        *last = text.clone();
+        changed
+    };
+    if changed {
+        if let Some(tray) = app.tray_by_id(TRAY_ID) {
+            let _ = tray.set_tooltip(Some(text));
+        }
+    }
+
+    let Some(budget) = budget_secs(&state) else {
+        return;
+    };
+    let used = used_secs(&state);
+    if used < budget {
+        return;
+    }
+    let day_start_hour = *state.day_start_hour.lock().unwrap();
+    let today = analytics_date(clock::now(), day_start_hour)
+        .format("%Y-%m-%d")
+        .to_string();
+    {
+        let mut alerted = state.budget_alert_date.lock().unwrap();
+        if alerted.as_deref() == Some(today.as_str()) {
+            return;
+        }
+        *alerted = Some(today);
+    }
+    let zh = *state.language.lock().unwrap() == "zh-CN";
+    let tips: &[&str] = if zh { &BUDGET_TIPS_ZH } else { &BUDGET_TIPS_EN };
+    let tip = tips[rand::thread_rng().gen_range(0..tips.len())].to_string();
+    let title = if zh {
+        "久坐额度已用完"
+    } else {
+        "Sitting budget exceeded"
+    };
+    notify(app, title, &tip);
+    let _ = app.emit(
+        "sitting-budget-exceeded",
+        BudgetExceeded {
+            budget_secs: budget,
+            used_secs: used,
+            tip,
+        },
+    );
+}
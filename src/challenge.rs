@@ -0,0 +1,143 @@
+use rand::seq::SliceRandom;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{desk, network_profiles, AppState};
+
+pub const MIN_WAIT_SECS: u64 = 5;
+pub const MAX_WAIT_SECS: u64 = 300;
+const PHRASE_WORDS: usize = 3;
+const WORDS: &[&str] = &[
+    "apple", "bridge", "cloud", "desk", "eagle", "forest", "garden", "harbor", "island", "jacket",
+    "kettle", "lemon", "meadow", "nickel", "orange", "pepper", "river", "stone", "tiger", "window",
+];
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChallengeKind {
+    /// "Stood up" is accepted once `wait_secs` have passed.
+    Countdown,
+    /// After `wait_secs` a short phrase is shown that has to be typed back.
+    Phrase,
+}
+
+/// Makes answering "stood up" take a real pause. Enforced here rather than
+/// in the reminder page, so a click or call that skips the page's countdown
+/// is still refused. Dismissing without standing isn't affected.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChallengeConfig {
+    #[serde(default)]
+    pub kind: Option<ChallengeKind>,
+    #[serde(default = "default_wait_secs")]
+    pub wait_secs: u64,
+}
+
+impl Default for ChallengeConfig {
+    fn default() -> Self {
+        Self {
+            kind: None,
+            wait_secs: default_wait_secs(),
+        }
+    }
+}
+
+fn default_wait_secs() -> u64 {
+    60
+}
+
+pub fn sanitize(mut cfg: ChallengeConfig) -> ChallengeConfig {
+    cfg.wait_secs = cfg.wait_secs.clamp(MIN_WAIT_SECS, MAX_WAIT_SECS);
+    cfg
+}
+
+/// The open reminder's challenge, as the reminder page sees it.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct ChallengeStatus {
+    pub kind: ChallengeKind,
+    /// Seconds until it can be answered; 0 once it can.
+    pub ready_in_secs: u64,
+    /// The phrase to type, shown only once it is ready.
+    pub phrase: Option<String>,
+}
+
+fn new_phrase() -> String {
+    let mut rng = rand::thread_rng();
+    WORDS
+        .choose_multiple(&mut rng, PHRASE_WORDS)
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether the open reminder's "stood up" has a challenge to pass. Lowering
+/// a standing desk doesn't. A network profile's kind wins over the config's.
+fn applies(state: &AppState) -> Option<ChallengeConfig> {
+    let mut cfg = state.challenge.lock().unwrap().clone();
+    if let Some(kind) = network_profiles::active(state).and_then(|profile| profile.challenge) {
+        cfg.kind = Some(kind);
+    }
+    (cfg.kind.is_some() && !desk::standing(state)).then_some(cfg)
+}
+
+fn shown_secs(state: &AppState) -> u64 {
+    state
+        .active_reminder_shown_at
+        .lock()
+        .unwrap()
+        .map_or(0, |shown_at| shown_at.elapsed().as_secs())
+}
+
+pub fn status(state: &AppState) -> Option<ChallengeStatus> {
+    let cfg = applies(state)?;
+    let kind = cfg.kind?;
+    let ready_in_secs = cfg.wait_secs.saturating_sub(shown_secs(state));
+    // Picked on first use, so a phrase challenge turned on while a reminder
+    // is open gets one too.
+    let phrase = match kind {
+        ChallengeKind::Phrase if ready_in_secs == 0 => Some(
+            state
+                .challenge_phrase
+                .lock()
+                .unwrap()
+                .get_or_insert_with(new_phrase)
+                .clone(),
+        ),
+        _ => None,
+    };
+    Some(ChallengeStatus {
+        kind,
+        ready_in_secs,
+        phrase,
+    })
+}
+
+/// Check a "stood up" answer for the open reminder. Errors are
+/// `CHALLENGE_PENDING` before the wait is over and `CHALLENGE_FAILED` for a
+/// phrase typed wrong.
+pub fn check(state: &AppState, answer: Option<&str>) -> Result<(), String> {
+    let Some(status) = status(state) else {
+        return Ok(());
+    };
+    if status.ready_in_secs > 0 {
+        return Err("CHALLENGE_PENDING".to_string());
+    }
+    match status.kind {
+        ChallengeKind::Countdown => Ok(()),
+        ChallengeKind::Phrase => {
+            let expected = status.phrase.unwrap_or_default();
+            let typed = answer.unwrap_or_default();
+            if normalize(typed) == normalize(&expected) {
+                Ok(())
+            } else {
+                Err("CHALLENGE_FAILED".to_string())
+            }
+        }
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
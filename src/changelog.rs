@@ -0,0 +1,99 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::{events, save_config, AppState};
+
+/// Release notes bundled with the app, newest first.
+const CHANGELOG_JSON: &str = include_str!("../changelog.json");
+/// `whats-new` goes out once the splash has closed and the settings window
+/// has had time to load and subscribe.
+const WHATS_NEW_DELAY_SECS: u64 = 4;
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChangelogEntry {
+    pub version: String,
+    /// "YYYY-MM-DD".
+    pub date: String,
+    #[serde(default)]
+    pub added: Vec<String>,
+    #[serde(default)]
+    pub changed: Vec<String>,
+    #[serde(default)]
+    pub fixed: Vec<String>,
+}
+
+/// Payload of `whats-new`: the notes between the last version this install
+/// ran and the current one.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct WhatsNew {
+    pub previous_version: String,
+    pub version: String,
+    pub entries: Vec<ChangelogEntry>,
+}
+
+/// "1.2.3", "v1.2" or "1.2.3-beta.1" as a comparable triple; pre-release and
+/// build suffixes are ignored.
+pub fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.trim().trim_start_matches('v');
+    let core = core.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().unwrap_or(Some(0))?;
+    let patch = parts.next().unwrap_or(Some(0))?;
+    parts.next().is_none().then_some((major, minor, patch))
+}
+
+pub fn entries() -> Vec<ChangelogEntry> {
+    serde_json::from_str(CHANGELOG_JSON).unwrap_or_default()
+}
+
+/// Entries newer than `since` up to the running version, newest first.
+pub fn since(since: &str) -> Result<Vec<ChangelogEntry>, String> {
+    let since = parse_version(since).ok_or_else(|| format!("invalid version: {}", since))?;
+    let current = parse_version(env!("CARGO_PKG_VERSION"));
+    Ok(entries()
+        .into_iter()
+        .filter(|entry| {
+            parse_version(&entry.version)
+                .is_some_and(|v| v > since && current.is_none_or(|current| v <= current))
+        })
+        .collect())
+}
+
+/// Record the running version and, after an update, keep its notes for
+/// `get_whats_new` and announce them with `whats-new`. A config without a
+/// recorded version is treated as a fresh install: there is nothing to
+/// compare against, so no notes are shown.
+pub fn check_for_update(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let version = env!("CARGO_PKG_VERSION").to_string();
+    let previous = state
+        .last_seen_version
+        .lock()
+        .unwrap()
+        .replace(version.clone());
+    if previous.as_deref() == Some(version.as_str()) {
+        return;
+    }
+    let _ = save_config(app, &state);
+    let Some(previous_version) = previous else {
+        return;
+    };
+    let entries = since(&previous_version).unwrap_or_default();
+    if entries.is_empty() {
+        return;
+    }
+    let whats_new = WhatsNew {
+        previous_version,
+        version,
+        entries,
+    };
+    *state.whats_new.lock().unwrap() = Some(whats_new.clone());
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(WHATS_NEW_DELAY_SECS)).await;
+        events::emit(&handle, "whats-new", whats_new);
+    });
+}
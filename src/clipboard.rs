@@ -0,0 +1,118 @@
+use chrono::{Local, TimeZone};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::{
+    build_analytics_for_period, formatting::Formatter, normalize_period, AnalyticsData, AppState,
+};
+
+fn period_label(lang: &str, period: &str) -> &'static str {
+    match (lang == "zh-CN", period) {
+        (true, "weekly") => "本周",
+        (true, "monthly") => "本月",
+        (true, _) => "今天",
+        (false, "weekly") => "This week",
+        (false, "monthly") => "This month",
+        (false, _) => "Today",
+    }
+}
+
+/// Standups as a share of all answered reminders; `None` with no reminders.
+fn compliance(analytics: &AnalyticsData) -> Option<u32> {
+    let total = analytics.standup_sessions + analytics.sedentary_sessions;
+    (total > 0).then(|| (analytics.standup_sessions * 100 + total / 2) / total)
+}
+
+/// One line for chat, optionally followed by a markdown table of the same
+/// figures plus the period start.
+pub fn summary(
+    analytics: &AnalyticsData,
+    period: &str,
+    lang: &str,
+    fmt: &Formatter,
+    markdown: bool,
+) -> String {
+    let zh = lang == "zh-CN";
+    let label = period_label(lang, period);
+    let standups = fmt.count(u64::from(analytics.standup_sessions));
+    let ignored = fmt.count(u64::from(analytics.ignored_reminders));
+    let sitting = fmt.duration(analytics.total_sitting_secs);
+    let rate = compliance(analytics).map(|pct| format!("{}%", pct));
+    let mut text = if zh {
+        format!(
+            "{}站立 {} 次，久坐 {}，忽略提醒 {} 次",
+            label, standups, sitting, ignored
+        )
+    } else {
+        format!(
+            "{}: {} standup{}, {} sitting, {} reminder{} ignored",
+            label,
+            standups,
+            if analytics.standup_sessions == 1 {
+                ""
+            } else {
+                "s"
+            },
+            sitting,
+            ignored,
+            if analytics.ignored_reminders == 1 {
+                ""
+            } else {
+                "s"
+            }
+        )
+    };
+    if let Some(rate) = rate.as_deref() {
+        text.push_str(&if zh {
+            format!("，达成率 {}", rate)
+        } else {
+            format!(", {} on time", rate)
+        });
+    }
+    if !markdown {
+        return text;
+    }
+
+    let start = Local
+        .timestamp_opt(analytics.period_start_ts, 0)
+        .single()
+        .map(|dt| fmt.date(dt.date_naive()))
+        .unwrap_or_default();
+    let rows: [(&str, &str, String); 5] = [
+        ("Since", "起始", start),
+        ("Standups", "站立", standups),
+        ("Sitting", "久坐", sitting),
+        ("Ignored reminders", "忽略提醒", ignored),
+        ("On time", "达成率", rate.unwrap_or_else(|| "-".to_string())),
+    ];
+    text.push_str("\n\n");
+    text.push_str(if zh {
+        "| 项目 | 数值 |\n"
+    } else {
+        "| Metric | Value |\n"
+    });
+    text.push_str("|---|---|\n");
+    for (en, cn, value) in rows.iter() {
+        text.push_str(&format!("| {} | {} |\n", if zh { cn } else { en }, value));
+    }
+    text.trim_end().to_string()
+}
+
+/// Put the period's summary on the clipboard and return what was copied.
+pub fn copy_summary(app: &AppHandle, period: &str, markdown: bool) -> Result<String, String> {
+    let state = app.state::<AppState>();
+    let period = normalize_period(period);
+    let analytics = build_analytics_for_period(&state, period);
+    let lang = state.language.lock().unwrap().clone();
+    let text = summary(
+        &analytics,
+        period,
+        &lang,
+        &Formatter::for_reports(&state),
+        markdown,
+    );
+    app.clipboard()
+        .write_text(text.clone())
+        .map_err(|e| format!("clipboard write failed: {}", e))?;
+    Ok(text)
+}
@@ -0,0 +1,96 @@
+use chrono::{DateTime, Local};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Beyond this the 1 s timer tick drops under a millisecond of real time.
+const MAX_TIME_SCALE: f64 = 1000.0;
+
+/// Simulated time: starts at the real time it was installed and runs
+/// `scale` times faster.
+#[derive(Clone, Copy)]
+struct Simulated {
+    scale: f64,
+    origin: Instant,
+    origin_wall: DateTime<Local>,
+}
+
+static CLOCK: Mutex<Option<Simulated>> = Mutex::new(None);
+
+#[cfg(test)]
+thread_local! {
+    /// Tests install their clock per thread so they can't skew each other.
+    static TEST_CLOCK: std::cell::Cell<Option<Simulated>> = const { std::cell::Cell::new(None) };
+}
+
+fn current() -> Option<Simulated> {
+    #[cfg(test)]
+    if let Some(sim) = TEST_CLOCK.get() {
+        return Some(sim);
+    }
+    *CLOCK.lock().unwrap()
+}
+
+/// `--time-scale N` or `--time-scale=N`, for running a simulated day in
+/// minutes. Ignored unless N is a number above 1.
+pub fn scale_from_args(args: &[String]) -> Option<f64> {
+    let value =
+        args.iter()
+            .enumerate()
+            .find_map(|(i, arg)| match arg.strip_prefix("--time-scale=") {
+                Some(value) => Some(value),
+                None if arg == "--time-scale" => args.get(i + 1).map(String::as_str),
+                None => None,
+            })?;
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|scale| *scale > 1.0)
+        .map(|scale| scale.min(MAX_TIME_SCALE))
+}
+
+/// Switch every timer and analytics clock to simulated time. Call before
+/// anything reads the clock; the origin is the moment of the call.
+pub fn install(scale: f64) {
+    install_at(scale, Local::now());
+}
+
+/// `install`, with simulated time starting from `origin_wall`.
+pub fn install_at(scale: f64, origin_wall: DateTime<Local>) {
+    let sim = Simulated {
+        scale,
+        origin: Instant::now(),
+        origin_wall,
+    };
+    #[cfg(test)]
+    TEST_CLOCK.set(Some(sim));
+    #[cfg(not(test))]
+    {
+        *CLOCK.lock().unwrap() = Some(sim);
+    }
+}
+
+pub fn scale() -> f64 {
+    current().map_or(1.0, |sim| sim.scale)
+}
+
+/// Whether time is simulated. Nothing that leaves the machine may run on it.
+pub fn simulated() -> bool {
+    current().is_some()
+}
+
+/// The current local time, simulated or real.
+pub fn now() -> DateTime<Local> {
+    match current() {
+        Some(sim) => {
+            let elapsed = sim.origin.elapsed().mul_f64(sim.scale);
+            sim.origin_wall
+                + chrono::Duration::from_std(elapsed).unwrap_or(chrono::Duration::zero())
+        }
+        None => Local::now(),
+    }
+}
+
+/// Sleep for `duration` of clock time, i.e. `duration / scale` of real time.
+pub async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration.div_f64(scale())).await;
+}
@@ -0,0 +1,187 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    apply_config, config_from_state, data_dir, read_only, save_config, settings_bundle,
+    AppConfigFile, AppState,
+};
+
+const MAX_ENTRIES: usize = 200;
+/// What a change is put down to when nothing more specific was said.
+const DEFAULT_SOURCE: &str = "settings";
+/// Fields the app rewrites by itself as bookkeeping, as JSON pointers.
+/// Changes to them aren't settings changes.
+const BOOKKEEPING: [&str; 6] = [
+    "/window_prefs",
+    "/export_schedule/last_run_date",
+    "/google_sheets/last_appended_date",
+    "/day_summary/last_sent_date",
+    "/last_rollover_date",
+    "/last_seen_version",
+];
+
+/// One top-level config key changing value.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ConfigChange {
+    pub id: u64,
+    pub ts: i64,
+    /// What made the change: "settings" for a settings command, "import",
+    /// "sanitizer" when a loaded value wasn't accepted as it was, or
+    /// "revert".
+    pub source: String,
+    pub key: String,
+    /// `null` when the key wasn't set.
+    pub old: Value,
+    pub new: Value,
+}
+
+/// Kept next to config.json rather than in it, so settings exports don't
+/// carry the history of the machine they came from.
+fn path(app: &AppHandle) -> Option<PathBuf> {
+    data_dir::dir(app).map(|dir| dir.join("config_history.json"))
+}
+
+/// `cfg` as compared between saves, bookkeeping blanked out.
+pub fn tracked(cfg: &AppConfigFile) -> Value {
+    let mut value = serde_json::to_value(cfg).unwrap_or_default();
+    for pointer in BOOKKEEPING {
+        if let Some(field) = value.pointer_mut(pointer) {
+            *field = Value::Null;
+        }
+    }
+    value
+}
+
+/// Top-level keys whose values differ, with the old and new values.
+pub fn diff(old: &Value, new: &Value) -> Vec<(String, Value, Value)> {
+    let (Some(old), Some(new)) = (old.as_object(), new.as_object()) else {
+        return Vec::new();
+    };
+    let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter_map(|key| {
+            let before = old.get(key).cloned().unwrap_or(Value::Null);
+            let after = new.get(key).cloned().unwrap_or(Value::Null);
+            (before != after).then(|| (key.clone(), before, after))
+        })
+        .collect()
+}
+
+/// Put the next recorded change down to `source`.
+pub fn set_source(state: &AppState, source: &str) {
+    *state.config_change_source.lock().unwrap() = Some(source.to_string());
+}
+
+/// Load the history and take the config as read from disk as the starting
+/// point, so whatever the sanitizer changes about it is logged on the first
+/// save.
+pub fn start(app: &AppHandle, loaded: &AppConfigFile) {
+    let state = app.state::<AppState>();
+    if let Some(contents) = path(app).and_then(|p| fs::read_to_string(p).ok()) {
+        match serde_json::from_str::<Vec<ConfigChange>>(&contents) {
+            Ok(history) => *state.config_history.lock().unwrap() = history,
+            Err(e) => eprintln!("ignoring unreadable config_history.json: {}", e),
+        }
+    }
+    *state.config_baseline.lock().unwrap() = Some(tracked(loaded));
+    set_source(&state, "sanitizer");
+}
+
+/// Log how `cfg`, about to be saved, differs from the last saved config.
+/// Returns whether anything was logged.
+pub fn record(state: &AppState, cfg: &AppConfigFile, now: i64) -> bool {
+    let source = state
+        .config_change_source
+        .lock()
+        .unwrap()
+        .take()
+        .unwrap_or_else(|| DEFAULT_SOURCE.to_string());
+    let next = tracked(cfg);
+    let Some(previous) = state.config_baseline.lock().unwrap().replace(next.clone()) else {
+        return false;
+    };
+    let changes = diff(&previous, &next);
+    if changes.is_empty() {
+        return false;
+    }
+    let mut history = state.config_history.lock().unwrap();
+    let first_id = history.last().map_or(0, |change| change.id) + 1;
+    for (id, (key, old, new)) in (first_id..).zip(changes) {
+        history.push(ConfigChange {
+            id,
+            ts: now,
+            source: source.clone(),
+            key,
+            old,
+            new,
+        });
+    }
+    let excess = history.len().saturating_sub(MAX_ENTRIES);
+    history.drain(..excess);
+    true
+}
+
+pub fn save(app: &AppHandle, state: &AppState) {
+    if state.safe_mode || read_only::enabled(state) {
+        return;
+    }
+    let Some(path) = path(app) else {
+        return;
+    };
+    let history = state.config_history.lock().unwrap().clone();
+    if let Ok(json) = serde_json::to_string_pretty(&history) {
+        if let Err(e) = data_dir::write(state, &path, json) {
+            eprintln!("saving config history failed: {}", e);
+        }
+    }
+}
+
+/// Newest first.
+pub fn list(state: &AppState, limit: usize) -> Vec<ConfigChange> {
+    state
+        .config_history
+        .lock()
+        .unwrap()
+        .iter()
+        .rev()
+        .take(limit)
+        .cloned()
+        .collect()
+}
+
+/// Set the key of change `id` back to the value it had before. The revert
+/// goes through the sanitizer like any other change and is logged too.
+pub fn revert(app: &AppHandle, id: u64) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let change = state
+        .config_history
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|change| change.id == id)
+        .cloned()
+        .ok_or_else(|| "no such change".to_string())?;
+    let mut current = serde_json::to_value(config_from_state(&state)).map_err(|e| e.to_string())?;
+    let Some(fields) = current.as_object_mut() else {
+        return Err("config isn't an object".to_string());
+    };
+    // Unset means the default, which a missing key gives.
+    if change.old.is_null() {
+        fields.remove(&change.key);
+    } else {
+        fields.insert(change.key.clone(), change.old);
+    }
+    let cfg = serde_json::from_value::<AppConfigFile>(current)
+        .map_err(|e| format!("can't revert {}: {}", change.key, e))?;
+    apply_config(&state, cfg);
+    set_source(&state, "revert");
+    save_config(app, &state)?;
+    settings_bundle::announce(app);
+    Ok(())
+}
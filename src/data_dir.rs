@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::{read_only, save_analytics, save_config, store_lock, AppState};
+
+/// Kept in the OS data directory, since it has to be found before the
+/// relocated one is known.
+const LOCATION_FILE: &str = "data_location.json";
+/// Directories and files that make up the store. Webview caches and the lock
+/// file stay where they are.
+const STORE_DIRS: [&str; 1] = ["tip_packs"];
+const STORE_FILES: [&str; 6] = [
+    "config.json",
+    "config_history.json",
+    "export_snapshots.json",
+    "analytics.json",
+    "analytics.journal.jsonl",
+    "api_tokens.json",
+];
+/// Suffix `repair_data_files` gives the copies it sets aside.
+const BACKUP_MARKER: &str = ".corrupt-";
+
+#[derive(Serialize, Deserialize)]
+struct Location {
+    path: PathBuf,
+}
+
+/// Where the OS wants the app's data.
+pub fn default_dir(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_data_dir().ok()
+}
+
+/// Where the data actually lives: the relocated directory, if any.
+pub fn dir(app: &AppHandle) -> Option<PathBuf> {
+    let relocated = app.state::<AppState>().data_dir.lock().unwrap().clone();
+    relocated.or_else(|| default_dir(app))
+}
+
+/// Write a file of the store, refusing with [`read_only::ERROR`] in
+/// read-only mode and while another process holds the lock. Every write
+/// into the data directory goes through here.
+pub fn write(state: &AppState, path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    read_only::check(state).map_err(std::io::Error::other)?;
+    store_lock::check(state).map_err(std::io::Error::other)?;
+    fs::write(path, contents)
+}
+
+/// Pick up a relocation recorded by an earlier `move_data_dir`. Call before
+/// anything touches the store. A location that has gone missing (an
+/// unplugged drive) falls back to the default rather than scattering files
+/// over a half-mounted path.
+pub fn load_override(app: &AppHandle) {
+    let Some(pointer) = default_dir(app).map(|dir| dir.join(LOCATION_FILE)) else {
+        return;
+    };
+    let Ok(contents) = fs::read_to_string(&pointer) else {
+        return;
+    };
+    let Ok(location) = serde_json::from_str::<Location>(&contents) else {
+        eprintln!("ignoring unreadable {}", pointer.display());
+        return;
+    };
+    if location.path.is_dir() {
+        *app.state::<AppState>().data_dir.lock().unwrap() = Some(location.path);
+    } else {
+        eprintln!(
+            "data directory {} is missing, using the default",
+            location.path.display()
+        );
+    }
+}
+
+/// Relative paths of everything in `dir` that belongs to the store.
+pub fn store_entries(dir: &Path) -> Vec<PathBuf> {
+    let mut entries: Vec<PathBuf> = STORE_FILES.iter().map(PathBuf::from).collect();
+    if let Ok(listing) = fs::read_dir(dir) {
+        entries.extend(
+            listing
+                .flatten()
+                .map(|entry| PathBuf::from(entry.file_name()))
+                .filter(|name| name.to_string_lossy().contains(BACKUP_MARKER)),
+        );
+    }
+    for sub in STORE_DIRS {
+        if let Ok(listing) = fs::read_dir(dir.join(sub)) {
+            entries.extend(
+                listing
+                    .flatten()
+                    .filter(|entry| entry.path().is_file())
+                    .map(|entry| Path::new(sub).join(entry.file_name())),
+            );
+        }
+    }
+    entries.retain(|entry| dir.join(entry).is_file());
+    entries
+}
+
+fn remove_entries(dir: &Path, entries: &[PathBuf]) {
+    for entry in entries {
+        let _ = fs::remove_file(dir.join(entry));
+    }
+    for sub in STORE_DIRS {
+        let _ = fs::remove_dir(dir.join(sub));
+    }
+}
+
+/// Copy every entry and read it back; on any failure remove what was copied.
+pub fn copy_verified(from: &Path, to: &Path, entries: &[PathBuf]) -> Result<(), String> {
+    let mut copied = Vec::new();
+    let result = entries.iter().try_for_each(|entry| {
+        let target = to.join(entry);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("create dir failed: {}", e))?;
+        }
+        let source = fs::read(from.join(entry))
+            .map_err(|e| format!("read {} failed: {}", entry.display(), e))?;
+        copied.push(entry.clone());
+        fs::write(&target, &source)
+            .map_err(|e| format!("write {} failed: {}", target.display(), e))?;
+        match fs::read(&target) {
+            Ok(written) if written == source => Ok(()),
+            _ => Err(format!("verifying {} failed", target.display())),
+        }
+    });
+    if result.is_err() {
+        remove_entries(to, &copied);
+    }
+    result
+}
+
+fn write_location(app: &AppHandle, new_dir: &Path) -> Result<(), String> {
+    let default = default_dir(app).ok_or("data dir unavailable")?;
+    let pointer = default.join(LOCATION_FILE);
+    if default
+        .canonicalize()
+        .is_ok_and(|default| default == new_dir)
+    {
+        return match fs::remove_file(&pointer) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("reset data location failed: {}", e))
+            }
+            _ => Ok(()),
+        };
+    }
+    fs::create_dir_all(&default).map_err(|e| format!("create dir failed: {}", e))?;
+    let json = serde_json::to_string_pretty(&Location {
+        path: new_dir.to_path_buf(),
+    })
+    .map_err(|e| e.to_string())?;
+    fs::write(&pointer, json).map_err(|e| format!("save data location failed: {}", e))
+}
+
+/// Move the store to `new_dir` and use it from now on, this launch and the
+/// next. Files are copied and verified before the switch and the originals
+/// removed only after it, so a failure at any step leaves the old directory
+/// in charge. Moving back to the OS default clears the override.
+pub fn move_to(app: &AppHandle, new_dir: &Path) -> Result<PathBuf, String> {
+    let state = app.state::<AppState>();
+    if state.safe_mode {
+        return Err("Data can't be moved in safe mode.".to_string());
+    }
+    read_only::check(&state)?;
+    store_lock::check(&state)?;
+    if !new_dir.is_absolute() {
+        return Err("The new data folder must be an absolute path.".to_string());
+    }
+    let old_dir = dir(app).ok_or("data dir unavailable")?;
+    fs::create_dir_all(new_dir).map_err(|e| format!("create dir failed: {}", e))?;
+    let new_dir = new_dir
+        .canonicalize()
+        .map_err(|e| format!("resolve path failed: {}", e))?;
+    let old_canonical = old_dir.canonicalize().unwrap_or_else(|_| old_dir.clone());
+    if new_dir == old_canonical {
+        return Ok(new_dir);
+    }
+    if new_dir.starts_with(&old_canonical) {
+        return Err("The new data folder can't be inside the current one.".to_string());
+    }
+    if STORE_FILES.iter().any(|file| new_dir.join(file).exists()) {
+        return Err(format!(
+            "{} already holds Upstand data; pick an empty folder.",
+            new_dir.display()
+        ));
+    }
+
+    // Flush both files so the copy is complete; the journal is emptied by
+    // the analytics write. Holding the journal lock keeps new events out
+    // until the switch is done.
+    save_config(app, &state)?;
+    save_analytics(app, &state)?;
+    let entries = {
+        let _journal = state.journal_lock.lock().unwrap();
+        let entries = store_entries(&old_dir);
+        copy_verified(&old_dir, &new_dir, &entries)?;
+        if let Err(e) = write_location(app, &new_dir) {
+            remove_entries(&new_dir, &entries);
+            return Err(e);
+        }
+        *state.data_dir.lock().unwrap() = Some(new_dir.clone());
+        entries
+    };
+    // Move the lock along; it only fails if another process got there first,
+    // and then that process is the one warned about as usual.
+    let _ = store_lock::acquire(app);
+    remove_entries(&old_dir, &entries);
+    Ok(new_dir)
+}
@@ -0,0 +1,136 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::{analytics_date, clock, day_start_ts, notify, now_ts, AppState};
+
+/// Why a due reminder wasn't put on screen right away.
+#[derive(Clone, Copy)]
+pub enum Suppression {
+    /// Held until screen sharing ends; counted as shown too if it then is.
+    ScreenSharing,
+    /// A standup was logged by hand just before; the interval restarted.
+    RecentStandup,
+}
+
+pub enum Outcome {
+    /// The countdown ran out.
+    Scheduled,
+    /// The reminder with this id reached the screen.
+    Shown(u64),
+    Suppressed(Suppression),
+    /// No reminder window could be built or rebuilt.
+    Failed,
+}
+
+#[derive(Clone, Default, Serialize, JsonSchema)]
+pub struct SuppressedCounts {
+    pub screen_sharing: u32,
+    pub recent_standup: u32,
+}
+
+/// Reminder delivery for the current analytics day. Paused time (holidays,
+/// weekends off, displays off) stops the countdown, so nothing comes due and
+/// nothing is counted then.
+#[derive(Clone, Default, Serialize, JsonSchema)]
+pub struct DeliveryStats {
+    /// "YYYY-MM-DD".
+    pub date: String,
+    /// Unix seconds the counts start from: the day start, or the app launch
+    /// if that was later.
+    pub since_ts: i64,
+    pub scheduled: u32,
+    pub shown: u32,
+    pub suppressed: SuppressedCounts,
+    pub failed: u32,
+    #[serde(skip)]
+    last_shown_id: u64,
+}
+
+impl DeliveryStats {
+    /// Due reminders that neither reached the screen nor were deliberately
+    /// skipped after a standup.
+    pub fn missed(&self) -> u32 {
+        self.scheduled
+            .saturating_sub(self.shown + self.suppressed.recent_standup)
+    }
+}
+
+fn roll(state: &AppState, stats: &mut DeliveryStats) {
+    let day_start_hour = *state.day_start_hour.lock().unwrap();
+    let today = analytics_date(clock::now(), day_start_hour);
+    let date = today.format("%Y-%m-%d").to_string();
+    if stats.date != date {
+        // An empty date means this is the first count since launch.
+        let since_ts = if stats.date.is_empty() {
+            now_ts()
+        } else {
+            day_start_ts(today, day_start_hour)
+        };
+        *stats = DeliveryStats {
+            date,
+            since_ts,
+            last_shown_id: stats.last_shown_id,
+            ..DeliveryStats::default()
+        };
+    }
+}
+
+pub fn record(state: &AppState, outcome: Outcome) {
+    let mut stats = state.delivery_stats.lock().unwrap();
+    roll(state, &mut stats);
+    match outcome {
+        Outcome::Scheduled => stats.scheduled += 1,
+        // Re-presenting the same reminder (after sharing ends, after a
+        // rebuilt window) is still one delivery.
+        Outcome::Shown(id) => {
+            if stats.last_shown_id != id {
+                stats.last_shown_id = id;
+                stats.shown += 1;
+            }
+        }
+        Outcome::Suppressed(Suppression::ScreenSharing) => stats.suppressed.screen_sharing += 1,
+        Outcome::Suppressed(Suppression::RecentStandup) => stats.suppressed.recent_standup += 1,
+        Outcome::Failed => stats.failed += 1,
+    }
+}
+
+/// Reminder windows that were missing or wouldn't show, this launch.
+#[derive(Default)]
+pub struct WindowFailures {
+    pub count: u32,
+    last_id: Option<u64>,
+}
+
+/// The reminder window is missing or wouldn't show: log it, count it, and
+/// nudge through a native notification instead so nobody sits on waiting
+/// for a card that never comes. A reminder that keeps failing on retry is
+/// reported once.
+pub fn window_failed(app: &AppHandle, reminder_id: Option<u64>, reason: &str) {
+    let state = app.state::<AppState>();
+    {
+        let mut failures = state.reminder_window_failures.lock().unwrap();
+        if reminder_id.is_some() && failures.last_id == reminder_id {
+            return;
+        }
+        failures.last_id = reminder_id;
+        failures.count += 1;
+    }
+    record(&state, Outcome::Failed);
+    eprintln!("upstand: reminder window failed: {}", reason);
+    let (title, body) = if *state.language.lock().unwrap() == "zh-CN" {
+        ("该站起来了", "提醒窗口无法显示，起身活动一下吧。")
+    } else {
+        (
+            "Time to stand up",
+            "The reminder window couldn't be shown, so here's your nudge instead.",
+        )
+    };
+    notify(app, title, body);
+}
+
+pub fn today(state: &AppState) -> DeliveryStats {
+    let mut stats = state.delivery_stats.lock().unwrap();
+    roll(state, &mut stats);
+    stats.clone()
+}
@@ -0,0 +1,215 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::{events, journal, prune_changes, AppState};
+
+pub const MIN_PHASE_MINUTES: u64 = 5;
+pub const MAX_PHASE_MINUTES: u64 = 240;
+
+/// For standing-desk owners: instead of one sitting interval, reminders
+/// alternate "sit for `sit_minutes`, then raise the desk" and "stand for
+/// `stand_minutes`, then lower it".
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeskModeConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_sit_minutes")]
+    pub sit_minutes: u64,
+    #[serde(default = "default_stand_minutes")]
+    pub stand_minutes: u64,
+}
+
+impl Default for DeskModeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sit_minutes: default_sit_minutes(),
+            stand_minutes: default_stand_minutes(),
+        }
+    }
+}
+
+fn default_sit_minutes() -> u64 {
+    45
+}
+
+fn default_stand_minutes() -> u64 {
+    15
+}
+
+pub fn sanitize(mut cfg: DeskModeConfig) -> DeskModeConfig {
+    cfg.sit_minutes = cfg.sit_minutes.clamp(MIN_PHASE_MINUTES, MAX_PHASE_MINUTES);
+    cfg.stand_minutes = cfg
+        .stand_minutes
+        .clamp(MIN_PHASE_MINUTES, MAX_PHASE_MINUTES);
+    cfg
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeskPosition {
+    Sitting,
+    Standing,
+}
+
+impl DeskPosition {
+    pub fn other(self) -> Self {
+        match self {
+            DeskPosition::Sitting => DeskPosition::Standing,
+            DeskPosition::Standing => DeskPosition::Sitting,
+        }
+    }
+}
+
+/// The desk was moved to `position` at `ts`; `None` when desk mode was
+/// turned off.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeskChange {
+    pub ts: i64,
+    pub position: Option<DeskPosition>,
+    /// Reported by a desk integration through `report_desk_position` rather
+    /// than by answering a reminder.
+    #[serde(default)]
+    pub from_desk: bool,
+}
+
+/// Desk time for one analytics period.
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DeskTotals {
+    pub sitting_secs: u64,
+    pub standing_secs: u64,
+    /// Times the desk went up.
+    pub raises: u32,
+}
+
+/// Sitting until the first move is logged.
+pub fn position(state: &AppState) -> DeskPosition {
+    state
+        .desk_log
+        .lock()
+        .unwrap()
+        .last()
+        .and_then(|change| change.position)
+        .unwrap_or(DeskPosition::Sitting)
+}
+
+/// Log desk mode being turned on (a cycle always starts sitting) or off.
+/// Returns whether it changed.
+pub fn set_enabled(state: &AppState, ts: i64, enabled: bool) -> bool {
+    let mut log = state.desk_log.lock().unwrap();
+    let was_on = log.last().is_some_and(|change| change.position.is_some());
+    if was_on == enabled {
+        return false;
+    }
+    log.push(DeskChange {
+        ts,
+        position: enabled.then_some(DeskPosition::Sitting),
+        from_desk: false,
+    });
+    true
+}
+
+pub fn enabled(state: &AppState) -> bool {
+    state.desk_mode.lock().unwrap().enabled
+}
+
+/// Desk mode is on and the desk is up: the countdown is for lowering it, so
+/// it counts no sitting time.
+pub fn standing(state: &AppState) -> bool {
+    enabled(state) && position(state) == DeskPosition::Standing
+}
+
+/// Length of the current phase, `None` outside desk mode.
+pub fn phase_secs(state: &AppState) -> Option<u64> {
+    let cfg = state.desk_mode.lock().unwrap().clone();
+    cfg.enabled.then(|| match position(state) {
+        DeskPosition::Sitting => cfg.sit_minutes * 60,
+        DeskPosition::Standing => cfg.stand_minutes * 60,
+    })
+}
+
+/// Where the next reminder asks the desk to go, `None` outside desk mode.
+pub fn next_move(state: &AppState) -> Option<DeskPosition> {
+    enabled(state).then(|| position(state).other())
+}
+
+/// Reminder text for desk mode, replacing the tip.
+pub fn reminder_text(state: &AppState) -> Option<String> {
+    let target = next_move(state)?;
+    let cfg = state.desk_mode.lock().unwrap().clone();
+    let zh = *state.reminder_language.lock().unwrap() == "zh-CN";
+    Some(match (target, zh) {
+        (DeskPosition::Standing, false) => format!(
+            "Raise your desk and stand for {} minutes.",
+            cfg.stand_minutes
+        ),
+        (DeskPosition::Standing, true) => format!("升起桌面，站立 {} 分钟。", cfg.stand_minutes),
+        (DeskPosition::Sitting, false) => format!(
+            "Lower your desk and sit for up to {} minutes.",
+            cfg.sit_minutes
+        ),
+        (DeskPosition::Sitting, true) => format!("降下桌面，坐下最多 {} 分钟。", cfg.sit_minutes),
+    })
+}
+
+/// Log a move to `to`, announcing it as "desk-position-changed" so a desk
+/// integration can follow. Returns false if the desk was already there.
+pub fn moved(
+    app: &AppHandle,
+    state: &AppState,
+    ts: i64,
+    to: DeskPosition,
+    from_desk: bool,
+) -> bool {
+    if position(state) == to {
+        return false;
+    }
+    let change = DeskChange {
+        ts,
+        position: Some(to),
+        from_desk,
+    };
+    state.desk_log.lock().unwrap().push(change.clone());
+    events::emit(app, "desk-position-changed", change);
+    events::analytics_updated(app);
+    journal::compact_soon(app);
+    true
+}
+
+pub fn prune(log: &mut Vec<DeskChange>, cutoff: i64) {
+    prune_changes(log, cutoff, |change| change.ts);
+}
+
+/// Time in each position between `start_ts` and `end_ts` (capped at `now`),
+/// counting segments whose start passes `keep`.
+pub fn totals(
+    log: &[DeskChange],
+    start_ts: i64,
+    end_ts: i64,
+    now: i64,
+    keep: &dyn Fn(i64) -> bool,
+) -> DeskTotals {
+    let end_ts = end_ts.min(now);
+    let mut totals = DeskTotals::default();
+    for (i, change) in log.iter().enumerate() {
+        let until = log.get(i + 1).map_or(end_ts, |next| next.ts.min(end_ts));
+        let from = change.ts.max(start_ts);
+        if until > from && keep(from) {
+            let secs = (until - from) as u64;
+            match change.position {
+                Some(DeskPosition::Sitting) => totals.sitting_secs += secs,
+                Some(DeskPosition::Standing) => totals.standing_secs += secs,
+                None => {}
+            }
+        }
+        if change.position == Some(DeskPosition::Standing)
+            && change.ts >= start_ts
+            && change.ts < end_ts
+            && keep(change.ts)
+        {
+            totals.raises += 1;
+        }
+    }
+    totals
+}
@@ -0,0 +1,36 @@
+use crate::AppState;
+
+/// Extra interval choices, for demos and manual testing.
+pub const INTERVAL_MINUTES: [u64; 2] = [1, 2];
+/// Anti-misclick guards are capped at these so a reminder can be answered
+/// right away.
+pub const MAX_ACK_GRACE_MS: u64 = 150;
+pub const MAX_STANDUP_COOLDOWN_SECS: u64 = 10;
+
+/// `--dev-intervals` on the command line.
+pub fn from_args(args: &[String]) -> bool {
+    args.iter().any(|arg| arg == "--dev-intervals")
+}
+
+/// Unlocked by the flag or the hidden `dev_intervals` config key.
+pub fn enabled(state: &AppState) -> bool {
+    *state.dev_intervals_from_args.lock().unwrap() || *state.dev_intervals.lock().unwrap()
+}
+
+pub fn ack_grace_ms(state: &AppState) -> u64 {
+    let configured = *state.ack_grace_ms.lock().unwrap();
+    if enabled(state) {
+        configured.min(MAX_ACK_GRACE_MS)
+    } else {
+        configured
+    }
+}
+
+pub fn standup_cooldown_secs(state: &AppState) -> u64 {
+    let configured = *state.standup_cooldown_secs.lock().unwrap();
+    if enabled(state) {
+        configured.min(MAX_STANDUP_COOLDOWN_SECS)
+    } else {
+        configured
+    }
+}
@@ -0,0 +1,109 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{
+    active_reminder_payload, build_analytics,
+    hooks::{self, HookEvent},
+    ActiveReminderPayload, AppState,
+};
+
+/// Analytics changes arriving within this window go out as one event.
+const ANALYTICS_COALESCE_MS: u64 = 250;
+
+/// Payload of `analytics-updated`.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct EventSeq {
+    pub seq: u64,
+}
+
+/// Payload of `reminder-fired`: the reminder as it was when the event was sent.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct ReminderFired {
+    pub seq: u64,
+    pub reminder: ActiveReminderPayload,
+}
+
+/// Payload of `reminder-ignored`.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct ReminderIgnored {
+    pub seq: u64,
+    pub reminder_id: u64,
+    /// Reminders ignored so far today, this one included.
+    pub ignored_today: u32,
+}
+
+/// Emit under the sequence lock so events reach listeners in sequence order
+/// no matter which thread sends them. Returns the event's sequence number;
+/// a frontend that sees a gap (or a newer `get_event_seq`) missed something
+/// and should refetch.
+fn emit_with<S: Serialize + Clone>(
+    app: &AppHandle,
+    event: &str,
+    payload: impl FnOnce(u64) -> S,
+) -> u64 {
+    let state = app.state::<AppState>();
+    let mut seq = state.event_seq.lock().unwrap();
+    *seq += 1;
+    let _ = app.emit(event, payload(*seq));
+    *seq
+}
+
+pub fn emit<S: Serialize + Clone>(app: &AppHandle, event: &str, payload: S) -> u64 {
+    emit_with(app, event, |_| payload)
+}
+
+pub fn last_seq(state: &AppState) -> u64 {
+    *state.event_seq.lock().unwrap()
+}
+
+/// The snapshot is taken inside the sequence lock, so it can't be older
+/// than a `refresh_tip` sent before it.
+pub fn reminder_fired(app: &AppHandle) -> u64 {
+    let mut fired = None;
+    let seq = emit_with(app, "reminder-fired", |seq| {
+        let reminder = active_reminder_payload(&app.state::<AppState>());
+        fired = Some((reminder.id, reminder.text.clone()));
+        ReminderFired { seq, reminder }
+    });
+    if let Some((id, text)) = fired {
+        hooks::dispatch(
+            app,
+            HookEvent::ReminderFired,
+            &[("reminder_id", id.to_string()), ("text", text)],
+        );
+    }
+    seq
+}
+
+/// The reminder went unanswered past the sedentary threshold and was logged
+/// as sitting. Call after the record is stored.
+pub fn reminder_ignored(app: &AppHandle, reminder_id: u64) -> u64 {
+    let ignored_today = build_analytics(&app.state::<AppState>()).ignored_reminders;
+    emit_with(app, "reminder-ignored", |seq| ReminderIgnored {
+        seq,
+        reminder_id,
+        ignored_today,
+    })
+}
+
+/// Coalesce bursts (e.g. a standup saving analytics and a goal update right
+/// after) into a single `analytics-updated`.
+pub fn analytics_updated(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    {
+        let mut pending = state.analytics_event_pending.lock().unwrap();
+        if *pending {
+            return;
+        }
+        *pending = true;
+    }
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(ANALYTICS_COALESCE_MS)).await;
+        let state = handle.state::<AppState>();
+        *state.analytics_event_pending.lock().unwrap() = false;
+        emit_with(&handle, "analytics-updated", |seq| EventSeq { seq });
+    });
+}
@@ -0,0 +1,129 @@
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::formatting::Formatter;
+use crate::{csv_field, data_dir, read_only, write_export, AnalyticsData, AppState};
+
+/// The totals of one export, remembered to compare the next one against.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ExportSnapshot {
+    pub exported_ts: i64,
+    pub period_start_ts: i64,
+    pub records: u32,
+    pub standups: u32,
+    pub sedentary: u32,
+    pub sitting_secs: u64,
+    pub skips: u32,
+}
+
+impl ExportSnapshot {
+    pub fn of(analytics: &AnalyticsData, exported_ts: i64) -> Self {
+        Self {
+            exported_ts,
+            period_start_ts: analytics.period_start_ts,
+            records: analytics.record_count,
+            standups: analytics.standup_sessions,
+            sedentary: analytics.sedentary_sessions,
+            sitting_secs: analytics.total_sitting_secs,
+            skips: analytics.skips,
+        }
+    }
+}
+
+/// The last export of each period, keyed by period.
+fn path(app: &AppHandle) -> Option<PathBuf> {
+    data_dir::dir(app).map(|dir| dir.join("export_snapshots.json"))
+}
+
+fn load(app: &AppHandle) -> BTreeMap<String, ExportSnapshot> {
+    path(app)
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn change(previous: u64, current: u64) -> String {
+    let delta = current as i64 - previous as i64;
+    if delta > 0 {
+        format!("+{}", delta)
+    } else {
+        delta.to_string()
+    }
+}
+
+/// How `current` differs from `previous`, as a CSV of
+/// `metric,previous,current,change`. `same_period` says whether both cover
+/// the same period, in which case the changes are records added since.
+pub fn diff_csv(previous: &ExportSnapshot, current: &ExportSnapshot, fmt: &Formatter) -> String {
+    let mut rows = vec!["metric,previous,current,change".to_string()];
+    let counts = [
+        ("records", previous.records, current.records),
+        ("standup_sessions", previous.standups, current.standups),
+        ("sedentary_sessions", previous.sedentary, current.sedentary),
+        ("skips", previous.skips, current.skips),
+    ];
+    for (metric, before, after) in counts {
+        rows.push(format!(
+            "{},{},{},{}",
+            metric,
+            before,
+            after,
+            change(before.into(), after.into())
+        ));
+    }
+    let (before, after) = (previous.sitting_secs / 60, current.sitting_secs / 60);
+    rows.push(format!(
+        "total_sitting_minutes,{},{},{}",
+        before,
+        after,
+        change(before, after)
+    ));
+    rows.push(format!(
+        "same_period,{},,",
+        previous.period_start_ts == current.period_start_ts
+    ));
+    if let Some(at) = Local.timestamp_opt(previous.exported_ts, 0).single() {
+        rows.push(format!(
+            "previous_export,{},,",
+            csv_field(&fmt.datetime(at))
+        ));
+    }
+    rows.join("\n")
+}
+
+/// Remember this export of `period` and, if `write` is set and there was an
+/// earlier one, write the changes since next to it as `<name>_diff.csv`.
+/// Returns the path of the diff.
+pub fn record(
+    app: &AppHandle,
+    state: &AppState,
+    period: &str,
+    current: ExportSnapshot,
+    export_path: &Path,
+    write: bool,
+) -> Result<Option<PathBuf>, String> {
+    let mut snapshots = load(app);
+    let previous = snapshots.insert(period.to_string(), current.clone());
+    if !state.safe_mode && !read_only::enabled(state) {
+        if let (Some(path), Ok(json)) = (path(app), serde_json::to_string_pretty(&snapshots)) {
+            if let Err(e) = data_dir::write(state, &path, json) {
+                eprintln!("saving export snapshots failed: {}", e);
+            }
+        }
+    }
+    let Some(previous) = previous.filter(|_| write) else {
+        return Ok(None);
+    };
+    let stem = export_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let diff_path = export_path.with_file_name(format!("{}_diff.csv", stem));
+    let csv = diff_csv(&previous, &current, &Formatter::for_exports(state));
+    write_export(state, &diff_path, csv)?;
+    Ok(Some(diff_path))
+}
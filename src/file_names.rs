@@ -0,0 +1,113 @@
+use chrono::{DateTime, Local};
+
+use crate::AppState;
+
+pub const MAX_TEMPLATE_CHARS: usize = 120;
+const TOKENS: [&str; 5] = ["app", "period", "date", "time", "type"];
+
+/// What an export holds, for the `{type}` token and the default names.
+#[derive(Clone, Copy)]
+pub enum ExportKind {
+    Analytics,
+    Heatmap,
+    RawEvents,
+    Settings,
+}
+
+impl ExportKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            ExportKind::Analytics => "analytics",
+            ExportKind::Heatmap => "heatmap",
+            ExportKind::RawEvents => "raw_events",
+            ExportKind::Settings => "settings",
+        }
+    }
+}
+
+/// Check that `template` only uses known `{tokens}` and has something in it;
+/// blank means the built-in names. Returns it trimmed.
+pub fn validate(template: &str) -> Result<String, String> {
+    let template = template.trim();
+    if template.chars().count() > MAX_TEMPLATE_CHARS {
+        return Err(format!(
+            "template is longer than {} characters",
+            MAX_TEMPLATE_CHARS
+        ));
+    }
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let after = &rest[open + 1..];
+        let close = after
+            .find('}')
+            .ok_or_else(|| "unclosed { in template".to_string())?;
+        let token = &after[..close];
+        if !TOKENS.contains(&token) {
+            return Err(format!("unknown token: {{{}}}", token));
+        }
+        rest = &after[close + 1..];
+    }
+    Ok(template.to_string())
+}
+
+/// The names exports had before templates; kept as the default so existing
+/// scripts keep working.
+fn builtin(kind: ExportKind, period: &str, stamp: &str) -> String {
+    match kind {
+        ExportKind::Analytics => format!("standby_{}_analytics_{}", period, stamp),
+        ExportKind::Heatmap => format!("standby_{}_heatmap_{}", period, stamp),
+        ExportKind::RawEvents => format!("standby_raw_events_{}", stamp),
+        ExportKind::Settings => format!("upstand_settings_{}", stamp),
+    }
+}
+
+/// Fill in a validated template. Anything a file system would reject is
+/// replaced with "_", so a token can't reach outside the export folder.
+pub fn render(
+    template: &str,
+    kind: ExportKind,
+    period: &str,
+    now: DateTime<Local>,
+    extension: &str,
+) -> String {
+    let stamp = now.format("%Y%m%d_%H%M%S").to_string();
+    let stem = if template.is_empty() {
+        builtin(kind, period, &stamp)
+    } else {
+        template
+            .replace("{app}", "upstand")
+            .replace("{period}", period)
+            .replace("{date}", &now.format("%Y%m%d").to_string())
+            .replace("{time}", &now.format("%H%M%S").to_string())
+            .replace("{type}", kind.as_str())
+    };
+    let stem: String = stem
+        .chars()
+        .map(|c| {
+            if c.is_control() || "/\\:*?\"<>|".contains(c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let stem = stem.trim_matches(|c: char| c == '.' || c.is_whitespace());
+    let stem = if stem.is_empty() {
+        builtin(kind, period, &stamp)
+    } else {
+        stem.to_string()
+    };
+    format!("{}.{}", stem, extension)
+}
+
+/// File name for an export made now with the configured template.
+pub fn for_export(
+    state: &AppState,
+    kind: ExportKind,
+    period: &str,
+    now: DateTime<Local>,
+    extension: &str,
+) -> String {
+    let template = state.export_filename_template.lock().unwrap().clone();
+    render(&template, kind, period, now, extension)
+}
@@ -0,0 +1,51 @@
+/// Whatever was in the foreground right before the reminder took focus.
+#[derive(Clone)]
+pub struct ForegroundWindow {
+    #[cfg(target_os = "windows")]
+    hwnd: isize,
+    #[cfg(target_os = "macos")]
+    pid: i32,
+}
+
+#[cfg(target_os = "windows")]
+pub fn capture_foreground() -> Option<ForegroundWindow> {
+    use windows_sys::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+    let hwnd = unsafe { GetForegroundWindow() };
+    (hwnd != 0).then_some(ForegroundWindow { hwnd })
+}
+
+#[cfg(target_os = "windows")]
+pub fn restore_foreground(previous: &ForegroundWindow) {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{IsWindow, SetForegroundWindow};
+    unsafe {
+        if IsWindow(previous.hwnd) != 0 {
+            SetForegroundWindow(previous.hwnd);
+        }
+    }
+}
+
+/// Asks NSWorkspace rather than System Events, so it neither blocks on a
+/// subprocess nor needs the Automation permission.
+#[cfg(target_os = "macos")]
+pub fn capture_foreground() -> Option<ForegroundWindow> {
+    use objc2_app_kit::NSWorkspace;
+    let app = NSWorkspace::sharedWorkspace().frontmostApplication()?;
+    let pid = app.processIdentifier();
+    (pid > 0).then_some(ForegroundWindow { pid })
+}
+
+#[cfg(target_os = "macos")]
+pub fn restore_foreground(previous: &ForegroundWindow) {
+    use objc2_app_kit::{NSApplicationActivationOptions, NSRunningApplication};
+    if let Some(app) = NSRunningApplication::runningApplicationWithProcessIdentifier(previous.pid) {
+        app.activateWithOptions(NSApplicationActivationOptions::empty());
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn capture_foreground() -> Option<ForegroundWindow> {
+    None
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn restore_foreground(_previous: &ForegroundWindow) {}
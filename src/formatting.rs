@@ -0,0 +1,114 @@
+use chrono::{DateTime, Datelike, Local, NaiveDate, Timelike};
+
+use crate::AppState;
+
+const EN_MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Formatting rules for one output: the UI language, optionally overridden
+/// by ISO 8601 for files that end up in spreadsheets.
+#[derive(Clone)]
+pub struct Formatter {
+    lang: String,
+    iso: bool,
+}
+
+impl Formatter {
+    pub fn new(lang: &str, iso: bool) -> Self {
+        Self {
+            lang: lang.to_string(),
+            iso,
+        }
+    }
+
+    /// Reports and notifications always follow the UI language.
+    pub fn for_reports(state: &AppState) -> Self {
+        Self::new(&state.language.lock().unwrap(), false)
+    }
+
+    /// Exports honour the `export_date_format` override.
+    pub fn for_exports(state: &AppState) -> Self {
+        let iso = *state.export_date_format.lock().unwrap() == "iso8601";
+        Self::new(&state.language.lock().unwrap(), iso)
+    }
+
+    fn zh(&self) -> bool {
+        self.lang == "zh-CN"
+    }
+
+    /// "2026-10-16", "Oct 16, 2026" or "2026年10月16日".
+    pub fn date(&self, date: NaiveDate) -> String {
+        if self.iso {
+            date.format("%Y-%m-%d").to_string()
+        } else if self.zh() {
+            format!("{}年{}月{}日", date.year(), date.month(), date.day())
+        } else {
+            format!(
+                "{} {}, {}",
+                EN_MONTHS[date.month0() as usize],
+                date.day(),
+                date.year()
+            )
+        }
+    }
+
+    /// "2026-10-16T18:05:00+02:00", "Oct 16, 2026 6:05 PM" or "2026年10月16日 18:05".
+    pub fn datetime(&self, dt: DateTime<Local>) -> String {
+        if self.iso {
+            return dt.format("%Y-%m-%dT%H:%M:%S%:z").to_string();
+        }
+        let date = self.date(dt.date_naive());
+        if self.zh() {
+            format!("{} {:02}:{:02}", date, dt.hour(), dt.minute())
+        } else {
+            let (pm, hour) = dt.hour12();
+            format!(
+                "{} {}:{:02} {}",
+                date,
+                hour,
+                dt.minute(),
+                if pm { "PM" } else { "AM" }
+            )
+        }
+    }
+
+    /// "PT4H5M", "4h05m" / "35min", or "4小时05分" / "35分钟".
+    pub fn duration(&self, secs: u64) -> String {
+        let minutes = secs / 60;
+        let (h, m) = (minutes / 60, minutes % 60);
+        if self.iso {
+            match (h, m) {
+                (0, m) => format!("PT{}M", m),
+                (h, 0) => format!("PT{}H", h),
+                (h, m) => format!("PT{}H{}M", h, m),
+            }
+        } else if self.zh() {
+            if h == 0 {
+                format!("{}分钟", m)
+            } else {
+                format!("{}小时{:02}分", h, m)
+            }
+        } else if h == 0 {
+            format!("{}min", m)
+        } else {
+            format!("{}h{:02}m", h, m)
+        }
+    }
+
+    /// Whole numbers with thousands separators; plain digits for ISO output.
+    pub fn count(&self, n: u64) -> String {
+        let digits = n.to_string();
+        if self.iso {
+            return digits;
+        }
+        let mut out = String::new();
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                out.push(',');
+            }
+            out.push(c);
+        }
+        out
+    }
+}
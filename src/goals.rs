@@ -0,0 +1,156 @@
+use chrono::{DateTime, Datelike, Local, NaiveDate, TimeZone};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::{analytics_date, holidays, AppState};
+
+const MAX_DAILY_STANDUPS: u32 = 48;
+
+/// "Stand up at least `daily_standups` times a day on at least `target_days` days this month."
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MonthlyGoal {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_daily_standups")]
+    pub daily_standups: u32,
+    #[serde(default = "default_target_days")]
+    pub target_days: u32,
+}
+
+impl Default for MonthlyGoal {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            daily_standups: default_daily_standups(),
+            target_days: default_target_days(),
+        }
+    }
+}
+
+fn default_daily_standups() -> u32 {
+    8
+}
+
+fn default_target_days() -> u32 {
+    20
+}
+
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct MonthlyGoalProgress {
+    /// "YYYY-MM".
+    pub month: String,
+    pub enabled: bool,
+    pub daily_standups: u32,
+    /// `target_days`, capped at the month's non-holiday, unmarked days.
+    pub target_days: u32,
+    pub holidays: u32,
+    /// Rest, sick and travel days this month; they count like holidays.
+    pub marked_days: u32,
+    pub days_met: u32,
+    pub days_in_month: u32,
+    /// Non-holiday days after today left in the month.
+    pub days_remaining: u32,
+    pub today_standups: u32,
+    pub attained: bool,
+    /// The target is still reachable if every remaining day (today included) is met.
+    pub on_track: bool,
+}
+
+pub fn sanitize(mut goal: MonthlyGoal) -> MonthlyGoal {
+    goal.daily_standups = goal.daily_standups.clamp(1, MAX_DAILY_STANDUPS);
+    goal.target_days = goal.target_days.clamp(1, 31);
+    goal
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|next| next.pred_opt())
+        .map(|last| last.day())
+        .unwrap_or(30)
+}
+
+pub fn progress(state: &AppState, now: DateTime<Local>) -> MonthlyGoalProgress {
+    let goal = state.monthly_goal.lock().unwrap().clone();
+    let day_start_hour = *state.day_start_hour.lock().unwrap();
+    let today = analytics_date(now, day_start_hour);
+
+    let mut per_day: HashMap<u32, u32> = HashMap::new();
+    for ts in state.standup_events.lock().unwrap().iter() {
+        if let Some(dt) = Local.timestamp_opt(*ts, 0).single() {
+            let day = analytics_date(dt, day_start_hour);
+            if day.year() == today.year() && day.month() == today.month() {
+                *per_day.entry(day.day()).or_default() += 1;
+            }
+        }
+    }
+
+    let days_met = per_day
+        .values()
+        .filter(|count| **count >= goal.daily_standups)
+        .count() as u32;
+    let today_standups = per_day.get(&today.day()).copied().unwrap_or(0);
+    let days_in_month = days_in_month(today.year(), today.month());
+
+    // Holidays and marked days never count against the goal: they shrink
+    // the target and aren't counted as days left to make it up.
+    let month_prefix = today.format("%Y-%m-").to_string();
+    let mut holiday_days: Vec<u32> = {
+        let cfg = state.holidays.lock().unwrap();
+        holidays::holidays_for_year(&cfg, today.year())
+            .into_iter()
+            .filter(|h| h.date.starts_with(&month_prefix))
+            .filter_map(|h| h.date.get(8..).and_then(|d| d.parse().ok()))
+            .collect()
+    };
+    let holidays = holiday_days.len() as u32;
+    let mut marked_days = 0;
+    for day in state.marked_days.lock().unwrap().iter() {
+        if day.date.starts_with(&month_prefix) {
+            marked_days += 1;
+            holiday_days.extend(day.date.get(8..).and_then(|d| d.parse::<u32>().ok()));
+        }
+    }
+    holiday_days.sort_unstable();
+    holiday_days.dedup();
+    let target_days = goal
+        .target_days
+        .min(days_in_month.saturating_sub(holiday_days.len() as u32))
+        .max(1);
+    let days_remaining = ((today.day() + 1)..=days_in_month)
+        .filter(|day| !holiday_days.contains(day))
+        .count() as u32;
+    let today_open =
+        u32::from(today_standups < goal.daily_standups && !holiday_days.contains(&today.day()));
+    let attained = days_met >= target_days;
+
+    MonthlyGoalProgress {
+        month: today.format("%Y-%m").to_string(),
+        enabled: goal.enabled,
+        daily_standups: goal.daily_standups,
+        target_days,
+        holidays,
+        marked_days,
+        days_met,
+        days_in_month,
+        days_remaining,
+        today_standups,
+        attained,
+        on_track: attained || days_met + today_open + days_remaining >= target_days,
+    }
+}
+
+/// Extra rows appended to the monthly CSV export.
+pub fn csv_rows(progress: &MonthlyGoalProgress) -> Vec<String> {
+    vec![
+        format!("goal_daily_standups,{},", progress.daily_standups),
+        format!("goal_target_days,{},", progress.target_days),
+        format!("goal_days_met,{},", progress.days_met),
+        format!("goal_attained,{},", progress.attained),
+    ]
+}
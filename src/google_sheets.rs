@@ -0,0 +1,246 @@
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use chrono::NaiveDate;
+use ring::{rand::SystemRandom, signature};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{fs, time::Duration};
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    analytics_date, build_analytics_between, clock, day_start_ts, leaderboard, read_only,
+    save_config, AppState,
+};
+
+const REQUEST_TIMEOUT_SECS: u64 = 15;
+const SCOPE: &str = "https://www.googleapis.com/auth/spreadsheets";
+/// A sheet left alone for longer only gets the most recent days.
+const MAX_BACKFILL_DAYS: i64 = 31;
+
+/// Appends one row per finished day to a Google Sheet the user shared with a
+/// service account. Only the key file's path is stored here.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GoogleSheetsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// The service account's JSON key, as downloaded from Google Cloud.
+    #[serde(default)]
+    pub key_file: Option<String>,
+    #[serde(default)]
+    pub spreadsheet_id: Option<String>,
+    /// Tab the rows go to.
+    #[serde(default = "default_sheet")]
+    pub sheet: String,
+    /// Last day a row was appended for.
+    #[serde(default)]
+    pub last_appended_date: Option<String>,
+}
+
+impl Default for GoogleSheetsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            key_file: None,
+            spreadsheet_id: None,
+            sheet: default_sheet(),
+            last_appended_date: None,
+        }
+    }
+}
+
+fn default_sheet() -> String {
+    "Upstand".to_string()
+}
+
+pub fn sanitize(mut cfg: GoogleSheetsConfig) -> GoogleSheetsConfig {
+    let trimmed = |value: Option<String>| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    cfg.key_file = trimmed(cfg.key_file);
+    cfg.spreadsheet_id = trimmed(cfg.spreadsheet_id);
+    cfg.sheet = cfg.sheet.trim().to_string();
+    if cfg.sheet.is_empty() {
+        cfg.sheet = default_sheet();
+    }
+    cfg
+}
+
+/// The fields of a service-account key file that are used.
+#[derive(Deserialize)]
+pub struct ServiceAccountKey {
+    pub client_email: String,
+    pub private_key: String,
+    #[serde(default = "default_token_uri")]
+    pub token_uri: String,
+}
+
+fn default_token_uri() -> String {
+    "https://oauth2.googleapis.com/token".to_string()
+}
+
+/// Read a key file and check its private key can sign.
+pub fn load_key(path: &str) -> Result<(ServiceAccountKey, signature::RsaKeyPair), String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("read key file failed: {}", e))?;
+    let key = serde_json::from_str::<ServiceAccountKey>(&text)
+        .map_err(|e| format!("not a service account key: {}", e))?;
+    let der: String = key
+        .private_key
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .collect();
+    let der = STANDARD
+        .decode(der.trim())
+        .map_err(|e| format!("private key isn't valid PEM: {}", e))?;
+    let pair = signature::RsaKeyPair::from_pkcs8(&der)
+        .map_err(|e| format!("private key rejected: {}", e))?;
+    Ok((key, pair))
+}
+
+/// A signed JWT asking for Sheets access, valid for an hour from `now`.
+pub fn assertion(
+    key: &ServiceAccountKey,
+    pair: &signature::RsaKeyPair,
+    now: i64,
+) -> Result<String, String> {
+    let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"RS256","typ":"JWT"}"#);
+    let claims = json!({
+        "iss": key.client_email,
+        "scope": SCOPE,
+        "aud": key.token_uri,
+        "iat": now,
+        "exp": now + 3600,
+    });
+    let claims = URL_SAFE_NO_PAD.encode(claims.to_string());
+    let message = format!("{}.{}", header, claims);
+    let mut sig = vec![0u8; pair.public().modulus_len()];
+    pair.sign(
+        &signature::RSA_PKCS1_SHA256,
+        &SystemRandom::new(),
+        message.as_bytes(),
+        &mut sig,
+    )
+    .map_err(|_| "signing failed".to_string())?;
+    Ok(format!("{}.{}", message, URL_SAFE_NO_PAD.encode(sig)))
+}
+
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+}
+
+fn access_token(agent: &ureq::Agent, key_file: &str) -> Result<String, String> {
+    #[derive(Deserialize)]
+    struct TokenResponse {
+        access_token: String,
+    }
+    let (key, pair) = load_key(key_file)?;
+    let jwt = assertion(&key, &pair, clock::now().timestamp())?;
+    agent
+        .post(&key.token_uri)
+        .send_form(&[
+            ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+            ("assertion", &jwt),
+        ])
+        .map_err(|e| format!("sign-in failed: {}", e))?
+        .into_json::<TokenResponse>()
+        .map(|t| t.access_token)
+        .map_err(|e| format!("invalid sign-in response: {}", e))
+}
+
+/// Percent-encode a sheet name for the request path.
+fn encode_path(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Finished days not yet in the sheet, oldest first: everything after
+/// `last` up to yesterday, or just yesterday on the first run.
+pub fn pending_days(last: Option<&str>, today: NaiveDate) -> Vec<NaiveDate> {
+    let Some(yesterday) = today.pred_opt() else {
+        return Vec::new();
+    };
+    let oldest = today - chrono::Duration::days(MAX_BACKFILL_DAYS);
+    let first = last
+        .and_then(|last| NaiveDate::parse_from_str(last, "%Y-%m-%d").ok())
+        .and_then(|last| last.succ_opt())
+        .unwrap_or(yesterday)
+        .max(oldest);
+    first
+        .iter_days()
+        .take_while(|day| *day <= yesterday)
+        .collect()
+}
+
+/// Date, standups, ignored reminders, sitting minutes, score.
+fn row(state: &AppState, date: NaiveDate) -> serde_json::Value {
+    let day_start_hour = *state.day_start_hour.lock().unwrap();
+    let start = day_start_ts(date, day_start_hour);
+    let end = date
+        .succ_opt()
+        .map(|next| day_start_ts(next, day_start_hour))
+        .unwrap_or(i64::MAX);
+    let analytics = build_analytics_between(state, start, end, &|_| true);
+    json!([
+        date.format("%Y-%m-%d").to_string(),
+        analytics.standup_sessions,
+        analytics.sedentary_sessions,
+        analytics.total_sitting_secs / 60,
+        leaderboard::daily_score(analytics.standup_sessions, analytics.sedentary_sessions),
+    ])
+}
+
+/// Whether the export loop should call [`append_pending`].
+pub fn is_due(state: &AppState) -> bool {
+    let cfg = state.google_sheets.lock().unwrap().clone();
+    let today = analytics_date(clock::now(), *state.day_start_hour.lock().unwrap());
+    cfg.enabled
+        && cfg.key_file.is_some()
+        && cfg.spreadsheet_id.is_some()
+        && !pending_days(cfg.last_appended_date.as_deref(), today).is_empty()
+}
+
+/// Append a row for every finished day not yet in the sheet. Blocks on the
+/// network; returns how many rows were added. Refused in read-only mode,
+/// where the cursor couldn't be saved.
+pub fn append_pending(app: &AppHandle) -> Result<u32, String> {
+    let state = app.state::<AppState>();
+    read_only::check(&state)?;
+    let cfg = state.google_sheets.lock().unwrap().clone();
+    let (Some(key_file), Some(spreadsheet_id)) = (cfg.key_file, cfg.spreadsheet_id) else {
+        return Err("Google Sheets isn't set up".to_string());
+    };
+    let today = analytics_date(clock::now(), *state.day_start_hour.lock().unwrap());
+    let days = pending_days(cfg.last_appended_date.as_deref(), today);
+    let Some(last) = days.last().copied() else {
+        return Ok(0);
+    };
+    let values: Vec<serde_json::Value> = days.iter().map(|day| row(&state, *day)).collect();
+    let agent = agent();
+    let token = access_token(&agent, &key_file)?;
+    let url = format!(
+        "https://sheets.googleapis.com/v4/spreadsheets/{}/values/{}:append",
+        encode_path(&spreadsheet_id),
+        encode_path(&cfg.sheet)
+    );
+    agent
+        .post(&url)
+        .query("valueInputOption", "USER_ENTERED")
+        .query("insertDataOption", "INSERT_ROWS")
+        .set("Authorization", &format!("Bearer {}", token))
+        .send_json(json!({ "values": values }))
+        .map_err(|e| format!("append failed: {}", e))?;
+    state.google_sheets.lock().unwrap().last_appended_date =
+        Some(last.format("%Y-%m-%d").to_string());
+    save_config(app, &state)?;
+    Ok(values.len() as u32)
+}
@@ -0,0 +1,363 @@
+use rand::RngCore;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path, time::Duration};
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    activity, analytics_date, clock, data_dir, day_start_ts, events, journal, now_ts, read_only,
+    timers, AppState, ReminderRecord,
+};
+
+const POLL_SECS: u64 = 30;
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+/// Input older than this means the user has left the device.
+const AWAY_SECS: u64 = 120;
+/// A countdown is only carried over if the other device was used this
+/// recently; after a longer break the user wasn't sitting all along.
+const MAX_GAP_SECS: i64 = 15 * 60;
+const FILE_PREFIX: &str = "upstand-handoff-";
+const DEVICE_ID_FILE: &str = "device_id";
+
+/// Hands the countdown and today's events between the user's own machines,
+/// through a folder they all reach (a synced folder or a network share) or
+/// a relay the user runs. The relay only needs two routes: `PUT
+/// {relay_url}/devices/{id}` storing a snapshot and `GET
+/// {relay_url}/devices` listing them all.
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct HandoffConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub folder: Option<String>,
+    #[serde(default)]
+    pub relay_url: Option<String>,
+    /// Sent as a bearer token to the relay.
+    #[serde(default)]
+    pub relay_token: Option<String>,
+    /// Shown on the other devices; the host name if unset.
+    #[serde(default)]
+    pub device_name: Option<String>,
+}
+
+pub fn sanitize(mut cfg: HandoffConfig) -> HandoffConfig {
+    let trimmed = |value: Option<String>| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    cfg.folder = trimmed(cfg.folder);
+    cfg.relay_url = trimmed(cfg.relay_url).map(|url| url.trim_end_matches('/').to_string());
+    cfg.relay_token = trimmed(cfg.relay_token);
+    cfg.device_name = trimmed(cfg.device_name);
+    cfg
+}
+
+pub fn validate(cfg: &HandoffConfig) -> Result<(), String> {
+    if cfg.folder.is_some() && cfg.relay_url.is_some() {
+        return Err("choose either a shared folder or a relay".to_string());
+    }
+    if let Some(url) = &cfg.relay_url {
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err("relay URL must be http(s)".to_string());
+        }
+    }
+    if cfg.enabled && cfg.folder.is_none() && cfg.relay_url.is_none() {
+        return Err("set a shared folder or a relay first".to_string());
+    }
+    Ok(())
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SittingRecord {
+    pub ts: i64,
+    pub duration_secs: u64,
+}
+
+/// What one device publishes about itself.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HandoffSnapshot {
+    pub device_id: String,
+    pub device_name: String,
+    pub ts: i64,
+    /// Last keyboard or mouse input there.
+    pub last_active_ts: i64,
+    /// Analytics date the events belong to, "YYYY-MM-DD".
+    pub date: String,
+    pub elapsed_secs: u64,
+    pub standups: Vec<i64>,
+    pub sitting: Vec<SittingRecord>,
+}
+
+/// Another device, as of its last snapshot. Payload of `handoff-received`
+/// when the countdown was taken over from it.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct HandoffPeer {
+    pub device_id: String,
+    pub device_name: String,
+    pub ts: i64,
+    pub last_active_ts: i64,
+    pub elapsed_secs: u64,
+}
+
+impl From<&HandoffSnapshot> for HandoffPeer {
+    fn from(snapshot: &HandoffSnapshot) -> Self {
+        Self {
+            device_id: snapshot.device_id.clone(),
+            device_name: snapshot.device_name.clone(),
+            ts: snapshot.ts,
+            last_active_ts: snapshot.last_active_ts,
+            elapsed_secs: snapshot.elapsed_secs,
+        }
+    }
+}
+
+/// A random id kept in the data directory rather than the config, so a
+/// settings export imported on another machine doesn't clone it.
+fn device_id(app: &AppHandle) -> Option<String> {
+    let path = data_dir::dir(app)?.join(DEVICE_ID_FILE);
+    if let Some(id) = fs::read_to_string(&path)
+        .ok()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+    {
+        return Some(id);
+    }
+    let mut bytes = [0u8; 8];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    let id: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    data_dir::write(&app.state::<AppState>(), &path, &id).ok()?;
+    Some(id)
+}
+
+fn host_name() -> String {
+    ["COMPUTERNAME", "HOSTNAME"]
+        .iter()
+        .find_map(|key| std::env::var(key).ok().filter(|v| !v.is_empty()))
+        .unwrap_or_else(|| "Upstand".to_string())
+}
+
+fn today_bounds(state: &AppState) -> (String, i64) {
+    let day_start_hour = *state.day_start_hour.lock().unwrap();
+    let today = analytics_date(clock::now(), day_start_hour);
+    (
+        today.format("%Y-%m-%d").to_string(),
+        day_start_ts(today, day_start_hour),
+    )
+}
+
+fn snapshot(state: &AppState, cfg: &HandoffConfig, device_id: &str) -> HandoffSnapshot {
+    let (date, start) = today_bounds(state);
+    HandoffSnapshot {
+        device_id: device_id.to_string(),
+        device_name: cfg.device_name.clone().unwrap_or_else(host_name),
+        ts: now_ts(),
+        last_active_ts: *state.handoff_last_active.lock().unwrap(),
+        date,
+        elapsed_secs: *state.elapsed.lock().unwrap(),
+        standups: state
+            .standup_events
+            .lock()
+            .unwrap()
+            .iter()
+            .copied()
+            .filter(|ts| *ts >= start)
+            .collect(),
+        sitting: state
+            .reminder_events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|record| record.ts >= start)
+            .map(|record| SittingRecord {
+                ts: record.ts,
+                duration_secs: record.duration_secs,
+            })
+            .collect(),
+    }
+}
+
+/// Add the events of `peers` this device doesn't have yet. Returns how many
+/// were added.
+pub fn merge(
+    standups: &mut Vec<i64>,
+    sitting: &mut Vec<ReminderRecord>,
+    peers: &[HandoffSnapshot],
+) -> usize {
+    let mut added = 0;
+    for peer in peers {
+        for ts in &peer.standups {
+            if !standups.contains(ts) {
+                standups.push(*ts);
+                added += 1;
+            }
+        }
+        for record in &peer.sitting {
+            if !sitting.iter().any(|own| own.ts == record.ts) {
+                sitting.push(ReminderRecord {
+                    ts: record.ts,
+                    duration_secs: record.duration_secs,
+                });
+                added += 1;
+            }
+        }
+    }
+    if added > 0 {
+        standups.sort_unstable();
+        sitting.sort_by_key(|record| record.ts);
+    }
+    added
+}
+
+/// The peer whose countdown to take over on arriving at this device: the
+/// one used last, if that was after this device and not too long ago.
+pub fn pick_takeover(
+    peers: &[HandoffSnapshot],
+    own_last_active: i64,
+    now: i64,
+) -> Option<&HandoffSnapshot> {
+    peers
+        .iter()
+        .max_by_key(|peer| peer.last_active_ts)
+        .filter(|peer| peer.last_active_ts > own_last_active)
+        .filter(|peer| now - peer.last_active_ts <= MAX_GAP_SECS)
+}
+
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+}
+
+fn with_token(request: ureq::Request, cfg: &HandoffConfig) -> ureq::Request {
+    match &cfg.relay_token {
+        Some(token) => request.set("Authorization", &format!("Bearer {}", token)),
+        None => request,
+    }
+}
+
+fn publish(cfg: &HandoffConfig, own: &HandoffSnapshot) -> Result<(), String> {
+    if let Some(folder) = &cfg.folder {
+        let path = Path::new(folder).join(format!("{}{}.json", FILE_PREFIX, own.device_id));
+        let json = serde_json::to_string(own).map_err(|e| e.to_string())?;
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        fs::write(&tmp, json).map_err(|e| format!("write failed: {}", e))?;
+        return fs::rename(&tmp, &path).map_err(|e| format!("rename failed: {}", e));
+    }
+    if let Some(url) = &cfg.relay_url {
+        let url = format!("{}/devices/{}", url, own.device_id);
+        with_token(agent().put(&url), cfg)
+            .send_json(own)
+            .map_err(|e| format!("relay upload failed: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Every other device's snapshot for `date`.
+fn fetch(cfg: &HandoffConfig, own_id: &str, date: &str) -> Result<Vec<HandoffSnapshot>, String> {
+    let all: Vec<HandoffSnapshot> = if let Some(folder) = &cfg.folder {
+        fs::read_dir(folder)
+            .map_err(|e| format!("read folder failed: {}", e))?
+            .flatten()
+            .filter(|entry| {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                name.starts_with(FILE_PREFIX) && name.ends_with(".json")
+            })
+            .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+            .filter_map(|text| serde_json::from_str(&text).ok())
+            .collect()
+    } else if let Some(url) = &cfg.relay_url {
+        with_token(agent().get(&format!("{}/devices", url)), cfg)
+            .call()
+            .map_err(|e| format!("relay download failed: {}", e))?
+            .into_json()
+            .map_err(|e| format!("invalid relay response: {}", e))?
+    } else {
+        Vec::new()
+    };
+    Ok(all
+        .into_iter()
+        .filter(|peer| peer.device_id != own_id && peer.date == date)
+        .collect())
+}
+
+/// One round: note this device's activity, pull in the other devices'
+/// events, take over the countdown if the user just `arrived` from one of
+/// them, then publish this device's snapshot. Blocks on file or network IO.
+fn sync(app: &AppHandle, idle_secs: Option<u64>, arrived: bool) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let cfg = state.handoff.lock().unwrap().clone();
+    let own_id = device_id(app).ok_or_else(|| "app data directory unavailable".to_string())?;
+    let now = now_ts();
+    let previous_active = *state.handoff_last_active.lock().unwrap();
+    if let Some(idle) = idle_secs.filter(|idle| *idle < AWAY_SECS) {
+        *state.handoff_last_active.lock().unwrap() = now - idle as i64;
+    } else if idle_secs.is_none() {
+        *state.handoff_last_active.lock().unwrap() = now;
+    }
+
+    let (date, _) = today_bounds(&state);
+    let peers = fetch(&cfg, &own_id, &date)?;
+    let added = {
+        let mut standups = state.standup_events.lock().unwrap();
+        let mut sitting = state.reminder_events.lock().unwrap();
+        merge(&mut standups, &mut sitting, &peers)
+    };
+    if added > 0 {
+        events::analytics_updated(app);
+        journal::compact_soon(app);
+    }
+    if arrived && !*state.reminder_visible.lock().unwrap() {
+        if let Some(peer) = pick_takeover(&peers, previous_active, now) {
+            *state.elapsed.lock().unwrap() = peer.elapsed_secs;
+            timers::wake(&state);
+            events::emit(app, "handoff-received", HandoffPeer::from(peer));
+        }
+    }
+    *state.handoff_peers.lock().unwrap() = peers.iter().map(HandoffPeer::from).collect();
+
+    publish(&cfg, &snapshot(&state, &cfg, &own_id))
+}
+
+/// Sync every `POLL_SECS` while enabled. Arriving means input after being
+/// away, or the first round after launch or enabling; without input
+/// detection only the latter counts. A failure is reported once until it
+/// works again.
+pub async fn run(app: AppHandle) {
+    let mut was_active = false;
+    let mut failed: Option<String> = None;
+    loop {
+        let enabled = {
+            let state = app.state::<AppState>();
+            let cfg = state.handoff.lock().unwrap();
+            cfg.enabled && !read_only::enabled(&state)
+        };
+        if enabled {
+            let idle = tauri::async_runtime::spawn_blocking(activity::seconds_since_input)
+                .await
+                .ok()
+                .flatten();
+            let active = idle.is_none_or(|idle| idle < AWAY_SECS);
+            let handle = app.clone();
+            let result = tauri::async_runtime::spawn_blocking(move || {
+                sync(&handle, idle, active && !was_active)
+            })
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()));
+            match result {
+                Ok(()) => failed = None,
+                Err(e) if failed.as_ref() != Some(&e) => {
+                    eprintln!("handoff: {}", e);
+                    failed = Some(e);
+                }
+                Err(_) => {}
+            }
+            was_active = active;
+        } else {
+            was_active = false;
+        }
+        clock::sleep(Duration::from_secs(POLL_SECS)).await;
+    }
+}
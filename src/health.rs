@@ -0,0 +1,105 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{image::Image, AppHandle, Emitter, Manager};
+
+use crate::delivery::{self, DeliveryStats};
+use crate::{now_ts, read_only, timers, AppState};
+
+const HEALTH_POLL_SECS: u64 = 10;
+
+/// Outcome of the most recent config/analytics writes.
+#[derive(Clone, Default, Serialize, JsonSchema)]
+pub struct StorageStatus {
+    pub last_write_ts: Option<i64>,
+    pub last_error: Option<String>,
+    pub last_error_ts: Option<i64>,
+}
+
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct Health {
+    /// The timer loop is ticking and the last write succeeded.
+    pub healthy: bool,
+    /// Unix seconds of the timer loop's last completed tick.
+    pub last_tick_ts: i64,
+    pub heartbeat_age_secs: u64,
+    pub timer_alive: bool,
+    /// How often the watchdog had to restart the timer loop this launch.
+    pub timer_restarts: u32,
+    pub storage: StorageStatus,
+    pub safe_mode: bool,
+    pub read_only: bool,
+    /// Today's reminders: due, shown, held back, or lost to a missing window.
+    pub delivery: DeliveryStats,
+    /// Reminders this launch whose window was missing or wouldn't show; each
+    /// fell back to a notification.
+    pub reminder_window_failures: u32,
+}
+
+pub fn record_write(state: &AppState, path: &std::path::Path, result: std::io::Result<()>) {
+    let mut status = state.storage_status.lock().unwrap();
+    match result {
+        Ok(()) => {
+            status.last_write_ts = Some(now_ts());
+            status.last_error = None;
+        }
+        Err(e) => {
+            status.last_error = Some(format!("write {} failed: {}", path.display(), e));
+            status.last_error_ts = Some(now_ts());
+        }
+    }
+}
+
+pub fn check(state: &AppState) -> Health {
+    let heartbeat_age_secs = state.timer_heartbeat.lock().unwrap().elapsed().as_secs();
+    // The loop may sleep for a long time while nothing is due; it's only
+    // dead once it misses its own planned wakeup.
+    let timer_alive = timers::overdue(state).as_secs() <= timers::WATCHDOG_STALE_SECS;
+    let storage = state.storage_status.lock().unwrap().clone();
+    Health {
+        healthy: timer_alive && storage.last_error.is_none(),
+        last_tick_ts: now_ts() - heartbeat_age_secs as i64,
+        heartbeat_age_secs,
+        timer_alive,
+        timer_restarts: *state.timer_restarts.lock().unwrap(),
+        storage,
+        safe_mode: state.safe_mode,
+        read_only: read_only::enabled(state),
+        delivery: delivery::today(state),
+        reminder_window_failures: state.reminder_window_failures.lock().unwrap().count,
+    }
+}
+
+/// The normal tray icon with an amber badge in the bottom-right quarter.
+pub fn warning_icon(base: &Image<'_>) -> Image<'static> {
+    let (width, height) = (base.width(), base.height());
+    let mut rgba = base.rgba().to_vec();
+    let radius = width.min(height) as f32 / 4.0;
+    let (cx, cy) = (width as f32 - radius, height as f32 - radius);
+    for y in 0..height {
+        for x in 0..width {
+            let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+            if dx * dx + dy * dy <= radius * radius {
+                let i = ((y * width + x) * 4) as usize;
+                rgba[i..i + 4].copy_from_slice(&[0xf5, 0x9e, 0x0b, 0xff]);
+            }
+        }
+    }
+    Image::new(&rgba, width, height).to_owned()
+}
+
+/// Announce the timer heartbeat going stale or recovering; the tray icon
+/// service badges the icon meanwhile.
+pub async fn monitor(app: AppHandle) {
+    let mut stale = false;
+    loop {
+        tokio::time::sleep(Duration::from_secs(HEALTH_POLL_SECS)).await;
+        let health = check(&app.state::<AppState>());
+        let now_stale = !health.timer_alive;
+        if now_stale == stale {
+            continue;
+        }
+        stale = now_stale;
+        let _ = app.emit("health-changed", health);
+    }
+}
@@ -0,0 +1,257 @@
+use chrono::{Datelike, Duration as ChronoDuration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// Regions with bundled holiday rules. Lunar-calendar holidays (e.g. Spring
+/// Festival) aren't computed; import an ICS file for those.
+pub const SUPPORTED_REGIONS: [&str; 4] = ["US", "GB", "DE", "CN"];
+const MAX_CUSTOM_HOLIDAYS: usize = 1000;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Holiday {
+    /// "YYYY-MM-DD".
+    pub date: String,
+    pub name: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HolidayConfig {
+    /// One of `SUPPORTED_REGIONS`, or `None` for imported days only.
+    #[serde(default)]
+    pub region: Option<String>,
+    /// Hold reminders on holidays.
+    #[serde(default = "default_pause_reminders")]
+    pub pause_reminders: bool,
+    /// Days imported from ICS files.
+    #[serde(default)]
+    pub custom: Vec<Holiday>,
+}
+
+impl Default for HolidayConfig {
+    fn default() -> Self {
+        Self {
+            region: None,
+            pause_reminders: default_pause_reminders(),
+            custom: Vec::new(),
+        }
+    }
+}
+
+fn default_pause_reminders() -> bool {
+    true
+}
+
+pub fn sanitize(mut cfg: HolidayConfig) -> HolidayConfig {
+    cfg.region = cfg
+        .region
+        .map(|r| r.trim().to_uppercase())
+        .filter(|r| SUPPORTED_REGIONS.contains(&r.as_str()));
+    cfg.custom
+        .retain(|h| NaiveDate::parse_from_str(&h.date, "%Y-%m-%d").is_ok());
+    cfg.custom.sort_by(|a, b| a.date.cmp(&b.date));
+    cfg.custom.dedup_by(|a, b| a.date == b.date);
+    cfg.custom.truncate(MAX_CUSTOM_HOLIDAYS);
+    cfg
+}
+
+fn date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+/// The `n`th (1-based) `weekday` of a month, or the last one when `n` is 0.
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: u32) -> Option<NaiveDate> {
+    if n == 0 {
+        let next_month = if month == 12 {
+            date(year + 1, 1, 1)
+        } else {
+            date(year, month + 1, 1)
+        }?;
+        let mut day = next_month.pred_opt()?;
+        while day.weekday() != weekday {
+            day = day.pred_opt()?;
+        }
+        return Some(day);
+    }
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, n as u8)
+}
+
+/// Western Easter Sunday (anonymous Gregorian algorithm).
+fn easter(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    date(year, month as u32, day as u32)
+}
+
+fn regional(region: &str, year: i32) -> Vec<(Option<NaiveDate>, &'static str)> {
+    let easter_offset = |days: i64| easter(year).map(|e| e + ChronoDuration::days(days));
+    match region {
+        "US" => vec![
+            (date(year, 1, 1), "New Year's Day"),
+            (
+                nth_weekday(year, 1, Weekday::Mon, 3),
+                "Martin Luther King Jr. Day",
+            ),
+            (nth_weekday(year, 2, Weekday::Mon, 3), "Presidents' Day"),
+            (nth_weekday(year, 5, Weekday::Mon, 0), "Memorial Day"),
+            (date(year, 6, 19), "Juneteenth"),
+            (date(year, 7, 4), "Independence Day"),
+            (nth_weekday(year, 9, Weekday::Mon, 1), "Labor Day"),
+            (nth_weekday(year, 11, Weekday::Thu, 4), "Thanksgiving"),
+            (date(year, 12, 25), "Christmas Day"),
+        ],
+        "GB" => vec![
+            (date(year, 1, 1), "New Year's Day"),
+            (easter_offset(-2), "Good Friday"),
+            (easter_offset(1), "Easter Monday"),
+            (
+                nth_weekday(year, 5, Weekday::Mon, 1),
+                "Early May bank holiday",
+            ),
+            (nth_weekday(year, 5, Weekday::Mon, 0), "Spring bank holiday"),
+            (nth_weekday(year, 8, Weekday::Mon, 0), "Summer bank holiday"),
+            (date(year, 12, 25), "Christmas Day"),
+            (date(year, 12, 26), "Boxing Day"),
+        ],
+        "DE" => vec![
+            (date(year, 1, 1), "Neujahr"),
+            (easter_offset(-2), "Karfreitag"),
+            (easter_offset(1), "Ostermontag"),
+            (date(year, 5, 1), "Tag der Arbeit"),
+            (easter_offset(39), "Christi Himmelfahrt"),
+            (easter_offset(50), "Pfingstmontag"),
+            (date(year, 10, 3), "Tag der Deutschen Einheit"),
+            (date(year, 12, 25), "1. Weihnachtstag"),
+            (date(year, 12, 26), "2. Weihnachtstag"),
+        ],
+        "CN" => vec![
+            (date(year, 1, 1), "元旦"),
+            (date(year, 5, 1), "劳动节"),
+            (date(year, 10, 1), "国庆节"),
+            (date(year, 10, 2), "国庆节"),
+            (date(year, 10, 3), "国庆节"),
+        ],
+        _ => Vec::new(),
+    }
+}
+
+/// Every holiday in `year`: bundled regional days plus imported ones.
+pub fn holidays_for_year(cfg: &HolidayConfig, year: i32) -> Vec<Holiday> {
+    let mut days: Vec<Holiday> = cfg
+        .region
+        .as_deref()
+        .map(|region| regional(region, year))
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(day, name)| {
+            day.map(|d| Holiday {
+                date: d.format("%Y-%m-%d").to_string(),
+                name: name.to_string(),
+            })
+        })
+        .collect();
+    let prefix = format!("{:04}-", year);
+    days.extend(
+        cfg.custom
+            .iter()
+            .filter(|h| h.date.starts_with(&prefix))
+            .cloned(),
+    );
+    days.sort_by(|a, b| a.date.cmp(&b.date));
+    days.dedup_by(|a, b| a.date == b.date);
+    days
+}
+
+pub fn holiday_on(cfg: &HolidayConfig, day: NaiveDate) -> Option<Holiday> {
+    let key = day.format("%Y-%m-%d").to_string();
+    holidays_for_year(cfg, day.year())
+        .into_iter()
+        .find(|h| h.date == key)
+}
+
+fn parse_ics_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
+
+/// All-day (and timed) `VEVENT`s from an ICS calendar. Multi-day all-day
+/// events expand to one entry per day; `DTEND` is exclusive per RFC 5545.
+pub fn parse_ics(contents: &str) -> Vec<Holiday> {
+    // Undo line folding: continuation lines start with a space or tab.
+    let mut lines: Vec<String> = Vec::new();
+    for raw in contents.lines() {
+        if let Some(rest) = raw.strip_prefix([' ', '\t']) {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(rest);
+                continue;
+            }
+        }
+        lines.push(raw.trim_end_matches('\r').to_string());
+    }
+
+    let mut holidays = Vec::new();
+    let mut start: Option<NaiveDate> = None;
+    let mut end: Option<NaiveDate> = None;
+    let mut summary = String::new();
+    for line in lines {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let name = key.split(';').next().unwrap_or("");
+        match name {
+            "BEGIN" if value == "VEVENT" => {
+                start = None;
+                end = None;
+                summary.clear();
+            }
+            "DTSTART" => start = parse_ics_date(value),
+            "DTEND" => end = parse_ics_date(value),
+            "SUMMARY" => summary = value.replace("\\,", ",").replace("\\;", ";"),
+            "END" if value == "VEVENT" => {
+                let Some(first) = start else {
+                    continue;
+                };
+                let last = end
+                    .and_then(|e| e.pred_opt())
+                    .filter(|e| *e >= first)
+                    .unwrap_or(first);
+                let mut day = first;
+                while day <= last && holidays.len() < MAX_CUSTOM_HOLIDAYS {
+                    holidays.push(Holiday {
+                        date: day.format("%Y-%m-%d").to_string(),
+                        name: if summary.is_empty() {
+                            "Holiday".to_string()
+                        } else {
+                            summary.clone()
+                        },
+                    });
+                    let Some(next) = day.succ_opt() else {
+                        break;
+                    };
+                    day = next;
+                }
+            }
+            _ => {}
+        }
+    }
+    holidays
+}
+
+pub fn import_ics_file(path: &str) -> Result<Vec<Holiday>, String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("read failed: {}", e))?;
+    let holidays = parse_ics(&contents);
+    if holidays.is_empty() {
+        return Err("no events found in calendar".to_string());
+    }
+    Ok(holidays)
+}
@@ -0,0 +1,224 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, process::Stdio, time::Duration};
+use tauri::{AppHandle, Manager};
+
+use crate::{data_dir, events, now_ts, read_only, AppState};
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+pub const MAX_HOOKS: usize = 16;
+pub const MAX_TIMEOUT_SECS: u64 = 60;
+/// Runs kept for `get_hook_runs`; they aren't persisted.
+const MAX_RUNS: usize = 50;
+/// Output kept per run, stdout and stderr together.
+const MAX_OUTPUT_BYTES: usize = 4096;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookEvent {
+    ReminderFired,
+    StandupLogged,
+    DayRolledOver,
+}
+
+impl HookEvent {
+    pub fn name(self) -> &'static str {
+        match self {
+            HookEvent::ReminderFired => "reminder-fired",
+            HookEvent::StandupLogged => "standup-logged",
+            HookEvent::DayRolledOver => "day-rolled-over",
+        }
+    }
+}
+
+/// A program to run when `event` happens. It is started directly, not
+/// through a shell, so a placeholder's value is always one argument however
+/// it is quoted. Placeholders look like `{ts}`; see [`variables`].
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HookConfig {
+    pub event: HookEvent,
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_timeout_secs() -> u64 {
+    10
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Hooks without a program are dropped, and only the first [`MAX_HOOKS`]
+/// are kept.
+pub fn sanitize(hooks: Vec<HookConfig>) -> Vec<HookConfig> {
+    hooks
+        .into_iter()
+        .filter_map(|mut hook| {
+            hook.program = hook.program.trim().to_string();
+            hook.timeout_secs = hook.timeout_secs.clamp(1, MAX_TIMEOUT_SECS);
+            (!hook.program.is_empty()).then_some(hook)
+        })
+        .take(MAX_HOOKS)
+        .collect()
+}
+
+/// One finished (or failed to start) hook.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct HookRun {
+    pub ts: i64,
+    pub event: HookEvent,
+    pub program: String,
+    /// `None` if it didn't start, timed out or was ended by a signal.
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub error: Option<String>,
+    /// The start of what it printed.
+    pub output: String,
+}
+
+/// The placeholders of `event`: `{event}` and `{ts}` everywhere, plus what
+/// the event carries.
+pub fn variables(event: HookEvent, ts: i64, extra: &[(&str, String)]) -> HashMap<String, String> {
+    let mut vars: HashMap<String, String> = extra
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.clone()))
+        .collect();
+    vars.insert("event".to_string(), event.name().to_string());
+    vars.insert("ts".to_string(), ts.to_string());
+    vars
+}
+
+/// Replace every known `{name}` in `arg`; unknown ones are left as written.
+pub fn expand(arg: &str, vars: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(arg.len());
+    let mut rest = arg;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        match after
+            .find('}')
+            .and_then(|close| vars.get(&after[..close]).map(|value| (close, value)))
+        {
+            Some((close, value)) => {
+                out.push_str(value);
+                rest = &after[close + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Start every enabled hook for `event` in the background. Nothing runs in
+/// safe mode or read-only mode, where a second instance would otherwise
+/// repeat the first one's side effects.
+pub fn dispatch(app: &AppHandle, event: HookEvent, extra: &[(&str, String)]) {
+    let state = app.state::<AppState>();
+    if state.safe_mode || read_only::enabled(&state) {
+        return;
+    }
+    let hooks: Vec<HookConfig> = state
+        .hooks
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|hook| hook.enabled && hook.event == event)
+        .cloned()
+        .collect();
+    if hooks.is_empty() {
+        return;
+    }
+    let ts = now_ts();
+    let vars = variables(event, ts, extra);
+    for hook in hooks {
+        let handle = app.clone();
+        let args: Vec<String> = hook.args.iter().map(|arg| expand(arg, &vars)).collect();
+        tauri::async_runtime::spawn(async move {
+            let run = run(&handle, &hook, args, ts).await;
+            if let Some(error) = &run.error {
+                eprintln!("hook {}: {}", hook.program, error);
+            }
+            {
+                let state = handle.state::<AppState>();
+                let mut runs = state.hook_runs.lock().unwrap();
+                runs.push(run.clone());
+                let excess = runs.len().saturating_sub(MAX_RUNS);
+                runs.drain(..excess);
+            }
+            events::emit(&handle, "hook-finished", run);
+        });
+    }
+}
+
+/// Start the program with no shell, an environment holding only what's
+/// needed to find and start programs, no stdin and the data directory as
+/// working directory, and kill it once `timeout_secs` runs out.
+async fn run(app: &AppHandle, hook: &HookConfig, args: Vec<String>, ts: i64) -> HookRun {
+    let mut result = HookRun {
+        ts,
+        event: hook.event,
+        program: hook.program.clone(),
+        exit_code: None,
+        timed_out: false,
+        error: None,
+        output: String::new(),
+    };
+    let mut command = tokio::process::Command::new(&hook.program);
+    command
+        .args(args)
+        .env_clear()
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    for key in ["PATH", "HOME", "LANG", "SYSTEMROOT", "TEMP", "TMP"] {
+        if let Some(value) = std::env::var_os(key) {
+            command.env(key, value);
+        }
+    }
+    if let Some(dir) = data_dir::dir(app).filter(|dir| dir.is_dir()) {
+        command.current_dir(dir);
+    }
+    #[cfg(target_os = "windows")]
+    command.creation_flags(CREATE_NO_WINDOW);
+
+    let child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            result.error = Some(format!("start failed: {}", e));
+            return result;
+        }
+    };
+    let timeout = Duration::from_secs(hook.timeout_secs);
+    // Dropping the unfinished future drops the child, which kills it.
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(Ok(output)) => {
+            result.exit_code = output.status.code();
+            let mut printed = output.stdout;
+            printed.extend_from_slice(&output.stderr);
+            printed.truncate(MAX_OUTPUT_BYTES);
+            result.output = String::from_utf8_lossy(&printed).into_owned();
+            if !output.status.success() {
+                result.error = Some(format!("exited with {}", output.status));
+            }
+        }
+        Ok(Err(e)) => result.error = Some(format!("wait failed: {}", e)),
+        Err(_) => {
+            result.timed_out = true;
+            result.error = Some(format!("killed after {}s", hook.timeout_secs));
+        }
+    }
+    result
+}
@@ -0,0 +1,236 @@
+use chrono::{Local, NaiveDate, TimeZone};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+
+use crate::{now_ts, AppState, ReminderRecord, RETENTION_SECS};
+
+/// Workrave break ids and per-break counters, as written by its Statistics.cc.
+const WORKRAVE_REST_BREAK: usize = 1;
+const WORKRAVE_TAKEN: usize = 1;
+const WORKRAVE_NATURAL_TAKEN: usize = 2;
+const WORKRAVE_SKIPPED: usize = 3;
+const MAX_IMPORT_BYTES: u64 = 8 * 1024 * 1024;
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Source {
+    Workrave,
+    Stretchly,
+    TimeOut,
+}
+
+/// One day of another app's history, converted to Upstand terms: taken rest
+/// breaks count as standups, skipped ones as ignored reminders.
+struct ImportedDay {
+    date: NaiveDate,
+    start_ts: i64,
+    end_ts: i64,
+    standups: u32,
+    sedentary: u32,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ImportPreview {
+    pub source: Source,
+    pub days: u32,
+    pub standups: u32,
+    pub sedentary: u32,
+    pub first_date: Option<String>,
+    pub last_date: Option<String>,
+    /// Days left out because Upstand already has events for them.
+    pub skipped_existing_days: u32,
+    /// Days left out because they are older than analytics retention.
+    pub skipped_old_days: u32,
+    /// False for a dry run.
+    pub applied: bool,
+}
+
+pub fn detect(path: &str, contents: &str) -> Result<Source, String> {
+    let lower = path.to_lowercase();
+    if contents.starts_with("WorkRaveStats") {
+        return Ok(Source::Workrave);
+    }
+    if lower.contains("stretchly") || contents.contains("\"microbreakInterval\"") {
+        return Ok(Source::Stretchly);
+    }
+    if lower.contains("com.dejal.timeout") || contents.starts_with("bplist") {
+        return Ok(Source::TimeOut);
+    }
+    Err("unrecognized history file".to_string())
+}
+
+fn tm_to_ts(fields: &[i64]) -> Option<i64> {
+    let [mday, mon, year, hour, min] = fields else {
+        return None;
+    };
+    let date = NaiveDate::from_ymd_opt(
+        (*year + 1900) as i32,
+        u32::try_from(*mon + 1).ok()?,
+        u32::try_from(*mday).ok()?,
+    )?;
+    let naive = date.and_hms_opt(u32::try_from(*hour).ok()?, u32::try_from(*min).ok()?, 0)?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.timestamp())
+}
+
+/// Workrave's `historystats` / `todaystats`: a `D` line per day with start
+/// and stop times, then `B <break id> <count> <values...>` lines.
+fn parse_workrave(contents: &str) -> Vec<ImportedDay> {
+    let mut days = Vec::new();
+    let mut current: Option<ImportedDay> = None;
+    for line in contents.lines() {
+        let mut parts = line.split_whitespace();
+        match parts.next() {
+            Some("D") => {
+                let numbers: Vec<i64> = parts.filter_map(|p| p.parse().ok()).collect();
+                if numbers.len() < 10 {
+                    continue;
+                }
+                let (Some(start_ts), Some(end_ts)) =
+                    (tm_to_ts(&numbers[0..5]), tm_to_ts(&numbers[5..10]))
+                else {
+                    continue;
+                };
+                let Some(date) = Local.timestamp_opt(start_ts, 0).single() else {
+                    continue;
+                };
+                days.extend(current.take());
+                current = Some(ImportedDay {
+                    date: date.date_naive(),
+                    start_ts,
+                    end_ts: end_ts.max(start_ts),
+                    standups: 0,
+                    sedentary: 0,
+                });
+            }
+            Some("B") => {
+                let values: Vec<u32> = parts.filter_map(|p| p.parse().ok()).collect();
+                let (Some(day), Some(&break_id)) = (current.as_mut(), values.first()) else {
+                    continue;
+                };
+                if break_id as usize != WORKRAVE_REST_BREAK {
+                    continue;
+                }
+                // values[1] is the counter count; the counters follow it.
+                let counter = |i: usize| values.get(2 + i).copied().unwrap_or(0);
+                day.standups = counter(WORKRAVE_TAKEN) + counter(WORKRAVE_NATURAL_TAKEN);
+                day.sedentary = counter(WORKRAVE_SKIPPED);
+            }
+            _ => {}
+        }
+    }
+    days.extend(current);
+    days
+}
+
+fn read_days(path: &str) -> Result<(Source, Vec<ImportedDay>), String> {
+    let size = fs::metadata(path)
+        .map_err(|e| format!("read failed: {}", e))?
+        .len();
+    if size > MAX_IMPORT_BYTES {
+        return Err("history file is too large".to_string());
+    }
+    let bytes = fs::read(path).map_err(|e| format!("read failed: {}", e))?;
+    let contents = String::from_utf8_lossy(&bytes);
+    match detect(path, &contents)? {
+        Source::Workrave => Ok((Source::Workrave, parse_workrave(&contents))),
+        Source::Stretchly => {
+            Err("Stretchly keeps no break history, only settings; nothing to import".to_string())
+        }
+        Source::TimeOut => Err(
+            "Time Out stores its history in a private binary format that can't be read yet"
+                .to_string(),
+        ),
+    }
+}
+
+/// Spread `count` events evenly over the day's active span.
+fn spread(day: &ImportedDay, count: u32) -> impl Iterator<Item = i64> + '_ {
+    let span = (day.end_ts - day.start_ts).max(0);
+    (0..count).map(move |i| day.start_ts + span * (i64::from(i) + 1) / (i64::from(count) + 1))
+}
+
+/// Parse the file and, unless `dry_run`, merge it into the in-memory events.
+/// Days Upstand already tracked are never touched, so importing twice is safe.
+pub fn import(
+    state: &AppState,
+    path: &str,
+    dry_run: bool,
+    sedentary_secs: u64,
+) -> Result<ImportPreview, String> {
+    let (source, days) = read_days(path)?;
+    if days.is_empty() {
+        return Err("no days found in history file".to_string());
+    }
+
+    let local_date = |ts: i64| {
+        Local
+            .timestamp_opt(ts, 0)
+            .single()
+            .map(|dt| dt.date_naive())
+    };
+    let existing: HashSet<NaiveDate> = {
+        let reminders = state.reminder_events.lock().unwrap();
+        let standups = state.standup_events.lock().unwrap();
+        standups
+            .iter()
+            .copied()
+            .chain(reminders.iter().map(|r| r.ts))
+            .filter_map(local_date)
+            .collect()
+    };
+    let cutoff = now_ts() - RETENTION_SECS;
+
+    let mut preview = ImportPreview {
+        source,
+        days: 0,
+        standups: 0,
+        sedentary: 0,
+        first_date: None,
+        last_date: None,
+        skipped_existing_days: 0,
+        skipped_old_days: 0,
+        applied: !dry_run,
+    };
+    let mut new_standups = Vec::new();
+    let mut new_reminders = Vec::new();
+    let mut seen = HashSet::new();
+    for day in days.iter() {
+        if day.end_ts < cutoff {
+            preview.skipped_old_days += 1;
+            continue;
+        }
+        if existing.contains(&day.date) || !seen.insert(day.date) {
+            preview.skipped_existing_days += 1;
+            continue;
+        }
+        preview.days += 1;
+        preview.standups += day.standups;
+        preview.sedentary += day.sedentary;
+        let key = day.date.format("%Y-%m-%d").to_string();
+        if preview.first_date.as_ref().is_none_or(|d| key < *d) {
+            preview.first_date = Some(key.clone());
+        }
+        if preview.last_date.as_ref().is_none_or(|d| key > *d) {
+            preview.last_date = Some(key);
+        }
+        new_standups.extend(spread(day, day.standups));
+        new_reminders.extend(spread(day, day.sedentary).map(|ts| ReminderRecord {
+            ts,
+            duration_secs: sedentary_secs,
+        }));
+    }
+
+    if !dry_run {
+        let mut reminders = state.reminder_events.lock().unwrap();
+        reminders.extend(new_reminders);
+        reminders.sort_by_key(|r| r.ts);
+        let mut standups = state.standup_events.lock().unwrap();
+        standups.extend(new_standups);
+        standups.sort_unstable();
+    }
+    Ok(preview)
+}
@@ -0,0 +1,109 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{now_ts, AppState};
+
+/// What the reminder was answered with.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Control {
+    /// A click anywhere on the card.
+    Card,
+    /// The × button.
+    Close,
+    Enter,
+    Escape,
+    /// A desk integration reported the move (`report_desk_position`).
+    Desk,
+}
+
+impl Control {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "card" => Some(Control::Card),
+            "close" => Some(Control::Close),
+            "enter" | "Enter" => Some(Control::Enter),
+            "escape" | "Escape" => Some(Control::Escape),
+            "desk" => Some(Control::Desk),
+            _ => None,
+        }
+    }
+}
+
+/// One answered reminder. Kept on this machine only, to tune the
+/// escalation and placement defaults against what people actually do.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Interaction {
+    pub ts: i64,
+    pub reminder_id: u64,
+    /// `None` when the page didn't say (older reminder pages).
+    pub control: Option<Control>,
+    pub stood_up: bool,
+    /// From the window appearing to the answer.
+    pub latency_ms: u64,
+    /// The reminder window had keyboard focus when it was answered.
+    pub focused: bool,
+}
+
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct ControlStats {
+    pub control: Option<Control>,
+    pub count: u32,
+    pub median_latency_ms: u64,
+}
+
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct InteractionStats {
+    pub since_ts: i64,
+    pub count: u32,
+    /// Most used first.
+    pub by_control: Vec<ControlStats>,
+    /// `None` with nothing logged.
+    pub median_latency_ms: Option<u64>,
+    /// Share answered while the window had focus, 0.0-1.0.
+    pub focused_share: Option<f32>,
+}
+
+pub fn record(state: &AppState, interaction: Interaction) {
+    state.interactions.lock().unwrap().push(interaction);
+}
+
+pub fn prune(log: &mut Vec<Interaction>, cutoff: i64) {
+    log.retain(|i| i.ts >= cutoff);
+}
+
+fn median(mut values: Vec<u64>) -> Option<u64> {
+    values.sort_unstable();
+    values.get(values.len() / 2).copied()
+}
+
+/// Summary of the interactions of the last `days` days.
+pub fn stats(log: &[Interaction], days: u32) -> InteractionStats {
+    let since_ts = now_ts() - i64::from(days) * 86_400;
+    let recent: Vec<&Interaction> = log.iter().filter(|i| i.ts >= since_ts).collect();
+    let mut by_control: Vec<ControlStats> = Vec::new();
+    for control in recent.iter().map(|i| i.control) {
+        if by_control.iter().any(|c| c.control == control) {
+            continue;
+        }
+        let latencies: Vec<u64> = recent
+            .iter()
+            .filter(|i| i.control == control)
+            .map(|i| i.latency_ms)
+            .collect();
+        by_control.push(ControlStats {
+            control,
+            count: latencies.len() as u32,
+            median_latency_ms: median(latencies).unwrap_or(0),
+        });
+    }
+    by_control.sort_by_key(|c| std::cmp::Reverse(c.count));
+    let focused = recent.iter().filter(|i| i.focused).count();
+    InteractionStats {
+        since_ts,
+        count: recent.len() as u32,
+        by_control,
+        median_latency_ms: median(recent.iter().map(|i| i.latency_ms).collect()),
+        focused_share: (!recent.is_empty()).then(|| focused as f32 / recent.len() as f32),
+    }
+}
@@ -0,0 +1,97 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::raw_events::{self, RawEvent};
+use crate::{data_dir, read_only, save_analytics, store_lock, AppState};
+
+/// Journaled events are folded into analytics.json at most this long after
+/// they happen; until then the journal alone carries them.
+const COMPACT_DELAY_SECS: u64 = 30;
+
+pub fn journal_path(handle: &AppHandle) -> Option<PathBuf> {
+    data_dir::dir(handle).map(|dir| dir.join("analytics.journal.jsonl"))
+}
+
+/// Append one event as a JSON line and flush it before returning, then
+/// schedule a compaction. Call after the event is in memory and without
+/// holding any event lock.
+pub fn record(app: &AppHandle, event: RawEvent) {
+    let state = app.state::<AppState>();
+    if state.safe_mode || read_only::enabled(&state) || store_lock::check(&state).is_err() {
+        return;
+    }
+    {
+        let _guard = state.journal_lock.lock().unwrap();
+        if let Some(path) = journal_path(app) {
+            if let Some(parent) = path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            let result = serde_json::to_string(&event)
+                .map_err(std::io::Error::other)
+                .and_then(|line| {
+                    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+                    writeln!(file, "{}", line)?;
+                    file.sync_data()
+                });
+            if let Err(e) = result {
+                eprintln!("journal append failed: {}", e);
+            }
+        }
+    }
+    compact_soon(app);
+}
+
+/// Debounced `save_analytics`; a burst of events costs one full rewrite.
+pub fn compact_soon(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    {
+        let mut pending = state.journal_compaction_pending.lock().unwrap();
+        if *pending {
+            return;
+        }
+        *pending = true;
+    }
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(COMPACT_DELAY_SECS)).await;
+        let state = handle.state::<AppState>();
+        *state.journal_compaction_pending.lock().unwrap() = false;
+        let _ = save_analytics(&handle, &state);
+    });
+}
+
+/// Called by `save_analytics` once the store is safely on disk, with the
+/// journal lock still held so no append can slip in between.
+pub fn truncate(handle: &AppHandle) {
+    if let Some(path) = journal_path(handle) {
+        if path.exists() {
+            if let Err(e) = data_dir::write(&handle.state::<AppState>(), &path, "") {
+                eprintln!("journal truncate failed: {}", e);
+            }
+        }
+    }
+}
+
+/// Merge events left in the journal by a crash since the last compaction.
+/// A torn last line is skipped; events already in the store are deduplicated
+/// by id. Returns the number of events recovered.
+pub fn replay(app: &AppHandle, state: &AppState) -> u32 {
+    let Some(contents) = journal_path(app).and_then(|path| fs::read_to_string(path).ok()) else {
+        return 0;
+    };
+    let mut events: Vec<RawEvent> = contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    if events.is_empty() {
+        return 0;
+    }
+    events.sort_by(|a, b| a.ts.cmp(&b.ts).then_with(|| a.kind.cmp(&b.kind)));
+    raw_events::assign_ids(&mut events);
+    let result = raw_events::merge(state, events);
+    let _ = save_analytics(app, state);
+    result.imported
+}
@@ -0,0 +1,53 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Used for anything unknown.
+pub const FALLBACK: &str = "en";
+
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct LanguageInfo {
+    /// BCP 47 tag, as stored in the config.
+    pub code: String,
+    /// The language's name in itself, for pickers.
+    pub native_name: String,
+    /// Written right to left; layouts and the reminder corner are mirrored.
+    pub rtl: bool,
+}
+
+/// (code, native name, right-to-left). A locale is added here first; the
+/// tray, reminder tips and formatters fall back to English until they learn it.
+const SUPPORTED: &[(&str, &str, bool)] = &[("en", "English", false), ("zh-CN", "简体中文", false)];
+
+pub fn available() -> Vec<LanguageInfo> {
+    SUPPORTED
+        .iter()
+        .map(|&(code, native_name, rtl)| LanguageInfo {
+            code: code.to_string(),
+            native_name: native_name.to_string(),
+            rtl,
+        })
+        .collect()
+}
+
+/// The supported code for `code`, or the fallback.
+pub fn normalize(code: &str) -> String {
+    SUPPORTED
+        .iter()
+        .find(|(supported, _, _)| *supported == code)
+        .map_or(FALLBACK, |(supported, _, _)| supported)
+        .to_string()
+}
+
+pub fn info(code: &str) -> LanguageInfo {
+    let code = normalize(code);
+    available()
+        .into_iter()
+        .find(|language| language.code == code)
+        .expect("normalize only returns supported codes")
+}
+
+pub fn is_rtl(code: &str) -> bool {
+    SUPPORTED
+        .iter()
+        .any(|&(supported, _, rtl)| supported == code && rtl)
+}
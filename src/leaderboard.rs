@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::{analytics_date, build_analytics_for_period, clock, AppState};
+
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+const MAX_DISPLAY_NAME_CHARS: usize = 32;
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct LeaderboardConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub display_name: String,
+}
+
+/// The only data that ever leaves the machine: no timestamps, no event lists.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub display_name: String,
+    pub date: String,
+    pub standups: u32,
+    pub score: u32,
+}
+
+pub fn sanitize(mut cfg: LeaderboardConfig) -> LeaderboardConfig {
+    cfg.endpoint = cfg
+        .endpoint
+        .map(|e| e.trim().to_string())
+        .filter(|e| e.starts_with("https://") || e.starts_with("http://"));
+    cfg.display_name = cfg
+        .display_name
+        .trim()
+        .chars()
+        .take(MAX_DISPLAY_NAME_CHARS)
+        .collect();
+    cfg
+}
+
+/// Share of today's reminders answered by standing, 0-100.
+pub fn daily_score(standups: u32, sedentary: u32) -> u32 {
+    (standups * 100)
+        .checked_div(standups + sedentary)
+        .unwrap_or(0)
+}
+
+fn today(state: &AppState) -> String {
+    let day_start_hour = *state.day_start_hour.lock().unwrap();
+    analytics_date(clock::now(), day_start_hour)
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+}
+
+/// Returns the endpoint and today's entry when sharing is enabled and configured
+/// (never in safe mode or on simulated time).
+pub fn todays_entry(state: &AppState) -> Option<(String, LeaderboardEntry)> {
+    if state.safe_mode || clock::simulated() {
+        return None;
+    }
+    let cfg = state.leaderboard.lock().unwrap().clone();
+    let endpoint = cfg
+        .endpoint
+        .filter(|_| cfg.enabled && !cfg.display_name.is_empty())?;
+    let analytics = build_analytics_for_period(state, "daily");
+    Some((
+        endpoint,
+        LeaderboardEntry {
+            display_name: cfg.display_name,
+            date: today(state),
+            standups: analytics.standup_sessions,
+            score: daily_score(analytics.standup_sessions, analytics.sedentary_sessions),
+        },
+    ))
+}
+
+pub fn publish(endpoint: &str, entry: &LeaderboardEntry) -> Result<(), String> {
+    agent()
+        .post(endpoint)
+        .send_json(entry)
+        .map(|_| ())
+        .map_err(|e| format!("publish failed: {}", e))
+}
+
+pub fn fetch(endpoint: &str, date: &str) -> Result<Vec<LeaderboardEntry>, String> {
+    agent()
+        .get(endpoint)
+        .query("date", date)
+        .call()
+        .map_err(|e| format!("fetch failed: {}", e))?
+        .into_json::<Vec<LeaderboardEntry>>()
+        .map_err(|e| format!("invalid leaderboard response: {}", e))
+}
+
+/// Fire-and-forget publish after a standup; failures are retried implicitly by
+/// the next standup since each post carries the full daily count.
+pub fn publish_in_background(app: &AppHandle) {
+    let Some((endpoint, entry)) = todays_entry(&app.state::<AppState>()) else {
+        return;
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        let _ = publish(&endpoint, &entry);
+    });
+}
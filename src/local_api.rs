@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::{analytics_date, api_tokens, build_analytics_for_period, clock, leaderboard, AppState};
+
+/// How often a stopped server checks whether it was turned on, and a running
+/// one whether it was turned off or moved.
+const RECHECK_SECS: u64 = 5;
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// Read-only HTTP on 127.0.0.1 for note-taking plugins. Every request needs
+/// `Authorization: Bearer <token>` from `generate_api_token`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LocalApiConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_port")]
+    pub port: u16,
+}
+
+impl Default for LocalApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_port(),
+        }
+    }
+}
+
+fn default_port() -> u16 {
+    47653
+}
+
+pub fn sanitize(mut cfg: LocalApiConfig) -> LocalApiConfig {
+    if cfg.port < 1024 {
+        cfg.port = default_port();
+    }
+    cfg
+}
+
+/// Body of `GET /v1/today`.
+#[derive(Serialize)]
+struct TodayStats {
+    date: String,
+    standups: u32,
+    sedentary: u32,
+    sitting_secs: u64,
+    score: u32,
+}
+
+fn today(state: &AppState) -> TodayStats {
+    let analytics = build_analytics_for_period(state, "daily");
+    let day_start_hour = *state.day_start_hour.lock().unwrap();
+    TodayStats {
+        date: analytics_date(clock::now(), day_start_hour)
+            .format("%Y-%m-%d")
+            .to_string(),
+        standups: analytics.standup_sessions,
+        sedentary: analytics.sedentary_sessions,
+        sitting_secs: analytics.total_sitting_secs,
+        score: leaderboard::daily_score(analytics.standup_sessions, analytics.sedentary_sessions),
+    }
+}
+
+/// Status line and JSON body for one request head.
+pub fn respond(state: &AppState, head: &str) -> (&'static str, String) {
+    let mut lines = head.lines();
+    let target = lines
+        .next()
+        .and_then(|line| line.strip_prefix("GET "))
+        .and_then(|rest| rest.split(' ').next());
+    let token = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("authorization"))
+        .and_then(|(_, value)| value.trim().strip_prefix("Bearer "));
+    if !token.is_some_and(|token| api_tokens::authorize(state, token)) {
+        return (
+            "401 Unauthorized",
+            r#"{"error":"unauthorized"}"#.to_string(),
+        );
+    }
+    match target {
+        Some("/v1/today") => (
+            "200 OK",
+            serde_json::to_string(&today(state)).unwrap_or_default(),
+        ),
+        Some(_) => ("404 Not Found", r#"{"error":"not found"}"#.to_string()),
+        None => (
+            "405 Method Not Allowed",
+            r#"{"error":"method not allowed"}"#.to_string(),
+        ),
+    }
+}
+
+async fn serve(app: AppHandle, mut stream: TcpStream) {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") && buf.len() < MAX_REQUEST_BYTES {
+        match tokio::time::timeout(Duration::from_secs(RECHECK_SECS), stream.read(&mut chunk)).await
+        {
+            Ok(Ok(n)) if n > 0 => buf.extend_from_slice(&chunk[..n]),
+            _ => return,
+        }
+    }
+    let head = String::from_utf8_lossy(&buf);
+    let (status, body) = respond(&app.state::<AppState>(), &head);
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+fn wanted(app: &AppHandle) -> Option<u16> {
+    let cfg = app.state::<AppState>().local_api.lock().unwrap().clone();
+    cfg.enabled.then_some(cfg.port)
+}
+
+/// Listen while the API is enabled, rebinding when the port changes.
+pub async fn run(app: AppHandle) {
+    loop {
+        let Some(port) = wanted(&app) else {
+            clock::sleep(Duration::from_secs(RECHECK_SECS)).await;
+            continue;
+        };
+        let listener = match TcpListener::bind(("127.0.0.1", port)).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("local API: cannot listen on port {}: {}", port, e);
+                // Try again only once the setting changes.
+                while wanted(&app) == Some(port) {
+                    clock::sleep(Duration::from_secs(RECHECK_SECS)).await;
+                }
+                continue;
+            }
+        };
+        while wanted(&app) == Some(port) {
+            let accepted =
+                tokio::time::timeout(Duration::from_secs(RECHECK_SECS), listener.accept()).await;
+            if let Ok(Ok((stream, _))) = accepted {
+                tauri::async_runtime::spawn(serve(app.clone(), stream));
+            }
+        }
+    }
+}
@@ -1,18 +1,145 @@
 ﻿#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod about;
+mod accessibility;
+mod activity;
+mod analytics_filter;
+mod api;
+mod api_tokens;
+mod budget;
+mod challenge;
+mod changelog;
+mod clipboard;
+mod clock;
+mod config_history;
+mod data_dir;
+mod delivery;
+mod desk;
+mod dev_intervals;
+mod events;
+mod export_diff;
+mod file_names;
+mod focus;
+mod formatting;
+mod goals;
+mod google_sheets;
+mod handoff;
+mod health;
+mod holidays;
+mod hooks;
+mod importers;
+mod interactions;
+mod journal;
+mod languages;
+mod leaderboard;
+mod local_api;
+mod markdown_journal;
+mod marked_days;
+mod network_info;
+mod network_profiles;
+mod overlay;
+mod plugins;
+mod power;
+mod ratio;
+mod raw_events;
+mod read_only;
+mod reminder_reason;
+mod reminder_windows;
+mod repeats;
+mod reports;
+mod rollover;
+mod schedule;
+mod scheduled_export;
+mod screen_share;
+mod settings_bundle;
+mod skips;
+mod sleep_drift;
+mod status_file;
+mod store_lock;
+mod tags;
+mod themes;
+mod timeline;
+mod timers;
+mod tip_packs;
+mod tip_ratings;
+mod tray_icons;
+mod weekdays;
+mod window_focus;
+mod window_manager;
+
+#[cfg(test)]
+mod benches;
+#[cfg(test)]
+mod tests;
+
+use base64::Engine;
 use chrono::{Datelike, Duration as ChronoDuration, Local, TimeZone, Timelike};
 use rand::Rng;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::time::{Duration, Instant};
-use std::{fs, path::PathBuf, sync::Mutex};
+use std::collections::HashMap;
 use std::process::Command as ProcessCommand;
-use base64::Engine;
+use std::time::{Duration, Instant};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem},
-    tray::TrayIconBuilder,
-    AppHandle, Emitter, Manager, PhysicalPosition, State, WebviewUrl, WebviewWindowBuilder,
+    menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, Submenu},
+    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
+    AppHandle, Emitter, Manager, State,
 };
+use tauri_plugin_notification::NotificationExt;
+
+use accessibility::AccessibilityPrefs;
+use activity::{ActivityBucket, BlockingApps};
+use analytics_filter::{AnalyticsFilter, EventType, OutcomeClass};
+use api_tokens::{ApiToken, NewApiToken, StoredToken};
+use challenge::{ChallengeConfig, ChallengeStatus};
+use changelog::{ChangelogEntry, WhatsNew};
+use config_history::ConfigChange;
+use delivery::DeliveryStats;
+use desk::{DeskChange, DeskModeConfig, DeskPosition, DeskTotals};
+use export_diff::ExportSnapshot;
+use formatting::Formatter;
+use goals::{MonthlyGoal, MonthlyGoalProgress};
+use google_sheets::GoogleSheetsConfig;
+use handoff::{HandoffConfig, HandoffPeer};
+use health::{Health, StorageStatus};
+use holidays::{Holiday, HolidayConfig};
+use hooks::{HookConfig, HookRun};
+use importers::ImportPreview;
+use interactions::{Interaction, InteractionStats};
+use leaderboard::{LeaderboardConfig, LeaderboardEntry};
+use local_api::LocalApiConfig;
+use markdown_journal::MarkdownJournalConfig;
+use marked_days::{DayKind, MarkedDay};
+use network_profiles::{NetworkProfile, NetworkStatus, ProfileChange};
+use overlay::PresentationConfig;
+use plugins::{PluginInfo, PluginInsight};
+use power::{PowerChange, PowerState};
+use raw_events::{RawEvent, RawImportResult};
+use reminder_reason::{ReminderHistoryEntry, ReminderReason};
+use reminder_windows::ReminderWindowInfo;
+use repeats::RepeatConfig;
+use reports::{DaySummary, DaySummaryConfig};
+use schedule::{ScheduleSlot, WeekendOverride};
+use scheduled_export::{ExportScheduleConfig, ScheduledExportResult};
+use skips::SkipBudget;
+use sleep_drift::SleepPolicy;
+use status_file::StatusFileConfig;
+use tags::{TagBreakdown, TagChange};
+use themes::ThemeTokens;
+use timeline::{HoldChange, TimelineSegment};
+use timers::{FocusRecord, FocusSession, TickResolution, TimerStatus};
+use tip_packs::{TipLanguageMatch, TipPackCoverage, TipPackInfo};
+use tip_ratings::{ShownTip, TipRating, TipRatingSummary, TipSource};
+use tray_icons::TrayIconConfig;
+use weekdays::WeekdayBreakdown;
+use window_focus::WindowFocusInfo;
+use window_manager::WindowPrefs;
 
 const HOURS: usize = 24;
 const WINDOW_24H_SECS: i64 = 24 * 60 * 60;
@@ -23,7 +150,22 @@ const REMINDER_HEIGHT: i32 = 196;
 const REMINDER_PROMPT_COUNT: usize = 15;
 const DEFAULT_INTERVAL_MINUTES: u64 = 50;
 const ALLOWED_INTERVAL_MINUTES: [u64; 5] = [5, 10, 20, 30, 50];
+const DEFAULT_STANDUP_COOLDOWN_SECS: u64 = 120;
+const MAX_STANDUP_COOLDOWN_SECS: u64 = 30 * 60;
+const DEFAULT_ACK_GRACE_MS: u64 = 700;
+const MAX_ACK_GRACE_MS: u64 = 5000;
+const MAX_DAY_START_HOUR: u32 = 23;
+const DEFAULT_SEDENTARY_THRESHOLD_SECS: u64 = 60;
+const MIN_SEDENTARY_THRESHOLD_SECS: u64 = 15;
+const MAX_SEDENTARY_THRESHOLD_SECS: u64 = 30 * 60;
 const TRAY_ID: &str = "main_tray";
+const ACCESSIBILITY_POLL_SECS: u64 = 10;
+/// Also catches a locale switched while the machine slept or was locked,
+/// within one poll of waking.
+const LOCALE_POLL_SECS: u64 = 30;
+const POWER_POLL_SECS: u64 = 30;
+const SCREEN_SHARE_POLL_SECS: u64 = 10;
+const DISPLAY_POLL_SECS: u64 = 3;
 const REMINDER_TIPS_EN: [&str; REMINDER_PROMPT_COUNT] = [
     "Smelly butt, smelly butt, please stand up!",
     "Your chakras are literally flattening. Stand up!",
@@ -51,41 +193,302 @@ struct ReminderRecord {
 #[derive(Serialize, Deserialize)]
 struct AppConfigFile {
     interval_minutes: u64,
+    /// Hidden: unlocks the `--dev-intervals` choices without the flag.
+    #[serde(default)]
+    dev_intervals: bool,
     #[serde(default = "default_language")]
     language: String,
     #[serde(default = "default_reminder_language")]
     reminder_language: String,
     #[serde(default = "default_theme")]
     theme: String,
+    #[serde(default = "default_reminder_focus_mode")]
+    reminder_focus_mode: String,
+    #[serde(default = "default_standup_cooldown_secs")]
+    standup_cooldown_secs: u64,
+    #[serde(default = "schedule::default_schedule")]
+    schedule: Vec<ScheduleSlot>,
+    #[serde(default)]
+    export_schedule: ExportScheduleConfig,
+    #[serde(default)]
+    leaderboard: LeaderboardConfig,
+    #[serde(default)]
+    google_sheets: GoogleSheetsConfig,
+    #[serde(default)]
+    markdown_journal: MarkdownJournalConfig,
+    #[serde(default)]
+    hooks: Vec<HookConfig>,
+    #[serde(default)]
+    local_api: LocalApiConfig,
+    #[serde(default)]
+    status_file: StatusFileConfig,
+    #[serde(default)]
+    handoff: HandoffConfig,
+    #[serde(default = "default_tick_event_secs")]
+    tick_event_secs: u64,
+    #[serde(default)]
+    tick_resolution: TickResolution,
+    #[serde(default)]
+    window_prefs: HashMap<String, WindowPrefs>,
+    #[serde(default = "default_true")]
+    show_splash: bool,
+    #[serde(default = "default_true")]
+    open_settings_on_launch: bool,
+    #[serde(default = "tip_packs::default_enabled_packs")]
+    enabled_tip_packs: Vec<String>,
+    #[serde(default)]
+    enabled_plugins: Vec<String>,
+    #[serde(default)]
+    day_summary: DaySummaryConfig,
+    #[serde(default)]
+    monthly_goal: MonthlyGoal,
+    #[serde(default)]
+    holidays: HolidayConfig,
+    #[serde(default)]
+    weekend: WeekendOverride,
+    /// Minutes before a reminder to post a heads-up; 0 turns it off.
+    #[serde(default)]
+    pre_warning_minutes: u64,
+    /// Daily sitting budget; 0 turns it off.
+    #[serde(default)]
+    sitting_budget_minutes: u64,
+    /// Nudge after the settings or stats window has been focused this long
+    /// without a break; 0 turns it off.
+    #[serde(default = "default_window_nudge_minutes")]
+    window_nudge_minutes: u64,
+    /// Seconds sat per second stood to stay under; `None` turns it off.
+    #[serde(default)]
+    sit_stand_target: Option<f64>,
+    /// What a system sleep does to the sitting countdown.
+    #[serde(default)]
+    sleep_policy: SleepPolicy,
+    #[serde(default)]
+    input_activity_enabled: bool,
+    #[serde(default)]
+    blocking_apps: BlockingApps,
+    #[serde(default)]
+    tray_icons: TrayIconConfig,
+    /// Hold back reminder sound and notification chimes while a microphone
+    /// is recording.
+    #[serde(default)]
+    mute_when_mic_in_use: bool,
+    #[serde(default = "default_ack_grace_ms")]
+    ack_grace_ms: u64,
+    #[serde(default = "default_sedentary_threshold_secs")]
+    sedentary_threshold_secs: u64,
+    /// Show a reminder on every monitor instead of only the primary one.
+    #[serde(default)]
+    reminder_all_monitors: bool,
+    /// "auto" (next to the taskbar) or a fixed corner such as "top_left".
+    #[serde(default = "default_reminder_anchor")]
+    reminder_anchor: String,
+    /// Reminders that may be dismissed while still sitting each day; `None`
+    /// for no limit.
+    #[serde(default)]
+    free_skips_per_day: Option<u32>,
+    #[serde(default)]
+    repeat_when_dismissed: RepeatConfig,
+    #[serde(default)]
+    reminder_presentation: PresentationConfig,
+    #[serde(default)]
+    desk_mode: DeskModeConfig,
+    #[serde(default)]
+    challenge: ChallengeConfig,
+    #[serde(default)]
+    network_profiles: Vec<NetworkProfile>,
+    /// Last local date seen by the midnight rollover, "YYYY-MM-DD".
+    #[serde(default)]
+    last_rollover_date: Option<String>,
+    /// Hour (0-23) at which an analytics day starts.
+    #[serde(default)]
+    day_start_hour: u32,
+    /// "monday", "sunday" or "saturday".
+    #[serde(default = "default_week_start")]
+    week_start: String,
+    /// "rolling" (last 7 days) or "calendar" (since `week_start`).
+    #[serde(default = "default_weekly_mode")]
+    weekly_mode: String,
+    /// "locale" follows the UI language; "iso8601" is for spreadsheets.
+    #[serde(default = "default_export_date_format")]
+    export_date_format: String,
+    /// Write what changed since the last export of the same period next to
+    /// each analytics export.
+    #[serde(default)]
+    export_diff: bool,
+    /// Export file names, e.g. "{app}-{type}-{period}-{date}"; blank keeps
+    /// the built-in names.
+    #[serde(default)]
+    export_filename_template: String,
+    #[serde(default)]
+    record_power_source: bool,
+    /// App version of the last launch, for release notes after an update.
+    #[serde(default)]
+    last_seen_version: Option<String>,
+    /// Observer mode, as with `--read-only`. Edit the file to turn it off;
+    /// the app can't save the change itself.
+    #[serde(default)]
+    read_only: bool,
 }
 
+#[derive(Clone, Serialize)]
+struct StartupOptions {
+    show_splash: bool,
+    open_settings_on_launch: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// New installs follow the OS until a language is picked.
 fn default_language() -> String {
-    "en".to_string()
+    "system".to_string()
 }
 
 fn default_reminder_language() -> String {
     "en".to_string()
 }
 
+fn default_reminder_anchor() -> String {
+    "auto".to_string()
+}
+
 fn default_theme() -> String {
     "night".to_string()
 }
 
-fn sanitize_interval_minutes(value: u64) -> u64 {
-    if ALLOWED_INTERVAL_MINUTES.contains(&value) {
+fn default_reminder_focus_mode() -> String {
+    "steal".to_string()
+}
+
+fn default_standup_cooldown_secs() -> u64 {
+    DEFAULT_STANDUP_COOLDOWN_SECS
+}
+
+fn default_window_nudge_minutes() -> u64 {
+    30
+}
+
+fn default_week_start() -> String {
+    "monday".to_string()
+}
+
+fn default_weekly_mode() -> String {
+    "rolling".to_string()
+}
+
+fn default_export_date_format() -> String {
+    "locale".to_string()
+}
+
+fn normalize_export_date_format(format: &str) -> String {
+    if format == "iso8601" {
+        "iso8601".to_string()
+    } else {
+        default_export_date_format()
+    }
+}
+
+fn default_ack_grace_ms() -> u64 {
+    DEFAULT_ACK_GRACE_MS
+}
+
+fn default_sedentary_threshold_secs() -> u64 {
+    DEFAULT_SEDENTARY_THRESHOLD_SECS
+}
+
+fn normalize_sedentary_threshold_secs(secs: u64) -> u64 {
+    secs.clamp(MIN_SEDENTARY_THRESHOLD_SECS, MAX_SEDENTARY_THRESHOLD_SECS)
+}
+
+fn default_tick_event_secs() -> u64 {
+    timers::DEFAULT_TICK_EVENT_SECS
+}
+
+const REMINDER_FOCUS_MODES: [&str; 2] = ["steal", "show-without-focus"];
+
+fn parse_reminder_focus_mode(mode: &str) -> Result<String, String> {
+    REMINDER_FOCUS_MODES
+        .contains(&mode)
+        .then(|| mode.to_string())
+        .ok_or_else(|| format!("unknown focus mode: {}", mode))
+}
+
+/// For values read from the config file, where an unknown mode can only be
+/// dropped.
+fn normalize_reminder_focus_mode(mode: &str) -> String {
+    parse_reminder_focus_mode(mode).unwrap_or_else(|_| default_reminder_focus_mode())
+}
+
+/// The preset intervals, plus the short ones when dev intervals are unlocked.
+fn allowed_interval_minutes(state: &AppState) -> Vec<u64> {
+    let mut allowed = Vec::new();
+    if dev_intervals::enabled(state) {
+        allowed.extend(dev_intervals::INTERVAL_MINUTES);
+    }
+    allowed.extend(ALLOWED_INTERVAL_MINUTES);
+    allowed
+}
+
+fn sanitize_interval_minutes(state: &AppState, value: u64) -> u64 {
+    if allowed_interval_minutes(state).contains(&value) {
         value
     } else {
         DEFAULT_INTERVAL_MINUTES
     }
 }
 
+fn sanitize_weekend_override(state: &AppState, mut weekend: WeekendOverride) -> WeekendOverride {
+    weekend.interval_minutes = weekend
+        .interval_minutes
+        .map(|minutes| sanitize_interval_minutes(state, minutes));
+    weekend.schedule = weekend.schedule.map(schedule::sanitize_schedule);
+    weekend
+}
+
+fn sanitize_network_profiles(state: &AppState, profiles: Vec<NetworkProfile>) -> Vec<NetworkProfile> {
+    network_profiles::sanitize(profiles)
+        .into_iter()
+        .map(|mut profile| {
+            profile.interval_minutes = profile
+                .interval_minutes
+                .map(|minutes| sanitize_interval_minutes(state, minutes));
+            profile.free_skips_per_day = profile.free_skips_per_day.map(|n| n.min(skips::MAX_FREE_SKIPS));
+            profile
+        })
+        .collect()
+}
+
 #[derive(Serialize, Deserialize)]
 struct AnalyticsStore {
     reminder_events: Vec<ReminderRecord>,
     standup_events: Vec<i64>,
+    #[serde(default)]
+    focus_sessions: Vec<FocusRecord>,
+    #[serde(default)]
+    activity: Vec<ActivityBucket>,
+    #[serde(default)]
+    power_log: Vec<PowerChange>,
+    #[serde(default)]
+    tag_log: Vec<TagChange>,
+    #[serde(default)]
+    profile_log: Vec<ProfileChange>,
+    #[serde(default)]
+    hold_log: Vec<HoldChange>,
+    #[serde(default)]
+    marked_days: Vec<MarkedDay>,
+    #[serde(default)]
+    interactions: Vec<Interaction>,
+    /// Reminders dismissed while still sitting.
+    #[serde(default)]
+    skip_events: Vec<i64>,
+    #[serde(default)]
+    desk_log: Vec<DeskChange>,
+    #[serde(default)]
+    tip_ratings: Vec<TipRating>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 struct AnalyticsData {
     hourly_sedentary: Vec<u32>,
     hourly_standup: Vec<u32>,
@@ -94,26 +497,236 @@ struct AnalyticsData {
     sedentary_sessions: u32,
     total_sitting_secs: u64,
     record_count: u32,
+    /// Reminders left unanswered past the sedentary threshold. Each one is
+    /// what a sedentary session records, so this matches
+    /// `sedentary_sessions`; it is spelled out for the dashboard's banner.
+    #[serde(default)]
+    ignored_reminders: u32,
+    /// Sampled sitting seconds with / without keyboard or mouse input (opt-in).
+    #[serde(default)]
+    hourly_active_secs: Vec<u64>,
+    #[serde(default)]
+    hourly_idle_secs: Vec<u64>,
+    /// Share of sampled sitting time that was active; `None` without samples.
+    #[serde(default)]
+    active_sitting_ratio: Option<f32>,
+    /// Unix seconds where the period starts.
+    #[serde(default)]
+    period_start_ts: i64,
+    /// Per session tag; empty until a tag has been used.
+    #[serde(default)]
+    tags: Vec<TagBreakdown>,
+    /// Per day of the week, Monday first; empty for the daily period.
+    #[serde(default)]
+    weekdays: Vec<WeekdayBreakdown>,
+    /// Rest, sick and travel days in the period. Weekly and monthly totals
+    /// leave their events out.
+    #[serde(default)]
+    marked_days: Vec<MarkedDay>,
+    /// Reminders dismissed while still sitting.
+    #[serde(default)]
+    skips: u32,
+    /// Days on which every free skip was used; 0 without a skip budget.
+    #[serde(default)]
+    skip_budget_days_used_up: u32,
+    /// Desk mode time, kept apart from the sitting reminders above.
+    #[serde(default)]
+    desk: DeskTotals,
 }
 
-#[derive(Clone, Serialize)]
+#[derive(Clone, Serialize, JsonSchema)]
 struct ActiveReminderPayload {
     id: u64,
     text: String,
-    theme: String,
+    /// Resolved from the theme registry, so the page needs no styles per theme.
+    theme: ThemeTokens,
     visible: bool,
+    accessibility: AccessibilityPrefs,
+    /// Chime the reminder page plays, one of `schedule::SOUNDS`.
+    sound: Option<String>,
+    /// A sound was dropped because the microphone is in use.
+    sound_muted: bool,
+    /// Why it fired; `None` when no reminder is open.
+    reason: Option<ReminderReason>,
+    /// Opened by `preview_reminder`: answering it logs nothing.
+    preview: bool,
+    /// In desk mode, where the reminder asks the desk to go.
+    desk_move: Option<DeskPosition>,
+    /// What answering "stood up" takes, if a challenge is on.
+    challenge: Option<ChallengeStatus>,
+    /// Answers sooner than this after the reminder shows are ignored, so the
+    /// page holds its buttons back at least as long.
+    ack_grace_ms: u64,
+}
+
+/// A sample reminder opened from settings.
+#[derive(Clone)]
+struct ReminderPreview {
+    text: String,
+    theme: String,
+    sound: Option<String>,
 }
 
 struct AppState {
     interval: Mutex<u64>,
     elapsed: Mutex<u64>,
     last_interval_change: Mutex<Instant>,
+    /// Not part of the config: it only lasts the day.
+    interval_today: Mutex<Option<timers::IntervalToday>>,
+    reminder_preview: Mutex<Option<ReminderPreview>>,
+    /// Monitors as last polled; `None` until the first poll.
+    display_layout: Mutex<Option<Vec<reminder_windows::MonitorLayout>>>,
     reminder_events: Mutex<Vec<ReminderRecord>>,
     standup_events: Mutex<Vec<i64>>,
     reminder_visible: Mutex<bool>,
     language: Mutex<String>,
+    /// `language` tracks the OS language instead of a fixed choice.
+    language_follows_system: Mutex<bool>,
     reminder_language: Mutex<String>,
     theme: Mutex<String>,
+    reminder_focus_mode: Mutex<String>,
+    standup_cooldown_secs: Mutex<u64>,
+    schedule: Mutex<Vec<ScheduleSlot>>,
+    export_schedule: Mutex<ExportScheduleConfig>,
+    leaderboard: Mutex<LeaderboardConfig>,
+    google_sheets: Mutex<GoogleSheetsConfig>,
+    markdown_journal: Mutex<MarkdownJournalConfig>,
+    hooks: Mutex<Vec<HookConfig>>,
+    /// Recent hook runs, newest last.
+    hook_runs: Mutex<Vec<HookRun>>,
+    local_api: Mutex<LocalApiConfig>,
+    status_file: Mutex<StatusFileConfig>,
+    handoff: Mutex<HandoffConfig>,
+    /// Last input on this device as far as handoff has seen.
+    handoff_last_active: Mutex<i64>,
+    /// The other devices as of the last handoff round.
+    handoff_peers: Mutex<Vec<HandoffPeer>>,
+    /// Loaded from api_tokens.json, not the config.
+    api_tokens: Mutex<Vec<StoredToken>>,
+    tick_event_secs: Mutex<u64>,
+    tick_resolution: Mutex<TickResolution>,
+    window_prefs: Mutex<HashMap<String, WindowPrefs>>,
+    window_prefs_save_pending: Mutex<bool>,
+    show_splash: Mutex<bool>,
+    open_settings_on_launch: Mutex<bool>,
+    enabled_tip_packs: Mutex<Vec<String>>,
+    /// Merged tips of the enabled packs, rebuilt whenever packs or the reminder language change.
+    tip_pool: Mutex<Vec<ShownTip>>,
+    enabled_plugins: Mutex<Vec<String>>,
+    /// Tips contributed by plugins at their last load.
+    plugin_tips: Mutex<Vec<ShownTip>>,
+    /// Plugin id to why it failed, since its last load.
+    plugin_errors: Mutex<HashMap<String, String>>,
+    day_summary: Mutex<DaySummaryConfig>,
+    /// A day summary was posted and the next tray click should open the stats window.
+    day_summary_pending_click: Mutex<bool>,
+    monthly_goal: Mutex<MonthlyGoal>,
+    holidays: Mutex<HolidayConfig>,
+    weekend: Mutex<WeekendOverride>,
+    pre_warning_minutes: Mutex<u64>,
+    pre_warning_sent: Mutex<bool>,
+    sitting_budget_minutes: Mutex<u64>,
+    /// Analytics date the budget alert was last posted for.
+    budget_alert_date: Mutex<Option<String>>,
+    sit_stand_target: Mutex<Option<f64>>,
+    window_nudge_minutes: Mutex<u64>,
+    sleep_policy: Mutex<SleepPolicy>,
+    window_focus: Mutex<HashMap<String, window_focus::WindowFocus>>,
+    config_history: Mutex<Vec<ConfigChange>>,
+    /// The config as last saved, for telling what the next save changes.
+    config_baseline: Mutex<Option<serde_json::Value>>,
+    /// What the next config change is put down to, if not a settings command.
+    config_change_source: Mutex<Option<String>>,
+    /// Whether the day was below the sit:stand target at the last tick.
+    sit_stand_below: Mutex<bool>,
+    sit_stand_alerted_at: Mutex<Option<i64>>,
+    tray_tooltip: Mutex<String>,
+    tray_icons: Mutex<TrayIconConfig>,
+    input_activity_enabled: Mutex<bool>,
+    blocking_apps: Mutex<BlockingApps>,
+    /// The listed app found running by the last scan; holds the countdown.
+    blocking_app: Mutex<Option<String>>,
+    mute_when_mic_in_use: Mutex<bool>,
+    /// As of the last microphone check; always false while muting is off.
+    mic_in_use: Mutex<bool>,
+    activity_buckets: Mutex<Vec<ActivityBucket>>,
+    /// Clicks on a reminder this soon after it appears are ignored.
+    ack_grace_ms: Mutex<u64>,
+    /// A reminder left unanswered this long is logged as sedentary.
+    sedentary_threshold_secs: Mutex<u64>,
+    reminder_all_monitors: Mutex<bool>,
+    reminder_anchor: Mutex<String>,
+    free_skips_per_day: Mutex<Option<u32>>,
+    repeat_when_dismissed: Mutex<RepeatConfig>,
+    reminder_presentation: Mutex<PresentationConfig>,
+    /// The open reminder is on the click-through banner.
+    reminder_overlay: Mutex<bool>,
+    /// Countdown offset of the follow-up to a dismissal, while one is pending.
+    repeat_due: Mutex<Option<u64>>,
+    /// Follow-ups fired in the last hour.
+    repeat_log: Mutex<Vec<i64>>,
+    desk_mode: Mutex<DeskModeConfig>,
+    challenge: Mutex<ChallengeConfig>,
+    /// The phrase the open reminder asks for, once picked.
+    challenge_phrase: Mutex<Option<String>>,
+    network_profiles: Mutex<Vec<NetworkProfile>>,
+    /// Wi-Fi network as of the last poll; only polled while profiles exist.
+    ssid: Mutex<Option<String>>,
+    /// Network profile switches; the last entry is the profile in effect.
+    profile_log: Mutex<Vec<ProfileChange>>,
+    /// Last time the timer loop completed a tick; watched by the supervisor.
+    timer_heartbeat: Mutex<Instant>,
+    /// When the sleeping timer loop plans to tick next.
+    timer_next_wake: Mutex<Instant>,
+    timer_wake: tokio::sync::Notify,
+    timer_restarts: Mutex<u32>,
+    storage_status: Mutex<StorageStatus>,
+    delivery_stats: Mutex<DeliveryStats>,
+    reminder_window_failures: Mutex<delivery::WindowFailures>,
+    event_seq: Mutex<u64>,
+    analytics_event_pending: Mutex<bool>,
+    /// Held across journal appends and across a store write plus journal
+    /// truncation, so an event is always in one or the other.
+    journal_lock: Mutex<()>,
+    journal_compaction_pending: Mutex<bool>,
+    /// Held for the life of the process; see `store_lock::acquire`.
+    store_lock: Mutex<Option<fs::File>>,
+    /// Set when another process holds the lock; every write is refused.
+    store_lock_error: Mutex<Option<String>>,
+    /// Reminder windows created for the current reminder, primary first.
+    reminder_windows: Mutex<Vec<ReminderWindowInfo>>,
+    last_rollover_date: Mutex<Option<String>>,
+    day_start_hour: Mutex<u32>,
+    week_start: Mutex<String>,
+    weekly_mode: Mutex<String>,
+    export_date_format: Mutex<String>,
+    export_diff: Mutex<bool>,
+    export_filename_template: Mutex<String>,
+    /// Opt-in: log AC/battery switches next to the analytics events.
+    record_power_source: Mutex<bool>,
+    power_log: Mutex<Vec<PowerChange>>,
+    /// Session tag switches; the last entry is the current tag.
+    tag_log: Mutex<Vec<TagChange>>,
+    /// Idle, locked and paused spans for the day timeline.
+    hold_log: Mutex<Vec<HoldChange>>,
+    interactions: Mutex<Vec<Interaction>>,
+    skip_events: Mutex<Vec<i64>>,
+    desk_log: Mutex<Vec<DeskChange>>,
+    tip_ratings: Mutex<Vec<TipRating>>,
+    /// The input sampler last saw no input for `timeline::IDLE_AFTER_SECS`.
+    input_idle: Mutex<bool>,
+    marked_days: Mutex<Vec<MarkedDay>>,
+    last_seen_version: Mutex<Option<String>>,
+    /// Set when the store was moved off the OS default with `move_data_dir`.
+    data_dir: Mutex<Option<PathBuf>>,
+    /// Observer mode: nothing is written to disk.
+    read_only: Mutex<bool>,
+    /// Started with `--dev-intervals`.
+    dev_intervals_from_args: Mutex<bool>,
+    /// The hidden `dev_intervals` config key.
+    dev_intervals: Mutex<bool>,
+    /// Release notes for the update this launch follows, if any.
+    whats_new: Mutex<Option<WhatsNew>>,
     last_tip_index: Mutex<Option<usize>>,
     active_reminder_id: Mutex<u64>,
     active_reminder_start_ts: Mutex<Option<i64>>,
@@ -121,10 +734,172 @@ struct AppState {
     active_reminder_interval_secs: Mutex<u64>,
     active_reminder_logged_sedentary: Mutex<bool>,
     active_reminder_tip: Mutex<String>,
+    active_reminder_sound: Mutex<Option<String>>,
+    active_reminder_reason: Mutex<Option<ReminderReason>>,
+    /// Physical height of the reminder windows, grown for long tips.
+    reminder_height: Mutex<i32>,
+    reminder_history: Mutex<Vec<ReminderHistoryEntry>>,
+    /// A finished focus session pulled the countdown forward from this much
+    /// sitting time.
+    focus_break_due: Mutex<Option<u64>>,
+    accessibility: Mutex<AccessibilityPrefs>,
+    power: Mutex<PowerState>,
+    screen_sharing: Mutex<bool>,
+    previous_foreground: Mutex<Option<focus::ForegroundWindow>>,
+    focus_session: Mutex<Option<FocusSession>>,
+    focus_events: Mutex<Vec<FocusRecord>>,
+    /// Started with `--safe-mode`: defaults only, nothing written back to disk.
+    safe_mode: bool,
+    launched_ts: i64,
+}
+
+impl AppState {
+    fn new(safe_mode: bool) -> Self {
+        AppState {
+            interval: Mutex::new(DEFAULT_INTERVAL_MINUTES * 60),
+            elapsed: Mutex::new(0),
+            last_interval_change: Mutex::new(Instant::now()),
+            interval_today: Mutex::new(None),
+            reminder_preview: Mutex::new(None),
+            display_layout: Mutex::new(None),
+            reminder_events: Mutex::new(Vec::new()),
+            standup_events: Mutex::new(Vec::new()),
+            reminder_visible: Mutex::new(false),
+            language: Mutex::new("en".to_string()),
+            language_follows_system: Mutex::new(false),
+            reminder_language: Mutex::new("en".to_string()),
+            theme: Mutex::new("night".to_string()),
+            reminder_focus_mode: Mutex::new(default_reminder_focus_mode()),
+            standup_cooldown_secs: Mutex::new(DEFAULT_STANDUP_COOLDOWN_SECS),
+            schedule: Mutex::new(schedule::default_schedule()),
+            export_schedule: Mutex::new(ExportScheduleConfig::default()),
+            leaderboard: Mutex::new(LeaderboardConfig::default()),
+            google_sheets: Mutex::new(GoogleSheetsConfig::default()),
+            markdown_journal: Mutex::new(MarkdownJournalConfig::default()),
+            hooks: Mutex::new(Vec::new()),
+            hook_runs: Mutex::new(Vec::new()),
+            local_api: Mutex::new(LocalApiConfig::default()),
+            status_file: Mutex::new(StatusFileConfig::default()),
+            handoff: Mutex::new(HandoffConfig::default()),
+            handoff_last_active: Mutex::new(0),
+            handoff_peers: Mutex::new(Vec::new()),
+            api_tokens: Mutex::new(Vec::new()),
+            tick_event_secs: Mutex::new(timers::DEFAULT_TICK_EVENT_SECS),
+            tick_resolution: Mutex::new(TickResolution::default()),
+            window_prefs: Mutex::new(HashMap::new()),
+            window_prefs_save_pending: Mutex::new(false),
+            show_splash: Mutex::new(true),
+            open_settings_on_launch: Mutex::new(true),
+            enabled_tip_packs: Mutex::new(tip_packs::default_enabled_packs()),
+            tip_pool: Mutex::new(Vec::new()),
+            enabled_plugins: Mutex::new(Vec::new()),
+            plugin_tips: Mutex::new(Vec::new()),
+            plugin_errors: Mutex::new(HashMap::new()),
+            day_summary: Mutex::new(DaySummaryConfig::default()),
+            day_summary_pending_click: Mutex::new(false),
+            monthly_goal: Mutex::new(MonthlyGoal::default()),
+            holidays: Mutex::new(HolidayConfig::default()),
+            weekend: Mutex::new(WeekendOverride::default()),
+            pre_warning_minutes: Mutex::new(0),
+            pre_warning_sent: Mutex::new(false),
+            sitting_budget_minutes: Mutex::new(0),
+            budget_alert_date: Mutex::new(None),
+            sit_stand_target: Mutex::new(None),
+            window_nudge_minutes: Mutex::new(default_window_nudge_minutes()),
+            sleep_policy: Mutex::new(SleepPolicy::default()),
+            window_focus: Mutex::new(HashMap::new()),
+            config_history: Mutex::new(Vec::new()),
+            config_baseline: Mutex::new(None),
+            config_change_source: Mutex::new(None),
+            sit_stand_below: Mutex::new(false),
+            sit_stand_alerted_at: Mutex::new(None),
+            tray_tooltip: Mutex::new(String::new()),
+            tray_icons: Mutex::new(TrayIconConfig::default()),
+            input_activity_enabled: Mutex::new(false),
+            mute_when_mic_in_use: Mutex::new(false),
+            mic_in_use: Mutex::new(false),
+            blocking_apps: Mutex::new(BlockingApps::default()),
+            blocking_app: Mutex::new(None),
+            reminder_all_monitors: Mutex::new(false),
+            reminder_anchor: Mutex::new(default_reminder_anchor()),
+            free_skips_per_day: Mutex::new(None),
+            repeat_when_dismissed: Mutex::new(RepeatConfig::default()),
+            reminder_presentation: Mutex::new(PresentationConfig::default()),
+            reminder_overlay: Mutex::new(false),
+            repeat_due: Mutex::new(None),
+            repeat_log: Mutex::new(Vec::new()),
+            desk_mode: Mutex::new(DeskModeConfig::default()),
+            challenge: Mutex::new(ChallengeConfig::default()),
+            challenge_phrase: Mutex::new(None),
+            network_profiles: Mutex::new(Vec::new()),
+            ssid: Mutex::new(None),
+            profile_log: Mutex::new(Vec::new()),
+            timer_heartbeat: Mutex::new(Instant::now()),
+            timer_next_wake: Mutex::new(Instant::now()),
+            timer_wake: tokio::sync::Notify::new(),
+            timer_restarts: Mutex::new(0),
+            storage_status: Mutex::new(StorageStatus::default()),
+            delivery_stats: Mutex::new(DeliveryStats::default()),
+            reminder_window_failures: Mutex::new(delivery::WindowFailures::default()),
+            event_seq: Mutex::new(0),
+            analytics_event_pending: Mutex::new(false),
+            journal_lock: Mutex::new(()),
+            journal_compaction_pending: Mutex::new(false),
+            store_lock: Mutex::new(None),
+            store_lock_error: Mutex::new(None),
+            reminder_windows: Mutex::new(Vec::new()),
+            last_rollover_date: Mutex::new(None),
+            day_start_hour: Mutex::new(0),
+            week_start: Mutex::new(default_week_start()),
+            weekly_mode: Mutex::new(default_weekly_mode()),
+            export_date_format: Mutex::new(default_export_date_format()),
+            export_diff: Mutex::new(false),
+            export_filename_template: Mutex::new(String::new()),
+            record_power_source: Mutex::new(false),
+            last_seen_version: Mutex::new(None),
+            data_dir: Mutex::new(None),
+            read_only: Mutex::new(false),
+            dev_intervals_from_args: Mutex::new(false),
+            dev_intervals: Mutex::new(false),
+            whats_new: Mutex::new(None),
+            power_log: Mutex::new(Vec::new()),
+            tag_log: Mutex::new(Vec::new()),
+            hold_log: Mutex::new(Vec::new()),
+            interactions: Mutex::new(Vec::new()),
+            skip_events: Mutex::new(Vec::new()),
+            desk_log: Mutex::new(Vec::new()),
+            tip_ratings: Mutex::new(Vec::new()),
+            input_idle: Mutex::new(false),
+            marked_days: Mutex::new(Vec::new()),
+            activity_buckets: Mutex::new(Vec::new()),
+            ack_grace_ms: Mutex::new(DEFAULT_ACK_GRACE_MS),
+            sedentary_threshold_secs: Mutex::new(DEFAULT_SEDENTARY_THRESHOLD_SECS),
+            last_tip_index: Mutex::new(None),
+            active_reminder_id: Mutex::new(0),
+            active_reminder_start_ts: Mutex::new(None),
+            active_reminder_shown_at: Mutex::new(None),
+            active_reminder_interval_secs: Mutex::new(DEFAULT_INTERVAL_MINUTES * 60),
+            active_reminder_logged_sedentary: Mutex::new(false),
+            active_reminder_tip: Mutex::new("Time to stand up and stretch.".to_string()),
+            active_reminder_sound: Mutex::new(None),
+            active_reminder_reason: Mutex::new(None),
+            reminder_height: Mutex::new(REMINDER_HEIGHT),
+            reminder_history: Mutex::new(Vec::new()),
+            focus_break_due: Mutex::new(None),
+            accessibility: Mutex::new(AccessibilityPrefs::default()),
+            power: Mutex::new(PowerState::default()),
+            screen_sharing: Mutex::new(false),
+            previous_foreground: Mutex::new(None),
+            focus_session: Mutex::new(None),
+            focus_events: Mutex::new(Vec::new()),
+            safe_mode,
+            launched_ts: now_ts(),
+        }
+    }
 }
 
 fn now_ts() -> i64 {
-    Local::now().timestamp()
+    clock::now().timestamp()
 }
 
 fn prune_old_events(reminders: &mut Vec<ReminderRecord>, standups: &mut Vec<i64>, now: i64) {
@@ -133,6 +908,13 @@ fn prune_old_events(reminders: &mut Vec<ReminderRecord>, standups: &mut Vec<i64>
     standups.retain(|ts| *ts >= cutoff);
 }
 
+/// Drop entries of a change log (sorted by `ts`) before `cutoff`, keeping
+/// the one still in effect at it.
+fn prune_changes<T>(log: &mut Vec<T>, cutoff: i64, ts: impl Fn(&T) -> i64) {
+    let in_effect = log.partition_point(|change| ts(change) <= cutoff);
+    log.drain(..in_effect.saturating_sub(1));
+}
+
 fn normalize_period(period: &str) -> &'static str {
     match period {
         "weekly" => "weekly",
@@ -141,46 +923,90 @@ fn normalize_period(period: &str) -> &'static str {
     }
 }
 
-fn local_midnight_ts(date: chrono::NaiveDate) -> i64 {
-    let Some(naive) = date.and_hms_opt(0, 0, 0) else {
-        return Local::now().timestamp();
+/// Start of `date` as an analytics day, which begins at `day_start_hour`
+/// rather than midnight for people whose workday crosses it.
+fn day_start_ts(date: chrono::NaiveDate, day_start_hour: u32) -> i64 {
+    let Some(naive) = date.and_hms_opt(day_start_hour.min(23), 0, 0) else {
+        return clock::now().timestamp();
     };
-    Local
-        .from_local_datetime(&naive)
-        .single()
-        .or_else(|| Local.from_local_datetime(&naive).earliest())
-        .or_else(|| Local.from_local_datetime(&naive).latest())
+    // Where DST skips the boundary the day starts at the first valid hour.
+    (0..3)
+        .map(|hours| naive + ChronoDuration::hours(hours))
+        .find_map(|candidate| Local.from_local_datetime(&candidate).earliest())
         .map(|dt| dt.timestamp())
-        .unwrap_or_else(|| Local::now().timestamp())
+        .unwrap_or_else(|| clock::now().timestamp())
+}
+
+/// The analytics day `now` belongs to: before `day_start_hour` it is still
+/// the previous day.
+fn analytics_date(now: chrono::DateTime<Local>, day_start_hour: u32) -> chrono::NaiveDate {
+    (now - ChronoDuration::hours(i64::from(day_start_hour))).date_naive()
+}
+
+fn normalize_week_start(day: &str) -> String {
+    match day {
+        "sunday" | "saturday" => day.to_string(),
+        _ => "monday".to_string(),
+    }
+}
+
+fn normalize_weekly_mode(mode: &str) -> String {
+    if mode == "calendar" {
+        "calendar".to_string()
+    } else {
+        "rolling".to_string()
+    }
+}
+
+/// How days and weeks are cut for per-period analytics.
+#[derive(Clone, Copy)]
+struct PeriodOptions {
+    day_start_hour: u32,
+    week_start: chrono::Weekday,
+    /// "Weekly" is the current calendar week instead of the last 7 days.
+    calendar_week: bool,
+}
+
+fn period_options(state: &AppState) -> PeriodOptions {
+    let week_start = match state.week_start.lock().unwrap().as_str() {
+        "sunday" => chrono::Weekday::Sun,
+        "saturday" => chrono::Weekday::Sat,
+        _ => chrono::Weekday::Mon,
+    };
+    PeriodOptions {
+        day_start_hour: *state.day_start_hour.lock().unwrap(),
+        week_start,
+        calendar_week: *state.weekly_mode.lock().unwrap() == "calendar",
+    }
 }
 
-fn period_start_ts(period: &str, now: chrono::DateTime<Local>) -> i64 {
+fn period_start_ts(period: &str, now: chrono::DateTime<Local>, opts: &PeriodOptions) -> i64 {
     let p = normalize_period(period);
+    let day_start_hour = opts.day_start_hour;
+    let today = analytics_date(now, day_start_hour);
     match p {
-        "weekly" => local_midnight_ts(now.date_naive() - ChronoDuration::days(6)),
+        "weekly" if opts.calendar_week => {
+            let into_week = (7 + today.weekday().num_days_from_monday()
+                - opts.week_start.num_days_from_monday())
+                % 7;
+            day_start_ts(today - ChronoDuration::days(i64::from(into_week)), day_start_hour)
+        }
+        "weekly" => day_start_ts(today - ChronoDuration::days(6), day_start_hour),
         "monthly" => {
-            let first = chrono::NaiveDate::from_ymd_opt(now.year(), now.month(), 1)
-                .unwrap_or_else(|| now.date_naive());
-            local_midnight_ts(first)
+            let first =
+                chrono::NaiveDate::from_ymd_opt(today.year(), today.month(), 1).unwrap_or(today);
+            day_start_ts(first, day_start_hour)
         }
-        _ => local_midnight_ts(now.date_naive()),
+        _ => day_start_ts(today, day_start_hour),
     }
 }
 
 fn config_path(handle: &AppHandle) -> Option<PathBuf> {
-    handle
-        .path()
-        .app_data_dir()
-        .ok()
-        .map(|dir| dir.join("config.json"))
+    data_dir::dir(handle).map(|dir| dir.join("config.json"))
 }
 
 fn analytics_path(handle: &AppHandle) -> Option<PathBuf> {
-    handle
-        .path()
-        .app_data_dir()
-        .ok()
-        .map(|dir| dir.join("analytics.json"))
+    data_dir::dir(handle).map(|dir| dir.join("analytics.json"))
 }
 
 fn legacy_app_data_dir(handle: &AppHandle) -> Option<PathBuf> {
@@ -196,7 +1022,17 @@ fn export_dir(handle: &AppHandle) -> Option<PathBuf> {
         .download_dir()
         .ok()
         .or_else(|| handle.path().desktop_dir().ok())
-        .or_else(|| handle.path().app_data_dir().ok())
+        .or_else(|| data_dir::dir(handle))
+}
+
+/// Write an export the user asked for, creating its folder. Refused with
+/// [`read_only::ERROR`] in read-only mode.
+fn write_export(state: &AppState, path: &Path, contents: impl AsRef<[u8]>) -> Result<(), String> {
+    read_only::check(state)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("create folder failed: {}", e))?;
+    }
+    fs::write(path, contents).map_err(|e| format!("write failed: {}", e))
 }
 
 fn read_config(handle: &AppHandle) -> AppConfigFile {
@@ -216,67 +1052,254 @@ fn read_config(handle: &AppHandle) -> AppConfigFile {
     }
     AppConfigFile {
         interval_minutes: DEFAULT_INTERVAL_MINUTES,
+        dev_intervals: false,
         language: default_language(),
         reminder_language: default_reminder_language(),
         theme: default_theme(),
+        reminder_focus_mode: default_reminder_focus_mode(),
+        standup_cooldown_secs: default_standup_cooldown_secs(),
+        schedule: schedule::default_schedule(),
+        export_schedule: ExportScheduleConfig::default(),
+        leaderboard: LeaderboardConfig::default(),
+        google_sheets: GoogleSheetsConfig::default(),
+        markdown_journal: MarkdownJournalConfig::default(),
+        hooks: Vec::new(),
+        local_api: LocalApiConfig::default(),
+        status_file: StatusFileConfig::default(),
+        handoff: HandoffConfig::default(),
+        tick_event_secs: default_tick_event_secs(),
+        tick_resolution: TickResolution::default(),
+        window_prefs: HashMap::new(),
+        show_splash: true,
+        open_settings_on_launch: true,
+        enabled_tip_packs: tip_packs::default_enabled_packs(),
+        enabled_plugins: Vec::new(),
+        day_summary: DaySummaryConfig::default(),
+        monthly_goal: MonthlyGoal::default(),
+        holidays: HolidayConfig::default(),
+        weekend: WeekendOverride::default(),
+        pre_warning_minutes: 0,
+        sitting_budget_minutes: 0,
+        window_nudge_minutes: default_window_nudge_minutes(),
+        sit_stand_target: None,
+        sleep_policy: SleepPolicy::default(),
+        input_activity_enabled: false,
+        mute_when_mic_in_use: false,
+        blocking_apps: BlockingApps::default(),
+        tray_icons: TrayIconConfig::default(),
+        ack_grace_ms: default_ack_grace_ms(),
+        sedentary_threshold_secs: default_sedentary_threshold_secs(),
+        reminder_all_monitors: false,
+        reminder_anchor: default_reminder_anchor(),
+        free_skips_per_day: None,
+        repeat_when_dismissed: RepeatConfig::default(),
+        reminder_presentation: PresentationConfig::default(),
+        desk_mode: DeskModeConfig::default(),
+        challenge: ChallengeConfig::default(),
+        network_profiles: Vec::new(),
+        last_rollover_date: None,
+        day_start_hour: 0,
+        week_start: default_week_start(),
+        weekly_mode: default_weekly_mode(),
+        export_date_format: default_export_date_format(),
+        export_diff: false,
+        export_filename_template: String::new(),
+        record_power_source: false,
+        last_seen_version: None,
+        read_only: false,
+    }
+}
+
+fn config_from_state(state: &AppState) -> AppConfigFile {
+    AppConfigFile {
+        interval_minutes: (*state.interval.lock().unwrap()) / 60,
+        dev_intervals: *state.dev_intervals.lock().unwrap(),
+        language: language_preference(state),
+        reminder_language: state.reminder_language.lock().unwrap().clone(),
+        theme: state.theme.lock().unwrap().clone(),
+        reminder_focus_mode: state.reminder_focus_mode.lock().unwrap().clone(),
+        standup_cooldown_secs: *state.standup_cooldown_secs.lock().unwrap(),
+        schedule: state.schedule.lock().unwrap().clone(),
+        export_schedule: state.export_schedule.lock().unwrap().clone(),
+        leaderboard: state.leaderboard.lock().unwrap().clone(),
+        google_sheets: state.google_sheets.lock().unwrap().clone(),
+        markdown_journal: state.markdown_journal.lock().unwrap().clone(),
+        hooks: state.hooks.lock().unwrap().clone(),
+        local_api: state.local_api.lock().unwrap().clone(),
+        status_file: state.status_file.lock().unwrap().clone(),
+        handoff: state.handoff.lock().unwrap().clone(),
+        tick_event_secs: *state.tick_event_secs.lock().unwrap(),
+        tick_resolution: state.tick_resolution.lock().unwrap().clone(),
+        window_prefs: state.window_prefs.lock().unwrap().clone(),
+        show_splash: *state.show_splash.lock().unwrap(),
+        open_settings_on_launch: *state.open_settings_on_launch.lock().unwrap(),
+        enabled_tip_packs: state.enabled_tip_packs.lock().unwrap().clone(),
+        enabled_plugins: state.enabled_plugins.lock().unwrap().clone(),
+        day_summary: state.day_summary.lock().unwrap().clone(),
+        monthly_goal: state.monthly_goal.lock().unwrap().clone(),
+        holidays: state.holidays.lock().unwrap().clone(),
+        weekend: state.weekend.lock().unwrap().clone(),
+        pre_warning_minutes: *state.pre_warning_minutes.lock().unwrap(),
+        sitting_budget_minutes: *state.sitting_budget_minutes.lock().unwrap(),
+        window_nudge_minutes: *state.window_nudge_minutes.lock().unwrap(),
+        sit_stand_target: *state.sit_stand_target.lock().unwrap(),
+        sleep_policy: *state.sleep_policy.lock().unwrap(),
+        input_activity_enabled: *state.input_activity_enabled.lock().unwrap(),
+        mute_when_mic_in_use: *state.mute_when_mic_in_use.lock().unwrap(),
+        blocking_apps: state.blocking_apps.lock().unwrap().clone(),
+        tray_icons: state.tray_icons.lock().unwrap().clone(),
+        ack_grace_ms: *state.ack_grace_ms.lock().unwrap(),
+        sedentary_threshold_secs: *state.sedentary_threshold_secs.lock().unwrap(),
+        reminder_all_monitors: *state.reminder_all_monitors.lock().unwrap(),
+        reminder_anchor: state.reminder_anchor.lock().unwrap().clone(),
+        free_skips_per_day: *state.free_skips_per_day.lock().unwrap(),
+        repeat_when_dismissed: state.repeat_when_dismissed.lock().unwrap().clone(),
+        reminder_presentation: state.reminder_presentation.lock().unwrap().clone(),
+        desk_mode: state.desk_mode.lock().unwrap().clone(),
+        challenge: state.challenge.lock().unwrap().clone(),
+        network_profiles: state.network_profiles.lock().unwrap().clone(),
+        last_rollover_date: state.last_rollover_date.lock().unwrap().clone(),
+        day_start_hour: *state.day_start_hour.lock().unwrap(),
+        week_start: state.week_start.lock().unwrap().clone(),
+        weekly_mode: state.weekly_mode.lock().unwrap().clone(),
+        export_date_format: state.export_date_format.lock().unwrap().clone(),
+        export_diff: *state.export_diff.lock().unwrap(),
+        export_filename_template: state.export_filename_template.lock().unwrap().clone(),
+        record_power_source: *state.record_power_source.lock().unwrap(),
+        last_seen_version: state.last_seen_version.lock().unwrap().clone(),
+        read_only: *state.read_only.lock().unwrap(),
     }
 }
 
-fn save_config(
-    handle: &AppHandle,
-    minutes: u64,
-    language: &str,
-    reminder_language: &str,
-    theme: &str,
-) {
+/// Persist the current in-memory settings. Callers must not hold any of the
+/// settings locks while calling this. Fails only with [`read_only::ERROR`];
+/// safe mode skips the write, and write errors go to the health report.
+fn save_config(handle: &AppHandle, state: &AppState) -> Result<(), String> {
+    // Most settings can move the timer's next deadline.
+    timers::wake(state);
+    read_only::check(state)?;
+    if state.safe_mode {
+        return Ok(());
+    }
+    let cfg = config_from_state(state);
+    if config_history::record(state, &cfg, now_ts()) {
+        config_history::save(handle, state);
+    }
     if let Some(path) = config_path(handle) {
         if let Some(parent) = path.parent() {
             let _ = fs::create_dir_all(parent);
         }
-        let cfg = AppConfigFile {
-            interval_minutes: minutes,
-            language: language.to_string(),
-            reminder_language: reminder_language.to_string(),
-            theme: theme.to_string(),
-        };
         if let Ok(json) = serde_json::to_string_pretty(&cfg) {
-            let _ = fs::write(path, json);
+            let result = data_dir::write(state, &path, json);
+            health::record_write(state, &path, result);
         }
     }
+    Ok(())
 }
 
-fn load_config(handle: &AppHandle, state: &AppState) {
-    let cfg = read_config(handle);
-    let normalized_minutes = sanitize_interval_minutes(cfg.interval_minutes);
-    let normalized_language = if cfg.language == "zh-CN" {
-        "zh-CN".to_string()
-    } else {
-        "en".to_string()
-    };
-    let normalized_reminder_language = if cfg.reminder_language == "zh-CN" {
-        "zh-CN".to_string()
-    } else {
-        "en".to_string()
-    };
-    let normalized_theme = if cfg.theme == "day" {
-        "day".to_string()
-    } else {
-        "night".to_string()
+/// Normalize a config file (possibly from an older version) into the state.
+fn apply_config(state: &AppState, cfg: AppConfigFile) {
+    *state.dev_intervals.lock().unwrap() = cfg.dev_intervals;
+    let normalized_minutes = sanitize_interval_minutes(state, cfg.interval_minutes);
+    let follow_system = cfg.language == "system";
+    let normalized_language = match cfg.language.as_str() {
+        "system" => detect_system_language(),
+        language => languages::normalize(language),
     };
+    let normalized_reminder_language = languages::normalize(&cfg.reminder_language);
+    let normalized_theme = normalize_theme(&cfg.theme);
 
     *state.interval.lock().unwrap() = normalized_minutes * 60;
-    *state.language.lock().unwrap() = normalized_language.clone();
-    *state.reminder_language.lock().unwrap() = normalized_reminder_language.clone();
-    *state.theme.lock().unwrap() = normalized_theme.clone();
-
-    // Persist normalized/migrated config into the current app data path.
-    save_config(
-        handle,
-        normalized_minutes,
-        &normalized_language,
-        &normalized_reminder_language,
-        &normalized_theme,
-    );
+    *state.language.lock().unwrap() = normalized_language;
+    *state.language_follows_system.lock().unwrap() = follow_system;
+    *state.reminder_language.lock().unwrap() = normalized_reminder_language;
+    *state.theme.lock().unwrap() = normalized_theme;
+    *state.reminder_focus_mode.lock().unwrap() =
+        normalize_reminder_focus_mode(&cfg.reminder_focus_mode);
+    *state.standup_cooldown_secs.lock().unwrap() =
+        cfg.standup_cooldown_secs.min(MAX_STANDUP_COOLDOWN_SECS);
+    *state.schedule.lock().unwrap() = schedule::sanitize_schedule(cfg.schedule);
+    *state.export_schedule.lock().unwrap() = scheduled_export::sanitize(cfg.export_schedule);
+    *state.leaderboard.lock().unwrap() = leaderboard::sanitize(cfg.leaderboard);
+    *state.google_sheets.lock().unwrap() = google_sheets::sanitize(cfg.google_sheets);
+    *state.markdown_journal.lock().unwrap() = markdown_journal::sanitize(cfg.markdown_journal);
+    *state.hooks.lock().unwrap() = hooks::sanitize(cfg.hooks);
+    *state.local_api.lock().unwrap() = local_api::sanitize(cfg.local_api);
+    *state.status_file.lock().unwrap() = status_file::sanitize(cfg.status_file);
+    *state.handoff.lock().unwrap() = handoff::sanitize(cfg.handoff);
+    *state.tick_event_secs.lock().unwrap() =
+        cfg.tick_event_secs.clamp(1, timers::MAX_TICK_EVENT_SECS);
+    *state.tick_resolution.lock().unwrap() = timers::sanitize_tick_resolution(cfg.tick_resolution);
+    *state.window_prefs.lock().unwrap() = cfg
+        .window_prefs
+        .into_iter()
+        .filter(|(label, _)| window_manager::is_managed(label))
+        .collect();
+    *state.show_splash.lock().unwrap() = cfg.show_splash;
+    *state.open_settings_on_launch.lock().unwrap() = cfg.open_settings_on_launch;
+    *state.enabled_tip_packs.lock().unwrap() = cfg.enabled_tip_packs;
+    *state.enabled_plugins.lock().unwrap() = cfg.enabled_plugins;
+    *state.day_summary.lock().unwrap() = reports::sanitize(cfg.day_summary);
+    *state.monthly_goal.lock().unwrap() = goals::sanitize(cfg.monthly_goal);
+    *state.holidays.lock().unwrap() = holidays::sanitize(cfg.holidays);
+    *state.weekend.lock().unwrap() = sanitize_weekend_override(state, cfg.weekend);
+    *state.pre_warning_minutes.lock().unwrap() =
+        cfg.pre_warning_minutes.min(timers::MAX_PRE_WARNING_MINUTES);
+    *state.sitting_budget_minutes.lock().unwrap() =
+        cfg.sitting_budget_minutes.min(budget::MAX_BUDGET_MINUTES);
+    *state.sit_stand_target.lock().unwrap() = ratio::sanitize_target(cfg.sit_stand_target);
+    *state.window_nudge_minutes.lock().unwrap() =
+        cfg.window_nudge_minutes.min(window_focus::MAX_NUDGE_MINUTES);
+    *state.sleep_policy.lock().unwrap() = cfg.sleep_policy;
+    *state.input_activity_enabled.lock().unwrap() = cfg.input_activity_enabled;
+    *state.mute_when_mic_in_use.lock().unwrap() = cfg.mute_when_mic_in_use;
+    *state.blocking_apps.lock().unwrap() = activity::sanitize_blocking_apps(cfg.blocking_apps);
+    *state.tray_icons.lock().unwrap() = tray_icons::sanitize(cfg.tray_icons);
+    *state.ack_grace_ms.lock().unwrap() = cfg.ack_grace_ms.min(MAX_ACK_GRACE_MS);
+    *state.sedentary_threshold_secs.lock().unwrap() =
+        normalize_sedentary_threshold_secs(cfg.sedentary_threshold_secs);
+    *state.reminder_all_monitors.lock().unwrap() = cfg.reminder_all_monitors;
+    *state.reminder_anchor.lock().unwrap() = reminder_windows::normalize_anchor(&cfg.reminder_anchor);
+    *state.free_skips_per_day.lock().unwrap() =
+        cfg.free_skips_per_day.map(|n| n.min(skips::MAX_FREE_SKIPS));
+    *state.repeat_when_dismissed.lock().unwrap() = repeats::sanitize(cfg.repeat_when_dismissed);
+    *state.reminder_presentation.lock().unwrap() = overlay::sanitize(cfg.reminder_presentation);
+    *state.desk_mode.lock().unwrap() = desk::sanitize(cfg.desk_mode);
+    *state.challenge.lock().unwrap() = challenge::sanitize(cfg.challenge);
+    *state.network_profiles.lock().unwrap() = sanitize_network_profiles(state, cfg.network_profiles);
+    *state.last_rollover_date.lock().unwrap() = cfg.last_rollover_date;
+    *state.day_start_hour.lock().unwrap() = cfg.day_start_hour.min(MAX_DAY_START_HOUR);
+    *state.week_start.lock().unwrap() = normalize_week_start(&cfg.week_start);
+    *state.weekly_mode.lock().unwrap() = normalize_weekly_mode(&cfg.weekly_mode);
+    *state.export_date_format.lock().unwrap() =
+        normalize_export_date_format(&cfg.export_date_format);
+    *state.export_diff.lock().unwrap() = cfg.export_diff;
+    *state.export_filename_template.lock().unwrap() =
+        file_names::validate(&cfg.export_filename_template).unwrap_or_default();
+    *state.record_power_source.lock().unwrap() = cfg.record_power_source;
+    *state.last_seen_version.lock().unwrap() = cfg.last_seen_version;
+    // The command-line flag can't be overridden by the file.
+    if cfg.read_only {
+        *state.read_only.lock().unwrap() = true;
+    }
+}
+
+/// Silent while the microphone is in use and sound is held back for calls.
+fn notify(app: &AppHandle, title: &str, body: &str) {
+    if *app.state::<AppState>().mic_in_use.lock().unwrap() {
+        return notify_silent(app, title, body);
+    }
+    let _ = app.notification().builder().title(title).body(body).show();
+}
+
+fn notify_silent(app: &AppHandle, title: &str, body: &str) {
+    let _ = app
+        .notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .silent()
+        .show();
 }
 
 fn tray_label(lang: &str, en: &str, zh: &str) -> String {
@@ -295,6 +1318,31 @@ fn make_tray_menu(app: &AppHandle, lang: &str) -> tauri::Result<Menu<tauri::Wry>
         true,
         None::<&str>,
     )?;
+    let focus_running = app
+        .state::<AppState>()
+        .focus_session
+        .lock()
+        .unwrap()
+        .is_some();
+    let open_stats = MenuItem::with_id(
+        app,
+        "open_stats",
+        tray_label(lang, "Open Stats Window", "打开统计窗口"),
+        true,
+        None::<&str>,
+    )?;
+    let toggle_focus = MenuItem::with_id(
+        app,
+        "toggle_focus",
+        if focus_running {
+            tray_label(lang, "Stop Focus Session", "结束专注")
+        } else {
+            tray_label(lang, "Start Focus Session", "开始专注")
+        },
+        true,
+        None::<&str>,
+    )?;
+    let tag_menu = make_tag_submenu(app, lang)?;
     let quit = MenuItem::with_id(
         app,
         "quit",
@@ -302,16 +1350,69 @@ fn make_tray_menu(app: &AppHandle, lang: &str) -> tauri::Result<Menu<tauri::Wry>
         true,
         None::<&str>,
     )?;
-    Menu::with_items(app, &[&open_settings, &quit])
+    Menu::with_items(
+        app,
+        &[&open_settings, &open_stats, &toggle_focus, &tag_menu, &quit],
+    )
+}
+
+/// Recent tags, checked when current, plus "No Tag". New tags are typed in
+/// settings; a tray menu can't take text.
+fn make_tag_submenu(app: &AppHandle, lang: &str) -> tauri::Result<Submenu<tauri::Wry>> {
+    let state = app.state::<AppState>();
+    let current = tags::current(&state);
+    let mut items = Vec::new();
+    for tag in tags::recent(&state) {
+        let checked = current.as_deref() == Some(tag.as_str());
+        items.push(CheckMenuItem::with_id(
+            app,
+            format!("tag:{}", tag),
+            &tag,
+            true,
+            checked,
+            None::<&str>,
+        )?);
+    }
+    items.push(CheckMenuItem::with_id(
+        app,
+        "tag_none",
+        tray_label(lang, "No Tag", "无标签"),
+        true,
+        current.is_none(),
+        None::<&str>,
+    )?);
+    let refs: Vec<&dyn IsMenuItem<tauri::Wry>> =
+        items.iter().map(|item| item as &dyn IsMenuItem<tauri::Wry>).collect();
+    Submenu::with_items(app, tray_label(lang, "Session Tag", "会话标签"), true, &refs)
 }
 
-fn refresh_tray_menu(app: &AppHandle, lang: &str) {
-    if let (Some(tray), Ok(menu)) = (app.tray_by_id(TRAY_ID), make_tray_menu(app, lang)) {
+/// Switch the session tag from a command or the tray.
+fn apply_session_tag(app: &AppHandle, tag: Option<String>) -> Option<String> {
+    let state = app.state::<AppState>();
+    let tag = tags::normalize(tag.as_deref());
+    if tags::set_current(&state, now_ts(), tag.clone()) {
+        journal::compact_soon(app);
+        refresh_tray_menu(app);
+        events::emit(app, "session-tag-changed", tag.clone());
+    }
+    tag
+}
+
+fn refresh_tray_menu(app: &AppHandle) {
+    let lang = app.state::<AppState>().language.lock().unwrap().clone();
+    if let (Some(tray), Ok(menu)) = (app.tray_by_id(TRAY_ID), make_tray_menu(app, &lang)) {
         let _ = tray.set_menu(Some(menu));
     }
 }
 
-fn save_analytics(handle: &AppHandle, state: &AppState) {
+/// Like [`save_config`], for the events: fails only with
+/// [`read_only::ERROR`].
+fn save_analytics(handle: &AppHandle, state: &AppState) -> Result<(), String> {
+    read_only::check(state)?;
+    if state.safe_mode {
+        return Ok(());
+    }
+    let _journal = state.journal_lock.lock().unwrap();
     if let Some(path) = analytics_path(handle) {
         if let Some(parent) = path.parent() {
             let _ = fs::create_dir_all(parent);
@@ -320,16 +1421,57 @@ fn save_analytics(handle: &AppHandle, state: &AppState) {
         let mut reminders = state.reminder_events.lock().unwrap().clone();
         let mut standups = state.standup_events.lock().unwrap().clone();
         prune_old_events(&mut reminders, &mut standups, now);
+        let mut focus_sessions = state.focus_events.lock().unwrap().clone();
+        focus_sessions.retain(|r| r.ts >= now - RETENTION_SECS);
+        let mut activity = state.activity_buckets.lock().unwrap().clone();
+        activity.retain(|b| b.hour_ts >= now - RETENTION_SECS);
+        let mut power_log = state.power_log.lock().unwrap().clone();
+        power::prune(&mut power_log, now - RETENTION_SECS);
+        let mut tag_log = state.tag_log.lock().unwrap().clone();
+        tags::prune(&mut tag_log, now - RETENTION_SECS);
+        let mut profile_log = state.profile_log.lock().unwrap().clone();
+        network_profiles::prune(&mut profile_log, now - RETENTION_SECS);
+        let mut hold_log = state.hold_log.lock().unwrap().clone();
+        timeline::prune(&mut hold_log, now - RETENTION_SECS);
+        let mut interactions = state.interactions.lock().unwrap().clone();
+        interactions::prune(&mut interactions, now - RETENTION_SECS);
+        let mut skip_events = state.skip_events.lock().unwrap().clone();
+        skip_events.retain(|ts| *ts >= now - RETENTION_SECS);
+        let mut desk_log = state.desk_log.lock().unwrap().clone();
+        desk::prune(&mut desk_log, now - RETENTION_SECS);
+        let mut tip_ratings = state.tip_ratings.lock().unwrap().clone();
+        tip_ratings::prune(&mut tip_ratings, now - RETENTION_SECS);
+        let mut marked_days = state.marked_days.lock().unwrap().clone();
+        if let Some(cutoff) = Local.timestamp_opt(now - RETENTION_SECS, 0).single() {
+            marked_days::prune(&mut marked_days, cutoff.date_naive());
+        }
 
         let store = AnalyticsStore {
             reminder_events: reminders,
             standup_events: standups,
+            focus_sessions,
+            activity,
+            power_log,
+            tag_log,
+            profile_log,
+            hold_log,
+            marked_days,
+            interactions,
+            skip_events,
+            desk_log,
+            tip_ratings,
         };
 
         if let Ok(json) = serde_json::to_string_pretty(&store) {
-            let _ = fs::write(path, json);
+            let result = data_dir::write(state, &path, json);
+            let written = result.is_ok();
+            health::record_write(state, &path, result);
+            if written {
+                journal::truncate(handle);
+            }
         }
     }
+    Ok(())
 }
 
 fn load_analytics(handle: &AppHandle, state: &AppState) {
@@ -340,6 +1482,17 @@ fn load_analytics(handle: &AppHandle, state: &AppState) {
                 prune_old_events(&mut data.reminder_events, &mut data.standup_events, now);
                 *state.reminder_events.lock().unwrap() = data.reminder_events;
                 *state.standup_events.lock().unwrap() = data.standup_events;
+                *state.focus_events.lock().unwrap() = data.focus_sessions;
+                *state.activity_buckets.lock().unwrap() = data.activity;
+                *state.power_log.lock().unwrap() = data.power_log;
+                *state.tag_log.lock().unwrap() = data.tag_log;
+                *state.profile_log.lock().unwrap() = data.profile_log;
+                *state.hold_log.lock().unwrap() = data.hold_log;
+                *state.marked_days.lock().unwrap() = data.marked_days;
+                *state.interactions.lock().unwrap() = data.interactions;
+                *state.skip_events.lock().unwrap() = data.skip_events;
+                *state.desk_log.lock().unwrap() = data.desk_log;
+                *state.tip_ratings.lock().unwrap() = data.tip_ratings;
                 return;
             }
         }
@@ -351,28 +1504,124 @@ fn load_analytics(handle: &AppHandle, state: &AppState) {
                 prune_old_events(&mut data.reminder_events, &mut data.standup_events, now);
                 *state.reminder_events.lock().unwrap() = data.reminder_events;
                 *state.standup_events.lock().unwrap() = data.standup_events;
+                *state.focus_events.lock().unwrap() = data.focus_sessions;
+                *state.activity_buckets.lock().unwrap() = data.activity;
+                *state.power_log.lock().unwrap() = data.power_log;
+                *state.tag_log.lock().unwrap() = data.tag_log;
+                *state.profile_log.lock().unwrap() = data.profile_log;
+                *state.hold_log.lock().unwrap() = data.hold_log;
+                *state.marked_days.lock().unwrap() = data.marked_days;
+                *state.interactions.lock().unwrap() = data.interactions;
+                *state.skip_events.lock().unwrap() = data.skip_events;
+                *state.desk_log.lock().unwrap() = data.desk_log;
+                *state.tip_ratings.lock().unwrap() = data.tip_ratings;
             }
         }
     }
 }
 
+/// Move aside any data file that no longer parses so the next normal launch
+/// starts from defaults. Returns the new paths of the files that were moved.
+fn quarantine_corrupt_files(handle: &AppHandle) -> Result<Vec<String>, String> {
+    read_only::check(&handle.state::<AppState>())?;
+    store_lock::check(&handle.state::<AppState>())?;
+    let stamp = Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let mut moved = Vec::new();
+    let candidates = [
+        (config_path(handle), true),
+        (analytics_path(handle), false),
+    ];
+    for (path, is_config) in candidates {
+        let Some(path) = path else {
+            continue;
+        };
+        let Ok(contents) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let parses = if is_config {
+            serde_json::from_str::<AppConfigFile>(&contents).is_ok()
+        } else {
+            serde_json::from_str::<AnalyticsStore>(&contents).is_ok()
+        };
+        if parses {
+            continue;
+        }
+        let mut target = path.clone().into_os_string();
+        target.push(format!(".corrupt-{}", stamp));
+        let target = PathBuf::from(target);
+        fs::rename(&path, &target).map_err(|e| format!("repair failed: {}", e))?;
+        moved.push(target.to_string_lossy().to_string());
+    }
+    Ok(moved)
+}
+
 fn build_analytics_for_period(state: &AppState, period: &str) -> AnalyticsData {
+    let opts = period_options(state);
+    build_analytics_with_options(state, period, &opts, &AnalyticsFilter::default())
+}
+
+fn build_analytics_with_options(
+    state: &AppState,
+    period: &str,
+    opts: &PeriodOptions,
+    filter: &AnalyticsFilter,
+) -> AnalyticsData {
+    let start_ts = period_start_ts(period, clock::now(), opts);
+    if normalize_period(period) == "daily" {
+        let mut data = build_analytics_filtered(state, start_ts, i64::MAX, &|_| true, filter);
+        data.weekdays.clear();
+        return data;
+    }
+    // A rest or sick day would only drag the week down; leave it out.
+    let marked = state.marked_days.lock().unwrap().clone();
+    let keep = |ts: i64| !marked_days::covers(&marked, ts, opts.day_start_hour);
+    build_analytics_filtered(state, start_ts, i64::MAX, &keep, filter)
+}
+
+/// Aggregate events in `[start_ts, end_ts)` for which `keep` holds.
+fn build_analytics_between(
+    state: &AppState,
+    start_ts: i64,
+    end_ts: i64,
+    keep: &dyn Fn(i64) -> bool,
+) -> AnalyticsData {
+    build_analytics_filtered(state, start_ts, end_ts, keep, &AnalyticsFilter::default())
+}
+
+/// `build_analytics_between`, leaving out what `filter` doesn't match.
+fn build_analytics_filtered(
+    state: &AppState,
+    start_ts: i64,
+    end_ts: i64,
+    keep: &dyn Fn(i64) -> bool,
+    filter: &AnalyticsFilter,
+) -> AnalyticsData {
     let now = now_ts();
+    let tag_log = state.tag_log.lock().unwrap().clone();
+    let profile_log = state.profile_log.lock().unwrap().clone();
     let mut reminders = state.reminder_events.lock().unwrap();
     let mut standups = state.standup_events.lock().unwrap();
     prune_old_events(&mut reminders, &mut standups, now);
-    let start_ts = period_start_ts(period, Local::now());
+    let in_range = |ts: i64| {
+        ts >= start_ts && ts < end_ts && keep(ts) && filter.matches(ts, &tag_log, &profile_log)
+    };
 
     let mut hourly_sedentary = vec![0u32; HOURS];
     let mut hourly_standup = vec![0u32; HOURS];
     let mut hourly_sedentary_delay_secs = vec![0u64; HOURS];
 
+    let wants_ignored = filter.wants_outcome(OutcomeClass::Ignored);
     let filtered_reminders: Vec<ReminderRecord> = reminders
         .iter()
-        .filter(|e| e.ts >= start_ts)
+        .filter(|e| wants_ignored && in_range(e.ts))
         .cloned()
         .collect();
-    let filtered_standups: Vec<i64> = standups.iter().copied().filter(|ts| *ts >= start_ts).collect();
+    let wants_standups = filter.wants_outcome(OutcomeClass::StoodUp);
+    let filtered_standups: Vec<i64> = standups
+        .iter()
+        .copied()
+        .filter(|ts| wants_standups && in_range(*ts))
+        .collect();
 
     for event in filtered_reminders.iter() {
         if let Some(dt) = Local.timestamp_opt(event.ts, 0).single() {
@@ -387,9 +1636,60 @@ fn build_analytics_for_period(state: &AppState, period: &str) -> AnalyticsData {
         }
     }
 
+    let mut hourly_active_secs = vec![0u64; HOURS];
+    let mut hourly_idle_secs = vec![0u64; HOURS];
+    let wants_activity = filter.wants(EventType::Activity);
+    let activity_buckets = state.activity_buckets.lock().unwrap();
+    for bucket in activity_buckets.iter().filter(|b| wants_activity && in_range(b.hour_ts)) {
+        if let Some(dt) = Local.timestamp_opt(bucket.hour_ts, 0).single() {
+            hourly_active_secs[dt.hour() as usize] += bucket.active_secs;
+            hourly_idle_secs[dt.hour() as usize] += bucket.idle_secs;
+        }
+    }
+    let active_total = hourly_active_secs.iter().sum::<u64>();
+    let sampled_total = active_total + hourly_idle_secs.iter().sum::<u64>();
+    let active_sitting_ratio =
+        (sampled_total > 0).then(|| active_total as f32 / sampled_total as f32);
+
     let total_sitting_secs = filtered_reminders.iter().map(|e| e.duration_secs).sum::<u64>();
     let sedentary_sessions = filtered_reminders.len() as u32;
     let standup_sessions = filtered_standups.len() as u32;
+    let tags = tags::breakdown(&tag_log, &filtered_reminders, &filtered_standups);
+    let day_start_hour = *state.day_start_hour.lock().unwrap();
+    let weekdays = weekdays::breakdown(&filtered_reminders, &filtered_standups, day_start_hour);
+    let first_day = Local
+        .timestamp_opt(start_ts, 0)
+        .single()
+        .map(|dt| analytics_date(dt, day_start_hour));
+    let last_day = Local
+        .timestamp_opt(end_ts.min(now + 1) - 1, 0)
+        .single()
+        .map(|dt| analytics_date(dt, day_start_hour));
+    let marked_days = match (first_day, last_day) {
+        (Some(first), Some(last)) => {
+            marked_days::within(&state.marked_days.lock().unwrap(), first, last)
+        }
+        _ => Vec::new(),
+    };
+    let filtered_skips: Vec<i64> = state
+        .skip_events
+        .lock()
+        .unwrap()
+        .iter()
+        .copied()
+        .filter(|ts| filter.wants_outcome(OutcomeClass::Skipped) && in_range(*ts))
+        .collect();
+    let skip_budget_days_used_up = state
+        .free_skips_per_day
+        .lock()
+        .unwrap()
+        .map_or(0, |free| skips::days_used_up(&filtered_skips, free, day_start_hour));
+    let desk = if filter.wants(EventType::Desk) {
+        let keep = |ts: i64| keep(ts) && filter.matches(ts, &tag_log, &profile_log);
+        desk::totals(&state.desk_log.lock().unwrap(), start_ts, end_ts, now, &keep)
+    } else {
+        DeskTotals::default()
+    };
 
     AnalyticsData {
         hourly_sedentary,
@@ -399,6 +1699,17 @@ fn build_analytics_for_period(state: &AppState, period: &str) -> AnalyticsData {
         sedentary_sessions,
         total_sitting_secs,
         record_count: sedentary_sessions + standup_sessions,
+        ignored_reminders: sedentary_sessions,
+        hourly_active_secs,
+        hourly_idle_secs,
+        active_sitting_ratio,
+        period_start_ts: start_ts,
+        tags,
+        weekdays,
+        marked_days,
+        skips: filtered_skips.len() as u32,
+        skip_budget_days_used_up,
+        desk,
     }
 }
 
@@ -408,408 +1719,2230 @@ fn build_analytics(state: &AppState) -> AnalyticsData {
 
 #[tauri::command]
 fn set_reminder_interval(app: AppHandle, minutes: u64, state: State<'_, AppState>) -> String {
-    let normalized_minutes = sanitize_interval_minutes(minutes);
-    let mut interval = state.interval.lock().unwrap();
-    *interval = normalized_minutes * 60;
-
-    let mut elapsed = state.elapsed.lock().unwrap();
-    *elapsed = 0;
-
-    let mut last_change = state.last_interval_change.lock().unwrap();
-    *last_change = Instant::now();
-
-    let language = state.language.lock().unwrap().clone();
-    let reminder_language = state.reminder_language.lock().unwrap().clone();
-    let theme = state.theme.lock().unwrap().clone();
-    save_config(
-        &app,
-        normalized_minutes,
-        &language,
-        &reminder_language,
-        &theme,
-    );
+    let normalized_minutes = sanitize_interval_minutes(&state, minutes);
+    if timers::change_interval(&state, normalized_minutes * 60) {
+        let _ = save_config(&app, &state);
+        let _ = app.emit("tick", timers::tick_payload(&state));
+    }
     format!("Interval set to {} minutes", normalized_minutes)
 }
 
+/// Use `minutes` instead of the configured interval until the day ends,
+/// without saving it; `None` goes back to the configured interval.
+#[tauri::command]
+fn set_interval_today(
+    app: AppHandle,
+    minutes: Option<u64>,
+    state: State<'_, AppState>,
+) -> Result<TimerStatus, String> {
+    let min = if dev_intervals::enabled(&state) {
+        dev_intervals::INTERVAL_MINUTES[0]
+    } else {
+        timers::MIN_INTERVAL_TODAY_MINUTES
+    };
+    let range = min..=timers::MAX_INTERVAL_TODAY_MINUTES;
+    if let Some(minutes) = minutes.filter(|m| !range.contains(m)) {
+        return Err(format!(
+            "interval must be {}-{} minutes, got {}",
+            range.start(),
+            range.end(),
+            minutes
+        ));
+    }
+    timers::set_interval_today(&state, minutes.map(|m| m * 60));
+    let _ = app.emit("tick", timers::tick_payload(&state));
+    Ok(timers::timer_status(&state))
+}
+
 #[tauri::command]
 fn get_reminder_interval(state: State<'_, AppState>) -> u64 {
     (*state.interval.lock().unwrap()) / 60
 }
 
+/// The intervals `set_reminder_interval` accepts, shortest first.
 #[tauri::command]
-fn set_language(app: AppHandle, language: String, state: State<'_, AppState>) -> Result<(), String> {
-    let normalized = match language.as_str() {
-        "zh-CN" => "zh-CN".to_string(),
-        _ => "en".to_string(),
-    };
+fn get_allowed_intervals(state: State<'_, AppState>) -> Vec<u64> {
+    allowed_interval_minutes(&state)
+}
 
-    {
-        let mut lang = state.language.lock().unwrap();
-        *lang = normalized.clone();
-    }
+#[tauri::command]
+fn get_api_version() -> api::ApiVersion {
+    api::version()
+}
 
-    let minutes = (*state.interval.lock().unwrap()) / 60;
-    let reminder_language = state.reminder_language.lock().unwrap().clone();
-    let theme = state.theme.lock().unwrap().clone();
-    save_config(&app, minutes, &normalized, &reminder_language, &theme);
-    refresh_tray_menu(&app, &normalized);
-    let _ = app.emit("language-changed", normalized);
-    Ok(())
+/// Version, build, platform, data paths and third-party licenses for the
+/// About panel and bug reports.
+#[tauri::command]
+fn get_about_info(app: AppHandle) -> about::AboutInfo {
+    about::info(&app)
 }
 
+/// The full license texts of the crates listed in `get_about_info`.
 #[tauri::command]
-fn get_language(state: State<'_, AppState>) -> String {
-    state.language.lock().unwrap().clone()
+fn get_third_party_licenses() -> String {
+    about::THIRD_PARTY_LICENSES.to_string()
 }
 
+/// Move config, analytics, backups and tip packs to `new_path` and keep
+/// using it on later launches. Returns the resolved new location.
 #[tauri::command]
-fn set_reminder_language(
-    app: AppHandle,
-    language: String,
-    state: State<'_, AppState>,
-) -> Result<(), String> {
-    let normalized = match language.as_str() {
-        "zh-CN" => "zh-CN".to_string(),
-        _ => "en".to_string(),
-    };
-    {
-        let mut lang = state.reminder_language.lock().unwrap();
-        *lang = normalized.clone();
-    }
+fn move_data_dir(app: AppHandle, new_path: String) -> Result<String, String> {
+    data_dir::move_to(&app, Path::new(&new_path)).map(|dir| dir.display().to_string())
+}
 
-    let minutes = (*state.interval.lock().unwrap()) / 60;
-    let ui_language = state.language.lock().unwrap().clone();
-    let theme = state.theme.lock().unwrap().clone();
-    save_config(&app, minutes, &ui_language, &normalized, &theme);
-    let _ = app.emit("reminder-language-changed", normalized);
-    Ok(())
+/// Bundled release notes newer than `version`, newest first.
+#[tauri::command]
+fn get_changelog_since(version: String) -> Result<Vec<ChangelogEntry>, String> {
+    changelog::since(&version)
 }
 
+/// The notes announced by this launch's `whats-new`, for windows opened after it.
 #[tauri::command]
-fn get_reminder_language(state: State<'_, AppState>) -> String {
-    state.reminder_language.lock().unwrap().clone()
+fn get_whats_new(state: State<'_, AppState>) -> Option<WhatsNew> {
+    state.whats_new.lock().unwrap().clone()
 }
 
+/// Machine-readable list of commands, events and payload schemas.
 #[tauri::command]
-fn next_reminder_tip_index(state: State<'_, AppState>) -> u32 {
-    next_tip_index_from_state(&state) as u32
+fn get_api_description() -> api::ApiDescription {
+    api::describe()
 }
 
-fn next_tip_index_from_state(state: &AppState) -> usize {
-    let mut last = state.last_tip_index.lock().unwrap();
-    let count = REMINDER_PROMPT_COUNT.max(1);
-    let mut rng = rand::thread_rng();
-    let mut idx = rng.gen_range(0..count);
-    if let Some(prev) = *last {
-        if count > 1 && idx == prev {
-            idx = (idx + 1 + rng.gen_range(0..(count - 1))) % count;
-        }
-    }
-    *last = Some(idx);
-    idx
+#[tauri::command]
+fn get_power_state(state: State<'_, AppState>) -> PowerState {
+    *state.power.lock().unwrap()
 }
 
 #[tauri::command]
-fn next_reminder_tip_text(state: State<'_, AppState>) -> String {
-    let idx = next_tip_index_from_state(&state);
-    REMINDER_TIPS_EN[idx % REMINDER_TIPS_EN.len()].to_string()
+fn get_timer_status(state: State<'_, AppState>) -> TimerStatus {
+    timers::timer_status(&state)
 }
 
-fn normalize_theme(theme: &str) -> String {
-    if theme == "day" {
-        "day".to_string()
-    } else {
-        "night".to_string()
-    }
+#[tauri::command]
+fn set_tick_event_granularity(app: AppHandle, seconds: u64, state: State<'_, AppState>) -> u64 {
+    let normalized = seconds.clamp(1, timers::MAX_TICK_EVENT_SECS);
+    *state.tick_event_secs.lock().unwrap() = normalized;
+    let _ = save_config(&app, &state);
+    normalized
 }
 
 #[tauri::command]
-fn set_theme(app: AppHandle, theme: String, state: State<'_, AppState>) -> Result<(), String> {
-    let normalized = normalize_theme(&theme);
-    {
-        let mut t = state.theme.lock().unwrap();
-        *t = normalized.clone();
+fn get_tick_event_granularity(state: State<'_, AppState>) -> u64 {
+    *state.tick_event_secs.lock().unwrap()
+}
+
+#[tauri::command]
+fn get_tick_resolution(state: State<'_, AppState>) -> TickResolution {
+    state.tick_resolution.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn set_tick_resolution(
+    app: AppHandle,
+    resolution: TickResolution,
+    state: State<'_, AppState>,
+) -> TickResolution {
+    let normalized = timers::sanitize_tick_resolution(resolution);
+    *state.tick_resolution.lock().unwrap() = normalized.clone();
+    let _ = save_config(&app, &state);
+    normalized
+}
+
+#[tauri::command]
+fn set_language(app: AppHandle, language: String, state: State<'_, AppState>) -> Result<(), String> {
+    let follow_system = language == "system";
+    let normalized = match language.as_str() {
+        "system" => detect_system_language(),
+        language => languages::normalize(language),
+    };
+    *state.language_follows_system.lock().unwrap() = follow_system;
+
+    {
+        let mut lang = state.language.lock().unwrap();
+        *lang = normalized.clone();
     }
 
-    let minutes = (*state.interval.lock().unwrap()) / 60;
-    let ui_language = state.language.lock().unwrap().clone();
-    let reminder_language = state.reminder_language.lock().unwrap().clone();
-    save_config(&app, minutes, &ui_language, &reminder_language, &normalized);
-    let _ = app.emit("theme-changed", normalized);
+    save_config(&app, &state)?;
+    refresh_tray_menu(&app);
+    let _ = app.emit("language-changed", normalized);
     Ok(())
 }
 
 #[tauri::command]
-fn get_theme(state: State<'_, AppState>) -> String {
-    state.theme.lock().unwrap().clone()
+fn get_language(state: State<'_, AppState>) -> String {
+    state.language.lock().unwrap().clone()
 }
 
-#[tauri::command]
-fn get_active_reminder(state: State<'_, AppState>) -> ActiveReminderPayload {
-    ActiveReminderPayload {
-        id: *state.active_reminder_id.lock().unwrap(),
-        text: state.active_reminder_tip.lock().unwrap().clone(),
-        theme: state.theme.lock().unwrap().clone(),
-        visible: *state.reminder_visible.lock().unwrap(),
+/// "system" while following the OS, otherwise the fixed language.
+fn language_preference(state: &AppState) -> String {
+    if *state.language_follows_system.lock().unwrap() {
+        "system".to_string()
+    } else {
+        state.language.lock().unwrap().clone()
     }
 }
 
+/// Name and text direction of the UI language in effect.
 #[tauri::command]
-fn get_system_language() -> String {
-    #[cfg(target_os = "windows")]
+fn get_language_info(state: State<'_, AppState>) -> languages::LanguageInfo {
+    languages::info(&state.language.lock().unwrap())
+}
+
+#[tauri::command]
+fn list_available_languages() -> Vec<languages::LanguageInfo> {
+    languages::available()
+}
+
+/// The setting as chosen, which `get_language` resolves: "system" or a
+/// language code.
+#[tauri::command]
+fn get_language_preference(state: State<'_, AppState>) -> String {
+    language_preference(&state)
+}
+
+/// While the language is "system": switch to the OS language if it changed
+/// since, rebuilding the tray and telling the windows like a manual switch.
+fn follow_system_language(app: &AppHandle, detected: String) {
+    let state = app.state::<AppState>();
+    if !*state.language_follows_system.lock().unwrap() {
+        return;
+    }
     {
-        use windows_sys::Win32::Globalization::GetUserDefaultUILanguage;
-        // PRIMARYLANGID(langid)
-        let lang_id = unsafe { GetUserDefaultUILanguage() } as u16;
-        let primary = lang_id & 0x03ff;
-        return if primary == 0x04 {
-            "zh-CN".to_string()
-        } else {
-            "en".to_string()
-        };
+        let mut language = state.language.lock().unwrap();
+        if *language == detected {
+            return;
+        }
+        *language = detected.clone();
     }
+    refresh_tray_menu(app);
+    let _ = app.emit("language-changed", detected);
+}
 
-    #[cfg(not(target_os = "windows"))]
+/// Also switches the enabled tip packs to ones written in the language, if
+/// any are installed. Returns what was switched.
+#[tauri::command]
+fn set_reminder_language(
+    app: AppHandle,
+    language: String,
+    state: State<'_, AppState>,
+) -> Result<TipLanguageMatch, String> {
+    let normalized = languages::normalize(&language);
     {
-        let locale = sys_locale::get_locale()
-            .unwrap_or_else(|| "en-US".to_string())
-            .to_lowercase();
-        if locale.starts_with("zh") {
-            "zh-CN".to_string()
-        } else {
-            "en".to_string()
+        let mut lang = state.reminder_language.lock().unwrap();
+        *lang = normalized.clone();
+    }
+    let selection = tip_packs::select_for_language(&app, &normalized);
+
+    save_config(&app, &state)?;
+    tip_packs::rebuild_pool(&app);
+    let _ = app.emit("reminder-language-changed", normalized);
+    Ok(selection)
+}
+
+#[tauri::command]
+fn get_reminder_language(state: State<'_, AppState>) -> String {
+    state.reminder_language.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn next_reminder_tip_index(state: State<'_, AppState>) -> u32 {
+    next_tip_index_from_state(&state) as u32
+}
+
+fn next_tip_index_from_state(state: &AppState) -> usize {
+    pick_tip_index(state, REMINDER_PROMPT_COUNT)
+}
+
+/// Random index into a pool of `count` tips, avoiding an immediate repeat.
+fn pick_tip_index(state: &AppState, count: usize) -> usize {
+    let mut last = state.last_tip_index.lock().unwrap();
+    let count = count.max(1);
+    let mut rng = rand::thread_rng();
+    let mut idx = rng.gen_range(0..count);
+    if let Some(prev) = *last {
+        if count > 1 && idx == prev {
+            idx = (idx + 1 + rng.gen_range(0..(count - 1))) % count;
         }
     }
+    *last = Some(idx);
+    idx
 }
 
 #[tauri::command]
-fn reveal_in_explorer(path: String) -> Result<(), String> {
-    let path = PathBuf::from(path);
-    if !path.exists() {
-        return Err("path not found".to_string());
+fn next_reminder_tip_text(state: State<'_, AppState>) -> String {
+    pool_tip(&state).text
+}
+
+/// A tip from the merged pool of enabled tip packs.
+fn pool_tip(state: &AppState) -> ShownTip {
+    let pool = state.tip_pool.lock().unwrap().clone();
+    if pool.is_empty() {
+        let idx = next_tip_index_from_state(state);
+        return ShownTip {
+            text: REMINDER_TIPS_EN[idx % REMINDER_TIPS_EN.len()].to_string(),
+            source: TipSource::Pack {
+                id: tip_packs::BUILTIN_PACK_ID.to_string(),
+            },
+        };
     }
+    let idx = pick_tip_index(state, pool.len());
+    pool[idx % pool.len()].clone()
+}
 
-    #[cfg(target_os = "windows")]
-    {
-        let arg = format!("/select,{}", path.display());
-        ProcessCommand::new("explorer")
-            .arg(arg)
-            .spawn()
-            .map_err(|e| format!("open explorer failed: {}", e))?;
-        return Ok(());
+/// Pick the tip and sound for a reminder firing now, using the schedule slot
+/// that covers the current local hour.
+fn select_reminder_tip(state: &AppState) -> (ShownTip, Option<String>) {
+    let hour = clock::now().hour();
+    let weekend_slots = timers::active_weekend_override(state).and_then(|weekend| weekend.schedule);
+    let slot = match weekend_slots {
+        Some(slots) => schedule::slot_for_hour(&slots, hour).cloned(),
+        None => {
+            let slots = state.schedule.lock().unwrap();
+            schedule::slot_for_hour(&slots, hour).cloned()
+        }
+    };
+    match slot {
+        Some(slot) if !slot.tips.is_empty() => {
+            let idx = pick_tip_index(state, slot.tips.len());
+            let tip = ShownTip {
+                text: slot.tips[idx % slot.tips.len()].clone(),
+                source: TipSource::Schedule,
+            };
+            (tip, slot.sound)
+        }
+        other => (pool_tip(state), other.and_then(|slot| slot.sound)),
     }
+}
 
-    #[cfg(target_os = "macos")]
+#[tauri::command]
+fn list_tip_packs(app: AppHandle) -> Vec<TipPackInfo> {
+    tip_packs::list(&app)
+}
+
+/// How much of each installed pack is translated into `language` (the
+/// reminder language if omitted), so pack authors can see what to fill in.
+#[tauri::command]
+fn get_tip_pack_coverage(
+    app: AppHandle,
+    language: Option<String>,
+    state: State<'_, AppState>,
+) -> Vec<TipPackCoverage> {
+    let language = language
+        .map(|language| languages::normalize(&language))
+        .unwrap_or_else(|| state.reminder_language.lock().unwrap().clone());
+    tip_packs::load_packs(&app)
+        .iter()
+        .map(|pack| tip_packs::coverage(pack, &language))
+        .collect()
+}
+
+#[tauri::command]
+fn enable_tip_pack(
+    app: AppHandle,
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<TipPackInfo>, String> {
+    if !tip_packs::load_packs(&app).iter().any(|pack| pack.id == id) {
+        return Err("tip pack not found".into());
+    }
     {
-        ProcessCommand::new("open")
-            .arg("-R")
-            .arg(&path)
-            .spawn()
-            .map_err(|e| format!("reveal failed: {}", e))?;
-        return Ok(());
+        let mut enabled = state.enabled_tip_packs.lock().unwrap();
+        if !enabled.contains(&id) {
+            enabled.push(id);
+        }
     }
+    save_config(&app, &state)?;
+    tip_packs::rebuild_pool(&app);
+    Ok(tip_packs::list(&app))
+}
 
-    #[cfg(target_os = "linux")]
+#[tauri::command]
+fn disable_tip_pack(app: AppHandle, id: String, state: State<'_, AppState>) -> Vec<TipPackInfo> {
+    state.enabled_tip_packs.lock().unwrap().retain(|enabled| *enabled != id);
+    let _ = save_config(&app, &state);
+    tip_packs::rebuild_pool(&app);
+    tip_packs::list(&app)
+}
+
+#[tauri::command]
+fn list_plugins(app: AppHandle) -> Vec<PluginInfo> {
+    plugins::list(&app)
+}
+
+/// Enabling a plugin grants every capability its manifest asks for.
+#[tauri::command]
+async fn set_plugin_enabled(
+    app: AppHandle,
+    id: String,
+    enabled: bool,
+) -> Result<Vec<PluginInfo>, String> {
+    if !plugins::list(&app).iter().any(|plugin| plugin.id == id) {
+        return Err("plugin not found".into());
+    }
     {
-        let dir = path
-            .parent()
-            .map(|p| p.to_path_buf())
-            .unwrap_or_else(|| path.clone());
-        ProcessCommand::new("xdg-open")
-            .arg(dir)
-            .spawn()
-            .map_err(|e| format!("open folder failed: {}", e))?;
-        return Ok(());
+        let state = app.state::<AppState>();
+        {
+            let mut ids = state.enabled_plugins.lock().unwrap();
+            ids.retain(|enabled| *enabled != id);
+            if enabled {
+                ids.push(id);
+            }
+        }
+        save_config(&app, &state)?;
     }
+    reload_plugins(app).await
 }
 
+/// Load the enabled plugins again, e.g. after one was updated on disk.
 #[tauri::command]
-fn log_standup(app: AppHandle, state: State<'_, AppState>) -> u32 {
-    let mut elapsed = state.elapsed.lock().unwrap();
-    *elapsed = 0;
-    *state.reminder_visible.lock().unwrap() = false;
+async fn reload_plugins(app: AppHandle) -> Result<Vec<PluginInfo>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        plugins::reload(&app);
+        plugins::list(&app)
+    })
+    .await
+    .map_err(|e| format!("plugin task failed: {}", e))
+}
 
-    let now = now_ts();
+#[tauri::command]
+async fn get_plugin_insights(app: AppHandle) -> Result<Vec<PluginInsight>, String> {
+    tauri::async_runtime::spawn_blocking(move || plugins::insights(&app))
+        .await
+        .map_err(|e| format!("plugin task failed: {}", e))
+}
+
+#[tauri::command]
+fn import_tip_pack(app: AppHandle, path: String) -> Result<TipPackInfo, String> {
+    tip_packs::import_file(&app, &path)
+}
+
+#[tauri::command]
+async fn download_tip_pack(app: AppHandle, url: String) -> Result<TipPackInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || tip_packs::download(&app, &url))
+        .await
+        .map_err(|e| format!("download task failed: {}", e))?
+}
+
+#[tauri::command]
+fn get_schedule(state: State<'_, AppState>) -> Vec<ScheduleSlot> {
+    state.schedule.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn set_schedule(
+    app: AppHandle,
+    slots: Vec<ScheduleSlot>,
+    state: State<'_, AppState>,
+) -> Vec<ScheduleSlot> {
+    let sanitized = schedule::sanitize_schedule(slots);
+    *state.schedule.lock().unwrap() = sanitized.clone();
+    let _ = save_config(&app, &state);
+    sanitized
+}
+
+fn normalize_theme(theme: &str) -> String {
+    themes::resolve(theme).name
+}
+
+/// Every theme `set_theme` accepts, with its tokens.
+#[tauri::command]
+fn list_themes() -> Vec<ThemeTokens> {
+    themes::registry()
+}
+
+#[tauri::command]
+fn set_theme(app: AppHandle, theme: String, state: State<'_, AppState>) -> Result<(), String> {
+    let normalized = normalize_theme(&theme);
     {
-        let mut standups = state.standup_events.lock().unwrap();
-        standups.push(now);
+        let mut t = state.theme.lock().unwrap();
+        *t = normalized.clone();
     }
 
-    save_analytics(&app, &state);
-    let analytics = build_analytics(&state);
+    save_config(&app, &state)?;
+    let _ = app.emit("theme-changed", normalized);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_theme(state: State<'_, AppState>) -> String {
+    state.theme.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn set_reminder_focus_mode(
+    app: AppHandle,
+    mode: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let mode = parse_reminder_focus_mode(&mode)?;
+    *state.reminder_focus_mode.lock().unwrap() = mode;
+    save_config(&app, &state)?;
+    Ok(())
+}
+
+#[tauri::command]
+fn get_reminder_focus_mode(state: State<'_, AppState>) -> String {
+    state.reminder_focus_mode.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn set_standup_cooldown(app: AppHandle, seconds: u64, state: State<'_, AppState>) -> u64 {
+    let normalized = seconds.min(MAX_STANDUP_COOLDOWN_SECS);
+    *state.standup_cooldown_secs.lock().unwrap() = normalized;
+    let _ = save_config(&app, &state);
+    normalized
+}
+
+#[tauri::command]
+fn get_standup_cooldown(state: State<'_, AppState>) -> u64 {
+    *state.standup_cooldown_secs.lock().unwrap()
+}
+
+#[tauri::command]
+fn set_ack_grace_ms(app: AppHandle, ms: u64, state: State<'_, AppState>) -> Result<u64, String> {
+    if ms > MAX_ACK_GRACE_MS {
+        return Err(format!("ack_grace_ms must be at most {}", MAX_ACK_GRACE_MS));
+    }
+    *state.ack_grace_ms.lock().unwrap() = ms;
+    save_config(&app, &state)?;
+    Ok(ms)
+}
+
+#[tauri::command]
+fn get_ack_grace_ms(state: State<'_, AppState>) -> u64 {
+    *state.ack_grace_ms.lock().unwrap()
+}
+
+/// Applies to everything computed per day from now on: analytics periods,
+/// the daily reset, streaks, goals and the rollover event.
+#[tauri::command]
+fn set_day_start_hour(app: AppHandle, hour: u32, state: State<'_, AppState>) -> Result<u32, String> {
+    if hour > MAX_DAY_START_HOUR {
+        return Err(format!("day_start_hour must be at most {}", MAX_DAY_START_HOUR));
+    }
+    *state.day_start_hour.lock().unwrap() = hour;
+    save_config(&app, &state)?;
+    events::analytics_updated(&app);
+    Ok(hour)
+}
+
+#[tauri::command]
+fn get_day_start_hour(state: State<'_, AppState>) -> u32 {
+    *state.day_start_hour.lock().unwrap()
+}
+
+#[tauri::command]
+fn set_sedentary_threshold_secs(
+    app: AppHandle,
+    seconds: u64,
+    state: State<'_, AppState>,
+) -> Result<u64, String> {
+    if normalize_sedentary_threshold_secs(seconds) != seconds {
+        return Err(format!(
+            "sedentary_threshold_secs must be between {} and {}",
+            MIN_SEDENTARY_THRESHOLD_SECS, MAX_SEDENTARY_THRESHOLD_SECS
+        ));
+    }
+    *state.sedentary_threshold_secs.lock().unwrap() = seconds;
+    save_config(&app, &state)?;
+    Ok(seconds)
+}
+
+#[tauri::command]
+fn get_sedentary_threshold_secs(state: State<'_, AppState>) -> u64 {
+    *state.sedentary_threshold_secs.lock().unwrap()
+}
+
+/// Takes effect from the next reminder.
+#[tauri::command]
+fn set_reminder_all_monitors(app: AppHandle, enabled: bool, state: State<'_, AppState>) -> bool {
+    *state.reminder_all_monitors.lock().unwrap() = enabled;
+    let _ = save_config(&app, &state);
+    enabled
+}
+
+#[tauri::command]
+fn get_reminder_all_monitors(state: State<'_, AppState>) -> bool {
+    *state.reminder_all_monitors.lock().unwrap()
+}
+
+#[tauri::command]
+fn get_reminder_anchor(state: State<'_, AppState>) -> String {
+    state.reminder_anchor.lock().unwrap().clone()
+}
+
+/// "auto" places the reminder next to the Windows taskbar, wherever it is
+/// docked; "bottom_right", "bottom_left", "top_right" and "top_left" pin a
+/// corner. An open reminder moves right away. Returns the stored value.
+#[tauri::command]
+fn set_reminder_anchor(app: AppHandle, anchor: String, state: State<'_, AppState>) -> String {
+    let normalized = reminder_windows::normalize_anchor(&anchor);
+    *state.reminder_anchor.lock().unwrap() = normalized.clone();
+    let _ = save_config(&app, &state);
+    reminder_windows::relayout(&app);
+    normalized
+}
+
+#[tauri::command]
+fn get_reminder_windows(state: State<'_, AppState>) -> Vec<ReminderWindowInfo> {
+    state.reminder_windows.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn get_startup_options(state: State<'_, AppState>) -> StartupOptions {
+    StartupOptions {
+        show_splash: *state.show_splash.lock().unwrap(),
+        open_settings_on_launch: *state.open_settings_on_launch.lock().unwrap(),
+    }
+}
+
+#[tauri::command]
+fn set_startup_options(
+    app: AppHandle,
+    show_splash: bool,
+    open_settings_on_launch: bool,
+    state: State<'_, AppState>,
+) -> StartupOptions {
+    *state.show_splash.lock().unwrap() = show_splash;
+    *state.open_settings_on_launch.lock().unwrap() = open_settings_on_launch;
+    let _ = save_config(&app, &state);
+    get_startup_options(state)
+}
+
+/// Seconds between the most recent standup and `fire_ts`, when a reminder
+/// is due, if that falls inside the cooldown. A standup logged here restarts
+/// the countdown and is a whole interval old by then; this catches the ones
+/// that don't, such as standups handed off from another device. The
+/// cooldown is held to half the interval so it can't swallow reminders.
+fn recent_standup_age(state: &AppState, fire_ts: i64, interval_secs: u64) -> Option<u64> {
+    let cooldown = dev_intervals::standup_cooldown_secs(state).min(interval_secs / 2);
+    let last = state.standup_events.lock().unwrap().iter().copied().max()?;
+    let age = (fire_ts - last).max(0) as u64;
+    (age < cooldown).then_some(age)
+}
+
+fn active_reminder_payload(state: &AppState) -> ActiveReminderPayload {
+    if let Some(preview) = state.reminder_preview.lock().unwrap().clone() {
+        return ActiveReminderPayload {
+            id: *state.active_reminder_id.lock().unwrap(),
+            text: preview.text,
+            theme: themes::resolve(&preview.theme),
+            visible: true,
+            accessibility: *state.accessibility.lock().unwrap(),
+            sound: preview.sound,
+            sound_muted: false,
+            reason: None,
+            preview: true,
+            desk_move: None,
+            challenge: None,
+            ack_grace_ms: dev_intervals::ack_grace_ms(state),
+        };
+    }
+    let visible = *state.reminder_visible.lock().unwrap();
+    let mut sound = state.active_reminder_sound.lock().unwrap().clone();
+    let sound_muted = sound.is_some() && *state.mic_in_use.lock().unwrap();
+    if sound_muted {
+        sound = None;
+    }
+    ActiveReminderPayload {
+        id: *state.active_reminder_id.lock().unwrap(),
+        text: state.active_reminder_tip.lock().unwrap().clone(),
+        theme: themes::resolve(&state.theme.lock().unwrap()),
+        visible,
+        accessibility: *state.accessibility.lock().unwrap(),
+        sound,
+        sound_muted,
+        reason: state.active_reminder_reason.lock().unwrap().clone(),
+        preview: false,
+        desk_move: desk::next_move(state).filter(|_| visible),
+        challenge: challenge::status(state).filter(|_| visible),
+        ack_grace_ms: dev_intervals::ack_grace_ms(state),
+    }
+}
+
+/// Show the reminder window with a sample tip, in `theme` if given (else
+/// the current one), so placement, sound and theme can be tried out from
+/// settings. Nothing is logged and the countdown carries on; a reminder
+/// that comes due meanwhile replaces the preview.
+#[tauri::command]
+fn preview_reminder(
+    app: AppHandle,
+    theme: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    if *state.reminder_visible.lock().unwrap() {
+        return Err("REMINDER_ACTIVE".to_string());
+    }
+    // A fresh id makes an open window pick up the new text.
+    let next_id = *state.active_reminder_id.lock().unwrap() + 1;
+    let windows = reminder_windows::prepare(&app, next_id);
+    if windows.is_empty() {
+        return Err("the reminder window couldn't be built".to_string());
+    }
+    *state.active_reminder_id.lock().unwrap() = next_id;
+    let (ShownTip { text, .. }, sound) = select_reminder_tip(&state);
+    reminder_windows::resize(&app, &windows, reminder_windows::estimated_height(&text));
+    *state.reminder_preview.lock().unwrap() = Some(ReminderPreview {
+        text,
+        theme: theme
+            .map(|theme| normalize_theme(&theme))
+            .unwrap_or_else(|| state.theme.lock().unwrap().clone()),
+        sound,
+    });
+    reminder_windows::present_all(&app, &windows)?;
+    events::emit(&app, "refresh_tip", next_id);
+    for win in &windows {
+        let _ = win.eval("window.__standbyReminderSync && window.__standbyReminderSync();");
+    }
+    Ok(())
+}
+
+/// Close the preview if it is still open; returns whether it was.
+fn close_reminder_preview(app: &AppHandle, state: &AppState) -> bool {
+    if state.reminder_preview.lock().unwrap().take().is_none() {
+        return false;
+    }
+    reminder_windows::dismiss(app);
+    let previous = state.previous_foreground.lock().unwrap().take();
+    if let Some(previous) = previous {
+        focus::restore_foreground(&previous);
+    }
+    true
+}
+
+#[tauri::command]
+fn get_active_reminder(state: State<'_, AppState>) -> ActiveReminderPayload {
+    active_reminder_payload(&state)
+}
+
+/// The reminder page reports the height its content needs, in CSS pixels,
+/// once laid out; the windows are resized to fit, up to a maximum.
+#[tauri::command]
+fn set_reminder_content_height(app: AppHandle, reminder_id: u64, height: f64) {
+    let state = app.state::<AppState>();
+    if reminder_id != *state.active_reminder_id.lock().unwrap() || !height.is_finite() {
+        return;
+    }
+    let windows = reminder_windows::existing(&app);
+    let scale = windows
+        .first()
+        .and_then(|win| win.scale_factor().ok())
+        .unwrap_or(1.0);
+    reminder_windows::resize(&app, &windows, (height * scale).ceil() as i32);
+}
+
+/// How reminders were answered over the last `days` days (default 30):
+/// which control, how fast, and whether the window had focus.
+#[tauri::command]
+fn get_interaction_stats(days: Option<u32>, state: State<'_, AppState>) -> InteractionStats {
+    let days = days.unwrap_or(30).clamp(1, (RETENTION_SECS / 86_400) as u32);
+    interactions::stats(&state.interactions.lock().unwrap(), days)
+}
+
+#[tauri::command]
+fn get_repeat_when_dismissed(state: State<'_, AppState>) -> RepeatConfig {
+    state.repeat_when_dismissed.lock().unwrap().clone()
+}
+
+/// Takes effect from the next dismissal. Returns the stored config.
+#[tauri::command]
+fn set_repeat_when_dismissed(
+    app: AppHandle,
+    config: RepeatConfig,
+    state: State<'_, AppState>,
+) -> RepeatConfig {
+    let config = repeats::sanitize(config);
+    *state.repeat_when_dismissed.lock().unwrap() = config.clone();
+    let _ = save_config(&app, &state);
+    config
+}
+
+#[tauri::command]
+fn get_reminder_presentation(state: State<'_, AppState>) -> PresentationConfig {
+    state.reminder_presentation.lock().unwrap().clone()
+}
+
+/// Takes effect from the next reminder. Returns the stored config.
+#[tauri::command]
+fn set_reminder_presentation(
+    app: AppHandle,
+    config: PresentationConfig,
+    state: State<'_, AppState>,
+) -> PresentationConfig {
+    let config = overlay::sanitize(config);
+    *state.reminder_presentation.lock().unwrap() = config.clone();
+    let _ = save_config(&app, &state);
+    config
+}
+
+#[tauri::command]
+fn get_skip_budget(state: State<'_, AppState>) -> SkipBudget {
+    skips::budget(&state)
+}
+
+/// `free_per_day` reminders a day may be dismissed while still sitting
+/// (capped at `skips::MAX_FREE_SKIPS`); `None` removes the limit.
+#[tauri::command]
+fn set_skip_budget(
+    app: AppHandle,
+    free_per_day: Option<u32>,
+    state: State<'_, AppState>,
+) -> SkipBudget {
+    *state.free_skips_per_day.lock().unwrap() = free_per_day.map(|n| n.min(skips::MAX_FREE_SKIPS));
+    let _ = save_config(&app, &state);
+    events::analytics_updated(&app);
+    skips::budget(&state)
+}
+
+/// "That tip was great / annoying" from the reminder card. The rating goes
+/// to the tip reminder `reminder_id` showed, whichever pack or schedule slot
+/// it came from; rating the same reminder again replaces it.
+#[tauri::command]
+fn rate_tip_for_reminder(
+    app: AppHandle,
+    reminder_id: u64,
+    liked: bool,
+    state: State<'_, AppState>,
+) -> Result<TipRating, String> {
+    let rating = tip_ratings::rate(&state, reminder_id, liked, now_ts())?;
+    journal::compact_soon(&app);
+    Ok(rating)
+}
+
+#[tauri::command]
+fn get_tip_ratings(state: State<'_, AppState>) -> Vec<TipRatingSummary> {
+    tip_ratings::summary(&state.tip_ratings.lock().unwrap())
+}
+
+#[tauri::command]
+fn get_challenge_config(state: State<'_, AppState>) -> ChallengeConfig {
+    state.challenge.lock().unwrap().clone()
+}
+
+/// Applies to the open reminder right away. Returns the stored config.
+#[tauri::command]
+fn set_challenge_config(
+    app: AppHandle,
+    config: ChallengeConfig,
+    state: State<'_, AppState>,
+) -> ChallengeConfig {
+    let config = challenge::sanitize(config);
+    *state.challenge.lock().unwrap() = config.clone();
+    let _ = save_config(&app, &state);
+    config
+}
+
+#[tauri::command]
+fn get_network_profiles(state: State<'_, AppState>) -> Vec<NetworkProfile> {
+    state.network_profiles.lock().unwrap().clone()
+}
+
+/// Takes effect on the next network poll. Returns the stored profiles.
+#[tauri::command]
+fn set_network_profiles(
+    app: AppHandle,
+    profiles: Vec<NetworkProfile>,
+    state: State<'_, AppState>,
+) -> Vec<NetworkProfile> {
+    let sanitized = sanitize_network_profiles(&state, profiles);
+    *state.network_profiles.lock().unwrap() = sanitized.clone();
+    let _ = save_config(&app, &state);
+    let _ = app.emit("tick", timers::tick_payload(&state));
+    sanitized
+}
+
+#[tauri::command]
+fn get_network_status(state: State<'_, AppState>) -> NetworkStatus {
+    network_profiles::status(&state)
+}
+
+#[tauri::command]
+fn get_desk_mode(state: State<'_, AppState>) -> DeskModeConfig {
+    state.desk_mode.lock().unwrap().clone()
+}
+
+/// Turning desk mode on or off restarts the countdown; a cycle always
+/// starts sitting. Returns the stored config.
+#[tauri::command]
+fn set_desk_mode(
+    app: AppHandle,
+    config: DeskModeConfig,
+    state: State<'_, AppState>,
+) -> DeskModeConfig {
+    let config = desk::sanitize(config);
+    *state.desk_mode.lock().unwrap() = config.clone();
+    if desk::set_enabled(&state, now_ts(), config.enabled) {
+        *state.elapsed.lock().unwrap() = 0;
+        repeats::cancel(&state);
+        journal::compact_soon(&app);
+        events::analytics_updated(&app);
+    }
+    let _ = save_config(&app, &state);
+    timers::wake(&state);
+    let _ = app.emit("tick", timers::tick_payload(&state));
+    config
+}
+
+#[tauri::command]
+fn get_desk_position(state: State<'_, AppState>) -> DeskPosition {
+    desk::position(&state)
+}
+
+/// For desk integrations such as the BLE desk module: the desk moved to
+/// `position`. An open reminder asking for that move counts as answered;
+/// otherwise the next phase starts now. Returns the position as logged.
+#[tauri::command]
+fn report_desk_position(
+    app: AppHandle,
+    position: DeskPosition,
+    state: State<'_, AppState>,
+) -> Result<DeskPosition, String> {
+    if !desk::enabled(&state) {
+        return Err("DESK_MODE_OFF".to_string());
+    }
+    let asked = *state.reminder_visible.lock().unwrap() && desk::next_move(&state) == Some(position);
+    if asked {
+        acknowledge_reminder(
+            app.clone(),
+            true,
+            None,
+            Some("desk".to_string()),
+            None,
+            state.clone(),
+        )?;
+    }
+    if desk::moved(&app, &state, now_ts(), position, true) {
+        *state.elapsed.lock().unwrap() = 0;
+        repeats::cancel(&state);
+        timers::wake(&state);
+    }
+    Ok(desk::position(&state))
+}
+
+/// Reminders since launch with why each fired and how it was answered.
+#[tauri::command]
+fn get_reminder_history(state: State<'_, AppState>) -> Vec<ReminderHistoryEntry> {
+    reminder_reason::history(&state)
+}
+
+/// Today as sitting, standing, idle, locked and paused stretches, oldest
+/// first, for the dashboard's day view.
+#[tauri::command]
+fn get_today_timeline(state: State<'_, AppState>) -> Vec<TimelineSegment> {
+    timeline::today(&state)
+}
+
+/// Sequence number of the last event sent through the event bus.
+#[tauri::command]
+fn get_event_seq(state: State<'_, AppState>) -> u64 {
+    events::last_seq(&state)
+}
+
+#[tauri::command]
+fn get_accessibility_prefs(state: State<'_, AppState>) -> AccessibilityPrefs {
+    *state.accessibility.lock().unwrap()
+}
+
+#[tauri::command]
+fn get_system_language() -> String {
+    detect_system_language()
+}
+
+/// The OS UI language mapped onto a supported UI language.
+fn detect_system_language() -> String {
+    #[cfg(target_os = "windows")]
+    {
+        use windows_sys::Win32::Globalization::GetUserDefaultUILanguage;
+        // PRIMARYLANGID(langid)
+        let lang_id = unsafe { GetUserDefaultUILanguage() } as u16;
+        let primary = lang_id & 0x03ff;
+        return if primary == 0x04 {
+            "zh-CN".to_string()
+        } else {
+            "en".to_string()
+        };
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let locale = sys_locale::get_locale()
+            .unwrap_or_else(|| "en-US".to_string())
+            .to_lowercase();
+        if locale.starts_with("zh") {
+            "zh-CN".to_string()
+        } else {
+            "en".to_string()
+        }
+    }
+}
+
+#[tauri::command]
+fn reveal_in_explorer(path: String) -> Result<(), String> {
+    let path = PathBuf::from(path);
+    if !path.exists() {
+        return Err("path not found".to_string());
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let arg = format!("/select,{}", path.display());
+        ProcessCommand::new("explorer")
+            .arg(arg)
+            .spawn()
+            .map_err(|e| format!("open explorer failed: {}", e))?;
+        return Ok(());
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        ProcessCommand::new("open")
+            .arg("-R")
+            .arg(&path)
+            .spawn()
+            .map_err(|e| format!("reveal failed: {}", e))?;
+        return Ok(());
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let dir = path
+            .parent()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| path.clone());
+        ProcessCommand::new("xdg-open")
+            .arg(dir)
+            .spawn()
+            .map_err(|e| format!("open folder failed: {}", e))?;
+        Ok(())
+    }
+}
+
+#[tauri::command]
+fn log_standup(app: AppHandle, state: State<'_, AppState>) -> u32 {
+    // Logging a standup would answer the open reminder, so it has to wait
+    // for the reminder's challenge like answering it directly does.
+    if *state.reminder_visible.lock().unwrap() && challenge::check(&state, None).is_err() {
+        return build_analytics(&state).standup_sessions;
+    }
+    let now = now_ts();
+    if std::mem::take(&mut *state.reminder_visible.lock().unwrap()) {
+        let id = *state.active_reminder_id.lock().unwrap();
+        reminder_reason::settled(&state, id, "stood_up", now);
+        // The banner can't be clicked, so this is how it is usually answered.
+        overlay::hide(&app);
+    }
+    record_standup(&app, &state, now)
+}
+
+/// Log a standup that didn't come from answering a reminder: restart the
+/// countdown, drop pending breaks and repeats, and tell the windows, hooks
+/// and leaderboard. Returns today's standups.
+fn record_standup(app: &AppHandle, state: &AppState, now: i64) -> u32 {
+    *state.elapsed.lock().unwrap() = 0;
+    *state.focus_break_due.lock().unwrap() = None;
+    repeats::cancel(state);
+    state.standup_events.lock().unwrap().push(now);
+
+    timers::wake(state);
+    journal::record(app, raw_events::standup(now));
+    let analytics = build_analytics(state);
+
+    let _ = app.emit("standup-logged", ());
+    hooks::dispatch(
+        app,
+        hooks::HookEvent::StandupLogged,
+        &[("standups_today", analytics.standup_sessions.to_string())],
+    );
+    events::analytics_updated(app);
+    leaderboard::publish_in_background(app);
+    analytics.standup_sessions
+}
+
+/// Log the answer to the active reminder (a standup, or the sitting it
+/// ended if it went unanswered too long) and clear it. Returns the logged
+/// event, if any.
+fn settle_reminder(state: &AppState, stood_up: bool, now: i64) -> Option<RawEvent> {
+    let sedentary_threshold = *state.sedentary_threshold_secs.lock().unwrap();
+    // A desk-mode reminder to lower the desk: neither a standup nor sitting.
+    let lowering = desk::standing(state);
+
+    let start_ts = *state.active_reminder_start_ts.lock().unwrap();
+    let mut logged_sedentary = state.active_reminder_logged_sedentary.lock().unwrap();
+    let mut new_event = None;
+
+    if let Some(start) = start_ts {
+        let lag = (now - start).max(0) as u64;
+        if !*logged_sedentary && !lowering && lag >= sedentary_threshold {
+            let interval_secs = *state.active_reminder_interval_secs.lock().unwrap();
+            let record = ReminderRecord {
+                ts: start,
+                duration_secs: interval_secs,
+            };
+            new_event = Some(raw_events::sedentary(&record));
+            state.reminder_events.lock().unwrap().push(record);
+            *logged_sedentary = true;
+        } else if !*logged_sedentary && stood_up && !lowering {
+            let mut standups = state.standup_events.lock().unwrap();
+            standups.push(now);
+            new_event = Some(raw_events::standup(now));
+        }
+        let outcome = if *logged_sedentary {
+            "ignored"
+        } else if stood_up && lowering {
+            "desk_lowered"
+        } else if stood_up {
+            "stood_up"
+        } else {
+            "dismissed"
+        };
+        let id = *state.active_reminder_id.lock().unwrap();
+        reminder_reason::settled(state, id, outcome, now);
+    } else if stood_up {
+        let mut standups = state.standup_events.lock().unwrap();
+        standups.push(now);
+        new_event = Some(raw_events::standup(now));
+    }
+    *state.focus_break_due.lock().unwrap() = None;
+    repeats::cancel(state);
+
+    {
+        let mut elapsed = state.elapsed.lock().unwrap();
+        *elapsed = 0;
+    }
+    {
+        let mut visible = state.reminder_visible.lock().unwrap();
+        *visible = false;
+    }
+    {
+        let mut active_start = state.active_reminder_start_ts.lock().unwrap();
+        *active_start = None;
+    }
+    {
+        let mut shown_at = state.active_reminder_shown_at.lock().unwrap();
+        *shown_at = None;
+    }
+
+    new_event
+}
+
+/// `control` names what was used ("card", "close", "enter" or "escape")
+/// for the local interaction stats.
+#[tauri::command]
+fn acknowledge_reminder(
+    app: AppHandle,
+    stood_up: bool,
+    reminder_id: Option<u64>,
+    control: Option<String>,
+    answer: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let active_id = *state.active_reminder_id.lock().unwrap();
+    if let Some(id) = reminder_id {
+        if id != active_id {
+            return Ok(());
+        }
+    }
+    if close_reminder_preview(&app, &state) {
+        return Ok(());
+    }
+    if !*state.reminder_visible.lock().unwrap() {
+        return Ok(());
+    }
+
+    // Ignore very early clicks to prevent accidental auto-dismiss right after show.
+    let grace = Duration::from_millis(dev_intervals::ack_grace_ms(&state));
+    let shown_at = *state.active_reminder_shown_at.lock().unwrap();
+    if let Some(shown_at) = shown_at {
+        if shown_at.elapsed() < grace {
+            return Ok(());
+        }
+    }
+    let focused = app
+        .get_webview_window(reminder_windows::PRIMARY_LABEL)
+        .and_then(|win| win.is_focused().ok())
+        .unwrap_or(false);
+    // Out of free skips: the reminder stays until it is answered by standing.
+    let skip = !stood_up
+        && !*state.active_reminder_logged_sedentary.lock().unwrap()
+        && !desk::standing(&state);
+    if skip && skips::exhausted(&state) {
+        events::emit(&app, "skip-budget-exhausted", skips::budget(&state));
+        return Err("SKIP_BUDGET_EXHAUSTED".to_string());
+    }
+    // A desk reporting the move it was asked for is proof enough.
+    let desk_reported = control.as_deref() == Some("desk") && desk::enabled(&state);
+    if stood_up && !desk_reported {
+        challenge::check(&state, answer.as_deref())?;
+    }
+    let desk_move = desk::next_move(&state).filter(|_| stood_up);
+    let new_event = settle_reminder(&state, stood_up, now_ts());
+    if skip && new_event.is_none() {
+        skips::record(&state, now_ts());
+        repeats::schedule(&state, now_ts());
+        events::analytics_updated(&app);
+    }
+    if let Some(to) = desk_move {
+        desk::moved(&app, &state, now_ts(), to, control.as_deref() == Some("desk"));
+    }
+    interactions::record(
+        &state,
+        Interaction {
+            ts: now_ts(),
+            reminder_id: active_id,
+            control: control.as_deref().and_then(interactions::Control::parse),
+            stood_up,
+            latency_ms: shown_at.map_or(0, |at| at.elapsed().as_millis() as u64),
+            focused,
+        },
+    );
+    journal::compact_soon(&app);
+    timers::wake(&state);
+
+    if let Some(event) = new_event {
+        journal::record(&app, event);
+        events::analytics_updated(&app);
+        if stood_up {
+            let _ = app.emit("standup-logged", ());
+            let standups_today = build_analytics(&state).standup_sessions;
+            hooks::dispatch(
+                &app,
+                hooks::HookEvent::StandupLogged,
+                &[("standups_today", standups_today.to_string())],
+            );
+            leaderboard::publish_in_background(&app);
+        }
+    }
+
+    reminder_windows::dismiss(&app);
+    let previous = state.previous_foreground.lock().unwrap().take();
+    if let Some(previous) = previous {
+        focus::restore_foreground(&previous);
+    }
+    Ok(())
+}
+
+/// Keyboard bindings for the reminder card: Enter confirms a standup,
+/// Escape dismisses while still sitting.
+fn reminder_key_outcome(key: &str) -> Option<bool> {
+    match key {
+        "Enter" => Some(true),
+        "Escape" => Some(false),
+        _ => None,
+    }
+}
+
+#[tauri::command]
+fn acknowledge_reminder_key(
+    app: AppHandle,
+    key: String,
+    reminder_id: Option<u64>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let stood_up = reminder_key_outcome(&key).ok_or_else(|| format!("unsupported key: {}", key))?;
+    acknowledge_reminder(app, stood_up, reminder_id, Some(key), None, state)
+}
+
+/// Enter or Escape from a reminder window's accelerators. While a challenge
+/// is up, Enter goes to the page instead, which sends its answer along.
+fn answer_reminder_key(app: &AppHandle, label: &str, key: &str) {
+    let state = app.state::<AppState>();
+    if key == "Enter" && challenge::status(&state).is_some() {
+        let _ = app.emit_to(label, "reminder-key", key);
+        return;
+    }
+    let _ = acknowledge_reminder_key(app.clone(), key.to_string(), None, state);
+}
+
+#[tauri::command]
+fn start_focus_session(app: AppHandle, minutes: Option<u64>) -> FocusSession {
+    timers::start_focus(&app, minutes.unwrap_or(timers::DEFAULT_FOCUS_MINUTES))
+}
+
+#[tauri::command]
+fn stop_focus_session(app: AppHandle) -> Option<FocusRecord> {
+    timers::finish_focus(&app, false)
+}
+
+#[tauri::command]
+fn get_focus_session(state: State<'_, AppState>) -> Option<FocusSession> {
+    state.focus_session.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn get_standup_count(state: State<'_, AppState>) -> u32 {
+    build_analytics(&state).standup_sessions
+}
+
+/// `weekly_mode` ("calendar" or "rolling") overrides the configured mode for
+/// this call only. `filter` narrows the events counted, e.g. to ignored
+/// reminders under one profile in the afternoon.
+#[tauri::command]
+fn get_analytics(
+    state: State<'_, AppState>,
+    period: Option<String>,
+    weekly_mode: Option<String>,
+    filter: Option<AnalyticsFilter>,
+) -> Result<AnalyticsData, String> {
+    let filter = filter.unwrap_or_default();
+    filter.validate()?;
+    let mut opts = period_options(&state);
+    if let Some(mode) = weekly_mode {
+        opts.calendar_week = normalize_weekly_mode(&mode) == "calendar";
+    }
+    let period = period.as_deref().unwrap_or("daily");
+    Ok(build_analytics_with_options(&state, period, &opts, &filter))
+}
+
+#[derive(Clone, Serialize)]
+struct WeekSettings {
+    week_start: String,
+    weekly_mode: String,
+}
+
+#[tauri::command]
+fn get_week_settings(state: State<'_, AppState>) -> WeekSettings {
+    WeekSettings {
+        week_start: state.week_start.lock().unwrap().clone(),
+        weekly_mode: state.weekly_mode.lock().unwrap().clone(),
+    }
+}
+
+#[tauri::command]
+fn set_week_settings(
+    app: AppHandle,
+    week_start: String,
+    weekly_mode: String,
+    state: State<'_, AppState>,
+) -> WeekSettings {
+    *state.week_start.lock().unwrap() = normalize_week_start(&week_start);
+    *state.weekly_mode.lock().unwrap() = normalize_weekly_mode(&weekly_mode);
+    let _ = save_config(&app, &state);
+    events::analytics_updated(&app);
+    get_week_settings(state)
+}
+
+/// Copies a one-line summary of the period, with a markdown table below it
+/// when `markdown` is set, and returns the copied text.
+#[tauri::command]
+fn copy_summary_to_clipboard(
+    app: AppHandle,
+    period: Option<String>,
+    markdown: Option<bool>,
+) -> Result<String, String> {
+    clipboard::copy_summary(
+        &app,
+        period.as_deref().unwrap_or("daily"),
+        markdown.unwrap_or(false),
+    )
+}
+
+#[tauri::command]
+fn export_analytics_csv(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    period: Option<String>,
+) -> Result<String, String> {
+    let period_key = normalize_period(period.as_deref().unwrap_or("daily"));
+    let analytics = build_analytics_for_period(&state, period_key);
+    if analytics.record_count < MIN_EXPORT_RECORDS {
+        return Err(format!("NOT_ENOUGH_DATA:{}", MIN_EXPORT_RECORDS));
+    }
+
+    let now = clock::now();
+    let file_name =
+        file_names::for_export(&state, file_names::ExportKind::Analytics, period_key, now, "csv");
+    let export_path = export_dir(&app)
+        .ok_or_else(|| "cannot resolve export directory".to_string())?
+        .join(file_name);
+
+    let mut csv = analytics_csv(&analytics, &Formatter::for_exports(&state));
+    if period_key == "monthly" {
+        let goal = goals::progress(&state, now);
+        if goal.enabled {
+            for row in goals::csv_rows(&goal) {
+                csv.push('\n');
+                csv.push_str(&row);
+            }
+        }
+    }
+    write_export(&state, &export_path, csv)?;
+    export_diff::record(
+        &app,
+        &state,
+        period_key,
+        ExportSnapshot::of(&analytics, now.timestamp()),
+        &export_path,
+        *state.export_diff.lock().unwrap(),
+    )?;
+    Ok(export_path.display().to_string())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn analytics_csv(analytics: &AnalyticsData, fmt: &Formatter) -> String {
+    let mut rows = vec!["hour,sedentary_sessions,standup_sessions".to_string()];
+    for hour in 0..HOURS {
+        rows.push(format!(
+            "{:02}:00,{},{}",
+            hour,
+            analytics.hourly_sedentary[hour],
+            analytics.hourly_standup[hour]
+        ));
+    }
+    rows.push(format!(
+        "totals,{},{}",
+        analytics.sedentary_sessions, analytics.standup_sessions
+    ));
+    rows.push(format!(
+        "total_sitting_minutes,{},",
+        (analytics.total_sitting_secs / 60)
+    ));
+    rows.push(format!(
+        "total_sitting,{},",
+        csv_field(&fmt.duration(analytics.total_sitting_secs))
+    ));
+    if let Some(start) = Local.timestamp_opt(analytics.period_start_ts, 0).single() {
+        rows.push(format!("period_start,{},", csv_field(&fmt.date(start.date_naive()))));
+    }
+    rows.push(format!("exported_at,{},", csv_field(&fmt.datetime(clock::now()))));
+    if !analytics.tags.is_empty() {
+        rows.push(String::new());
+        rows.push("tag,standup_sessions,sedentary_sessions,total_sitting".to_string());
+        for group in analytics.tags.iter() {
+            rows.push(format!(
+                "{},{},{},{}",
+                csv_field(group.tag.as_deref().unwrap_or("(untagged)")),
+                group.standups,
+                group.sedentary,
+                csv_field(&fmt.duration(group.sitting_secs))
+            ));
+        }
+    }
+    if !analytics.marked_days.is_empty() {
+        rows.push(String::new());
+        rows.push("marked_day,kind".to_string());
+        for day in analytics.marked_days.iter() {
+            let date = marked_days::parse_date(&day.date)
+                .map(|date| fmt.date(date))
+                .unwrap_or_else(|_| day.date.clone());
+            rows.push(format!("{},{}", csv_field(&date), day.kind.as_str()));
+        }
+    }
+    rows.join("\n")
+}
+
+#[tauri::command]
+fn get_session_tag(state: State<'_, AppState>) -> Option<String> {
+    tags::current(&state)
+}
+
+/// Returns the tag as stored: trimmed and capped, `None` if blank.
+#[tauri::command]
+fn set_session_tag(app: AppHandle, tag: Option<String>) -> Option<String> {
+    apply_session_tag(&app, tag)
+}
+
+#[tauri::command]
+fn get_recent_tags(state: State<'_, AppState>) -> Vec<String> {
+    tags::recent(&state)
+}
+
+#[tauri::command]
+fn get_record_power_source(state: State<'_, AppState>) -> bool {
+    *state.record_power_source.lock().unwrap()
+}
+
+/// Turning recording off also forgets the log: a stale last entry would
+/// otherwise label everything after it.
+#[tauri::command]
+fn set_record_power_source(app: AppHandle, enabled: bool, state: State<'_, AppState>) -> bool {
+    *state.record_power_source.lock().unwrap() = enabled;
+    {
+        let mut log = state.power_log.lock().unwrap();
+        log.clear();
+        if enabled {
+            power::log_source(&mut log, now_ts(), state.power.lock().unwrap().on_battery);
+        }
+    }
+    let _ = save_config(&app, &state);
+    let _ = save_analytics(&app, &state);
+    enabled
+}
+
+/// Analytics for `[start_ts, end_ts)`. `power_source` ("ac" or "battery")
+/// keeps only events logged on that source; it needs `record_power_source`,
+/// and events from before recording started match neither. `filter` works as
+/// for `get_analytics`.
+#[tauri::command]
+fn get_analytics_range(
+    state: State<'_, AppState>,
+    start_ts: i64,
+    end_ts: i64,
+    power_source: Option<String>,
+    filter: Option<AnalyticsFilter>,
+) -> Result<AnalyticsData, String> {
+    if end_ts <= start_ts {
+        return Err("end_ts must be after start_ts".to_string());
+    }
+    let filter = filter.unwrap_or_default();
+    filter.validate()?;
+    let on_battery = match power_source.as_deref() {
+        None => None,
+        Some("ac") => Some(false),
+        Some("battery") => Some(true),
+        Some(other) => return Err(format!("unknown power source: {}", other)),
+    };
+    let log = state.power_log.lock().unwrap().clone();
+    let keep = |ts: i64| on_battery.is_none() || power::on_battery_at(&log, ts) == on_battery;
+    Ok(build_analytics_filtered(&state, start_ts, end_ts, &keep, &filter))
+}
+
+#[tauri::command]
+fn get_export_date_format(state: State<'_, AppState>) -> String {
+    state.export_date_format.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn set_export_date_format(app: AppHandle, format: String, state: State<'_, AppState>) -> String {
+    let normalized = normalize_export_date_format(&format);
+    *state.export_date_format.lock().unwrap() = normalized.clone();
+    let _ = save_config(&app, &state);
+    normalized
+}
+
+#[tauri::command]
+fn get_export_diff(state: State<'_, AppState>) -> bool {
+    *state.export_diff.lock().unwrap()
+}
+
+/// With `enabled`, analytics exports are followed by a `<name>_diff.csv`
+/// comparing them with the last export of the same period.
+#[tauri::command]
+fn set_export_diff(app: AppHandle, enabled: bool, state: State<'_, AppState>) -> bool {
+    *state.export_diff.lock().unwrap() = enabled;
+    let _ = save_config(&app, &state);
+    enabled
+}
+
+#[tauri::command]
+fn get_export_filename_template(state: State<'_, AppState>) -> String {
+    state.export_filename_template.lock().unwrap().clone()
+}
+
+/// Name exports after `template`, built from {app}, {period}, {date},
+/// {time} and {type}; the extension is added. Blank restores the built-in
+/// names.
+#[tauri::command]
+fn set_export_filename_template(
+    app: AppHandle,
+    template: String,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let template = file_names::validate(&template)?;
+    *state.export_filename_template.lock().unwrap() = template.clone();
+    save_config(&app, &state)?;
+    Ok(template)
+}
+
+#[tauri::command]
+fn get_export_schedule(state: State<'_, AppState>) -> ExportScheduleConfig {
+    state.export_schedule.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn set_export_schedule(
+    app: AppHandle,
+    schedule: ExportScheduleConfig,
+    state: State<'_, AppState>,
+) -> ExportScheduleConfig {
+    let mut sanitized = scheduled_export::sanitize(schedule);
+    {
+        let mut current = state.export_schedule.lock().unwrap();
+        // The run marker is owned by the scheduler, not the settings form.
+        sanitized.last_run_date = current.last_run_date.clone();
+        *current = sanitized.clone();
+    }
+    let _ = save_config(&app, &state);
+    sanitized
+}
+
+#[tauri::command]
+fn get_monthly_goal(state: State<'_, AppState>) -> MonthlyGoal {
+    state.monthly_goal.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn set_monthly_goal(app: AppHandle, goal: MonthlyGoal, state: State<'_, AppState>) -> MonthlyGoal {
+    let sanitized = goals::sanitize(goal);
+    *state.monthly_goal.lock().unwrap() = sanitized.clone();
+    let _ = save_config(&app, &state);
+    events::analytics_updated(&app);
+    sanitized
+}
+
+#[tauri::command]
+fn get_monthly_goal_progress(state: State<'_, AppState>) -> MonthlyGoalProgress {
+    goals::progress(&state, clock::now())
+}
+
+#[tauri::command]
+fn set_pre_warning_minutes(app: AppHandle, minutes: u64, state: State<'_, AppState>) -> u64 {
+    let normalized = minutes.min(timers::MAX_PRE_WARNING_MINUTES);
+    *state.pre_warning_minutes.lock().unwrap() = normalized;
+    let _ = save_config(&app, &state);
+    normalized
+}
+
+#[tauri::command]
+fn get_pre_warning_minutes(state: State<'_, AppState>) -> u64 {
+    *state.pre_warning_minutes.lock().unwrap()
+}
+
+/// Daily sitting budget in minutes; 0 turns it off.
+#[tauri::command]
+fn set_sitting_budget_minutes(app: AppHandle, minutes: u64, state: State<'_, AppState>) -> u64 {
+    let normalized = minutes.min(budget::MAX_BUDGET_MINUTES);
+    *state.sitting_budget_minutes.lock().unwrap() = normalized;
+    let _ = save_config(&app, &state);
+    timers::wake(&state);
+    normalized
+}
+
+#[tauri::command]
+fn get_sitting_budget_minutes(state: State<'_, AppState>) -> u64 {
+    *state.sitting_budget_minutes.lock().unwrap()
+}
+
+/// Seconds sat per second stood to stay under, e.g. 3.0 for 3:1; `None`
+/// turns the target off. Returns the stored target.
+#[tauri::command]
+fn set_sit_stand_target(
+    app: AppHandle,
+    target: Option<f64>,
+    state: State<'_, AppState>,
+) -> Option<f64> {
+    let normalized = ratio::sanitize_target(target);
+    *state.sit_stand_target.lock().unwrap() = normalized;
+    *state.sit_stand_below.lock().unwrap() = false;
+    let _ = save_config(&app, &state);
+    normalized
+}
+
+#[tauri::command]
+fn get_sit_stand_target(state: State<'_, AppState>) -> Option<f64> {
+    *state.sit_stand_target.lock().unwrap()
+}
+
+/// Minutes of unbroken focus on the settings or stats window before a
+/// nudge; 0 turns it off.
+#[tauri::command]
+fn set_window_nudge_minutes(app: AppHandle, minutes: u64, state: State<'_, AppState>) -> u64 {
+    let normalized = minutes.min(window_focus::MAX_NUDGE_MINUTES);
+    *state.window_nudge_minutes.lock().unwrap() = normalized;
+    let _ = save_config(&app, &state);
+    normalized
+}
+
+#[tauri::command]
+fn get_window_nudge_minutes(state: State<'_, AppState>) -> u64 {
+    *state.window_nudge_minutes.lock().unwrap()
+}
+
+/// Takes effect at the next wakeup.
+#[tauri::command]
+fn set_sleep_policy(
+    app: AppHandle,
+    policy: SleepPolicy,
+    state: State<'_, AppState>,
+) -> SleepPolicy {
+    *state.sleep_policy.lock().unwrap() = policy;
+    let _ = save_config(&app, &state);
+    policy
+}
+
+#[tauri::command]
+fn get_sleep_policy(state: State<'_, AppState>) -> SleepPolicy {
+    *state.sleep_policy.lock().unwrap()
+}
+
+/// Settings changes, newest first; `limit` defaults to 50.
+#[tauri::command]
+fn get_config_history(limit: Option<usize>, state: State<'_, AppState>) -> Vec<ConfigChange> {
+    config_history::list(&state, limit.unwrap_or(50))
+}
+
+/// Set the key changed by `id` back to its earlier value. Returns the
+/// history with the revert on top.
+#[tauri::command]
+fn revert_config_change(
+    app: AppHandle,
+    id: u64,
+    state: State<'_, AppState>,
+) -> Result<Vec<ConfigChange>, String> {
+    config_history::revert(&app, id)?;
+    Ok(config_history::list(&state, 50))
+}
+
+#[tauri::command]
+fn get_window_focus(state: State<'_, AppState>) -> Vec<WindowFocusInfo> {
+    window_focus::summary(&state, now_ts())
+}
+
+#[tauri::command]
+fn set_input_activity_enabled(app: AppHandle, enabled: bool, state: State<'_, AppState>) -> bool {
+    *state.input_activity_enabled.lock().unwrap() = enabled;
+    let _ = save_config(&app, &state);
+    enabled
+}
+
+#[tauri::command]
+fn get_input_activity_enabled(state: State<'_, AppState>) -> bool {
+    *state.input_activity_enabled.lock().unwrap()
+}
+
+#[tauri::command]
+fn get_mute_when_mic_in_use(state: State<'_, AppState>) -> bool {
+    *state.mute_when_mic_in_use.lock().unwrap()
+}
+
+/// Checked every `activity::MIC_POLL_SECS`; turning it off unmutes at once.
+#[tauri::command]
+fn set_mute_when_mic_in_use(app: AppHandle, enabled: bool, state: State<'_, AppState>) -> bool {
+    *state.mute_when_mic_in_use.lock().unwrap() = enabled;
+    if !enabled && std::mem::take(&mut *state.mic_in_use.lock().unwrap()) {
+        let _ = app.emit("mic-in-use-changed", false);
+    }
+    let _ = save_config(&app, &state);
+    enabled
+}
+
+#[tauri::command]
+fn get_tray_icons(state: State<'_, AppState>) -> TrayIconConfig {
+    state.tray_icons.lock().unwrap().clone()
+}
+
+/// Names usable as `{"bundled": name}` in the tray icon config.
+#[tauri::command]
+fn list_bundled_tray_icons() -> Vec<String> {
+    tray_icons::BUNDLED.iter().map(|name| name.to_string()).collect()
+}
+
+/// Pick the tray icon per mode (normal, paused, vacation, focus). Files must
+/// load as images; unknown bundled names are dropped. The icon swaps within
+/// a couple of seconds.
+#[tauri::command]
+fn set_tray_icons(
+    app: AppHandle,
+    config: TrayIconConfig,
+    state: State<'_, AppState>,
+) -> Result<TrayIconConfig, String> {
+    let config = tray_icons::sanitize(config);
+    tray_icons::validate(&config)?;
+    *state.tray_icons.lock().unwrap() = config.clone();
+    save_config(&app, &state)?;
+    Ok(config)
+}
+
+#[tauri::command]
+fn get_blocking_apps(state: State<'_, AppState>) -> BlockingApps {
+    state.blocking_apps.lock().unwrap().clone()
+}
+
+/// Process names that pause reminders while running. The next scan, at
+/// most one poll away, picks up the new list.
+#[tauri::command]
+fn set_blocking_apps(
+    app: AppHandle,
+    config: BlockingApps,
+    state: State<'_, AppState>,
+) -> BlockingApps {
+    let sanitized = activity::sanitize_blocking_apps(config);
+    *state.blocking_apps.lock().unwrap() = sanitized.clone();
+    let _ = save_config(&app, &state);
+    sanitized
+}
+
+#[tauri::command]
+fn get_weekend_override(state: State<'_, AppState>) -> WeekendOverride {
+    state.weekend.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn set_weekend_override(
+    app: AppHandle,
+    weekend: WeekendOverride,
+    state: State<'_, AppState>,
+) -> WeekendOverride {
+    let sanitized = sanitize_weekend_override(&state, weekend);
+    *state.weekend.lock().unwrap() = sanitized.clone();
+    let _ = save_config(&app, &state);
+    let _ = app.emit("tick", timers::tick_payload(&state));
+    sanitized
+}
+
+#[tauri::command]
+fn get_holiday_config(state: State<'_, AppState>) -> HolidayConfig {
+    state.holidays.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn set_holiday_config(
+    app: AppHandle,
+    config: HolidayConfig,
+    state: State<'_, AppState>,
+) -> HolidayConfig {
+    let sanitized = holidays::sanitize(config);
+    *state.holidays.lock().unwrap() = sanitized.clone();
+    let _ = save_config(&app, &state);
+    let _ = app.emit("tick", timers::tick_payload(&state));
+    sanitized
+}
+
+/// Mark `date` ("YYYY-MM-DD") as a "rest", "sick" or "travel" day so
+/// streaks, goals and weekly reports leave it out; "none" clears the mark.
+/// Returns every marked day.
+#[tauri::command]
+fn mark_day(
+    app: AppHandle,
+    day_type: String,
+    date: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<MarkedDay>, String> {
+    let date = marked_days::parse_date(&date)?;
+    let kind = match day_type.as_str() {
+        "none" => None,
+        other => Some(DayKind::parse(other).ok_or_else(|| format!("unknown day type: {}", other))?),
+    };
+    let days = marked_days::mark(&state, date, kind);
+    save_analytics(&app, &state)?;
+    events::analytics_updated(&app);
+    Ok(days)
+}
+
+#[tauri::command]
+fn get_marked_days(state: State<'_, AppState>) -> Vec<MarkedDay> {
+    state.marked_days.lock().unwrap().clone()
+}
+
+/// Dry run of `import_history`: what would be added from another app's
+/// statistics file, without touching analytics.
+#[tauri::command]
+fn preview_history_import(path: String, state: State<'_, AppState>) -> Result<ImportPreview, String> {
+    let interval = *state.interval.lock().unwrap();
+    importers::import(&state, &path, true, interval)
+}
+
+/// Import Workrave (or detect Stretchly / Time Out) history into analytics.
+#[tauri::command]
+fn import_history(
+    app: AppHandle,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<ImportPreview, String> {
+    let interval = *state.interval.lock().unwrap();
+    let preview = importers::import(&state, &path, false, interval)?;
+    if preview.days > 0 {
+        save_analytics(&app, &state)?;
+        events::analytics_updated(&app);
+    }
+    Ok(preview)
+}
+
+/// Merge the events of an ICS calendar into the custom holiday list.
+#[tauri::command]
+fn import_holidays_ics(
+    app: AppHandle,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<HolidayConfig, String> {
+    let imported = holidays::import_ics_file(&path)?;
+    let merged = {
+        let mut cfg = state.holidays.lock().unwrap();
+        let mut next = cfg.clone();
+        next.custom.extend(imported);
+        *cfg = holidays::sanitize(next);
+        cfg.clone()
+    };
+    save_config(&app, &state)?;
+    Ok(merged)
+}
+
+#[tauri::command]
+fn list_holidays(year: Option<i32>, state: State<'_, AppState>) -> Vec<Holiday> {
+    let year = year.unwrap_or_else(|| clock::now().year());
+    holidays::holidays_for_year(&state.holidays.lock().unwrap(), year)
+}
+
+#[tauri::command]
+fn get_today_holiday(state: State<'_, AppState>) -> Option<Holiday> {
+    holidays::holiday_on(&state.holidays.lock().unwrap(), clock::now().date_naive())
+}
+
+#[tauri::command]
+fn get_day_summary_config(state: State<'_, AppState>) -> DaySummaryConfig {
+    state.day_summary.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn set_day_summary_config(
+    app: AppHandle,
+    config: DaySummaryConfig,
+    state: State<'_, AppState>,
+) -> DaySummaryConfig {
+    let mut sanitized = reports::sanitize(config);
+    {
+        let mut current = state.day_summary.lock().unwrap();
+        sanitized.last_sent_date = current.last_sent_date.clone();
+        *current = sanitized.clone();
+    }
+    let _ = save_config(&app, &state);
+    sanitized
+}
+
+#[tauri::command]
+fn get_day_summary(state: State<'_, AppState>) -> DaySummary {
+    reports::day_summary(&state)
+}
+
+#[tauri::command]
+fn send_day_summary_now(app: AppHandle) -> DaySummary {
+    reports::send_day_summary(&app)
+}
+
+#[tauri::command]
+fn get_leaderboard_config(state: State<'_, AppState>) -> LeaderboardConfig {
+    state.leaderboard.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn set_leaderboard_config(
+    app: AppHandle,
+    config: LeaderboardConfig,
+    state: State<'_, AppState>,
+) -> LeaderboardConfig {
+    let sanitized = leaderboard::sanitize(config);
+    *state.leaderboard.lock().unwrap() = sanitized.clone();
+    let _ = save_config(&app, &state);
+    sanitized
+}
+
+#[tauri::command]
+fn get_google_sheets_config(state: State<'_, AppState>) -> GoogleSheetsConfig {
+    state.google_sheets.lock().unwrap().clone()
+}
+
+/// The key file must hold a usable service-account key. Pointing at another
+/// spreadsheet or tab starts it over with yesterday's row.
+#[tauri::command]
+fn set_google_sheets_config(
+    app: AppHandle,
+    config: GoogleSheetsConfig,
+    state: State<'_, AppState>,
+) -> Result<GoogleSheetsConfig, String> {
+    let mut config = google_sheets::sanitize(config);
+    if let Some(path) = config.key_file.as_deref().filter(|_| config.enabled) {
+        google_sheets::load_key(path)?;
+    }
+    {
+        let mut current = state.google_sheets.lock().unwrap();
+        let same_sheet =
+            current.spreadsheet_id == config.spreadsheet_id && current.sheet == config.sheet;
+        config.last_appended_date = current.last_appended_date.clone().filter(|_| same_sheet);
+        *current = config.clone();
+    }
+    save_config(&app, &state)?;
+    Ok(config)
+}
+
+/// Append the finished days not yet in the sheet now instead of waiting for
+/// the export loop. Returns how many rows were added.
+#[tauri::command]
+async fn append_google_sheets_now(app: AppHandle) -> Result<u32, String> {
+    tauri::async_runtime::spawn_blocking(move || google_sheets::append_pending(&app))
+        .await
+        .map_err(|e| format!("append task failed: {}", e))?
+}
+
+#[tauri::command]
+fn get_hooks(state: State<'_, AppState>) -> Vec<HookConfig> {
+    state.hooks.lock().unwrap().clone()
+}
+
+/// Replace every hook. Returns the stored list.
+#[tauri::command]
+fn set_hooks(
+    app: AppHandle,
+    hooks: Vec<HookConfig>,
+    state: State<'_, AppState>,
+) -> Vec<HookConfig> {
+    let hooks = hooks::sanitize(hooks);
+    *state.hooks.lock().unwrap() = hooks.clone();
+    let _ = save_config(&app, &state);
+    hooks
+}
+
+/// The last runs since launch, newest last.
+#[tauri::command]
+fn get_hook_runs(state: State<'_, AppState>) -> Vec<HookRun> {
+    state.hook_runs.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn get_markdown_journal_config(state: State<'_, AppState>) -> MarkdownJournalConfig {
+    state.markdown_journal.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn set_markdown_journal_config(
+    app: AppHandle,
+    config: MarkdownJournalConfig,
+    state: State<'_, AppState>,
+) -> MarkdownJournalConfig {
+    let sanitized = markdown_journal::sanitize(config);
+    *state.markdown_journal.lock().unwrap() = sanitized.clone();
+    let _ = save_config(&app, &state);
+    sanitized
+}
+
+/// Write the entry for `date` ("YYYY-MM-DD", default today) to the journal
+/// file, replacing that day's section if it is already there. Returns the
+/// file's path.
+#[tauri::command]
+fn append_markdown_journal(
+    date: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let date = match date {
+        Some(date) => marked_days::parse_date(&date)?,
+        None => analytics_date(clock::now(), *state.day_start_hour.lock().unwrap()),
+    };
+    markdown_journal::append(&state, date)
+}
+
+#[tauri::command]
+fn get_local_api_config(state: State<'_, AppState>) -> LocalApiConfig {
+    state.local_api.lock().unwrap().clone()
+}
+
+/// The server picks up the change within a few seconds.
+#[tauri::command]
+fn set_local_api_config(
+    app: AppHandle,
+    config: LocalApiConfig,
+    state: State<'_, AppState>,
+) -> LocalApiConfig {
+    let sanitized = local_api::sanitize(config);
+    *state.local_api.lock().unwrap() = sanitized.clone();
+    let _ = save_config(&app, &state);
+    sanitized
+}
+
+#[tauri::command]
+fn get_status_file_config(state: State<'_, AppState>) -> StatusFileConfig {
+    state.status_file.lock().unwrap().clone()
+}
+
+/// Takes effect from the next write, at most the old cadence away.
+#[tauri::command]
+fn set_status_file_config(
+    app: AppHandle,
+    config: StatusFileConfig,
+    state: State<'_, AppState>,
+) -> StatusFileConfig {
+    let sanitized = status_file::sanitize(config);
+    *state.status_file.lock().unwrap() = sanitized.clone();
+    let _ = save_config(&app, &state);
+    sanitized
+}
+
+#[tauri::command]
+fn get_handoff_config(state: State<'_, AppState>) -> HandoffConfig {
+    state.handoff.lock().unwrap().clone()
+}
+
+/// Takes effect from the next sync round, at most 30 seconds away.
+#[tauri::command]
+fn set_handoff_config(
+    app: AppHandle,
+    config: HandoffConfig,
+    state: State<'_, AppState>,
+) -> Result<HandoffConfig, String> {
+    let sanitized = handoff::sanitize(config);
+    handoff::validate(&sanitized)?;
+    *state.handoff.lock().unwrap() = sanitized.clone();
+    save_config(&app, &state)?;
+    Ok(sanitized)
+}
+
+/// The user's other devices as of the last sync round.
+#[tauri::command]
+fn get_handoff_peers(state: State<'_, AppState>) -> Vec<HandoffPeer> {
+    state.handoff_peers.lock().unwrap().clone()
+}
 
-    let _ = app.emit("standup-logged", ());
-    let _ = app.emit("analytics-updated", ());
-    analytics.standup_sessions
+#[tauri::command]
+fn list_api_tokens(state: State<'_, AppState>) -> Vec<ApiToken> {
+    api_tokens::list(&state)
 }
 
+/// The returned token is the only copy; only its hash is stored.
 #[tauri::command]
-fn acknowledge_reminder(
+fn generate_api_token(
     app: AppHandle,
-    stood_up: bool,
-    reminder_id: Option<u64>,
+    name: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let active_id = *state.active_reminder_id.lock().unwrap();
-    if let Some(id) = reminder_id {
-        if id != active_id {
-            return Ok(());
-        }
-    }
-    if !*state.reminder_visible.lock().unwrap() {
-        return Ok(());
-    }
-
-    // Ignore very early clicks to prevent accidental auto-dismiss right after show.
-    if let Some(shown_at) = *state.active_reminder_shown_at.lock().unwrap() {
-        if shown_at.elapsed() < Duration::from_millis(700) {
-            return Ok(());
-        }
-    }
+) -> Result<NewApiToken, String> {
+    let token = api_tokens::generate(&state, &name)?;
+    api_tokens::changed(&app);
+    Ok(token)
+}
 
-    let now = now_ts();
-    let start_ts = *state.active_reminder_start_ts.lock().unwrap();
-    let mut logged_sedentary = state.active_reminder_logged_sedentary.lock().unwrap();
-    let mut wrote_analytics = false;
+#[tauri::command]
+fn revoke_api_token(
+    app: AppHandle,
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<ApiToken, String> {
+    let token = api_tokens::revoke(&state, &id)?;
+    api_tokens::changed(&app);
+    Ok(token)
+}
 
-    if let Some(start) = start_ts {
-        let lag = (now - start).max(0) as u64;
-        if !*logged_sedentary && lag >= 60 {
-            let interval_secs = *state.active_reminder_interval_secs.lock().unwrap();
-            {
-                let mut reminders = state.reminder_events.lock().unwrap();
-                reminders.push(ReminderRecord {
-                    ts: start,
-                    duration_secs: interval_secs,
-                });
-            }
-            *logged_sedentary = true;
-            wrote_analytics = true;
-        } else if !*logged_sedentary && stood_up {
-            let mut standups = state.standup_events.lock().unwrap();
-            standups.push(now);
-            wrote_analytics = true;
-        }
-    } else if stood_up {
-        let mut standups = state.standup_events.lock().unwrap();
-        standups.push(now);
-        wrote_analytics = true;
-    }
+/// Publish today's entry, then return the team board for today.
+#[tauri::command]
+async fn fetch_leaderboard(app: AppHandle) -> Result<Vec<LeaderboardEntry>, String> {
+    let (endpoint, entry) = leaderboard::todays_entry(&app.state::<AppState>())
+        .ok_or_else(|| "leaderboard sharing is disabled".to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        leaderboard::publish(&endpoint, &entry)?;
+        leaderboard::fetch(&endpoint, &entry.date)
+    })
+    .await
+    .map_err(|e| format!("leaderboard task failed: {}", e))?
+}
 
-    {
-        let mut elapsed = state.elapsed.lock().unwrap();
-        *elapsed = 0;
-    }
-    {
-        let mut visible = state.reminder_visible.lock().unwrap();
-        *visible = false;
-    }
-    {
-        let mut active_start = state.active_reminder_start_ts.lock().unwrap();
-        *active_start = None;
-    }
-    {
-        let mut shown_at = state.active_reminder_shown_at.lock().unwrap();
-        *shown_at = None;
-    }
+#[tauri::command]
+fn get_health(state: State<AppState>) -> Health {
+    health::check(&state)
+}
 
-    if wrote_analytics {
-        save_analytics(&app, &state);
-        let _ = app.emit("analytics-updated", ());
-        if stood_up {
-            let _ = app.emit("standup-logged", ());
-        }
-    }
+#[tauri::command]
+fn get_safe_mode(state: State<AppState>) -> bool {
+    state.safe_mode
+}
 
-    if let Some(w) = app.get_webview_window("reminder") {
-        let _ = w.hide();
-    }
-    Ok(())
+/// Whether the app runs as a read-only observer; write commands then fail
+/// with "ReadOnlyMode".
+#[tauri::command]
+fn get_read_only(state: State<AppState>) -> bool {
+    read_only::enabled(&state)
 }
 
+/// Quarantine unreadable config/analytics files. Restart without
+/// `--safe-mode` afterwards to continue from defaults.
 #[tauri::command]
-fn get_standup_count(state: State<'_, AppState>) -> u32 {
-    build_analytics(&state).standup_sessions
+fn repair_data_files(app: AppHandle) -> Result<Vec<String>, String> {
+    quarantine_corrupt_files(&app)
 }
 
 #[tauri::command]
-fn get_analytics(state: State<'_, AppState>, period: Option<String>) -> AnalyticsData {
-    build_analytics_for_period(&state, period.as_deref().unwrap_or("daily"))
+fn run_scheduled_export_now(app: AppHandle) -> ScheduledExportResult {
+    scheduled_export::run(&app, false)
 }
 
 #[tauri::command]
-fn export_analytics_csv(
+fn export_analytics_png(
     app: AppHandle,
+    data_url: String,
     state: State<'_, AppState>,
-    period: Option<String>,
 ) -> Result<String, String> {
-    let period_key = normalize_period(period.as_deref().unwrap_or("daily"));
-    let analytics = build_analytics_for_period(&state, period_key);
-    if analytics.record_count < MIN_EXPORT_RECORDS {
-        return Err(format!("NOT_ENOUGH_DATA:{}", MIN_EXPORT_RECORDS));
-    }
+    let payload = data_url
+        .strip_prefix("data:image/png;base64,")
+        .ok_or_else(|| "invalid png payload".to_string())?;
 
-    let mut rows = vec!["hour,sedentary_sessions,standup_sessions".to_string()];
-    for hour in 0..HOURS {
-        rows.push(format!(
-            "{:02}:00,{},{}",
-            hour,
-            analytics.hourly_sedentary[hour],
-            analytics.hourly_standup[hour]
-        ));
-    }
-    rows.push(format!(
-        "totals,{},{}",
-        analytics.sedentary_sessions, analytics.standup_sessions
-    ));
-    rows.push(format!(
-        "total_sitting_minutes,{},",
-        (analytics.total_sitting_secs / 60)
-    ));
+    let png_bytes = base64::engine::general_purpose::STANDARD
+        .decode(payload)
+        .map_err(|e| format!("decode failed: {}", e))?;
 
-    let now = Local::now();
-    let file_name = format!(
-        "standby_{}_analytics_{}.csv",
-        period_key,
-        now.format("%Y%m%d_%H%M%S")
-    );
+    let now = clock::now();
+    let file_name = file_names::for_export(&state, file_names::ExportKind::Heatmap, "24h", now, "png");
     let export_path = export_dir(&app)
         .ok_or_else(|| "cannot resolve export directory".to_string())?
         .join(file_name);
 
-    if let Some(parent) = export_path.parent() {
-        let _ = fs::create_dir_all(parent);
-    }
-    fs::write(&export_path, rows.join("\n")).map_err(|e| format!("write failed: {}", e))?;
+    write_export(&state, &export_path, png_bytes)?;
     Ok(export_path.display().to_string())
 }
 
+/// Every stored event as schema-versioned JSON, for backups and for
+/// `import_analytics`. Unlike the CSV export nothing is aggregated.
 #[tauri::command]
-fn export_analytics_png(app: AppHandle, data_url: String) -> Result<String, String> {
-    let payload = data_url
-        .strip_prefix("data:image/png;base64,")
-        .ok_or_else(|| "invalid png payload".to_string())?;
+fn export_raw_events(app: AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let dump = raw_events::dump(&state);
+    let json =
+        serde_json::to_string_pretty(&dump).map_err(|e| format!("serialize failed: {}", e))?;
 
-    let png_bytes = base64::engine::general_purpose::STANDARD
-        .decode(payload)
-        .map_err(|e| format!("decode failed: {}", e))?;
+    let now = clock::now();
+    let file_name = file_names::for_export(&state, file_names::ExportKind::RawEvents, "all", now, "json");
+    let export_path = export_dir(&app)
+        .ok_or_else(|| "cannot resolve export directory".to_string())?
+        .join(file_name);
+
+    write_export(&state, &export_path, json)?;
+    Ok(export_path.display().to_string())
+}
+
+/// Settings and installed tip packs as one portable file, without analytics.
+#[tauri::command]
+fn export_settings(app: AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let now = clock::now();
+    let bundle = settings_bundle::bundle(&app, now.timestamp());
+    let json =
+        serde_json::to_string_pretty(&bundle).map_err(|e| format!("serialize failed: {}", e))?;
 
-    let now = Local::now();
-    let file_name = format!("standby_24h_heatmap_{}.png", now.format("%Y%m%d_%H%M%S"));
+    let file_name = file_names::for_export(&state, file_names::ExportKind::Settings, "all", now, "json");
     let export_path = export_dir(&app)
         .ok_or_else(|| "cannot resolve export directory".to_string())?
         .join(file_name);
 
-    if let Some(parent) = export_path.parent() {
-        let _ = fs::create_dir_all(parent);
-    }
-    fs::write(&export_path, png_bytes).map_err(|e| format!("write failed: {}", e))?;
+    write_export(&state, &export_path, json)?;
     Ok(export_path.display().to_string())
 }
 
+/// Replace the settings with a file written by `export_settings`. Analytics
+/// and this machine's window placement are left alone.
+#[tauri::command]
+fn import_settings(
+    app: AppHandle,
+    path: String,
+) -> Result<settings_bundle::SettingsImportResult, String> {
+    settings_bundle::import_file(&app, &path)
+}
+
+/// Merge a file written by `export_raw_events`; events already present are skipped.
+#[tauri::command]
+fn import_analytics(
+    app: AppHandle,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<RawImportResult, String> {
+    let result = raw_events::import_file(&state, &path)?;
+    if result.imported > 0 {
+        save_analytics(&app, &state)?;
+        events::analytics_updated(&app);
+    }
+    Ok(result)
+}
+
 #[tauri::command]
 fn reset_daily_records(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
-    let start_ts = period_start_ts("daily", Local::now());
+    let start_ts = period_start_ts("daily", clock::now(), &period_options(&state));
     {
         let mut reminders = state.reminder_events.lock().unwrap();
         reminders.retain(|e| e.ts < start_ts);
@@ -818,11 +3951,21 @@ fn reset_daily_records(app: AppHandle, state: State<'_, AppState>) -> Result<(),
         let mut standups = state.standup_events.lock().unwrap();
         standups.retain(|ts| *ts < start_ts);
     }
-    save_analytics(&app, &state);
-    let _ = app.emit("analytics-updated", ());
+    state
+        .activity_buckets
+        .lock()
+        .unwrap()
+        .retain(|b| b.hour_ts < start_ts);
+    save_analytics(&app, &state)?;
+    events::analytics_updated(&app);
     Ok(())
 }
 
+#[tauri::command]
+fn open_stats_window(app: AppHandle) -> Result<(), String> {
+    window_manager::show_or_create_stats_window(&app)
+}
+
 #[tauri::command]
 fn window_minimize(app: AppHandle, label: String) -> Result<(), String> {
     if let Some(w) = app.get_webview_window(&label) {
@@ -856,6 +3999,7 @@ fn window_toggle_maximize(app: AppHandle, label: String) -> Result<(), String> {
 #[tauri::command]
 fn window_close(app: AppHandle, label: String) -> Result<(), String> {
     if let Some(w) = app.get_webview_window(&label) {
+        window_manager::remember_geometry(&app, &w);
         if label == "settings" {
             w.hide().map_err(|e| format!("hide failed: {}", e))?;
         } else {
@@ -866,6 +4010,11 @@ fn window_close(app: AppHandle, label: String) -> Result<(), String> {
     Err("window not found".into())
 }
 
+#[tauri::command]
+fn window_set_always_on_top(app: AppHandle, label: String, on_top: bool) -> Result<(), String> {
+    window_manager::set_always_on_top(&app, &label, on_top)
+}
+
 #[tauri::command]
 fn window_hide(app: AppHandle, label: String) -> Result<(), String> {
     if let Some(w) = app.get_webview_window(&label) {
@@ -875,247 +4024,596 @@ fn window_hide(app: AppHandle, label: String) -> Result<(), String> {
     Err("window not found".into())
 }
 
-fn show_or_create_settings_window(app: &AppHandle) {
-    if let Some(win) = app.get_webview_window("settings") {
-        let _ = win.show();
-        let _ = win.set_focus();
-        return;
-    }
-
-    let created = WebviewWindowBuilder::new(
-        app,
-        "settings",
-        WebviewUrl::App("settings.html".into()),
-    )
-    .title("Upstand Dashboard")
-    .inner_size(980.0, 700.0)
-    .decorations(false)
-    .transparent(false)
-    .center()
-    .build();
+/// Show the reminder window according to `reminder_focus_mode`. In "steal" mode
+/// the current foreground window is captured first so it can be handed back on
+/// acknowledgment; "show-without-focus" keeps the window non-activating so
+/// typing elsewhere is never interrupted.
+fn present_reminder_window(rw: &tauri::WebviewWindow, state: &AppState) -> Result<(), String> {
+    let steal_focus = *state.reminder_focus_mode.lock().unwrap() == "steal";
+    let previous = if steal_focus {
+        focus::capture_foreground()
+    } else {
+        None
+    };
+    *state.previous_foreground.lock().unwrap() = previous;
 
-    if let Ok(win) = created {
-        let _ = win.show();
-        let _ = win.set_focus();
+    let _ = rw.set_focusable(steal_focus);
+    rw.show().map_err(|e| format!("show failed: {}", e))?;
+    if steal_focus {
+        rw.set_focus().map_err(|e| format!("focus failed: {}", e))?;
     }
+    Ok(())
 }
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let safe_mode = args.iter().any(|arg| arg == "--safe-mode");
+    let state = AppState::new(safe_mode);
+    *state.read_only.lock().unwrap() = read_only::from_args(&args);
+    *state.dev_intervals_from_args.lock().unwrap() = dev_intervals::from_args(&args);
+    if let Some(scale) = clock::scale_from_args(&args) {
+        clock::install(scale);
+        // Simulated days must not land in the real store, and read-only mode
+        // also keeps Sheets, hooks and scheduled exports quiet.
+        *state.read_only.lock().unwrap() = true;
+        eprintln!("running on simulated time at {}x, read-only", scale);
+    }
     tauri::Builder::default()
         .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
-            show_or_create_settings_window(app);
+            window_manager::show_or_create_settings_window(app);
         }))
-        .manage(AppState {
-            interval: Mutex::new(DEFAULT_INTERVAL_MINUTES * 60),
-            elapsed: Mutex::new(0),
-            last_interval_change: Mutex::new(Instant::now()),
-            reminder_events: Mutex::new(Vec::new()),
-            standup_events: Mutex::new(Vec::new()),
-            reminder_visible: Mutex::new(false),
-            language: Mutex::new("en".to_string()),
-            reminder_language: Mutex::new("en".to_string()),
-            theme: Mutex::new("night".to_string()),
-            last_tip_index: Mutex::new(None),
-            active_reminder_id: Mutex::new(0),
-            active_reminder_start_ts: Mutex::new(None),
-            active_reminder_shown_at: Mutex::new(None),
-            active_reminder_interval_secs: Mutex::new(DEFAULT_INTERVAL_MINUTES * 60),
-            active_reminder_logged_sedentary: Mutex::new(false),
-            active_reminder_tip: Mutex::new("Time to stand up and stretch.".to_string()),
-        })
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .manage(state)
         .setup(|app| {
             let app_handle = app.handle().clone();
 
             let state = app.state::<AppState>();
-            load_config(&app_handle, &state);
-            load_analytics(&app_handle, &state);
+            // Safe mode skips the user's files entirely so a corrupt config or
+            // store can't take startup down; the built-in tips stay enabled.
+            if !state.safe_mode {
+                data_dir::load_override(&app_handle);
+                let loaded = read_config(&app_handle);
+                config_history::start(&app_handle, &loaded);
+                apply_config(&state, loaded);
+                api_tokens::load(&app_handle);
+                // Read either way; only the lock holder writes or replays
+                // the journal, which belongs to the other process otherwise.
+                // Read-only mode doesn't even create the lock file.
+                let locked =
+                    !read_only::enabled(&state) && store_lock::acquire(&app_handle).is_ok();
+                // Persist normalized/migrated config into the current data path.
+                let _ = save_config(&app_handle, &state);
+                load_analytics(&app_handle, &state);
+                // The config may have been edited or imported since the last run.
+                desk::set_enabled(&state, now_ts(), desk::enabled(&state));
+                let recovered = if locked {
+                    journal::replay(&app_handle, &state)
+                } else {
+                    0
+                };
+                if recovered > 0 {
+                    eprintln!("recovered {} events from the journal", recovered);
+                }
+                changelog::check_for_update(&app_handle);
+            }
+            tip_packs::rebuild_pool(&app_handle);
+            {
+                let handle = app_handle.clone();
+                tauri::async_runtime::spawn_blocking(move || plugins::reload(&handle));
+            }
+            // Start today's delivery counts from launch.
+            delivery::today(&state);
+            *state.accessibility.lock().unwrap() = accessibility::detect();
             let startup_lang = state.language.lock().unwrap().clone();
+            // The settings window is declared in tauri.conf.json, so restore it here.
+            if let Some(settings) = app.get_webview_window(window_manager::SETTINGS_LABEL) {
+                window_manager::restore_window(&app_handle, &settings);
+            }
 
             let tray_menu = make_tray_menu(&app_handle, &startup_lang)?;
 
             let tray_icon = Image::from_path("icons/icon-16.png")
                 .or_else(|_| Image::from_path("icons/icon-32.png"))
                 .ok()
-                .or_else(|| app.default_window_icon().cloned())
+                .or_else(|| app.default_window_icon().cloned().map(Image::to_owned))
                 .ok_or("missing tray icon")?;
 
+            tauri::async_runtime::spawn(health::monitor(app_handle.clone()));
+            tauri::async_runtime::spawn(tray_icons::run(app_handle.clone(), tray_icon.clone()));
+
             let tray = TrayIconBuilder::with_id(TRAY_ID)
                 .icon(tray_icon)
                 .menu(&tray_menu)
                 .on_menu_event(|app, event| match event.id.as_ref() {
                     "open_settings" => {
-                        show_or_create_settings_window(app);
+                        window_manager::show_or_create_settings_window(app);
+                    }
+                    "open_stats" => {
+                        let _ = window_manager::show_or_create_stats_window(app);
+                    }
+                    "toggle_focus" => {
+                        let running = app.state::<AppState>().focus_session.lock().unwrap().is_some();
+                        if running {
+                            timers::finish_focus(app, false);
+                        } else {
+                            timers::start_focus(app, timers::DEFAULT_FOCUS_MINUTES);
+                        }
+                    }
+                    "tag_none" => {
+                        apply_session_tag(app, None);
+                    }
+                    id if id.starts_with("tag:") => {
+                        apply_session_tag(app, id.strip_prefix("tag:").map(str::to_string));
+                    }
+                    "quit" => {
+                        let _ = save_analytics(app, &app.state::<AppState>());
+                        app.exit(0)
                     }
-                    "quit" => app.exit(0),
                     _ => {}
                 })
+                .on_tray_icon_event(|tray, event| {
+                    if let TrayIconEvent::Click {
+                        button: MouseButton::Left,
+                        button_state: MouseButtonState::Up,
+                        ..
+                    } = event
+                    {
+                        let app = tray.app_handle();
+                        let pending = std::mem::take(
+                            &mut *app.state::<AppState>().day_summary_pending_click.lock().unwrap(),
+                        );
+                        if pending {
+                            let _ = window_manager::show_or_create_stats_window(app);
+                        }
+                    }
+                })
                 .build(app)?;
             std::mem::forget(tray);
 
-            let handle_for_splash = app_handle.clone();
+            let show_splash = *state.show_splash.lock().unwrap();
+            let open_settings = *state.open_settings_on_launch.lock().unwrap();
+            if show_splash {
+                window_manager::show_splash_window(&app_handle);
+                let handle_for_splash = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    tokio::time::sleep(Duration::from_secs_f32(2.6)).await;
+                    if let Some(main_win) = handle_for_splash.get_webview_window(window_manager::SPLASH_LABEL) {
+                        let _ = main_win.close();
+                    }
+                    if open_settings {
+                        window_manager::show_or_create_settings_window(&handle_for_splash);
+                    }
+                });
+            } else if open_settings {
+                window_manager::show_or_create_settings_window(&app_handle);
+            }
+
+            // OS language changes have no portable notification; poll for them.
+            let locale_handle = app_handle.clone();
             tauri::async_runtime::spawn(async move {
-                tokio::time::sleep(Duration::from_secs_f32(2.6)).await;
-                if let Some(main_win) = handle_for_splash.get_webview_window("main") {
-                    let _ = main_win.close();
+                loop {
+                    tokio::time::sleep(Duration::from_secs(LOCALE_POLL_SECS)).await;
+                    let Ok(detected) = tauri::async_runtime::spawn_blocking(detect_system_language).await else {
+                        continue;
+                    };
+                    follow_system_language(&locale_handle, detected);
                 }
-                show_or_create_settings_window(&handle_for_splash);
             });
 
-            let reminder_handle = app_handle.clone();
+            // OS accessibility settings have no portable change notification, so poll them.
+            let accessibility_handle = app_handle.clone();
             tauri::async_runtime::spawn(async move {
                 loop {
-                    tokio::time::sleep(Duration::from_secs(5)).await;
-
-                    let state = reminder_handle.state::<AppState>();
-                    if *state.reminder_visible.lock().unwrap() {
-                        if let Some(rw) = reminder_handle.get_webview_window("reminder") {
-                            if let Ok(false) = rw.is_visible() {
-                                let _ = rw.show();
-                                let _ = rw.set_focus();
-                                let reminder_id = *state.active_reminder_id.lock().unwrap();
-                                let _ = rw.emit("refresh_tip", reminder_id);
-                            }
-                        } else {
-                            *state.reminder_visible.lock().unwrap() = false;
-                            *state.active_reminder_start_ts.lock().unwrap() = None;
-                            *state.active_reminder_shown_at.lock().unwrap() = None;
-                            continue;
-                        }
+                    tokio::time::sleep(Duration::from_secs(ACCESSIBILITY_POLL_SECS)).await;
+                    let Ok(prefs) = tauri::async_runtime::spawn_blocking(accessibility::detect).await else {
+                        continue;
+                    };
+                    let state = accessibility_handle.state::<AppState>();
+                    let changed = {
+                        let mut current = state.accessibility.lock().unwrap();
+                        let changed = *current != prefs;
+                        *current = prefs;
+                        changed
+                    };
+                    if changed {
+                        let _ = accessibility_handle.emit("accessibility-changed", prefs);
+                    }
+                }
+            });
 
-                        let maybe_new_sedentary = {
-                            let start_opt = *state.active_reminder_start_ts.lock().unwrap();
-                            let mut logged = state.active_reminder_logged_sedentary.lock().unwrap();
-                            if let Some(start) = start_opt {
-                                let lag = (now_ts() - start).max(0) as u64;
-                                if !*logged && lag >= 60 {
-                                    *logged = true;
-                                    Some((start, lag))
-                                } else {
-                                    None
-                                }
-                            } else {
-                                None
-                            }
+            let power_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    let (on_battery, battery_saver) =
+                        tauri::async_runtime::spawn_blocking(power::detect_power_source)
+                            .await
+                            .unwrap_or_default();
+                    let display_off = power_handle
+                        .available_monitors()
+                        .map(|monitors| monitors.is_empty())
+                        .unwrap_or(false);
+                    let next = PowerState {
+                        on_battery,
+                        battery_saver,
+                        display_off,
+                    };
+                    let changed = {
+                        let state = power_handle.state::<AppState>();
+                        let mut current = state.power.lock().unwrap();
+                        let changed = *current != next;
+                        *current = next;
+                        changed
+                    };
+                    let logged = {
+                        let state = power_handle.state::<AppState>();
+                        let recording = *state.record_power_source.lock().unwrap();
+                        recording
+                            && power::log_source(
+                                &mut state.power_log.lock().unwrap(),
+                                now_ts(),
+                                on_battery,
+                            )
+                    };
+                    if logged {
+                        journal::compact_soon(&power_handle);
+                    }
+                    if changed {
+                        timers::wake(&power_handle.state::<AppState>());
+                        let _ = power_handle.emit("power-state-changed", next);
+                    }
+                    tokio::time::sleep(Duration::from_secs(POWER_POLL_SECS)).await;
+                }
+            });
+
+            // Presenters shouldn't get a full-window popup mid-demo; the tick
+            // holds the reminder while this flag is set.
+            let share_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    tokio::time::sleep(Duration::from_secs(SCREEN_SHARE_POLL_SECS)).await;
+                    let Ok(sharing) = tauri::async_runtime::spawn_blocking(screen_share::detect).await else {
+                        continue;
+                    };
+                    let state = share_handle.state::<AppState>();
+                    let changed = {
+                        let mut current = state.screen_sharing.lock().unwrap();
+                        let changed = *current != sharing;
+                        *current = sharing;
+                        changed
+                    };
+                    if !changed {
+                        continue;
+                    }
+                    if sharing && *state.reminder_visible.lock().unwrap() {
+                        reminder_windows::hide_all(&share_handle);
+                    }
+                    let _ = share_handle.emit("screen-sharing-changed", sharing);
+                }
+            });
+
+            // Docking or undocking can take away the monitor an open reminder
+            // is on; there is no portable notification for it.
+            let display_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    tokio::time::sleep(Duration::from_secs(DISPLAY_POLL_SECS)).await;
+                    let layout = reminder_windows::layout(&display_handle);
+                    let count = layout.len();
+                    if reminder_windows::layout_changed(&display_handle.state::<AppState>(), layout) {
+                        reminder_windows::relayout(&display_handle);
+                        let _ = display_handle.emit("display-changed", count);
+                    }
+                }
+            });
+
+            tauri::async_runtime::spawn(activity::run_blocking_scan(app_handle.clone()));
+            tauri::async_runtime::spawn(activity::run_mic_scan(app_handle.clone()));
+            tauri::async_runtime::spawn(network_profiles::run(app_handle.clone()));
+
+            if state.safe_mode {
+                notify(
+                    &app_handle,
+                    "Upstand",
+                    "Started in safe mode: default settings, no analytics, integrations off.",
+                );
+            }
+            if let Err(message) = store_lock::check(&state) {
+                notify(&app_handle, "Upstand", &message);
+            }
+
+            // Integrations and samplers stay off in safe mode.
+            if !state.safe_mode {
+                let export_handle = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    // A failed Sheets append is retried hourly and reported once.
+                    let mut sheets_retry_at: Option<Instant> = None;
+                    loop {
+                        clock::sleep(Duration::from_secs(60)).await;
+                        let due = {
+                            let state = export_handle.state::<AppState>();
+                            let power_saving = state.power.lock().unwrap().battery_saver;
+                            let cfg = state.export_schedule.lock().unwrap();
+                            !read_only::enabled(&state)
+                                && scheduled_export::is_due(&cfg, clock::now(), power_saving)
+                        };
+                        if due {
+                            scheduled_export::run(&export_handle, true);
+                        }
+                        let summary_due = {
+                            let state = export_handle.state::<AppState>();
+                            let cfg = state.day_summary.lock().unwrap();
+                            let day_start_hour = *state.day_start_hour.lock().unwrap();
+                            reports::is_due(&cfg, clock::now(), day_start_hour)
+                                && !timers::day_off(&state)
+                        };
+                        if summary_due {
+                            reports::send_day_summary(&export_handle);
+                        }
+                        let sheets_due = {
+                            let state = export_handle.state::<AppState>();
+                            !read_only::enabled(&state)
+                                && !state.power.lock().unwrap().battery_saver
+                                && sheets_retry_at.is_none_or(|at| Instant::now() >= at)
+                                && google_sheets::is_due(&state)
                         };
-                        if let Some((start, _lag)) = maybe_new_sedentary {
-                            let interval_secs = *state.active_reminder_interval_secs.lock().unwrap();
-                            {
-                                let mut reminders = state.reminder_events.lock().unwrap();
-                                reminders.push(ReminderRecord {
-                                    ts: start,
-                                    duration_secs: interval_secs,
-                                });
+                        if sheets_due {
+                            let handle = export_handle.clone();
+                            let result = tauri::async_runtime::spawn_blocking(move || {
+                                google_sheets::append_pending(&handle)
+                            })
+                            .await
+                            .unwrap_or_else(|e| Err(e.to_string()));
+                            match result {
+                                Ok(_) => sheets_retry_at = None,
+                                Err(e) => {
+                                    if sheets_retry_at.is_none() {
+                                        notify(&export_handle, "Google Sheets export failed", &e);
+                                    }
+                                    sheets_retry_at =
+                                        Some(Instant::now() + Duration::from_secs(60 * 60));
+                                }
                             }
-                            save_analytics(&reminder_handle, &state);
-                            let _ = reminder_handle.emit("analytics-updated", ());
                         }
-                        continue;
                     }
-                    let mut elapsed = state.elapsed.lock().unwrap();
-                    *elapsed += 5;
-
-                    let current_limit = *state.interval.lock().unwrap();
-
-                    if *elapsed >= current_limit {
-                        if let Some(rw) = reminder_handle.get_webview_window("reminder") {
-                            let reminder_id = {
-                                let mut id = state.active_reminder_id.lock().unwrap();
-                                *id += 1;
-                                *id
-                            };
-                            let tip_index = next_tip_index_from_state(&state);
-                            let tip = REMINDER_TIPS_EN[tip_index].to_string();
-                            {
-                                let mut tip_slot = state.active_reminder_tip.lock().unwrap();
-                                *tip_slot = tip;
-                            }
-                            {
-                                let mut start = state.active_reminder_start_ts.lock().unwrap();
-                                *start = Some(now_ts());
-                            }
-                            {
-                                let mut shown_at = state.active_reminder_shown_at.lock().unwrap();
-                                *shown_at = Some(Instant::now());
-                            }
-                            {
-                                let mut interval_secs = state.active_reminder_interval_secs.lock().unwrap();
-                                *interval_secs = current_limit;
-                            }
-                            {
-                                let mut logged = state.active_reminder_logged_sedentary.lock().unwrap();
-                                *logged = false;
-                            }
+                });
 
-                            let _ = rw.set_size(tauri::Size::Physical(tauri::PhysicalSize::new(
-                                REMINDER_WIDTH as u32,
-                                REMINDER_HEIGHT as u32,
-                            )));
-
-                            // Prefer primary monitor for taskbar/tray anchoring.
-                            let monitor = reminder_handle
-                                .primary_monitor()
-                                .ok()
-                                .flatten()
-                                .or_else(|| rw.current_monitor().ok().flatten());
-
-                            if let Some(monitor) = monitor {
-                                let margin = 28i32;
-                                let area = monitor.work_area();
-                                let area_pos = area.position;
-                                let area_size = area.size;
-                                let size = rw
-                                    .outer_size()
-                                    .ok()
-                                    .map(|s| (s.width as i32, s.height as i32))
-                                    .unwrap_or((REMINDER_WIDTH, REMINDER_HEIGHT));
-
-                                let x = area_pos.x + (area_size.width as i32) - size.0 - margin;
-                                let y = area_pos.y + (area_size.height as i32) - size.1 - margin;
-
-                                let _ = rw.set_position(PhysicalPosition::new(x, y));
-                            }
+                let activity_handle = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    activity::run(activity_handle).await;
+                });
 
-                            *state.reminder_visible.lock().unwrap() = true;
-                            let _ = rw.show();
-                            let _ = rw.set_focus();
-                            let _ = rw.emit("refresh_tip", reminder_id);
-                            let _ = rw.eval("window.__standbyReminderSync && window.__standbyReminderSync();");
-                        }
-                        let _ = reminder_handle.emit("reminder-fired", ());
+                let api_handle = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    local_api::run(api_handle).await;
+                });
 
-                        *elapsed = 0;
-                    }
-                }
+                let status_handle = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    status_file::run(status_handle).await;
+                });
+
+                let handoff_handle = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    handoff::run(handoff_handle).await;
+                });
+            }
+
+            let timer_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                timers::supervise(timer_handle).await;
+            });
+
+            let rollover_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                rollover::run(rollover_handle).await;
             });
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Focused(focused) = event {
+                let state = window.app_handle().state::<AppState>();
+                window_focus::focus_changed(&state, window.label(), *focused, now_ts());
+            }
+            if let tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) = event {
+                if window_manager::is_managed(window.label()) {
+                    window_manager::geometry_changed(window.app_handle(), window.label());
+                }
+            }
+        })
+        .invoke_handler(read_only::guard(tauri::generate_handler![
             set_reminder_interval,
             get_reminder_interval,
+            get_allowed_intervals,
+            get_timer_status,
+            set_interval_today,
+            get_power_state,
+            get_api_version,
+            get_about_info,
+            get_third_party_licenses,
+            move_data_dir,
+            get_changelog_since,
+            get_whats_new,
+            get_api_description,
+            get_health,
+            get_safe_mode,
+            get_read_only,
+            repair_data_files,
+            set_tick_event_granularity,
+            get_tick_event_granularity,
+            get_tick_resolution,
+            set_tick_resolution,
             log_standup,
             acknowledge_reminder,
+            acknowledge_reminder_key,
+            start_focus_session,
+            stop_focus_session,
+            get_focus_session,
             get_standup_count,
             get_analytics,
+            get_week_settings,
+            set_week_settings,
             export_analytics_csv,
+            copy_summary_to_clipboard,
+            get_export_date_format,
+            set_export_date_format,
+            get_export_diff,
+            set_export_diff,
+            get_export_filename_template,
+            set_export_filename_template,
+            get_record_power_source,
+            set_record_power_source,
+            get_analytics_range,
+            get_session_tag,
+            set_session_tag,
+            get_recent_tags,
             export_analytics_png,
+            export_raw_events,
+            import_analytics,
+            export_settings,
+            import_settings,
+            get_export_schedule,
+            get_monthly_goal,
+            set_monthly_goal,
+            get_monthly_goal_progress,
+            set_pre_warning_minutes,
+            get_pre_warning_minutes,
+            set_sitting_budget_minutes,
+            get_sitting_budget_minutes,
+            set_sit_stand_target,
+            get_sit_stand_target,
+            set_window_nudge_minutes,
+            get_window_nudge_minutes,
+            get_window_focus,
+            set_sleep_policy,
+            get_sleep_policy,
+            get_config_history,
+            revert_config_change,
+            set_input_activity_enabled,
+            get_input_activity_enabled,
+            get_tray_icons,
+            set_tray_icons,
+            list_bundled_tray_icons,
+            get_mute_when_mic_in_use,
+            set_mute_when_mic_in_use,
+            get_blocking_apps,
+            set_blocking_apps,
+            get_weekend_override,
+            set_weekend_override,
+            get_holiday_config,
+            set_holiday_config,
+            import_holidays_ics,
+            preview_history_import,
+            import_history,
+            list_holidays,
+            get_today_holiday,
+            get_day_summary_config,
+            set_day_summary_config,
+            get_day_summary,
+            send_day_summary_now,
+            set_export_schedule,
+            run_scheduled_export_now,
+            get_leaderboard_config,
+            set_leaderboard_config,
+            fetch_leaderboard,
+            get_google_sheets_config,
+            set_google_sheets_config,
+            append_google_sheets_now,
+            get_hooks,
+            set_hooks,
+            get_hook_runs,
+            get_markdown_journal_config,
+            set_markdown_journal_config,
+            append_markdown_journal,
+            get_local_api_config,
+            set_local_api_config,
+            get_status_file_config,
+            set_status_file_config,
+            get_handoff_config,
+            set_handoff_config,
+            get_handoff_peers,
+            list_api_tokens,
+            generate_api_token,
+            revoke_api_token,
             reset_daily_records,
             set_language,
             get_language,
+            get_language_preference,
+            get_language_info,
+            list_available_languages,
             set_reminder_language,
             get_reminder_language,
             next_reminder_tip_index,
             next_reminder_tip_text,
             get_active_reminder,
+            preview_reminder,
+            get_reminder_history,
+            get_interaction_stats,
+            get_repeat_when_dismissed,
+            set_repeat_when_dismissed,
+            get_reminder_presentation,
+            set_reminder_presentation,
+            get_skip_budget,
+            set_skip_budget,
+            rate_tip_for_reminder,
+            get_tip_ratings,
+            get_challenge_config,
+            set_challenge_config,
+            get_network_profiles,
+            set_network_profiles,
+            get_network_status,
+            get_desk_mode,
+            set_desk_mode,
+            get_desk_position,
+            report_desk_position,
+            get_today_timeline,
+            mark_day,
+            get_marked_days,
+            set_reminder_content_height,
+            get_event_seq,
+            get_accessibility_prefs,
             get_system_language,
             set_theme,
+            list_themes,
             get_theme,
+            set_reminder_focus_mode,
+            get_reminder_focus_mode,
+            set_standup_cooldown,
+            get_standup_cooldown,
+            set_ack_grace_ms,
+            get_ack_grace_ms,
+            set_day_start_hour,
+            get_day_start_hour,
+            set_reminder_all_monitors,
+            get_reminder_all_monitors,
+            get_reminder_anchor,
+            set_reminder_anchor,
+            get_reminder_windows,
+            set_sedentary_threshold_secs,
+            get_sedentary_threshold_secs,
+            get_startup_options,
+            list_tip_packs,
+            get_tip_pack_coverage,
+            enable_tip_pack,
+            disable_tip_pack,
+            import_tip_pack,
+            download_tip_pack,
+            list_plugins,
+            set_plugin_enabled,
+            reload_plugins,
+            get_plugin_insights,
+            set_startup_options,
+            get_schedule,
+            set_schedule,
             reveal_in_explorer,
+            open_stats_window,
             window_minimize,
             window_toggle_maximize,
             window_close,
-            window_hide
-        ])
+            window_hide,
+            window_set_always_on_top
+        ]))
         .run(tauri::generate_context!())
         .expect("error while running standby");
 }
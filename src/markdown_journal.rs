@@ -0,0 +1,197 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    build_analytics_between, day_start_ts, formatting::Formatter, leaderboard, marked_days,
+    marked_days::DayKind, read_only, AnalyticsData, AppState,
+};
+
+/// Entries are appended to one Markdown file, such as a note in an Obsidian
+/// vault, one `## YYYY-MM-DD` section per day.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct MarkdownJournalConfig {
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Append the finished day's entry at day rollover.
+    #[serde(default)]
+    pub auto_append: bool,
+}
+
+pub fn sanitize(mut cfg: MarkdownJournalConfig) -> MarkdownJournalConfig {
+    cfg.path = cfg
+        .path
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+    cfg
+}
+
+fn heading(date: NaiveDate) -> String {
+    format!("## {}", date.format("%Y-%m-%d"))
+}
+
+/// Short observations about the day; empty when there is nothing to say.
+fn insights(state: &AppState, date: NaiveDate, analytics: &AnalyticsData, zh: bool) -> Vec<String> {
+    let mut out = Vec::new();
+    if let Some(day) = state
+        .marked_days
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|day| marked_days::parse_date(&day.date).ok() == Some(date))
+    {
+        let kind = match (day.kind, zh) {
+            (DayKind::Rest, true) => "休息",
+            (DayKind::Sick, true) => "病假",
+            (DayKind::Travel, true) => "出行",
+            (kind, false) => kind.as_str(),
+        };
+        out.push(if zh {
+            format!("已标记为{}日，不计入连续记录", kind)
+        } else {
+            format!("Marked as a {} day; left out of streaks", kind)
+        });
+    }
+
+    let worst = (0..analytics.hourly_sedentary.len())
+        .max_by_key(|hour| (analytics.hourly_sedentary[*hour], std::cmp::Reverse(*hour)))
+        .filter(|hour| analytics.hourly_sedentary[*hour] > 0);
+    if let Some(hour) = worst {
+        out.push(if zh {
+            format!("{:02}:00 前后忽略的提醒最多", hour)
+        } else {
+            format!("Most reminders were ignored around {:02}:00", hour)
+        });
+    }
+
+    let target = state.monthly_goal.lock().unwrap().daily_standups;
+    if target > 0 && analytics.standup_sessions >= target {
+        out.push(if zh {
+            format!("达到每日 {} 次站立目标", target)
+        } else {
+            format!("Reached the daily target of {} standups", target)
+        });
+    }
+
+    let budget_secs = *state.sitting_budget_minutes.lock().unwrap() * 60;
+    if budget_secs > 0 && analytics.total_sitting_secs > budget_secs {
+        let over =
+            Formatter::for_reports(state).duration(analytics.total_sitting_secs - budget_secs);
+        out.push(if zh {
+            format!("超出久坐预算 {}", over)
+        } else {
+            format!("Went {} over the sitting budget", over)
+        });
+    }
+    out
+}
+
+/// The Markdown section for `date`, heading included, ending in a newline.
+pub fn entry(state: &AppState, date: NaiveDate) -> String {
+    let day_start_hour = *state.day_start_hour.lock().unwrap();
+    let start = day_start_ts(date, day_start_hour);
+    let end = date
+        .succ_opt()
+        .map(|next| day_start_ts(next, day_start_hour))
+        .unwrap_or(i64::MAX);
+    let analytics = build_analytics_between(state, start, end, &|_| true);
+    let zh = *state.language.lock().unwrap() == "zh-CN";
+    let fmt = Formatter::for_reports(state);
+    let score = leaderboard::daily_score(analytics.standup_sessions, analytics.sedentary_sessions);
+
+    let mut lines = vec![heading(date), String::new()];
+    let (standups, sitting, score_label, insights_label) = if zh {
+        ("站立", "久坐", "得分", "观察")
+    } else {
+        ("Standups", "Sitting", "Score", "Insights")
+    };
+    lines.push(format!(
+        "- {}: {}",
+        standups,
+        fmt.count(u64::from(analytics.standup_sessions))
+    ));
+    lines.push(format!(
+        "- {}: {}",
+        sitting,
+        fmt.duration(analytics.total_sitting_secs)
+    ));
+    lines.push(format!("- {}: {}/100", score_label, score));
+    let notes = insights(state, date, &analytics, zh);
+    if !notes.is_empty() {
+        lines.push(format!("- {}:", insights_label));
+        lines.extend(notes.iter().map(|note| format!("  - {}", note)));
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Put `entry` into `existing`: a section with the same heading is replaced,
+/// so re-exporting a day updates it instead of duplicating it; otherwise the
+/// entry goes at the end after a blank line.
+pub fn merge(existing: &str, date: NaiveDate, entry: &str) -> String {
+    let heading = heading(date);
+    let found = existing.match_indices(&heading).map(|(i, _)| i).find(|i| {
+        (*i == 0 || existing[..*i].ends_with('\n'))
+            && existing[i + heading.len()..]
+                .chars()
+                .next()
+                .is_none_or(|c| c == '\n' || c == '\r')
+    });
+    if let Some(start) = found {
+        let after = start + heading.len();
+        let end = existing[after..]
+            .find("\n## ")
+            .map(|i| after + i + 1)
+            .unwrap_or(existing.len());
+        let mut out = existing[..start].to_string();
+        out.push_str(entry);
+        if end < existing.len() {
+            out.push('\n');
+        }
+        out.push_str(&existing[end..]);
+        return out;
+    }
+    let mut out = existing.trim_end().to_string();
+    if !out.is_empty() {
+        out.push_str("\n\n");
+    }
+    out.push_str(entry);
+    out
+}
+
+/// Write the entry for `date` to the configured file and return its path.
+pub fn append(state: &AppState, date: NaiveDate) -> Result<String, String> {
+    let path = state
+        .markdown_journal
+        .lock()
+        .unwrap()
+        .path
+        .clone()
+        .ok_or_else(|| "no journal file configured".to_string())?;
+    let path = Path::new(&path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("create folder failed: {}", e))?;
+    }
+    let existing = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("read failed: {}", e)),
+    };
+    let merged = merge(&existing, date, &entry(state, date));
+    fs::write(path, merged).map_err(|e| format!("write failed: {}", e))?;
+    Ok(path.display().to_string())
+}
+
+/// Called when `previous` has just ended. Nothing is written in safe or
+/// read-only mode; a failure is logged and the day is not retried.
+pub fn on_rollover(app: &AppHandle, previous: NaiveDate) {
+    let state = app.state::<AppState>();
+    let cfg = state.markdown_journal.lock().unwrap().clone();
+    if !cfg.auto_append || cfg.path.is_none() || state.safe_mode || read_only::enabled(&state) {
+        return;
+    }
+    if let Err(e) = append(&state, previous) {
+        eprintln!("markdown journal: {}", e);
+    }
+}
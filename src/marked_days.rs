@@ -0,0 +1,92 @@
+use chrono::{Local, NaiveDate, TimeZone};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{analytics_date, AppState};
+
+/// Why a day doesn't count.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DayKind {
+    Rest,
+    Sick,
+    Travel,
+}
+
+impl DayKind {
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "rest" => Some(DayKind::Rest),
+            "sick" => Some(DayKind::Sick),
+            "travel" => Some(DayKind::Travel),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DayKind::Rest => "rest",
+            DayKind::Sick => "sick",
+            DayKind::Travel => "travel",
+        }
+    }
+}
+
+/// An analytics day left out of streaks, goals and weekly and monthly
+/// totals, instead of counting as a day that went badly.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MarkedDay {
+    /// "YYYY-MM-DD".
+    pub date: String,
+    pub kind: DayKind,
+}
+
+pub fn parse_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| format!("invalid date: {}", date))
+}
+
+/// Mark `date` as `kind`, replacing an earlier mark; `None` clears it.
+/// Returns every marked day, oldest first.
+pub fn mark(state: &AppState, date: NaiveDate, kind: Option<DayKind>) -> Vec<MarkedDay> {
+    let key = date.format("%Y-%m-%d").to_string();
+    let mut days = state.marked_days.lock().unwrap();
+    days.retain(|day| day.date != key);
+    if let Some(kind) = kind {
+        days.push(MarkedDay { date: key, kind });
+        days.sort_by(|a, b| a.date.cmp(&b.date));
+    }
+    days.clone()
+}
+
+pub fn is_marked(days: &[MarkedDay], date: NaiveDate) -> bool {
+    let key = date.format("%Y-%m-%d").to_string();
+    days.iter().any(|day| day.date == key)
+}
+
+/// Whether the event at `ts` falls on a marked analytics day.
+pub fn covers(days: &[MarkedDay], ts: i64, day_start_hour: u32) -> bool {
+    !days.is_empty()
+        && Local
+            .timestamp_opt(ts, 0)
+            .single()
+            .is_some_and(|dt| is_marked(days, analytics_date(dt, day_start_hour)))
+}
+
+/// Marks from `first` through `last`, for listing alongside a period's
+/// analytics.
+pub fn within(days: &[MarkedDay], first: NaiveDate, last: NaiveDate) -> Vec<MarkedDay> {
+    let (first, last) = (
+        first.format("%Y-%m-%d").to_string(),
+        last.format("%Y-%m-%d").to_string(),
+    );
+    days.iter()
+        .filter(|day| day.date >= first && day.date <= last)
+        .cloned()
+        .collect()
+}
+
+/// Drop marks older than `cutoff`; ISO dates compare as strings.
+pub fn prune(days: &mut Vec<MarkedDay>, cutoff: NaiveDate) {
+    let key = cutoff.format("%Y-%m-%d").to_string();
+    days.retain(|day| day.date >= key);
+}
@@ -0,0 +1,57 @@
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+use std::process::Command as ProcessCommand;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// The connected Wi-Fi network's name, read through the platform's own
+/// Wi-Fi tool; `None` on a wired or no connection, or where it can't tell.
+#[cfg(target_os = "windows")]
+pub fn current_ssid() -> Option<String> {
+    let out = ProcessCommand::new("netsh")
+        .args(["wlan", "show", "interfaces"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    parse_netsh(&String::from_utf8_lossy(&out.stdout))
+}
+
+#[cfg(target_os = "macos")]
+pub fn current_ssid() -> Option<String> {
+    let out = ProcessCommand::new("networksetup")
+        .args(["-getairportnetwork", "en0"])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&out.stdout);
+    text.trim()
+        .strip_prefix("Current Wi-Fi Network:")
+        .map(|ssid| ssid.trim().to_string())
+        .filter(|ssid| !ssid.is_empty())
+}
+
+/// NetworkManager; `nmcli -t` escapes colons inside the name as `\:`.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn current_ssid() -> Option<String> {
+    let out = ProcessCommand::new("nmcli")
+        .args(["-t", "-f", "active,ssid", "dev", "wifi"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())?;
+    String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("yes:"))
+        .map(|ssid| ssid.replace("\\:", ":"))
+        .filter(|ssid| !ssid.is_empty())
+}
+
+/// The `SSID : name` line of `netsh wlan show interfaces`, skipping `BSSID`.
+#[cfg(any(target_os = "windows", test))]
+pub fn parse_netsh(text: &str) -> Option<String> {
+    text.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == "SSID")
+            .then(|| value.trim().to_string())
+            .filter(|ssid| !ssid.is_empty())
+    })
+}
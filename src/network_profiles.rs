@@ -0,0 +1,146 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::challenge::ChallengeKind;
+use crate::{clock, events, network_info, now_ts, prune_changes, timers, AppState};
+
+const POLL_SECS: u64 = 30;
+pub const MAX_PROFILES: usize = 8;
+
+/// Settings that apply while the machine is on one of `ssids`, e.g. an
+/// "Office" profile with fewer free skips and a challenge. Unset fields keep
+/// the normal setting.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NetworkProfile {
+    pub name: String,
+    /// Wi-Fi network names, matched exactly.
+    #[serde(default)]
+    pub ssids: Vec<String>,
+    #[serde(default)]
+    pub interval_minutes: Option<u64>,
+    #[serde(default)]
+    pub free_skips_per_day: Option<u32>,
+    #[serde(default)]
+    pub challenge: Option<ChallengeKind>,
+}
+
+/// Payload of `network-profile-changed` and the result of
+/// `get_network_status`.
+#[derive(Clone, PartialEq, Serialize, JsonSchema)]
+pub struct NetworkStatus {
+    pub ssid: Option<String>,
+    /// Name of the profile in effect.
+    pub profile: Option<String>,
+}
+
+/// The profile in effect changed at `ts`; `None` when none applies. Kept
+/// with the analytics so events can be filtered by the profile they
+/// happened under.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProfileChange {
+    pub ts: i64,
+    pub profile: Option<String>,
+}
+
+/// Trim names and networks; profiles without a name or a network are
+/// dropped. Intervals are checked by the caller against the allowed list.
+pub fn sanitize(profiles: Vec<NetworkProfile>) -> Vec<NetworkProfile> {
+    profiles
+        .into_iter()
+        .filter_map(|mut profile| {
+            profile.name = profile.name.trim().to_string();
+            profile.ssids = profile
+                .ssids
+                .into_iter()
+                .map(|ssid| ssid.trim().to_string())
+                .filter(|ssid| !ssid.is_empty())
+                .collect();
+            (!profile.name.is_empty() && !profile.ssids.is_empty()).then_some(profile)
+        })
+        .take(MAX_PROFILES)
+        .collect()
+}
+
+/// The first profile listing `ssid`.
+pub fn matching<'a>(
+    profiles: &'a [NetworkProfile],
+    ssid: Option<&str>,
+) -> Option<&'a NetworkProfile> {
+    let ssid = ssid?;
+    profiles
+        .iter()
+        .find(|profile| profile.ssids.iter().any(|s| s == ssid))
+}
+
+/// The profile in effect on the network last seen.
+pub fn active(state: &AppState) -> Option<NetworkProfile> {
+    let ssid = state.ssid.lock().unwrap().clone();
+    matching(&state.network_profiles.lock().unwrap(), ssid.as_deref()).cloned()
+}
+
+pub fn status(state: &AppState) -> NetworkStatus {
+    NetworkStatus {
+        ssid: state.ssid.lock().unwrap().clone(),
+        profile: active(state).map(|profile| profile.name),
+    }
+}
+
+/// Log `profile` as in effect from `ts` unless it already is. Returns
+/// whether it was logged.
+pub fn log_profile(state: &AppState, ts: i64, profile: Option<String>) -> bool {
+    let mut log = state.profile_log.lock().unwrap();
+    let unchanged = match log.last() {
+        Some(last) => last.profile == profile,
+        None => profile.is_none(),
+    };
+    if unchanged {
+        return false;
+    }
+    log.push(ProfileChange { ts, profile });
+    true
+}
+
+pub fn profile_at(log: &[ProfileChange], ts: i64) -> Option<&str> {
+    let after = log.partition_point(|change| change.ts <= ts);
+    after.checked_sub(1).and_then(|i| log[i].profile.as_deref())
+}
+
+pub fn prune(log: &mut Vec<ProfileChange>, cutoff: i64) {
+    prune_changes(log, cutoff, |change| change.ts);
+}
+
+/// Watch the Wi-Fi network while any profile is configured, announcing a
+/// change of network or profile as `network-profile-changed`. Without
+/// profiles the network isn't looked at at all.
+pub async fn run(app: AppHandle) {
+    loop {
+        let wanted = !app
+            .state::<AppState>()
+            .network_profiles
+            .lock()
+            .unwrap()
+            .is_empty();
+        let ssid = if wanted {
+            tauri::async_runtime::spawn_blocking(network_info::current_ssid)
+                .await
+                .ok()
+                .flatten()
+        } else {
+            None
+        };
+        let state = app.state::<AppState>();
+        let before = status(&state);
+        *state.ssid.lock().unwrap() = ssid;
+        let after = status(&state);
+        // Against the log rather than `before`, which starts out empty on
+        // launch even if the last profile logged is still in effect.
+        log_profile(&state, now_ts(), after.profile.clone());
+        if after != before {
+            timers::wake(&state);
+            events::emit(&app, "network-profile-changed", after);
+        }
+        clock::sleep(Duration::from_secs(POLL_SECS)).await;
+    }
+}
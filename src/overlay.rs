@@ -0,0 +1,144 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::{
+    AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindow,
+    WebviewWindowBuilder,
+};
+
+use crate::AppState;
+
+/// Not a reminder label: the reminder windows are closed and rebuilt per
+/// monitor, the banner lives on its own.
+pub const LABEL: &str = "overlay";
+/// Logical height of the banner.
+const HEIGHT: f64 = 56.0;
+pub const MIN_ESCALATE_SECS: u64 = 30;
+pub const MAX_ESCALATE_SECS: u64 = 15 * 60;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Presentation {
+    /// The interactive reminder window.
+    Window,
+    /// A click-through banner across the top of the primary monitor, which
+    /// gives way to the window if the reminder is still open after
+    /// `escalate_after_secs`.
+    Overlay,
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PresentationConfig {
+    #[serde(default = "default_mode")]
+    pub mode: Presentation,
+    #[serde(default = "default_escalate_after_secs")]
+    pub escalate_after_secs: u64,
+}
+
+impl Default for PresentationConfig {
+    fn default() -> Self {
+        Self {
+            mode: default_mode(),
+            escalate_after_secs: default_escalate_after_secs(),
+        }
+    }
+}
+
+fn default_mode() -> Presentation {
+    Presentation::Window
+}
+
+fn default_escalate_after_secs() -> u64 {
+    120
+}
+
+pub fn sanitize(mut cfg: PresentationConfig) -> PresentationConfig {
+    cfg.escalate_after_secs = cfg
+        .escalate_after_secs
+        .clamp(MIN_ESCALATE_SECS, MAX_ESCALATE_SECS);
+    cfg
+}
+
+pub fn wanted(state: &AppState) -> bool {
+    state.reminder_presentation.lock().unwrap().mode == Presentation::Overlay
+}
+
+/// The open reminder is still on the banner and not yet due to escalate.
+pub fn holding(state: &AppState) -> bool {
+    if !*state.reminder_overlay.lock().unwrap() {
+        return false;
+    }
+    let after = state
+        .reminder_presentation
+        .lock()
+        .unwrap()
+        .escalate_after_secs;
+    state
+        .active_reminder_shown_at
+        .lock()
+        .unwrap()
+        .is_some_and(|shown_at| shown_at.elapsed().as_secs() < after)
+}
+
+fn build(app: &AppHandle) -> Option<WebviewWindow> {
+    let builder = WebviewWindowBuilder::new(app, LABEL, WebviewUrl::App("overlay.html".into()))
+        .title("Upstand")
+        .decorations(false)
+        .shadow(false)
+        .resizable(false)
+        .focused(false)
+        .visible(false)
+        .always_on_top(true)
+        .skip_taskbar(true);
+    // macOS only allows see-through windows with the private API, so the
+    // banner is opaque there.
+    #[cfg(not(target_os = "macos"))]
+    let builder = builder.transparent(true);
+    builder.build().ok()
+}
+
+/// Stretch the banner across the top of the primary monitor's work area.
+fn place(app: &AppHandle, win: &WebviewWindow) {
+    let Some(monitor) = app.primary_monitor().ok().flatten() else {
+        return;
+    };
+    let area = monitor.work_area();
+    let height = (HEIGHT * monitor.scale_factor()).round() as u32;
+    let _ = win.set_size(PhysicalSize::new(area.size.width, height));
+    let _ = win.set_position(PhysicalPosition::new(area.position.x, area.position.y));
+}
+
+/// Put the open reminder on the banner. Clicks go through it to whatever is
+/// underneath, so it never takes focus or gets in the way of typing.
+/// Returns false if the banner couldn't be shown.
+pub fn show(app: &AppHandle) -> bool {
+    let Some(win) = app.get_webview_window(LABEL).or_else(|| build(app)) else {
+        return false;
+    };
+    place(app, &win);
+    let _ = win.set_focusable(false);
+    if win.set_ignore_cursor_events(true).is_err() || win.show().is_err() {
+        let _ = win.hide();
+        return false;
+    }
+    *app.state::<AppState>().reminder_overlay.lock().unwrap() = true;
+    let _ = win.eval("window.__standbyReminderSync && window.__standbyReminderSync();");
+    true
+}
+
+pub fn visible(app: &AppHandle) -> bool {
+    app.get_webview_window(LABEL)
+        .is_some_and(|win| win.is_visible().unwrap_or(false))
+}
+
+/// Hide the banner, e.g. while screen sharing; the reminder stays on it.
+pub fn hide_window(app: &AppHandle) {
+    if let Some(win) = app.get_webview_window(LABEL) {
+        let _ = win.hide();
+    }
+}
+
+/// The reminder was answered or escalated.
+pub fn hide(app: &AppHandle) {
+    hide_window(app);
+    *app.state::<AppState>().reminder_overlay.lock().unwrap() = false;
+}
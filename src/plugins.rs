@@ -0,0 +1,324 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{fs, path::PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::tip_ratings::{ShownTip, TipSource};
+use crate::{data_dir, now_ts, tip_packs, AppState};
+
+const PLUGINS_DIR: &str = "plugins";
+const MANIFEST_FILE: &str = "plugin.json";
+const MODULE_FILE: &str = "plugin.wasm";
+/// Events older than this aren't handed to plugins.
+const EVENT_WINDOW_SECS: i64 = 30 * 24 * 60 * 60;
+const MAX_TIPS_PER_PLUGIN: usize = 100;
+const MAX_INSIGHTS_PER_PLUGIN: usize = 10;
+const MAX_TEXT_CHARS: usize = 280;
+
+/// What a plugin may do. A plugin gets exactly what its manifest asks for,
+/// and only once the user enables it; nothing else (files, network, the
+/// clock) is reachable from inside the sandbox.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    /// Standup and sitting events of the last 30 days are passed to `insights`.
+    ReadEvents,
+    /// `tips` is called at load and its tips join the reminder pool.
+    Tips,
+    /// `insights` is called when the stats window asks for them.
+    Insights,
+}
+
+/// `plugins/<id>/plugin.json`, next to `plugin.wasm`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub capabilities: Vec<Capability>,
+}
+
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct PluginInfo {
+    /// The plugin's folder name.
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    pub capabilities: Vec<Capability>,
+    pub enabled: bool,
+    /// Why it didn't load, as of the last load.
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct PluginInsight {
+    pub plugin_id: String,
+    pub text: String,
+}
+
+fn plugins_dir(app: &AppHandle) -> Option<PathBuf> {
+    data_dir::dir(app).map(|dir| dir.join(PLUGINS_DIR))
+}
+
+fn valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Every plugin folder with a readable manifest, sorted by id.
+fn discover(app: &AppHandle) -> Vec<(String, Result<PluginManifest, String>)> {
+    let Some(entries) = plugins_dir(app).and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut found: Vec<(String, Result<PluginManifest, String>)> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let id = entry.file_name().to_string_lossy().into_owned();
+            if !valid_id(&id) {
+                return None;
+            }
+            let manifest = fs::read_to_string(entry.path().join(MANIFEST_FILE))
+                .map_err(|e| format!("read {} failed: {}", MANIFEST_FILE, e))
+                .and_then(|text| {
+                    serde_json::from_str::<PluginManifest>(&text)
+                        .map_err(|e| format!("invalid {}: {}", MANIFEST_FILE, e))
+                });
+            Some((id, manifest))
+        })
+        .collect();
+    found.sort_by(|a, b| a.0.cmp(&b.0));
+    found
+}
+
+pub fn list(app: &AppHandle) -> Vec<PluginInfo> {
+    let state = app.state::<AppState>();
+    let enabled = state.enabled_plugins.lock().unwrap().clone();
+    let errors = state.plugin_errors.lock().unwrap().clone();
+    discover(app)
+        .into_iter()
+        .map(|(id, manifest)| {
+            let error = errors.get(&id).cloned();
+            match manifest {
+                Ok(manifest) => PluginInfo {
+                    enabled: enabled.contains(&id),
+                    id,
+                    name: manifest.name,
+                    version: manifest.version,
+                    description: manifest.description,
+                    capabilities: manifest.capabilities,
+                    error,
+                },
+                Err(e) => PluginInfo {
+                    enabled: enabled.contains(&id),
+                    name: id.clone(),
+                    id,
+                    version: String::new(),
+                    description: String::new(),
+                    capabilities: Vec::new(),
+                    error: Some(e),
+                },
+            }
+        })
+        .collect()
+}
+
+/// Enabled plugins that loaded a manifest, with their module path.
+fn enabled_plugins(app: &AppHandle) -> Vec<(String, PluginManifest, PathBuf)> {
+    let state = app.state::<AppState>();
+    let enabled = state.enabled_plugins.lock().unwrap().clone();
+    let Some(dir) = plugins_dir(app) else {
+        return Vec::new();
+    };
+    discover(app)
+        .into_iter()
+        .filter(|(id, _)| enabled.contains(id))
+        .filter_map(|(id, manifest)| {
+            let module = dir.join(&id).join(MODULE_FILE);
+            manifest.ok().map(|manifest| (id, manifest, module))
+        })
+        .collect()
+}
+
+/// Parse what a plugin returned: a JSON array of strings, trimmed, with
+/// empty and overlong entries dropped.
+pub fn texts(output: &[u8], max: usize) -> Result<Vec<String>, String> {
+    let texts = serde_json::from_slice::<Vec<String>>(output)
+        .map_err(|e| format!("expected a JSON array of strings: {}", e))?;
+    Ok(texts
+        .into_iter()
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty() && text.chars().count() <= MAX_TEXT_CHARS)
+        .take(max)
+        .collect())
+}
+
+/// Load every enabled plugin (none in safe mode), collecting the tips of
+/// those allowed to add some, then rebuild the tip pool. Runs the plugins,
+/// so call it off the async runtime.
+pub fn reload(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    if state.safe_mode {
+        return;
+    }
+    let mut tips = Vec::new();
+    let mut errors = std::collections::HashMap::new();
+    for (id, manifest, module) in enabled_plugins(app) {
+        if !manifest.capabilities.contains(&Capability::Tips) {
+            continue;
+        }
+        let result = runtime::call(&module, "tips", b"null").and_then(|output| match output {
+            Some(output) => texts(&output, MAX_TIPS_PER_PLUGIN),
+            None => Ok(Vec::new()),
+        });
+        match result {
+            Ok(texts) => tips.extend(texts.into_iter().map(|text| ShownTip {
+                text,
+                source: TipSource::Plugin { id: id.clone() },
+            })),
+            Err(e) => {
+                eprintln!("plugin {}: {}", id, e);
+                errors.insert(id, e);
+            }
+        }
+    }
+    *state.plugin_tips.lock().unwrap() = tips;
+    *state.plugin_errors.lock().unwrap() = errors;
+    tip_packs::rebuild_pool(app);
+}
+
+/// What a plugin with [`Capability::ReadEvents`] is shown.
+fn events_input(state: &AppState) -> Vec<u8> {
+    let cutoff = now_ts() - EVENT_WINDOW_SECS;
+    let standups: Vec<i64> = state
+        .standup_events
+        .lock()
+        .unwrap()
+        .iter()
+        .copied()
+        .filter(|ts| *ts >= cutoff)
+        .collect();
+    let sitting: Vec<serde_json::Value> = state
+        .reminder_events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|record| record.ts >= cutoff)
+        .map(|record| json!({ "ts": record.ts, "duration_secs": record.duration_secs }))
+        .collect();
+    json!({ "standups": standups, "sitting": sitting })
+        .to_string()
+        .into_bytes()
+}
+
+/// Ask every enabled plugin allowed to for its insights. A failing plugin
+/// is skipped and its error shown by [`list`].
+pub fn insights(app: &AppHandle) -> Vec<PluginInsight> {
+    let state = app.state::<AppState>();
+    let mut insights = Vec::new();
+    if state.safe_mode {
+        return insights;
+    }
+    for (id, manifest, module) in enabled_plugins(app) {
+        if !manifest.capabilities.contains(&Capability::Insights) {
+            continue;
+        }
+        let input = if manifest.capabilities.contains(&Capability::ReadEvents) {
+            events_input(&state)
+        } else {
+            b"null".to_vec()
+        };
+        let result = runtime::call(&module, "insights", &input).and_then(|output| match output {
+            Some(output) => texts(&output, MAX_INSIGHTS_PER_PLUGIN),
+            None => Ok(Vec::new()),
+        });
+        match result {
+            Ok(texts) => insights.extend(texts.into_iter().map(|text| PluginInsight {
+                plugin_id: id.clone(),
+                text,
+            })),
+            Err(e) => {
+                eprintln!("plugin {}: {}", id, e);
+                state.plugin_errors.lock().unwrap().insert(id, e);
+            }
+        }
+    }
+    insights
+}
+
+/// The WebAssembly side. A module exports `memory`, `alloc(len) -> ptr` and
+/// any of `tips` / `insights`, each taking `(ptr, len)` of UTF-8 JSON input
+/// and returning `(ptr << 32) | len` of its JSON output. It is given no
+/// imports at all, and runs with bounded fuel and memory.
+#[cfg(feature = "plugins")]
+mod runtime {
+    use std::path::Path;
+    use wasmtime::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+    const FUEL: u64 = 100_000_000;
+    const MAX_MEMORY_BYTES: usize = 32 * 1024 * 1024;
+    const MAX_OUTPUT_BYTES: usize = 256 * 1024;
+
+    /// Call `export` with `input`. `Ok(None)` if the module doesn't export it.
+    pub fn call(module: &Path, export: &str, input: &[u8]) -> Result<Option<Vec<u8>>, String> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config).map_err(|e| e.to_string())?;
+        let module =
+            Module::from_file(&engine, module).map_err(|e| format!("load module failed: {}", e))?;
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(MAX_MEMORY_BYTES)
+            .instances(1)
+            .build();
+        let mut store: Store<StoreLimits> = Store::new(&engine, limits);
+        store.limiter(|limits| limits);
+        store.set_fuel(FUEL).map_err(|e| e.to_string())?;
+        let instance = Linker::new(&engine)
+            .instantiate(&mut store, &module)
+            .map_err(|e| format!("instantiate failed: {}", e))?;
+        let Ok(func) = instance.get_typed_func::<(i32, i32), i64>(&mut store, export) else {
+            return Ok(None);
+        };
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| "module exports no memory".to_string())?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&mut store, "alloc")
+            .map_err(|e| format!("module exports no alloc: {}", e))?;
+        let len = i32::try_from(input.len()).map_err(|_| "input too large".to_string())?;
+        let ptr = alloc
+            .call(&mut store, len)
+            .map_err(|e| format!("alloc failed: {}", e))?;
+        memory
+            .write(&mut store, ptr as u32 as usize, input)
+            .map_err(|e| format!("write input failed: {}", e))?;
+        let packed = func
+            .call(&mut store, (ptr, len))
+            .map_err(|e| format!("{} failed: {}", export, e))? as u64;
+        let (out_ptr, out_len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+        if out_len > MAX_OUTPUT_BYTES {
+            return Err(format!("{} returned too much", export));
+        }
+        let mut output = vec![0u8; out_len];
+        memory
+            .read(&store, out_ptr, &mut output)
+            .map_err(|e| format!("read output failed: {}", e))?;
+        Ok(Some(output))
+    }
+}
+
+#[cfg(not(feature = "plugins"))]
+mod runtime {
+    use std::path::Path;
+
+    pub fn call(_module: &Path, _export: &str, _input: &[u8]) -> Result<Option<Vec<u8>>, String> {
+        Err("this build has no plugin support".to_string())
+    }
+}
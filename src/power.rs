@@ -0,0 +1,113 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+#[cfg(not(target_os = "windows"))]
+use std::process::Command as ProcessCommand;
+
+use crate::prune_changes;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct PowerState {
+    pub on_battery: bool,
+    pub battery_saver: bool,
+    /// No display is attached/awake (lid closed without an external monitor).
+    pub display_off: bool,
+}
+
+/// A switch between mains and battery. Logged only with `record_power_source`
+/// on, so each stored event can be matched to how the machine was powered.
+#[derive(Clone, Copy, Serialize, Deserialize, JsonSchema)]
+pub struct PowerChange {
+    pub ts: i64,
+    pub on_battery: bool,
+}
+
+/// Append a change unless the source is the one already logged last.
+/// Returns whether the log grew.
+pub fn log_source(log: &mut Vec<PowerChange>, ts: i64, on_battery: bool) -> bool {
+    if log.last().is_some_and(|last| last.on_battery == on_battery) {
+        return false;
+    }
+    log.push(PowerChange { ts, on_battery });
+    true
+}
+
+/// The source in effect at `ts`; `None` before the first logged change.
+pub fn on_battery_at(log: &[PowerChange], ts: i64) -> Option<bool> {
+    let after = log.partition_point(|change| change.ts <= ts);
+    after.checked_sub(1).map(|i| log[i].on_battery)
+}
+
+pub fn prune(log: &mut Vec<PowerChange>, cutoff: i64) {
+    prune_changes(log, cutoff, |change| change.ts);
+}
+
+#[cfg(target_os = "windows")]
+pub fn detect_power_source() -> (bool, bool) {
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+    let mut status = SYSTEM_POWER_STATUS {
+        ACLineStatus: 255,
+        BatteryFlag: 0,
+        BatteryLifePercent: 255,
+        SystemStatusFlag: 0,
+        BatteryLifeTime: 0,
+        BatteryFullLifeTime: 0,
+    };
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return (false, false);
+    }
+    // ACLineStatus: 0 = offline, 1 = online, 255 = unknown.
+    // SystemStatusFlag: 1 = battery saver is on.
+    (status.ACLineStatus == 0, status.SystemStatusFlag == 1)
+}
+
+#[cfg(target_os = "macos")]
+pub fn detect_power_source() -> (bool, bool) {
+    let batt = ProcessCommand::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .ok()
+        .map(|out| String::from_utf8_lossy(&out.stdout).to_string())
+        .unwrap_or_default();
+    let settings = ProcessCommand::new("pmset")
+        .arg("-g")
+        .output()
+        .ok()
+        .map(|out| String::from_utf8_lossy(&out.stdout).to_string())
+        .unwrap_or_default();
+    let low_power = settings.lines().any(|line| {
+        let mut parts = line.split_whitespace();
+        parts.next() == Some("lowpowermode") && parts.next() == Some("1")
+    });
+    (batt.contains("'Battery Power'"), low_power)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn detect_power_source() -> (bool, bool) {
+    let on_ac = std::fs::read_dir("/sys/class/power_supply")
+        .ok()
+        .map(|entries| {
+            entries.flatten().any(|entry| {
+                let dir = entry.path();
+                let kind = std::fs::read_to_string(dir.join("type")).unwrap_or_default();
+                let online = std::fs::read_to_string(dir.join("online")).unwrap_or_default();
+                kind.trim() == "Mains" && online.trim() == "1"
+            })
+        });
+    let has_battery = std::fs::read_dir("/sys/class/power_supply")
+        .ok()
+        .map(|entries| {
+            entries.flatten().any(|entry| {
+                std::fs::read_to_string(entry.path().join("type"))
+                    .map(|kind| kind.trim() == "Battery")
+                    .unwrap_or(false)
+            })
+        })
+        .unwrap_or(false);
+    let power_saver = ProcessCommand::new("powerprofilesctl")
+        .arg("get")
+        .output()
+        .ok()
+        .map(|out| String::from_utf8_lossy(&out.stdout).trim() == "power-saver")
+        .unwrap_or(false);
+    (has_battery && on_ac == Some(false), power_saver)
+}
@@ -0,0 +1,101 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::timeline::{self, SegmentKind, TimelineSegment};
+use crate::{notify, now_ts, AppState};
+
+/// Bounds for `set_sit_stand_target`, in seconds sat per second stood.
+pub const MIN_TARGET: f64 = 1.0;
+pub const MAX_TARGET: f64 = 30.0;
+/// Early in the day a few minutes of sitting would already read as far off
+/// target, so nothing is judged before this much sitting.
+const WARMUP_SITTING_SECS: u64 = 60 * 60;
+const ALERT_COOLDOWN_SECS: i64 = 60 * 60;
+
+/// Today's sitting against standing, as the day timeline draws it.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct SitStandRatio {
+    pub sitting_secs: u64,
+    pub standing_secs: u64,
+    /// Seconds sat per second stood; `None` until there is some standing.
+    pub ratio: Option<f64>,
+    /// The configured ratio to stay at or under, e.g. 3.0 for 3:1.
+    pub target: Option<f64>,
+    /// More sitting than the target allows, once past the first hour of
+    /// sitting.
+    pub below_target: bool,
+}
+
+pub fn sanitize_target(target: Option<f64>) -> Option<f64> {
+    target
+        .filter(|target| target.is_finite())
+        .map(|target| target.clamp(MIN_TARGET, MAX_TARGET))
+}
+
+pub fn of_segments(segments: &[TimelineSegment], target: Option<f64>) -> SitStandRatio {
+    let total = |kind: SegmentKind| -> u64 {
+        segments
+            .iter()
+            .filter(|segment| segment.kind == kind)
+            .map(|segment| (segment.end_ts - segment.start_ts).max(0) as u64)
+            .sum()
+    };
+    let sitting_secs = total(SegmentKind::Sitting);
+    let standing_secs = total(SegmentKind::Standing);
+    let ratio = (standing_secs > 0).then(|| sitting_secs as f64 / standing_secs as f64);
+    let below_target = target.is_some_and(|target| {
+        sitting_secs >= WARMUP_SITTING_SECS && ratio.is_none_or(|ratio| ratio > target)
+    });
+    SitStandRatio {
+        sitting_secs,
+        standing_secs,
+        ratio,
+        target,
+        below_target,
+    }
+}
+
+pub fn today(state: &AppState) -> SitStandRatio {
+    let target = *state.sit_stand_target.lock().unwrap();
+    of_segments(&timeline::today(state), target)
+}
+
+/// Alert when the day drops below the target. Once it has, it has to get
+/// back on target before alerting again, and at most once an hour.
+pub fn tick(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    if state.sit_stand_target.lock().unwrap().is_none() {
+        return;
+    }
+    let ratio = today(&state);
+    {
+        let mut below = state.sit_stand_below.lock().unwrap();
+        if *below == ratio.below_target {
+            return;
+        }
+        *below = ratio.below_target;
+    }
+    if !ratio.below_target {
+        return;
+    }
+    let now = now_ts();
+    {
+        let mut alerted = state.sit_stand_alerted_at.lock().unwrap();
+        if alerted.is_some_and(|ts| now - ts < ALERT_COOLDOWN_SECS) {
+            return;
+        }
+        *alerted = Some(now);
+    }
+    let zh = *state.language.lock().unwrap() == "zh-CN";
+    let (title, body) = if zh {
+        ("站立比例偏低", "今天坐得比目标多了，站起来活动一下吧。")
+    } else {
+        (
+            "Below your sit:stand target",
+            "You've sat more than your target allows today. Time to stand for a while.",
+        )
+    };
+    notify(app, title, body);
+    let _ = app.emit("sit-stand-below-target", ratio);
+}
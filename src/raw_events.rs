@@ -0,0 +1,262 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::fs;
+
+use crate::activity::ActivityBucket;
+use crate::marked_days::{DayKind, MarkedDay};
+use crate::power::PowerChange;
+use crate::tags::TagChange;
+use crate::timers::FocusRecord;
+use crate::{day_start_ts, marked_days, now_ts, AppState, ReminderRecord};
+
+/// Bump when a field changes meaning; importers refuse newer dumps.
+pub const SCHEMA_VERSION: u32 = 1;
+const MAX_IMPORT_BYTES: u64 = 64 * 1024 * 1024;
+
+/// One stored event. `id` is derived from kind and timestamp, so the same
+/// event gets the same id in every dump and re-importing is idempotent.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RawEvent {
+    pub id: String,
+    /// "standup", "sedentary", "focus", "activity", "power", "tag" or
+    /// "marked_day".
+    pub kind: String,
+    pub ts: i64,
+    #[serde(default)]
+    pub duration_secs: Option<u64>,
+    /// "stood_up", "ignored", "completed", "stopped", "ac"/"battery", the
+    /// tag name (absent when a tag was cleared), or "rest"/"sick"/"travel".
+    #[serde(default)]
+    pub outcome: Option<String>,
+    #[serde(default)]
+    pub meta: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RawEventDump {
+    pub schema_version: u32,
+    pub app_version: String,
+    pub exported_ts: i64,
+    pub events: Vec<RawEvent>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct RawImportResult {
+    pub imported: u32,
+    pub skipped_existing: u32,
+    pub skipped_unknown: u32,
+}
+
+/// Ids are "<kind>:<ts>", with "#n" appended for repeats within one second.
+pub fn assign_ids(events: &mut [RawEvent]) {
+    let mut seen = HashSet::new();
+    for event in events.iter_mut() {
+        let base = format!("{}:{}", event.kind, event.ts);
+        let mut id = base.clone();
+        let mut n = 1;
+        while !seen.insert(id.clone()) {
+            n += 1;
+            id = format!("{}#{}", base, n);
+        }
+        event.id = id;
+    }
+}
+
+fn event(kind: &str, ts: i64) -> RawEvent {
+    RawEvent {
+        id: String::new(),
+        kind: kind.to_string(),
+        ts,
+        duration_secs: None,
+        outcome: None,
+        meta: Map::new(),
+    }
+}
+
+pub fn sedentary(record: &ReminderRecord) -> RawEvent {
+    RawEvent {
+        duration_secs: Some(record.duration_secs),
+        outcome: Some("ignored".to_string()),
+        ..event("sedentary", record.ts)
+    }
+}
+
+pub fn standup(ts: i64) -> RawEvent {
+    RawEvent {
+        outcome: Some("stood_up".to_string()),
+        ..event("standup", ts)
+    }
+}
+
+pub fn focus(record: &FocusRecord) -> RawEvent {
+    let outcome = if record.completed {
+        "completed"
+    } else {
+        "stopped"
+    };
+    RawEvent {
+        duration_secs: Some(record.duration_secs),
+        outcome: Some(outcome.to_string()),
+        ..event("focus", record.ts)
+    }
+}
+
+fn collect(state: &AppState) -> Vec<RawEvent> {
+    let mut events = Vec::new();
+    for record in state.reminder_events.lock().unwrap().iter() {
+        events.push(sedentary(record));
+    }
+    for ts in state.standup_events.lock().unwrap().iter() {
+        events.push(standup(*ts));
+    }
+    for record in state.focus_events.lock().unwrap().iter() {
+        events.push(focus(record));
+    }
+    for bucket in state.activity_buckets.lock().unwrap().iter() {
+        let mut meta = Map::new();
+        meta.insert("active_secs".to_string(), bucket.active_secs.into());
+        meta.insert("idle_secs".to_string(), bucket.idle_secs.into());
+        events.push(RawEvent {
+            duration_secs: Some(bucket.active_secs + bucket.idle_secs),
+            meta,
+            ..event("activity", bucket.hour_ts)
+        });
+    }
+    for change in state.power_log.lock().unwrap().iter() {
+        let source = if change.on_battery { "battery" } else { "ac" };
+        events.push(RawEvent {
+            outcome: Some(source.to_string()),
+            ..event("power", change.ts)
+        });
+    }
+    for change in state.tag_log.lock().unwrap().iter() {
+        events.push(RawEvent {
+            outcome: change.tag.clone(),
+            ..event("tag", change.ts)
+        });
+    }
+    let day_start_hour = *state.day_start_hour.lock().unwrap();
+    for day in state.marked_days.lock().unwrap().iter() {
+        let Ok(date) = marked_days::parse_date(&day.date) else {
+            continue;
+        };
+        let mut meta = Map::new();
+        meta.insert("date".to_string(), day.date.clone().into());
+        events.push(RawEvent {
+            outcome: Some(day.kind.as_str().to_string()),
+            meta,
+            ..event("marked_day", day_start_ts(date, day_start_hour))
+        });
+    }
+    events.sort_by(|a, b| a.ts.cmp(&b.ts).then_with(|| a.kind.cmp(&b.kind)));
+    assign_ids(&mut events);
+    events
+}
+
+pub fn dump(state: &AppState) -> RawEventDump {
+    RawEventDump {
+        schema_version: SCHEMA_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_ts: now_ts(),
+        events: collect(state),
+    }
+}
+
+/// Merge a dump written by `dump`. Events whose id already exists are skipped.
+pub fn import_file(state: &AppState, path: &str) -> Result<RawImportResult, String> {
+    let size = fs::metadata(path)
+        .map_err(|e| format!("read failed: {}", e))?
+        .len();
+    if size > MAX_IMPORT_BYTES {
+        return Err("event dump is too large".to_string());
+    }
+    let contents = fs::read_to_string(path).map_err(|e| format!("read failed: {}", e))?;
+    let incoming: RawEventDump =
+        serde_json::from_str(&contents).map_err(|e| format!("invalid event dump: {}", e))?;
+    if incoming.schema_version > SCHEMA_VERSION {
+        return Err(format!(
+            "event dump schema {} is newer than supported ({})",
+            incoming.schema_version, SCHEMA_VERSION
+        ));
+    }
+    Ok(merge(state, incoming.events))
+}
+
+/// Add events whose id isn't in the store yet. Ids must already be assigned.
+pub fn merge(state: &AppState, incoming: Vec<RawEvent>) -> RawImportResult {
+    let existing: HashSet<String> = collect(state).into_iter().map(|e| e.id).collect();
+    let mut result = RawImportResult {
+        imported: 0,
+        skipped_existing: 0,
+        skipped_unknown: 0,
+    };
+    let mut reminders = state.reminder_events.lock().unwrap();
+    let mut standups = state.standup_events.lock().unwrap();
+    let mut focus = state.focus_events.lock().unwrap();
+    let mut activity = state.activity_buckets.lock().unwrap();
+    let mut power_log = state.power_log.lock().unwrap();
+    let mut tag_log = state.tag_log.lock().unwrap();
+    let mut marked = state.marked_days.lock().unwrap();
+    for event in incoming {
+        if existing.contains(&event.id) {
+            result.skipped_existing += 1;
+            continue;
+        }
+        let meta_secs = |key: &str| event.meta.get(key).and_then(Value::as_u64).unwrap_or(0);
+        match event.kind.as_str() {
+            "sedentary" => reminders.push(ReminderRecord {
+                ts: event.ts,
+                duration_secs: event.duration_secs.unwrap_or(0),
+            }),
+            "standup" => standups.push(event.ts),
+            "focus" => focus.push(FocusRecord {
+                ts: event.ts,
+                duration_secs: event.duration_secs.unwrap_or(0),
+                completed: event.outcome.as_deref() == Some("completed"),
+            }),
+            "activity" => activity.push(ActivityBucket {
+                hour_ts: event.ts,
+                active_secs: meta_secs("active_secs"),
+                idle_secs: meta_secs("idle_secs"),
+            }),
+            "power" => power_log.push(PowerChange {
+                ts: event.ts,
+                on_battery: event.outcome.as_deref() == Some("battery"),
+            }),
+            "tag" => tag_log.push(TagChange {
+                ts: event.ts,
+                tag: event.outcome.clone(),
+            }),
+            "marked_day" => {
+                let date = event.meta.get("date").and_then(Value::as_str);
+                let kind = event.outcome.as_deref().and_then(DayKind::parse);
+                let (Some(date), Some(kind)) = (date, kind) else {
+                    result.skipped_unknown += 1;
+                    continue;
+                };
+                if marked_days::parse_date(date).is_err() || marked.iter().any(|d| d.date == date) {
+                    result.skipped_existing += 1;
+                    continue;
+                }
+                marked.push(MarkedDay {
+                    date: date.to_string(),
+                    kind,
+                });
+            }
+            _ => {
+                result.skipped_unknown += 1;
+                continue;
+            }
+        }
+        result.imported += 1;
+    }
+    reminders.sort_by_key(|r| r.ts);
+    standups.sort_unstable();
+    focus.sort_by_key(|r| r.ts);
+    activity.sort_by_key(|b| b.hour_ts);
+    power_log.sort_by_key(|c| c.ts);
+    tag_log.sort_by_key(|c| c.ts);
+    marked.sort_by(|a, b| a.date.cmp(&b.date));
+    result
+}
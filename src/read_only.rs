@@ -0,0 +1,129 @@
+use tauri::ipc::Invoke;
+use tauri::{Manager, Runtime};
+
+use crate::AppState;
+
+/// The error every write command rejects with in read-only mode. Callers can
+/// match on it exactly; it never carries a message.
+pub const ERROR: &str = "ReadOnlyMode";
+
+/// Commands that change persisted settings or data, or write files. Reminders
+/// can still be answered; the answer just isn't stored.
+pub const WRITE_COMMANDS: &[&str] = &[
+    "set_reminder_interval",
+    "move_data_dir",
+    "set_tick_event_granularity",
+    "set_tick_resolution",
+    "set_language",
+    "set_reminder_language",
+    "enable_tip_pack",
+    "disable_tip_pack",
+    "import_tip_pack",
+    "download_tip_pack",
+    "set_plugin_enabled",
+    "set_schedule",
+    "set_theme",
+    "set_reminder_focus_mode",
+    "set_standup_cooldown",
+    "set_ack_grace_ms",
+    "set_day_start_hour",
+    "set_sedentary_threshold_secs",
+    "set_reminder_all_monitors",
+    "set_reminder_anchor",
+    "window_set_always_on_top",
+    "set_skip_budget",
+    "set_repeat_when_dismissed",
+    "set_reminder_presentation",
+    "set_handoff_config",
+    "set_hooks",
+    "rate_tip_for_reminder",
+    "set_challenge_config",
+    "set_network_profiles",
+    "set_desk_mode",
+    "report_desk_position",
+    "set_startup_options",
+    "log_standup",
+    "start_focus_session",
+    "stop_focus_session",
+    "set_week_settings",
+    "export_analytics_csv",
+    "set_session_tag",
+    "set_record_power_source",
+    "set_export_date_format",
+    "set_export_diff",
+    "set_export_filename_template",
+    "set_export_schedule",
+    "set_monthly_goal",
+    "set_pre_warning_minutes",
+    "set_sitting_budget_minutes",
+    "set_sit_stand_target",
+    "set_window_nudge_minutes",
+    "set_sleep_policy",
+    "revert_config_change",
+    "set_input_activity_enabled",
+    "set_mute_when_mic_in_use",
+    "set_tray_icons",
+    "set_blocking_apps",
+    "set_weekend_override",
+    "set_holiday_config",
+    "mark_day",
+    "import_history",
+    "import_holidays_ics",
+    "set_day_summary_config",
+    "set_leaderboard_config",
+    "set_google_sheets_config",
+    "append_google_sheets_now",
+    "set_markdown_journal_config",
+    "append_markdown_journal",
+    "set_local_api_config",
+    "generate_api_token",
+    "revoke_api_token",
+    "set_status_file_config",
+    "repair_data_files",
+    "run_scheduled_export_now",
+    "export_analytics_png",
+    "export_raw_events",
+    "import_analytics",
+    "export_settings",
+    "import_settings",
+    "reset_daily_records",
+];
+
+/// `--read-only` on the command line.
+pub fn from_args(args: &[String]) -> bool {
+    args.iter().any(|arg| arg == "--read-only")
+}
+
+/// Observer mode: config and analytics are read as usual and the timers run,
+/// but nothing is written to disk.
+pub fn enabled(state: &AppState) -> bool {
+    *state.read_only.lock().unwrap()
+}
+
+pub fn check(state: &AppState) -> Result<(), String> {
+    if enabled(state) {
+        Err(ERROR.to_string())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn is_write_command(command: &str) -> bool {
+    WRITE_COMMANDS.contains(&command)
+}
+
+/// Wrap the command handler so write commands are rejected with [`ERROR`]
+/// before they run while read-only mode is on.
+pub fn guard<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let blocked = is_write_command(invoke.message.command())
+            && enabled(&invoke.message.webview().state::<AppState>());
+        if blocked {
+            invoke.resolver.reject(ERROR);
+            return true;
+        }
+        handler(invoke)
+    }
+}
@@ -0,0 +1,108 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::tip_ratings::ShownTip;
+use crate::{now_ts, repeats, AppState};
+
+/// Reminders kept for `get_reminder_history`; the log lives for one launch.
+pub const MAX_HISTORY: usize = 100;
+
+/// What made a reminder come due. There is no snooze, escalation or adaptive
+/// interval yet; each gets a variant here when it lands.
+#[derive(Clone, Copy, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Trigger {
+    /// The sitting countdown ran out.
+    IntervalElapsed,
+    /// A completed focus session pulled the break forward.
+    FocusEnded,
+    /// A follow-up to a reminder dismissed while still sitting.
+    Repeat,
+}
+
+/// Why and how a reminder fired, so its timing can be checked after the fact.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct ReminderReason {
+    pub trigger: Trigger,
+    pub fired_ts: i64,
+    /// The interval in effect when it fired.
+    pub interval_secs: u64,
+    /// The interval came from the weekend override rather than the weekday one.
+    pub weekend_interval: bool,
+    /// Sitting time counted toward the interval; short of it when the
+    /// countdown was pulled forward, past it after a coarse tick or a wakeup.
+    pub sat_secs: u64,
+    /// Delivery was held until screen sharing ended.
+    pub held_for_screen_sharing: bool,
+}
+
+/// One reminder of this launch, newest last.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct ReminderHistoryEntry {
+    pub id: u64,
+    pub reason: ReminderReason,
+    pub tip: ShownTip,
+    /// "stood_up", "dismissed", "ignored" (logged as sitting) or "failed"
+    /// (the window couldn't be shown); `None` while it is still open.
+    pub outcome: Option<String>,
+    pub answered_ts: Option<i64>,
+}
+
+/// Consume the pending trigger and the sitting time it fired after; the
+/// countdown fires for its interval unless something pulled it forward.
+pub fn take_trigger(state: &AppState, elapsed_secs: u64) -> (Trigger, u64) {
+    let repeat = repeats::take(state, now_ts());
+    match (state.focus_break_due.lock().unwrap().take(), repeat) {
+        (Some(sat_secs), _) => (Trigger::FocusEnded, sat_secs),
+        (None, Some(offset)) => (Trigger::Repeat, elapsed_secs.saturating_sub(offset)),
+        (None, None) => (Trigger::IntervalElapsed, elapsed_secs),
+    }
+}
+
+pub fn fired(state: &AppState, id: u64, reason: ReminderReason, tip: ShownTip) {
+    *state.active_reminder_reason.lock().unwrap() = Some(reason.clone());
+    let mut history = state.reminder_history.lock().unwrap();
+    history.push(ReminderHistoryEntry {
+        id,
+        reason,
+        tip,
+        outcome: None,
+        answered_ts: None,
+    });
+    let excess = history.len().saturating_sub(MAX_HISTORY);
+    history.drain(..excess);
+}
+
+pub fn held_for_screen_sharing(state: &AppState, id: u64) {
+    if let Some(reason) = state.active_reminder_reason.lock().unwrap().as_mut() {
+        reason.held_for_screen_sharing = true;
+    }
+    if let Some(entry) = state
+        .reminder_history
+        .lock()
+        .unwrap()
+        .iter_mut()
+        .rfind(|entry| entry.id == id)
+    {
+        entry.reason.held_for_screen_sharing = true;
+    }
+}
+
+/// Close the entry for reminder `id`. Only the first answer counts.
+pub fn settled(state: &AppState, id: u64, outcome: &str, ts: i64) {
+    *state.active_reminder_reason.lock().unwrap() = None;
+    if let Some(entry) = state
+        .reminder_history
+        .lock()
+        .unwrap()
+        .iter_mut()
+        .rfind(|entry| entry.id == id && entry.outcome.is_none())
+    {
+        entry.outcome = Some(outcome.to_string());
+        entry.answered_ts = Some(ts);
+    }
+}
+
+pub fn history(state: &AppState) -> Vec<ReminderHistoryEntry> {
+    state.reminder_history.lock().unwrap().clone()
+}
@@ -0,0 +1,475 @@
+use serde::Serialize;
+use tauri::{
+    window::Color, AppHandle, Manager, Monitor, PhysicalPosition, PhysicalSize, WebviewUrl,
+    WebviewWindow, WebviewWindowBuilder,
+};
+
+use crate::{
+    answer_reminder_key, languages, overlay, present_reminder_window, AppState, REMINDER_HEIGHT,
+    REMINDER_WIDTH,
+};
+
+/// The reminder declared in tauri.conf.json. It is the one that takes focus;
+/// windows on other monitors are created next to it as needed.
+pub const PRIMARY_LABEL: &str = "reminder";
+const SECONDARY_PREFIX: &str = "reminder-";
+/// Menu ids of the Enter and Escape accelerators carry the key after this.
+const KEY_MENU_PREFIX: &str = "reminder-key:";
+const MARGIN: i32 = 28;
+/// Tallest a long tip can make the reminder.
+pub const MAX_HEIGHT: i32 = 420;
+/// Tip text the base height holds, in width units (a wide CJK character
+/// counts as two), and the height each further line adds.
+const UNITS_PER_LINE: usize = 56;
+const BASE_LINES: usize = 2;
+const LINE_HEIGHT: i32 = 26;
+
+/// What the manager knows about one reminder window.
+#[derive(Clone, Serialize)]
+pub struct ReminderWindowInfo {
+    pub label: String,
+    /// Name of the monitor the window was placed on, when the OS reports one.
+    pub monitor: Option<String>,
+    /// Id of the reminder the window was last shown for.
+    pub reminder_id: u64,
+    pub shown: bool,
+}
+
+pub fn is_reminder_label(label: &str) -> bool {
+    label == PRIMARY_LABEL || label.starts_with(SECONDARY_PREFIX)
+}
+
+fn label_for(index: usize) -> String {
+    if index == 0 {
+        PRIMARY_LABEL.to_string()
+    } else {
+        format!("{}{}", SECONDARY_PREFIX, index)
+    }
+}
+
+/// Monitors that should get a reminder: the primary one first, then the rest
+/// when `reminder_all_monitors` is on.
+fn target_monitors(app: &AppHandle) -> Vec<Monitor> {
+    let primary = app.primary_monitor().ok().flatten();
+    let mut monitors: Vec<Monitor> = primary.iter().cloned().collect();
+    let all_monitors = *app
+        .state::<AppState>()
+        .reminder_all_monitors
+        .lock()
+        .unwrap();
+    if all_monitors {
+        let others = app.available_monitors().unwrap_or_default();
+        monitors.extend(others.into_iter().filter(|m| {
+            primary
+                .as_ref()
+                .map(|p| p.position() != m.position())
+                .unwrap_or(true)
+        }));
+    }
+    monitors
+}
+
+fn build(app: &AppHandle, label: &str) -> Option<WebviewWindow> {
+    WebviewWindowBuilder::new(app, label, WebviewUrl::App("reminder_v2.html".into()))
+        .title("Upstand Reminder")
+        .inner_size(REMINDER_WIDTH as f64, REMINDER_HEIGHT as f64)
+        .decorations(false)
+        .transparent(false)
+        .background_color(Color(0xff, 0xff, 0xff, 0xff))
+        .visible(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .build()
+        .ok()
+}
+
+/// Give the window Enter and Escape as accelerators of a hidden menu, so the
+/// keys answer the reminder whatever the page does with them. macOS menus
+/// are app-wide rather than per window; there the page's listener answers.
+#[cfg(not(target_os = "macos"))]
+fn attach_keys(win: &WebviewWindow) {
+    use tauri::menu::{Menu, MenuItem};
+    let app = win.app_handle();
+    let item = |key: &str| {
+        let id = format!("{}{}", KEY_MENU_PREFIX, key);
+        MenuItem::with_id(app, id, key, true, Some(key))
+    };
+    let (Ok(enter), Ok(escape)) = (item("Enter"), item("Escape")) else {
+        return;
+    };
+    let Ok(menu) = Menu::with_items(app, &[&enter, &escape]) else {
+        return;
+    };
+    if win.set_menu(menu).is_err() {
+        return;
+    }
+    let _ = win.hide_menu();
+    win.on_menu_event(|window, event| {
+        if let Some(key) = event.id().as_ref().strip_prefix(KEY_MENU_PREFIX) {
+            answer_reminder_key(window.app_handle(), window.label(), key);
+        }
+    });
+}
+
+#[cfg(target_os = "macos")]
+fn attach_keys(_win: &WebviewWindow) {}
+
+/// Height needed to show `tip` without truncation, estimated from its
+/// length until the webview reports what it measured.
+pub fn estimated_height(tip: &str) -> i32 {
+    let units: usize = tip
+        .chars()
+        .map(|c| if (c as u32) >= 0x1100 { 2 } else { 1 })
+        .sum();
+    let lines = units.div_ceil(UNITS_PER_LINE).max(1);
+    let extra = lines.saturating_sub(BASE_LINES) as i32;
+    (REMINDER_HEIGHT + extra * LINE_HEIGHT).min(MAX_HEIGHT)
+}
+
+/// Screen edge the taskbar is docked to.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Edge {
+    Left,
+    Top,
+    Right,
+    Bottom,
+}
+
+/// Corner of the work area the reminder sits in.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Corner {
+    pub top: bool,
+    pub left: bool,
+}
+
+/// Accepted `reminder_anchor` values; "auto" follows the taskbar.
+pub const ANCHORS: [&str; 5] = [
+    "auto",
+    "bottom_right",
+    "bottom_left",
+    "top_right",
+    "top_left",
+];
+
+pub fn normalize_anchor(anchor: &str) -> String {
+    if ANCHORS.contains(&anchor) {
+        anchor.to_string()
+    } else {
+        ANCHORS[0].to_string()
+    }
+}
+
+/// The edge the work area is cut back from the most, given the monitor's
+/// bounds and its work area as `(x, y, width, height)`. An auto-hidden
+/// taskbar takes no space and reads as the usual bottom.
+pub fn taskbar_edge(bounds: (i32, i32, u32, u32), work: (i32, i32, u32, u32)) -> Edge {
+    let left = work.0 - bounds.0;
+    let top = work.1 - bounds.1;
+    let right = (bounds.0 + bounds.2 as i32) - (work.0 + work.2 as i32);
+    let bottom = (bounds.1 + bounds.3 as i32) - (work.1 + work.3 as i32);
+    [
+        (bottom, Edge::Bottom),
+        (left, Edge::Left),
+        (right, Edge::Right),
+        (top, Edge::Top),
+    ]
+    .into_iter()
+    .filter(|(inset, _)| *inset > 0)
+    .max_by_key(|(inset, _)| *inset)
+    .map_or(Edge::Bottom, |(_, edge)| edge)
+}
+
+/// Where to put the reminder. "auto" sits next to the taskbar, on the
+/// side the reading direction favours when it is at the top or bottom;
+/// the other values pin a corner regardless.
+pub fn corner(anchor: &str, rtl: bool, taskbar: Edge) -> Corner {
+    match anchor {
+        "bottom_right" => Corner {
+            top: false,
+            left: false,
+        },
+        "bottom_left" => Corner {
+            top: false,
+            left: true,
+        },
+        "top_right" => Corner {
+            top: true,
+            left: false,
+        },
+        "top_left" => Corner {
+            top: true,
+            left: true,
+        },
+        _ => match taskbar {
+            Edge::Bottom => Corner {
+                top: false,
+                left: rtl,
+            },
+            Edge::Top => Corner {
+                top: true,
+                left: rtl,
+            },
+            Edge::Left => Corner {
+                top: false,
+                left: true,
+            },
+            Edge::Right => Corner {
+                top: false,
+                left: false,
+            },
+        },
+    }
+}
+
+/// Only Windows reports the taskbar through the work area; elsewhere a
+/// menu bar or dock would be mistaken for it.
+fn monitor_taskbar_edge(monitor: &Monitor) -> Edge {
+    if !cfg!(windows) {
+        return Edge::Bottom;
+    }
+    let (pos, size, area) = (monitor.position(), monitor.size(), monitor.work_area());
+    taskbar_edge(
+        (pos.x, pos.y, size.width, size.height),
+        (
+            area.position.x,
+            area.position.y,
+            area.size.width,
+            area.size.height,
+        ),
+    )
+}
+
+/// The anchored corner of the monitor's work area, `MARGIN` in from it.
+/// The anchored edge stays put when the height changes.
+fn place(win: &WebviewWindow, monitor: Option<&Monitor>, anchor: &str, rtl: bool, height: i32) {
+    let _ = win.set_size(PhysicalSize::new(REMINDER_WIDTH as u32, height as u32));
+    let Some(monitor) = monitor else {
+        return;
+    };
+    let corner = corner(anchor, rtl, monitor_taskbar_edge(monitor));
+    let area = monitor.work_area();
+    let (width, height) = win
+        .outer_size()
+        .ok()
+        .map(|s| (s.width as i32, s.height as i32))
+        .unwrap_or((REMINDER_WIDTH, height));
+    let x = if corner.left {
+        area.position.x + MARGIN
+    } else {
+        area.position.x + area.size.width as i32 - width - MARGIN
+    };
+    let y = if corner.top {
+        area.position.y + MARGIN
+    } else {
+        area.position.y + area.size.height as i32 - height - MARGIN
+    };
+    let _ = win.set_position(PhysicalPosition::new(x, y));
+}
+
+/// Every reminder window that currently exists, primary first.
+pub fn existing(app: &AppHandle) -> Vec<WebviewWindow> {
+    let mut windows: Vec<WebviewWindow> = app
+        .webview_windows()
+        .into_iter()
+        .filter(|(label, _)| is_reminder_label(label))
+        .map(|(_, win)| win)
+        .collect();
+    windows.sort_by_key(|win| win.label() != PRIMARY_LABEL);
+    windows
+}
+
+/// Create (or reuse) one positioned, still hidden reminder window per target
+/// monitor and close any left over from a monitor that went away. Missing
+/// windows, including a destroyed primary, are rebuilt from scratch. The
+/// primary window is always first; an empty result means it couldn't be built.
+pub fn prepare(app: &AppHandle, reminder_id: u64) -> Vec<WebviewWindow> {
+    let mut monitors: Vec<Option<Monitor>> = target_monitors(app).into_iter().map(Some).collect();
+    if monitors.is_empty() {
+        monitors.push(None);
+    }
+
+    let state = app.state::<AppState>();
+    let rtl = languages::is_rtl(&state.reminder_language.lock().unwrap());
+    let anchor = state.reminder_anchor.lock().unwrap().clone();
+    let height = *state.reminder_height.lock().unwrap();
+    let mut windows = Vec::new();
+    let mut infos = Vec::new();
+    for (index, monitor) in monitors.iter().enumerate() {
+        let label = label_for(index);
+        let Some(win) = app
+            .get_webview_window(&label)
+            .or_else(|| build(app, &label))
+        else {
+            if index == 0 {
+                return Vec::new();
+            }
+            continue;
+        };
+        if win.menu().is_none() {
+            attach_keys(&win);
+        }
+        place(&win, monitor.as_ref(), &anchor, rtl, height);
+        infos.push(ReminderWindowInfo {
+            label,
+            monitor: monitor.as_ref().and_then(|m| m.name().cloned()),
+            reminder_id,
+            shown: false,
+        });
+        windows.push(win);
+    }
+
+    for win in existing(app) {
+        if !windows.iter().any(|w| w.label() == win.label()) {
+            let _ = win.close();
+        }
+    }
+    *app.state::<AppState>().reminder_windows.lock().unwrap() = infos;
+    windows
+}
+
+/// Resize the reminder windows to `height` (clamped to the base and maximum
+/// heights), re-anchoring each on its monitor.
+pub fn resize(app: &AppHandle, windows: &[WebviewWindow], height: i32) {
+    let state = app.state::<AppState>();
+    let height = height.clamp(REMINDER_HEIGHT, MAX_HEIGHT);
+    *state.reminder_height.lock().unwrap() = height;
+    let rtl = languages::is_rtl(&state.reminder_language.lock().unwrap());
+    let anchor = state.reminder_anchor.lock().unwrap().clone();
+    for win in windows {
+        let monitor = win.current_monitor().ok().flatten();
+        place(win, monitor.as_ref(), &anchor, rtl, height);
+    }
+}
+
+/// Show every prepared window. Only the primary one may take focus, so the
+/// foreground window captured for hand-back is never another reminder.
+/// Errs if the primary window couldn't be shown or focused.
+pub fn present_all(app: &AppHandle, windows: &[WebviewWindow]) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let mut result = Ok(());
+    for win in windows {
+        if win.label() == PRIMARY_LABEL {
+            result = present_reminder_window(win, &state);
+        } else {
+            let _ = win.set_focusable(false);
+            let _ = win.show();
+        }
+    }
+    for info in state.reminder_windows.lock().unwrap().iter_mut() {
+        info.shown = windows.iter().any(|w| w.label() == info.label);
+    }
+    result
+}
+
+/// Hide every reminder without forgetting them, e.g. while screen sharing.
+pub fn hide_all(app: &AppHandle) {
+    for win in existing(app) {
+        let _ = win.hide();
+    }
+    overlay::hide_window(app);
+    for info in app
+        .state::<AppState>()
+        .reminder_windows
+        .lock()
+        .unwrap()
+        .iter_mut()
+    {
+        info.shown = false;
+    }
+}
+
+/// The reminder was answered: hide the primary window and the banner, and
+/// close the others.
+pub fn dismiss(app: &AppHandle) {
+    overlay::hide(app);
+    for win in existing(app) {
+        if win.label() == PRIMARY_LABEL {
+            let _ = win.hide();
+        } else {
+            let _ = win.close();
+        }
+    }
+    app.state::<AppState>()
+        .reminder_windows
+        .lock()
+        .unwrap()
+        .clear();
+}
+
+/// One monitor as far as placement cares: where it is and how big.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MonitorLayout {
+    pub name: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_milli: u32,
+}
+
+/// The current monitors, sorted so the order the OS lists them in doesn't
+/// read as a change.
+pub fn layout(app: &AppHandle) -> Vec<MonitorLayout> {
+    let mut monitors: Vec<MonitorLayout> = app
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|m| {
+            let area = m.work_area();
+            MonitorLayout {
+                name: m.name().cloned(),
+                x: area.position.x,
+                y: area.position.y,
+                width: area.size.width,
+                height: area.size.height,
+                scale_milli: (m.scale_factor() * 1000.0).round() as u32,
+            }
+        })
+        .collect();
+    monitors.sort_by_key(|m| (m.x, m.y));
+    monitors
+}
+
+/// Record `next` as the layout; true if it differs from the last one seen.
+/// The first reading is never a change.
+pub fn layout_changed(state: &AppState, next: Vec<MonitorLayout>) -> bool {
+    let mut current = state.display_layout.lock().unwrap();
+    let changed = current.as_ref().is_some_and(|last| *last != next);
+    *current = Some(next);
+    changed
+}
+
+/// Monitors were added, removed or resized: place an open reminder on the
+/// monitors that exist now. Each window is hidden and shown again, so one
+/// left where a monitor used to be comes back on screen; a reminder held
+/// for screen sharing stays hidden.
+pub fn relayout(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let open = (*state.reminder_visible.lock().unwrap() && !*state.screen_sharing.lock().unwrap())
+        || state.reminder_preview.lock().unwrap().is_some();
+    if !open {
+        return;
+    }
+    let reminder_id = *state.active_reminder_id.lock().unwrap();
+    let windows = prepare(app, reminder_id);
+    if overlay::holding(&state) {
+        // The windows stay hidden until it escalates; the next tick shows
+        // the banner again, placed on the new layout.
+        overlay::hide_window(app);
+        return;
+    }
+    for win in &windows {
+        if win.label() != PRIMARY_LABEL {
+            let _ = win.set_focusable(false);
+        }
+        let _ = win.hide();
+        let _ = win.show();
+    }
+    for info in state.reminder_windows.lock().unwrap().iter_mut() {
+        info.shown = true;
+    }
+    // Windows made for a new monitor need the tip too.
+    for win in &windows {
+        let _ = win.eval("window.__standbyReminderSync && window.__standbyReminderSync();");
+    }
+}
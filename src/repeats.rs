@@ -0,0 +1,92 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{timers, AppState};
+
+pub const MIN_DELAY_MINUTES: u64 = 1;
+pub const MAX_DELAY_MINUTES: u64 = 30;
+pub const MAX_REPEATS_PER_HOUR: u32 = 6;
+const HOUR_SECS: i64 = 60 * 60;
+
+/// Follow up a reminder dismissed while still sitting after `delay_minutes`
+/// instead of a full interval, at most `max_per_hour` times an hour.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RepeatConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_delay_minutes")]
+    pub delay_minutes: u64,
+    #[serde(default = "default_max_per_hour")]
+    pub max_per_hour: u32,
+}
+
+impl Default for RepeatConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            delay_minutes: default_delay_minutes(),
+            max_per_hour: default_max_per_hour(),
+        }
+    }
+}
+
+fn default_delay_minutes() -> u64 {
+    5
+}
+
+fn default_max_per_hour() -> u32 {
+    2
+}
+
+pub fn sanitize(mut cfg: RepeatConfig) -> RepeatConfig {
+    cfg.delay_minutes = cfg
+        .delay_minutes
+        .clamp(MIN_DELAY_MINUTES, MAX_DELAY_MINUTES);
+    cfg.max_per_hour = cfg.max_per_hour.clamp(1, MAX_REPEATS_PER_HOUR);
+    cfg
+}
+
+/// After a dismissal: move the countdown to `delay_minutes` before it runs
+/// out, unless this hour's follow-ups are used up. Returns whether one was
+/// scheduled.
+pub fn schedule(state: &AppState, now: i64) -> bool {
+    let cfg = state.repeat_when_dismissed.lock().unwrap().clone();
+    if !cfg.enabled {
+        return false;
+    }
+    let recent = state
+        .repeat_log
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|ts| now - **ts < HOUR_SECS)
+        .count();
+    if recent >= cfg.max_per_hour as usize {
+        return false;
+    }
+    let interval = timers::effective_interval(state);
+    let offset = interval.saturating_sub(cfg.delay_minutes * 60);
+    *state.elapsed.lock().unwrap() = offset;
+    *state.repeat_due.lock().unwrap() = Some(offset);
+    true
+}
+
+/// A follow-up is counting down.
+pub fn pending(state: &AppState) -> bool {
+    state.repeat_due.lock().unwrap().is_some()
+}
+
+/// Drop a pending follow-up; anything that restarts the countdown does.
+pub fn cancel(state: &AppState) {
+    *state.repeat_due.lock().unwrap() = None;
+}
+
+/// Consume the pending follow-up as it fires. Returns the countdown offset
+/// it was scheduled with, i.e. what of the elapsed time wasn't sat.
+pub fn take(state: &AppState, now: i64) -> Option<u64> {
+    let offset = state.repeat_due.lock().unwrap().take()?;
+    let mut log = state.repeat_log.lock().unwrap();
+    log.retain(|ts| now - *ts < HOUR_SECS);
+    log.push(now);
+    Some(offset)
+}
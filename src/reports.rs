@@ -0,0 +1,214 @@
+use chrono::{DateTime, Local, Timelike};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::delivery::{self, DeliveryStats};
+use crate::{
+    analytics_date, build_analytics_for_period, clock, formatting::Formatter, goals,
+    goals::MonthlyGoalProgress, marked_days, notify, period_options, period_start_ts, save_config,
+    AppState,
+};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DaySummaryConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_hour")]
+    pub hour: u32,
+    #[serde(default)]
+    pub minute: u32,
+    #[serde(default)]
+    pub last_sent_date: Option<String>,
+}
+
+impl Default for DaySummaryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            hour: default_hour(),
+            minute: 0,
+            last_sent_date: None,
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_hour() -> u32 {
+    18
+}
+
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct DaySummary {
+    pub date: String,
+    /// `date` formatted for the UI language.
+    pub display_date: String,
+    pub standups: u32,
+    pub sitting_secs: u64,
+    /// Longest run of standups with no ignored reminder in between, measured
+    /// from the first to the last standup of the run.
+    pub best_streak_secs: u64,
+    /// Present when a monthly goal is set.
+    pub monthly_goal: Option<MonthlyGoalProgress>,
+    pub delivery: DeliveryStats,
+    pub text: String,
+}
+
+pub fn sanitize(mut cfg: DaySummaryConfig) -> DaySummaryConfig {
+    cfg.hour = cfg.hour.min(23);
+    cfg.minute = cfg.minute.min(59);
+    cfg
+}
+
+fn date_key(now: DateTime<Local>, day_start_hour: u32) -> String {
+    analytics_date(now, day_start_hour)
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+pub fn is_due(cfg: &DaySummaryConfig, now: DateTime<Local>, day_start_hour: u32) -> bool {
+    cfg.enabled
+        && (now.hour(), now.minute()) >= (cfg.hour, cfg.minute)
+        && cfg.last_sent_date.as_deref() != Some(date_key(now, day_start_hour).as_str())
+}
+
+fn best_streak_secs(state: &AppState, since: i64) -> u64 {
+    let marked = state.marked_days.lock().unwrap().clone();
+    let day_start_hour = *state.day_start_hour.lock().unwrap();
+    let mut events: Vec<(i64, bool)> = state
+        .standup_events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|ts| **ts >= since)
+        .map(|ts| (*ts, true))
+        .collect();
+    events.extend(
+        state
+            .reminder_events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| e.ts >= since)
+            .map(|e| (e.ts, false)),
+    );
+    // Marked days don't count toward streaks, good or bad.
+    events.retain(|(ts, _)| !marked_days::covers(&marked, *ts, day_start_hour));
+    events.sort_unstable();
+
+    let mut best = 0u64;
+    let mut run_start: Option<i64> = None;
+    for (ts, stood) in events {
+        if !stood {
+            run_start = None;
+            continue;
+        }
+        let start = *run_start.get_or_insert(ts);
+        best = best.max((ts - start).max(0) as u64);
+    }
+    best
+}
+
+fn summary_text(
+    lang: &str,
+    fmt: &Formatter,
+    standups: u32,
+    sitting_secs: u64,
+    streak_secs: u64,
+) -> String {
+    if lang == "zh-CN" {
+        format!(
+            "今天站立 {} 次，久坐 {}，最佳连续 {}",
+            fmt.count(u64::from(standups)),
+            fmt.duration(sitting_secs),
+            fmt.duration(streak_secs)
+        )
+    } else {
+        format!(
+            "{} standup{}, {} sitting, best streak {}",
+            fmt.count(u64::from(standups)),
+            if standups == 1 { "" } else { "s" },
+            fmt.duration(sitting_secs),
+            fmt.duration(streak_secs)
+        )
+    }
+}
+
+fn goal_text(lang: &str, goal: &MonthlyGoalProgress) -> String {
+    if lang == "zh-CN" {
+        format!("；本月目标 {}/{} 天", goal.days_met, goal.target_days)
+    } else {
+        format!("; monthly goal {}/{} days", goal.days_met, goal.target_days)
+    }
+}
+
+/// Only mentioned when something went wrong; a clean day says nothing.
+fn delivery_text(lang: &str, stats: &DeliveryStats) -> String {
+    let missed = stats.missed();
+    if missed == 0 {
+        String::new()
+    } else if lang == "zh-CN" {
+        format!("；{} 次提醒未能显示", missed)
+    } else {
+        format!(
+            "; {} reminder{} not shown",
+            missed,
+            if missed == 1 { "" } else { "s" }
+        )
+    }
+}
+
+pub fn day_summary(state: &AppState) -> DaySummary {
+    let now = clock::now();
+    let analytics = build_analytics_for_period(state, "daily");
+    let opts = period_options(state);
+    let day_start_hour = opts.day_start_hour;
+    let best_streak_secs = best_streak_secs(state, period_start_ts("daily", now, &opts));
+    let lang = state.language.lock().unwrap().clone();
+    let fmt = Formatter::for_reports(state);
+    let monthly_goal = Some(goals::progress(state, now)).filter(|goal| goal.enabled);
+    let mut text = summary_text(
+        &lang,
+        &fmt,
+        analytics.standup_sessions,
+        analytics.total_sitting_secs,
+        best_streak_secs,
+    );
+    if let Some(goal) = monthly_goal.as_ref() {
+        text.push_str(&goal_text(&lang, goal));
+    }
+    let delivery = delivery::today(state);
+    text.push_str(&delivery_text(&lang, &delivery));
+    DaySummary {
+        date: date_key(now, day_start_hour),
+        display_date: fmt.date(analytics_date(now, day_start_hour)),
+        standups: analytics.standup_sessions,
+        sitting_secs: analytics.total_sitting_secs,
+        best_streak_secs,
+        monthly_goal,
+        delivery,
+        text,
+    }
+}
+
+/// Post today's summary as a native notification. Desktop notifications have
+/// no click callback, so the click-through is armed on the tray icon instead:
+/// the next left click opens the stats window.
+pub fn send_day_summary(app: &AppHandle) -> DaySummary {
+    let state = app.state::<AppState>();
+    let summary = day_summary(&state);
+    let title = if *state.language.lock().unwrap() == "zh-CN" {
+        "今日总结"
+    } else {
+        "Today in Upstand"
+    };
+    notify(app, title, &summary.text);
+    state.day_summary.lock().unwrap().last_sent_date = Some(summary.date.clone());
+    *state.day_summary_pending_click.lock().unwrap() = true;
+    let _ = save_config(app, &state);
+    let _ = app.emit("day-summary", summary.clone());
+    summary
+}
@@ -0,0 +1,120 @@
+use chrono::NaiveDate;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    analytics_date, clock, day_start_ts, events,
+    hooks::{self, HookEvent},
+    markdown_journal, now_ts, save_config, AppState,
+};
+
+/// Upper bound on one sleep, so a suspend or clock change is noticed within
+/// a minute instead of waiting out a stale deadline.
+const MAX_WAIT_SECS: u64 = 60;
+
+/// Payload of `day-rolled-over`. The totals are final: the day is reported
+/// once, even if the app was closed over midnight.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct DayRollover {
+    /// The day that just ended (or the last day the app saw), "YYYY-MM-DD".
+    pub previous_date: String,
+    pub date: String,
+    pub previous_standups: u32,
+    pub previous_sedentary: u32,
+    /// The monthly goal's daily standup target was reached that day.
+    pub previous_goal_met: bool,
+}
+
+fn day_key(day: NaiveDate) -> String {
+    day.format("%Y-%m-%d").to_string()
+}
+
+fn finalize(state: &AppState, previous: NaiveDate, today: NaiveDate) -> DayRollover {
+    let day_start_hour = *state.day_start_hour.lock().unwrap();
+    let start = day_start_ts(previous, day_start_hour);
+    let end = previous
+        .succ_opt()
+        .map(|next| day_start_ts(next, day_start_hour))
+        .unwrap_or(i64::MAX);
+    let in_day = |ts: i64| ts >= start && ts < end;
+    let previous_standups = state
+        .standup_events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|ts| in_day(**ts))
+        .count() as u32;
+    let previous_sedentary = state
+        .reminder_events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|e| in_day(e.ts))
+        .count() as u32;
+    let daily_target = state.monthly_goal.lock().unwrap().daily_standups;
+    DayRollover {
+        previous_date: day_key(previous),
+        date: day_key(today),
+        previous_standups,
+        previous_sedentary,
+        previous_goal_met: previous_standups >= daily_target,
+    }
+}
+
+/// Emit `day-rolled-over` if the date changed since the last check. The first
+/// launch only records today; there is no earlier day to finalize.
+pub fn roll_if_needed(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let today = analytics_date(clock::now(), *state.day_start_hour.lock().unwrap());
+    let previous = {
+        let mut last = state.last_rollover_date.lock().unwrap();
+        let key = day_key(today);
+        if last.as_deref() == Some(key.as_str()) {
+            return;
+        }
+        last.replace(key)
+    };
+    let _ = save_config(app, &state);
+    let Some(previous) = previous.and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok())
+    else {
+        return;
+    };
+    if previous < today {
+        let rollover = finalize(&state, previous, today);
+        hooks::dispatch(
+            app,
+            HookEvent::DayRolledOver,
+            &[
+                ("previous_date", rollover.previous_date.clone()),
+                ("date", rollover.date.clone()),
+                ("previous_standups", rollover.previous_standups.to_string()),
+                (
+                    "previous_sedentary",
+                    rollover.previous_sedentary.to_string(),
+                ),
+                ("previous_goal_met", rollover.previous_goal_met.to_string()),
+            ],
+        );
+        events::emit(app, "day-rolled-over", rollover);
+        // Existing dashboards only listen for analytics changes; nudge them too.
+        events::analytics_updated(app);
+        markdown_journal::on_rollover(app, previous);
+    }
+}
+
+/// Sleep until just past the next day boundary (local midnight unless
+/// `day_start_hour` moves it), wherever DST puts it.
+pub async fn run(app: AppHandle) {
+    loop {
+        roll_if_needed(&app);
+        let day_start_hour = *app.state::<AppState>().day_start_hour.lock().unwrap();
+        let tomorrow = analytics_date(clock::now(), day_start_hour).succ_opt();
+        let until_boundary = tomorrow
+            .map(|day| (day_start_ts(day, day_start_hour) - now_ts()).max(0) as u64)
+            .unwrap_or(MAX_WAIT_SECS);
+        let wait = (until_boundary + 1).min(MAX_WAIT_SECS);
+        clock::sleep(Duration::from_secs(wait)).await;
+    }
+}
@@ -0,0 +1,98 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// Chimes the reminder page knows how to play.
+pub const SOUNDS: [&str; 3] = ["gentle", "energetic", "wind-down"];
+
+/// A time-of-day window with its own tip pool and reminder sound.
+/// `end_hour` is exclusive; a window may wrap past midnight (e.g. 18 -> 5).
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScheduleSlot {
+    pub name: String,
+    pub start_hour: u32,
+    pub end_hour: u32,
+    /// Empty means "use the built-in tips".
+    #[serde(default)]
+    pub tips: Vec<String>,
+    /// One of `SOUNDS`; `None`, the default, is silent.
+    #[serde(default)]
+    pub sound: Option<String>,
+}
+
+impl ScheduleSlot {
+    fn covers(&self, hour: u32) -> bool {
+        if self.start_hour == self.end_hour {
+            true
+        } else if self.start_hour < self.end_hour {
+            hour >= self.start_hour && hour < self.end_hour
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+pub fn default_schedule() -> Vec<ScheduleSlot> {
+    vec![
+        ScheduleSlot {
+            name: "morning".to_string(),
+            start_hour: 5,
+            end_hour: 12,
+            tips: Vec::new(),
+            sound: None,
+        },
+        ScheduleSlot {
+            name: "afternoon".to_string(),
+            start_hour: 12,
+            end_hour: 18,
+            tips: Vec::new(),
+            sound: None,
+        },
+        ScheduleSlot {
+            name: "evening".to_string(),
+            start_hour: 18,
+            end_hour: 5,
+            tips: Vec::new(),
+            sound: None,
+        },
+    ]
+}
+
+/// Drop slots with out-of-range hours, blank tips and unknown sounds.
+pub fn sanitize_schedule(slots: Vec<ScheduleSlot>) -> Vec<ScheduleSlot> {
+    slots
+        .into_iter()
+        .filter(|slot| slot.start_hour < 24 && slot.end_hour < 24)
+        .map(|mut slot| {
+            slot.tips.retain(|tip| !tip.trim().is_empty());
+            slot.sound = slot
+                .sound
+                .map(|s| s.trim().to_string())
+                .filter(|s| SOUNDS.contains(&s.as_str()));
+            slot
+        })
+        .collect()
+}
+
+pub fn slot_for_hour(slots: &[ScheduleSlot], hour: u32) -> Option<&ScheduleSlot> {
+    slots.iter().find(|slot| slot.covers(hour))
+}
+
+/// Weekend replacements for the weekday interval and schedule.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct WeekendOverride {
+    #[serde(default)]
+    pub enabled: bool,
+    /// No reminders at all on Saturday and Sunday.
+    #[serde(default)]
+    pub reminders_off: bool,
+    /// `None` keeps the weekday interval.
+    #[serde(default)]
+    pub interval_minutes: Option<u64>,
+    /// `None` keeps the weekday schedule.
+    #[serde(default)]
+    pub schedule: Option<Vec<ScheduleSlot>>,
+}
+
+pub fn is_weekend(day: NaiveDate) -> bool {
+    matches!(day.weekday(), Weekday::Sat | Weekday::Sun)
+}
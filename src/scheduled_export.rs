@@ -0,0 +1,192 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Timelike};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::export_diff::{self, ExportSnapshot};
+use crate::file_names::{self, ExportKind};
+use crate::{
+    analytics_csv, build_analytics_for_period, clock, formatting::Formatter, notify, save_config,
+    write_export, AppState,
+};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ExportScheduleConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub folder: Option<String>,
+    /// Any of "csv" / "json".
+    #[serde(default = "default_formats")]
+    pub formats: Vec<String>,
+    /// 0 = Monday ... 6 = Sunday.
+    #[serde(default = "default_weekday")]
+    pub weekday: u32,
+    #[serde(default = "default_hour")]
+    pub hour: u32,
+    #[serde(default)]
+    pub last_run_date: Option<String>,
+}
+
+impl Default for ExportScheduleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            folder: None,
+            formats: default_formats(),
+            weekday: default_weekday(),
+            hour: default_hour(),
+            last_run_date: None,
+        }
+    }
+}
+
+fn default_formats() -> Vec<String> {
+    vec!["csv".to_string()]
+}
+
+fn default_weekday() -> u32 {
+    6
+}
+
+fn default_hour() -> u32 {
+    21
+}
+
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct ScheduledExportResult {
+    pub ok: bool,
+    pub paths: Vec<String>,
+    pub error: Option<String>,
+}
+
+pub fn sanitize(mut cfg: ExportScheduleConfig) -> ExportScheduleConfig {
+    cfg.weekday = cfg.weekday.min(6);
+    cfg.hour = cfg.hour.min(23);
+    cfg.formats.retain(|f| f == "csv" || f == "json");
+    cfg.formats.sort();
+    cfg.formats.dedup();
+    if cfg.formats.is_empty() {
+        cfg.formats = default_formats();
+    }
+    cfg.folder = cfg.folder.filter(|f| !f.trim().is_empty());
+    cfg
+}
+
+/// The day of the latest scheduled run at or before `now`.
+fn latest_slot(cfg: &ExportScheduleConfig, now: DateTime<Local>) -> NaiveDate {
+    let mut days_back = (now.weekday().num_days_from_monday() + 7 - cfg.weekday) % 7;
+    if days_back == 0 && now.hour() < cfg.hour {
+        days_back = 7;
+    }
+    now.date_naive() - Duration::days(i64::from(days_back))
+}
+
+/// Due from the scheduled hour until a run is recorded, so a slot missed
+/// while the app was closed or on battery saver is caught up at the first
+/// check afterwards.
+pub fn is_due(cfg: &ExportScheduleConfig, now: DateTime<Local>, power_saving: bool) -> bool {
+    let last_run = cfg
+        .last_run_date
+        .as_deref()
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+    cfg.enabled
+        && !power_saving
+        && cfg.folder.is_some()
+        && last_run.is_none_or(|last| last < latest_slot(cfg, now))
+}
+
+fn write_exports(
+    app: &AppHandle,
+    state: &AppState,
+    cfg: &ExportScheduleConfig,
+) -> Result<Vec<String>, String> {
+    let folder = PathBuf::from(
+        cfg.folder
+            .as_deref()
+            .ok_or_else(|| "no export folder configured".to_string())?,
+    );
+    let analytics = build_analytics_for_period(state, "weekly");
+    let now = clock::now();
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for format in cfg.formats.iter() {
+        let (file_name, contents) = match format.as_str() {
+            "json" => (
+                file_names::for_export(state, ExportKind::Analytics, "weekly", now, "json"),
+                serde_json::to_string_pretty(&analytics)
+                    .map_err(|e| format!("serialize failed: {}", e))?,
+            ),
+            _ => (
+                file_names::for_export(state, ExportKind::Analytics, "weekly", now, "csv"),
+                analytics_csv(&analytics, &Formatter::for_exports(state)),
+            ),
+        };
+        let path = folder.join(file_name);
+        write_export(state, &path, contents)?;
+        paths.push(path);
+    }
+    // Next to the CSV if there is one; the diff is a CSV too.
+    let beside = paths
+        .iter()
+        .find(|path| path.extension().is_some_and(|ext| ext == "csv"))
+        .or(paths.first())
+        .cloned();
+    if let Some(beside) = beside {
+        let diff = export_diff::record(
+            app,
+            state,
+            "weekly",
+            ExportSnapshot::of(&analytics, now.timestamp()),
+            &beside,
+            *state.export_diff.lock().unwrap(),
+        )?;
+        paths.extend(diff);
+    }
+    Ok(paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect())
+}
+
+/// Run the export and report the outcome through a native notification and a
+/// `scheduled-export-finished` event. Scheduled runs record the date whether
+/// or not they succeed so a broken folder doesn't retry every minute.
+pub fn run(app: &AppHandle, scheduled: bool) -> ScheduledExportResult {
+    let state = app.state::<AppState>();
+    let cfg = state.export_schedule.lock().unwrap().clone();
+    let outcome = write_exports(app, &state, &cfg);
+    if scheduled {
+        state.export_schedule.lock().unwrap().last_run_date =
+            Some(clock::now().date_naive().format("%Y-%m-%d").to_string());
+        let _ = save_config(app, &state);
+    }
+    let result = match outcome {
+        Ok(paths) => {
+            notify(
+                app,
+                "Weekly export saved",
+                &format!(
+                    "{} file(s) written to {}",
+                    paths.len(),
+                    cfg.folder.unwrap_or_default()
+                ),
+            );
+            ScheduledExportResult {
+                ok: true,
+                paths,
+                error: None,
+            }
+        }
+        Err(e) => {
+            notify(app, "Weekly export failed", &e);
+            ScheduledExportResult {
+                ok: false,
+                paths: Vec::new(),
+                error: Some(e),
+            }
+        }
+    };
+    let _ = app.emit("scheduled-export-finished", result.clone());
+    result
+}
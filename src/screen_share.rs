@@ -0,0 +1,87 @@
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+use std::process::Command as ProcessCommand;
+
+/// Keeps `reg`/`tasklist` from flashing a console window on every poll.
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// Helper processes that only run while a screen is being shared or recorded.
+#[cfg(target_os = "windows")]
+const CAPTURE_PROCESSES: &[&str] = &[
+    "cpthost.exe",            // Zoom screen share
+    "obs64.exe",              // OBS Studio
+    "screenclippinghost.exe", // Snipping Tool recording
+];
+
+#[cfg(target_os = "macos")]
+const CAPTURE_PROCESSES: &[&str] = &["screencaptureui", "CptHost", "obs"];
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const CAPTURE_PROCESSES: &[&str] = &["obs", "simplescreenrecorder", "kazam", "peek"];
+
+/// Best-effort check for an active screen share or recording.
+#[cfg(target_os = "windows")]
+pub fn detect() -> bool {
+    graphics_capture_in_use() || capture_process_running()
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn detect() -> bool {
+    capture_process_running()
+}
+
+/// Windows Graphics Capture sessions are tracked in the capability consent
+/// store; an app that is capturing right now has `LastUsedTimeStop` set to 0.
+#[cfg(target_os = "windows")]
+fn graphics_capture_in_use() -> bool {
+    [
+        "graphicsCaptureProgrammatic",
+        "graphicsCaptureWithoutBorder",
+    ]
+    .iter()
+    .any(|capability| {
+        let key = format!(
+            "HKCU\\Software\\Microsoft\\Windows\\CurrentVersion\\CapabilityAccessManager\\ConsentStore\\{}",
+            capability
+        );
+        ProcessCommand::new("reg")
+            .args(["query", &key, "/s", "/v", "LastUsedTimeStop"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map(|out| {
+                String::from_utf8_lossy(&out.stdout).lines().any(|line| {
+                    let mut parts = line.split_whitespace();
+                    parts.next() == Some("LastUsedTimeStop") && parts.nth(1) == Some("0x0")
+                })
+            })
+            .unwrap_or(false)
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn capture_process_running() -> bool {
+    let Ok(out) = ProcessCommand::new("tasklist")
+        .args(["/FO", "CSV", "/NH"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+    else {
+        return false;
+    };
+    let listing = String::from_utf8_lossy(&out.stdout).to_lowercase();
+    listing.lines().any(|line| {
+        let name = line.split(',').next().unwrap_or("").trim_matches('"');
+        CAPTURE_PROCESSES.contains(&name)
+    })
+}
+
+#[cfg(not(target_os = "windows"))]
+fn capture_process_running() -> bool {
+    CAPTURE_PROCESSES.iter().any(|name| {
+        ProcessCommand::new("pgrep")
+            .args(["-x", name])
+            .output()
+            .map(|out| out.status.success())
+            .unwrap_or(false)
+    })
+}
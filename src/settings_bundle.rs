@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::config_history;
+use crate::tip_packs::{self, TipPack, BUILTIN_PACK_ID};
+use crate::tray_icons;
+use crate::{
+    apply_config, config_from_state, refresh_tray_menu, save_config, AppConfigFile, AppState,
+};
+
+/// Bump when a field changes meaning; newer bundles are refused.
+pub const SCHEMA_VERSION: u32 = 1;
+const MAX_BUNDLE_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Settings and installed tip packs, without any analytics. Fields tied to
+/// this machine (window placement, export folder, rollover and version
+/// bookkeeping) are left out on export and kept as they are on import. So
+/// are hooks: a settings file must never be a way to get programs run.
+#[derive(Serialize, Deserialize)]
+pub struct SettingsBundle {
+    pub schema_version: u32,
+    pub app_version: String,
+    pub exported_ts: i64,
+    pub config: AppConfigFile,
+    /// Every installed pack except the built-in one.
+    #[serde(default)]
+    pub tip_packs: Vec<TipPack>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct SettingsImportResult {
+    pub tip_packs: u32,
+    /// Custom tray icons that don't exist on this machine, reset to the
+    /// normal icon.
+    pub skipped_tray_icons: Vec<String>,
+}
+
+/// Copy the machine-specific fields of `local` over `cfg`.
+fn keep_local(cfg: &mut AppConfigFile, local: AppConfigFile) {
+    cfg.window_prefs = local.window_prefs;
+    cfg.export_schedule.folder = local.export_schedule.folder;
+    cfg.export_schedule.last_run_date = local.export_schedule.last_run_date;
+    cfg.last_rollover_date = local.last_rollover_date;
+    cfg.last_seen_version = local.last_seen_version;
+    cfg.read_only = local.read_only;
+    cfg.hooks = local.hooks;
+}
+
+pub fn bundle(app: &AppHandle, exported_ts: i64) -> SettingsBundle {
+    let mut config = config_from_state(&app.state::<AppState>());
+    config.window_prefs.clear();
+    config.export_schedule.folder = None;
+    config.export_schedule.last_run_date = None;
+    config.last_rollover_date = None;
+    config.last_seen_version = None;
+    config.read_only = false;
+    config.hooks.clear();
+    SettingsBundle {
+        schema_version: SCHEMA_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_ts,
+        config,
+        tip_packs: tip_packs::load_packs(app)
+            .into_iter()
+            .filter(|pack| pack.id != BUILTIN_PACK_ID)
+            .collect(),
+    }
+}
+
+/// Apply a bundle written by `export_settings` on this or another machine.
+/// Tip packs are installed (replacing packs with the same id) before the
+/// settings that enable them.
+pub fn import_file(app: &AppHandle, path: &str) -> Result<SettingsImportResult, String> {
+    let size = fs::metadata(path)
+        .map_err(|e| format!("read failed: {}", e))?
+        .len();
+    if size > MAX_BUNDLE_BYTES {
+        return Err("settings file is too large".to_string());
+    }
+    let contents = fs::read_to_string(path).map_err(|e| format!("read failed: {}", e))?;
+    let mut bundle = serde_json::from_str::<SettingsBundle>(&contents)
+        .map_err(|e| format!("invalid settings file: {}", e))?;
+    if bundle.schema_version > SCHEMA_VERSION {
+        return Err(format!(
+            "settings file is from a newer version of Upstand ({})",
+            bundle.app_version
+        ));
+    }
+
+    let mut installed = 0;
+    for pack in bundle.tip_packs {
+        tip_packs::install_pack(app, pack)?;
+        installed += 1;
+    }
+
+    let state = app.state::<AppState>();
+    keep_local(&mut bundle.config, config_from_state(&state));
+    let skipped_tray_icons = tray_icons::drop_unloadable(&mut bundle.config.tray_icons);
+    apply_config(&state, bundle.config);
+    config_history::set_source(&state, "import");
+    save_config(app, &state)?;
+    announce(app);
+    Ok(SettingsImportResult {
+        tip_packs: installed,
+        skipped_tray_icons,
+    })
+}
+
+/// The whole config was replaced: refresh what is derived from it and tell
+/// the windows.
+pub fn announce(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    tip_packs::rebuild_pool(app);
+    refresh_tray_menu(app);
+    let _ = app.emit("language-changed", state.language.lock().unwrap().clone());
+    let _ = app.emit(
+        "reminder-language-changed",
+        state.reminder_language.lock().unwrap().clone(),
+    );
+    let _ = app.emit("theme-changed", state.theme.lock().unwrap().clone());
+}
@@ -0,0 +1,69 @@
+use chrono::{Local, NaiveDate, TimeZone};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::{analytics_date, clock, network_profiles, AppState};
+
+/// Upper bound for `set_skip_budget`.
+pub const MAX_FREE_SKIPS: u32 = 20;
+
+/// A skip is a reminder dismissed while still sitting. Once the day's free
+/// skips are used up the reminder can't be dismissed, only answered by
+/// standing up.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct SkipBudget {
+    /// `None` when skips aren't limited.
+    pub free_per_day: Option<u32>,
+    pub used_today: u32,
+    pub remaining_today: Option<u32>,
+}
+
+fn day_of(ts: i64, day_start_hour: u32) -> Option<NaiveDate> {
+    Local
+        .timestamp_opt(ts, 0)
+        .single()
+        .map(|dt| analytics_date(dt, day_start_hour))
+}
+
+pub fn used_today(state: &AppState) -> u32 {
+    let day_start_hour = *state.day_start_hour.lock().unwrap();
+    let today = analytics_date(clock::now(), day_start_hour);
+    state
+        .skip_events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|ts| day_of(**ts, day_start_hour) == Some(today))
+        .count() as u32
+}
+
+pub fn budget(state: &AppState) -> SkipBudget {
+    let free_per_day = network_profiles::active(state)
+        .and_then(|profile| profile.free_skips_per_day)
+        .or(*state.free_skips_per_day.lock().unwrap());
+    let used_today = used_today(state);
+    SkipBudget {
+        free_per_day,
+        used_today,
+        remaining_today: free_per_day.map(|free| free.saturating_sub(used_today)),
+    }
+}
+
+/// No free skips left today; dismissing goes the strict way.
+pub fn exhausted(state: &AppState) -> bool {
+    budget(state).remaining_today == Some(0)
+}
+
+pub fn record(state: &AppState, ts: i64) {
+    state.skip_events.lock().unwrap().push(ts);
+}
+
+/// Days among `skips` on which all `free_per_day` skips were used.
+pub fn days_used_up(skips: &[i64], free_per_day: u32, day_start_hour: u32) -> u32 {
+    let mut per_day: HashMap<NaiveDate, u32> = HashMap::new();
+    for day in skips.iter().filter_map(|ts| day_of(*ts, day_start_hour)) {
+        *per_day.entry(day).or_default() += 1;
+    }
+    per_day.values().filter(|n| **n >= free_per_day).count() as u32
+}
@@ -0,0 +1,83 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{now_ts, record_standup, AppState};
+
+/// Wall-clock time the loop lost beyond this counts as a suspend rather
+/// than a late tick.
+pub const MIN_GAP_SECS: u64 = 60;
+
+/// What a suspend does to the sitting countdown, which would otherwise
+/// either stand still through it or jump by its whole length.
+#[derive(Clone, Copy, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SleepPolicy {
+    /// Start the countdown over.
+    #[default]
+    Reset,
+    /// Take the length of the sleep off the sitting time, as a break of
+    /// that length would.
+    SubtractGap,
+    /// Log a standup at wakeup and start the countdown over.
+    CountAsStanding,
+    /// Carry on where the countdown stopped.
+    Pause,
+}
+
+/// Payload of `system-resumed`.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct SystemResumed {
+    pub gap_secs: u64,
+    pub policy: SleepPolicy,
+    /// The countdown after the policy was applied.
+    pub elapsed_secs: u64,
+}
+
+/// How long a sleep planned for `planned_secs` was suspended, if long enough
+/// to count. Where the monotonic clock stops during a suspend (Linux, macOS)
+/// that is the wall-clock time it didn't see; where it keeps counting
+/// (Windows) it is the time slept past the plan. A wall clock set forward
+/// reads the same.
+pub fn gap(wall_secs: i64, monotonic_secs: u64, planned_secs: u64) -> Option<u64> {
+    let stopped = (wall_secs.max(0) as u64).saturating_sub(monotonic_secs);
+    let overslept = monotonic_secs.saturating_sub(planned_secs);
+    let gap = stopped.max(overslept);
+    (gap >= MIN_GAP_SECS).then_some(gap)
+}
+
+/// Apply the policy to the countdown after a `gap_secs` sleep. An open
+/// reminder is left alone; its lag already runs on wall time. Returns
+/// whether the policy wants a standup logged.
+pub fn reconcile(state: &AppState, gap_secs: u64) -> bool {
+    if *state.reminder_visible.lock().unwrap() {
+        return false;
+    }
+    let policy = *state.sleep_policy.lock().unwrap();
+    let mut elapsed = state.elapsed.lock().unwrap();
+    match policy {
+        SleepPolicy::Reset => *elapsed = 0,
+        SleepPolicy::SubtractGap => *elapsed = elapsed.saturating_sub(gap_secs),
+        SleepPolicy::CountAsStanding => {
+            *elapsed = 0;
+            return true;
+        }
+        SleepPolicy::Pause => {}
+    }
+    false
+}
+
+pub fn resumed(app: &AppHandle, gap_secs: u64) {
+    let state = app.state::<AppState>();
+    if reconcile(&state, gap_secs) {
+        record_standup(app, &state, now_ts());
+    }
+    let _ = app.emit(
+        "system-resumed",
+        SystemResumed {
+            gap_secs,
+            policy: *state.sleep_policy.lock().unwrap(),
+            elapsed_secs: *state.elapsed.lock().unwrap(),
+        },
+    );
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path, time::Duration};
+use tauri::{AppHandle, Manager};
+
+use crate::timers::{self, PauseReason};
+use crate::{build_analytics_for_period, clock, leaderboard, now_ts, read_only, AppState};
+
+pub const MIN_EVERY_SECS: u64 = 5;
+pub const MAX_EVERY_SECS: u64 = 3600;
+
+/// A small JSON file rewritten on a timer for desktop widget tools
+/// (Rainmeter, Übersicht) that can read files but not talk to the app.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct StatusFileConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default = "default_every_secs")]
+    pub every_secs: u64,
+}
+
+impl Default for StatusFileConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: None,
+            every_secs: default_every_secs(),
+        }
+    }
+}
+
+fn default_every_secs() -> u64 {
+    30
+}
+
+pub fn sanitize(mut cfg: StatusFileConfig) -> StatusFileConfig {
+    cfg.every_secs = cfg.every_secs.clamp(MIN_EVERY_SECS, MAX_EVERY_SECS);
+    cfg.path = cfg
+        .path
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+    cfg
+}
+
+/// What the file holds. Fields are only ever added, so widget skins keep working.
+#[derive(Serialize)]
+pub struct WidgetStatus {
+    pub updated_ts: i64,
+    /// `None` while the countdown is held.
+    pub next_reminder_ts: Option<i64>,
+    pub next_reminder_secs: Option<u64>,
+    pub paused: bool,
+    pub pause_reason: Option<PauseReason>,
+    pub standups_today: u32,
+    /// Share of today's reminders answered by standing, 0-100.
+    pub score: u32,
+}
+
+pub fn snapshot(state: &AppState) -> WidgetStatus {
+    let now = now_ts();
+    let timer = timers::timer_status(state);
+    let analytics = build_analytics_for_period(state, "daily");
+    WidgetStatus {
+        updated_ts: now,
+        next_reminder_ts: timer.next_fire_ts,
+        next_reminder_secs: timer.next_fire_ts.map(|ts| (ts - now).max(0) as u64),
+        paused: timer.paused,
+        pause_reason: timer.pause_reason,
+        standups_today: analytics.standup_sessions,
+        score: leaderboard::daily_score(analytics.standup_sessions, analytics.sedentary_sessions),
+    }
+}
+
+/// Write through a temporary file and rename it into place, so a widget
+/// polling mid-write never reads half a file.
+fn write(path: &Path, status: &WidgetStatus) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(status).map_err(|e| e.to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("create folder failed: {}", e))?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, json).map_err(|e| format!("write failed: {}", e))?;
+    fs::rename(&tmp, path).map_err(|e| format!("rename failed: {}", e))
+}
+
+/// Rewrite the file every `every_secs` while enabled. A failing path is
+/// reported once until it works again or the setting changes.
+pub async fn run(app: AppHandle) {
+    let mut failed: Option<String> = None;
+    loop {
+        let cfg = app.state::<AppState>().status_file.lock().unwrap().clone();
+        let path = cfg.path.clone().filter(|_| cfg.enabled);
+        if let Some(path) = path.filter(|_| !read_only::enabled(&app.state::<AppState>())) {
+            let status = snapshot(&app.state::<AppState>());
+            match write(Path::new(&path), &status) {
+                Ok(()) => failed = None,
+                Err(e) if failed.as_deref() != Some(path.as_str()) => {
+                    eprintln!("status file {}: {}", path, e);
+                    failed = Some(path);
+                }
+                Err(_) => {}
+            }
+        }
+        clock::sleep(Duration::from_secs(cfg.every_secs)).await;
+    }
+}
@@ -0,0 +1,49 @@
+use std::fs::{self, OpenOptions, TryLockError};
+use tauri::{AppHandle, Manager};
+
+use crate::{data_dir, AppState};
+
+/// Take an exclusive advisory lock on the data directory for the life of the
+/// process. The single-instance plugin normally makes this moot, but a second
+/// copy started some other way (a packaging wrapper, a stray autostart entry)
+/// must not interleave writes with ours. The OS drops the lock on exit.
+pub fn acquire(handle: &AppHandle) -> Result<(), String> {
+    let dir = data_dir::dir(handle).ok_or("data dir unavailable")?;
+    fs::create_dir_all(&dir).map_err(|e| format!("create dir failed: {}", e))?;
+    let path = dir.join("upstand.lock");
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .map_err(|e| format!("open lock failed: {}", e))?;
+    let state = handle.state::<AppState>();
+    match file.try_lock() {
+        Ok(()) => {
+            *state.store_lock.lock().unwrap() = Some(file);
+            Ok(())
+        }
+        Err(TryLockError::WouldBlock) => {
+            let message = format!(
+                "Another Upstand process is using {}; this one won't save settings or analytics.",
+                dir.display()
+            );
+            *state.store_lock_error.lock().unwrap() = Some(message.clone());
+            Err(message)
+        }
+        // Some network filesystems don't do locking; carry on unguarded
+        // rather than refuse to save.
+        Err(TryLockError::Error(e)) => {
+            eprintln!("lock failed, continuing without it: {}", e);
+            Ok(())
+        }
+    }
+}
+
+/// Err with the user-facing message when another process holds the lock.
+pub fn check(state: &AppState) -> Result<(), String> {
+    match state.store_lock_error.lock().unwrap().as_ref() {
+        Some(message) => Err(message.clone()),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,116 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{prune_changes, AppState, ReminderRecord};
+
+pub const MAX_TAG_CHARS: usize = 40;
+/// How many recent tags the tray submenu offers.
+pub const MAX_RECENT_TAGS: usize = 8;
+
+/// The session tag changed at `ts`; `None` clears it. Events take the tag in
+/// effect when they happened.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TagChange {
+    pub ts: i64,
+    pub tag: Option<String>,
+}
+
+/// Per-tag totals for one analytics period. `tag` is `None` for untagged time.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TagBreakdown {
+    pub tag: Option<String>,
+    pub standups: u32,
+    pub sedentary: u32,
+    pub sitting_secs: u64,
+}
+
+/// Trimmed and capped; blank means no tag.
+pub fn normalize(tag: Option<&str>) -> Option<String> {
+    let tag = tag?.trim();
+    (!tag.is_empty()).then(|| tag.chars().take(MAX_TAG_CHARS).collect())
+}
+
+pub fn current(state: &AppState) -> Option<String> {
+    state
+        .tag_log
+        .lock()
+        .unwrap()
+        .last()
+        .and_then(|change| change.tag.clone())
+}
+
+/// Switch the session tag. Returns whether it changed.
+pub fn set_current(state: &AppState, ts: i64, tag: Option<String>) -> bool {
+    let mut log = state.tag_log.lock().unwrap();
+    let unchanged = match log.last() {
+        Some(last) => last.tag == tag,
+        None => tag.is_none(),
+    };
+    if unchanged {
+        return false;
+    }
+    log.push(TagChange { ts, tag });
+    true
+}
+
+pub fn tag_at(log: &[TagChange], ts: i64) -> Option<&str> {
+    let after = log.partition_point(|change| change.ts <= ts);
+    after.checked_sub(1).and_then(|i| log[i].tag.as_deref())
+}
+
+/// Most recently used first, without repeats.
+pub fn recent(state: &AppState) -> Vec<String> {
+    let log = state.tag_log.lock().unwrap();
+    let mut tags: Vec<String> = Vec::new();
+    for tag in log.iter().rev().filter_map(|change| change.tag.as_ref()) {
+        if !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+        if tags.len() == MAX_RECENT_TAGS {
+            break;
+        }
+    }
+    tags
+}
+
+pub fn prune(log: &mut Vec<TagChange>, cutoff: i64) {
+    prune_changes(log, cutoff, |change| change.ts);
+}
+
+fn group<'a>(groups: &'a mut Vec<TagBreakdown>, tag: Option<&str>) -> &'a mut TagBreakdown {
+    match groups.iter().position(|g| g.tag.as_deref() == tag) {
+        Some(i) => &mut groups[i],
+        None => {
+            groups.push(TagBreakdown {
+                tag: tag.map(str::to_string),
+                standups: 0,
+                sedentary: 0,
+                sitting_secs: 0,
+            });
+            groups.last_mut().unwrap()
+        }
+    }
+}
+
+/// Group already-filtered events by tag, tagged groups first by name. Empty
+/// when tagging was never used, so untouched setups see no extra rows.
+pub fn breakdown(
+    log: &[TagChange],
+    reminders: &[ReminderRecord],
+    standups: &[i64],
+) -> Vec<TagBreakdown> {
+    if log.is_empty() {
+        return Vec::new();
+    }
+    let mut groups: Vec<TagBreakdown> = Vec::new();
+    for ts in standups {
+        group(&mut groups, tag_at(log, *ts)).standups += 1;
+    }
+    for record in reminders {
+        let entry = group(&mut groups, tag_at(log, record.ts));
+        entry.sedentary += 1;
+        entry.sitting_secs += record.duration_secs;
+    }
+    groups.sort_by(|a, b| (a.tag.is_none(), &a.tag).cmp(&(b.tag.is_none(), &b.tag)));
+    groups
+}
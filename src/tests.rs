@@ -0,0 +1,1472 @@
+//! The core loop end to end: a reminder is answered, the event lands in
+//! analytics, and exports and dumps carry it out again. Commands take a Wry
+//! `AppHandle`, which `tauri::test`'s mock runtime can't stand in for, so the
+//! harness drives the logic behind them through `AppState` directly.
+
+use super::*;
+
+/// What the timer sets up when a reminder fires.
+fn show_reminder(state: &AppState, start_ts: i64) {
+    *state.reminder_visible.lock().unwrap() = true;
+    *state.active_reminder_start_ts.lock().unwrap() = Some(start_ts);
+    *state.active_reminder_shown_at.lock().unwrap() = Some(Instant::now());
+    *state.active_reminder_interval_secs.lock().unwrap() = DEFAULT_INTERVAL_MINUTES * 60;
+    *state.active_reminder_logged_sedentary.lock().unwrap() = false;
+}
+
+fn seed(state: &AppState, standups: &[i64], reminders: &[(i64, u64)]) {
+    state.standup_events.lock().unwrap().extend(standups);
+    state.reminder_events.lock().unwrap().extend(
+        reminders
+            .iter()
+            .map(|&(ts, duration_secs)| ReminderRecord { ts, duration_secs }),
+    );
+}
+
+#[test]
+fn standing_up_logs_a_standup_and_clears_the_reminder() {
+    let state = AppState::new(false);
+    let now = now_ts();
+    show_reminder(&state, now);
+    *state.elapsed.lock().unwrap() = 120;
+
+    let event = settle_reminder(&state, true, now).expect("a standup is logged");
+
+    assert_eq!(event.kind, "standup");
+    assert_eq!(*state.standup_events.lock().unwrap(), vec![now]);
+    assert!(state.reminder_events.lock().unwrap().is_empty());
+    assert!(!*state.reminder_visible.lock().unwrap());
+    assert_eq!(*state.active_reminder_start_ts.lock().unwrap(), None);
+    assert_eq!(*state.elapsed.lock().unwrap(), 0);
+}
+
+#[test]
+fn a_late_answer_logs_the_sitting_instead() {
+    let state = AppState::new(false);
+    let now = now_ts();
+    let start = now - DEFAULT_SEDENTARY_THRESHOLD_SECS as i64 - 1;
+    show_reminder(&state, start);
+
+    let event = settle_reminder(&state, true, now).expect("the sitting is logged");
+
+    assert_eq!(event.kind, "sedentary");
+    assert!(state.standup_events.lock().unwrap().is_empty());
+    let reminders = state.reminder_events.lock().unwrap();
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].ts, start);
+    assert_eq!(reminders[0].duration_secs, DEFAULT_INTERVAL_MINUTES * 60);
+}
+
+#[test]
+fn sitting_already_logged_by_the_timer_is_not_logged_twice() {
+    let state = AppState::new(false);
+    let now = now_ts();
+    show_reminder(&state, now - 600);
+    *state.active_reminder_logged_sedentary.lock().unwrap() = true;
+
+    assert!(settle_reminder(&state, true, now).is_none());
+    assert!(state.standup_events.lock().unwrap().is_empty());
+    assert!(state.reminder_events.lock().unwrap().is_empty());
+    assert!(!*state.reminder_visible.lock().unwrap());
+}
+
+#[test]
+fn dismissing_without_a_reminder_logs_nothing() {
+    let state = AppState::new(false);
+    assert!(settle_reminder(&state, false, now_ts()).is_none());
+}
+
+#[test]
+fn daily_analytics_count_todays_events() {
+    let state = AppState::new(false);
+    let now = now_ts();
+    let old = now - RETENTION_SECS - 60;
+    seed(&state, &[now, now, old], &[(now, 1800)]);
+
+    let analytics = build_analytics(&state);
+
+    assert_eq!(analytics.standup_sessions, 2);
+    assert_eq!(analytics.sedentary_sessions, 1);
+    assert_eq!(analytics.total_sitting_secs, 1800);
+    assert_eq!(analytics.record_count, 3);
+    // Events past retention are pruned while aggregating.
+    assert_eq!(state.standup_events.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn csv_export_has_every_hour_and_the_totals() {
+    let state = AppState::new(false);
+    let now = now_ts();
+    seed(&state, &[now, now], &[(now, 5400)]);
+    let analytics = build_analytics(&state);
+
+    let csv = analytics_csv(&analytics, &Formatter::new("en", true));
+    let rows: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(rows[0], "hour,sedentary_sessions,standup_sessions");
+    assert_eq!(rows.len(), 30);
+    assert!(rows[1].starts_with("00:00,"));
+    assert!(rows[24].starts_with("23:00,"));
+    assert_eq!(rows[25], "totals,1,2");
+    assert_eq!(rows[26], "total_sitting_minutes,90,");
+    assert_eq!(rows[27], "total_sitting,PT1H30M,");
+    assert!(rows.iter().any(|row| row.starts_with("exported_at,")));
+}
+
+#[test]
+fn raw_event_dumps_round_trip_without_duplicates() {
+    let source = AppState::new(false);
+    let now = now_ts();
+    seed(&source, &[now - 60, now - 60, now], &[(now - 3600, 1200)]);
+    let dump = raw_events::dump(&source);
+    assert_eq!(dump.events.len(), 4);
+
+    let target = AppState::new(false);
+    let first = raw_events::merge(&target, dump.events.clone());
+    let second = raw_events::merge(&target, dump.events);
+
+    assert_eq!(first.imported, 4);
+    assert_eq!(second.imported, 0);
+    assert_eq!(second.skipped_existing, 4);
+    assert_eq!(target.standup_events.lock().unwrap().len(), 3);
+    assert_eq!(target.reminder_events.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn a_minimal_old_config_migrates_to_current_defaults() {
+    let state = AppState::new(false);
+    let cfg: AppConfigFile =
+        serde_json::from_str(r#"{"interval_minutes": 30, "language": "zh-CN"}"#)
+            .expect("older config files still parse");
+
+    apply_config(&state, cfg);
+
+    assert_eq!(*state.interval.lock().unwrap(), 30 * 60);
+    assert_eq!(*state.language.lock().unwrap(), "zh-CN");
+    assert_eq!(*state.reminder_language.lock().unwrap(), "en");
+    assert_eq!(*state.day_start_hour.lock().unwrap(), 0);
+    assert_eq!(*state.week_start.lock().unwrap(), default_week_start());
+    assert_eq!(
+        *state.sedentary_threshold_secs.lock().unwrap(),
+        DEFAULT_SEDENTARY_THRESHOLD_SECS
+    );
+}
+
+#[test]
+fn out_of_range_config_values_are_normalized() {
+    let state = AppState::new(false);
+    let cfg: AppConfigFile = serde_json::from_str(
+        r#"{"interval_minutes": 7, "language": "fr", "theme": "neon", "day_start_hour": 40}"#,
+    )
+    .unwrap();
+
+    apply_config(&state, cfg);
+
+    assert_eq!(
+        *state.interval.lock().unwrap(),
+        DEFAULT_INTERVAL_MINUTES * 60
+    );
+    assert_eq!(*state.language.lock().unwrap(), "en");
+    assert_eq!(*state.theme.lock().unwrap(), "night");
+    assert_eq!(*state.day_start_hour.lock().unwrap(), MAX_DAY_START_HOUR);
+}
+
+#[test]
+fn time_scale_flag_accepts_both_spellings() {
+    let args = |list: &[&str]| list.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        clock::scale_from_args(&args(&["upstand", "--time-scale", "60"])),
+        Some(60.0)
+    );
+    assert_eq!(
+        clock::scale_from_args(&args(&["upstand", "--time-scale=120"])),
+        Some(120.0)
+    );
+    assert_eq!(
+        clock::scale_from_args(&args(&["upstand", "--time-scale=1"])),
+        None
+    );
+    assert_eq!(
+        clock::scale_from_args(&args(&["upstand", "--time-scale"])),
+        None
+    );
+    assert_eq!(
+        clock::scale_from_args(&args(&["upstand", "--time-scale=1e9"])),
+        Some(1000.0)
+    );
+}
+
+#[test]
+fn simulated_clock_drives_the_timer_across_the_day_boundary() {
+    let today = analytics_date(Local::now(), 0);
+    let tomorrow = today.succ_opt().unwrap();
+    let midnight = day_start_ts(tomorrow, 0);
+    clock::install_at(100.0, Local.timestamp_opt(midnight - 30, 0).unwrap());
+    assert_eq!(analytics_date(clock::now(), 0), today);
+
+    // One planned minute of the timer loop, in well under a real second.
+    let started = Instant::now();
+    let started_ts = now_ts();
+    tauri::async_runtime::block_on(clock::sleep(Duration::from_secs(60)));
+    assert!(started.elapsed() < Duration::from_secs(5));
+    let slept = started.elapsed().mul_f64(clock::scale());
+    assert!(slept >= Duration::from_secs(60));
+    // Wall and monotonic time agree, so it isn't taken for a suspend.
+    assert_eq!(
+        sleep_drift::gap(now_ts() - started_ts, slept.as_secs(), 60),
+        None
+    );
+    assert_eq!(analytics_date(clock::now(), 0), tomorrow);
+    assert!(clock::simulated());
+}
+
+#[test]
+fn power_log_answers_the_source_at_any_time() {
+    let mut log = Vec::new();
+    assert!(power::log_source(&mut log, 100, false));
+    assert!(!power::log_source(&mut log, 150, false));
+    assert!(power::log_source(&mut log, 200, true));
+    assert!(power::log_source(&mut log, 300, false));
+
+    assert_eq!(power::on_battery_at(&log, 99), None);
+    assert_eq!(power::on_battery_at(&log, 100), Some(false));
+    assert_eq!(power::on_battery_at(&log, 250), Some(true));
+    assert_eq!(power::on_battery_at(&log, 300), Some(false));
+
+    power::prune(&mut log, 250);
+    assert_eq!(log.len(), 2);
+    assert_eq!(power::on_battery_at(&log, 250), Some(true));
+}
+
+#[test]
+fn analytics_break_events_down_by_session_tag() {
+    let state = AppState::new(false);
+    let now = now_ts();
+    assert!(tags::set_current(
+        &state,
+        now - 30,
+        tags::normalize(Some("  Acme  "))
+    ));
+    assert!(!tags::set_current(
+        &state,
+        now - 25,
+        Some("Acme".to_string())
+    ));
+    seed(&state, &[now - 20], &[(now - 20, 600)]);
+    tags::set_current(&state, now - 10, None);
+    seed(&state, &[now], &[]);
+
+    let analytics = build_analytics_between(&state, 0, i64::MAX, &|_| true);
+
+    assert_eq!(analytics.tags.len(), 2);
+    assert_eq!(analytics.tags[0].tag.as_deref(), Some("Acme"));
+    assert_eq!(analytics.tags[0].standups, 1);
+    assert_eq!(analytics.tags[0].sitting_secs, 600);
+    assert_eq!(analytics.tags[1].tag, None);
+    assert_eq!(analytics.tags[1].standups, 1);
+    assert_eq!(tags::recent(&state), vec!["Acme".to_string()]);
+}
+
+#[test]
+fn reminder_history_records_why_it_fired_and_the_answer() {
+    let state = AppState::new(false);
+    let now = now_ts();
+    show_reminder(&state, now);
+    *state.focus_break_due.lock().unwrap() = Some(900);
+    let (trigger, sat_secs) = reminder_reason::take_trigger(&state, 1800);
+    reminder_reason::fired(
+        &state,
+        0,
+        ReminderReason {
+            trigger,
+            fired_ts: now,
+            interval_secs: 1800,
+            weekend_interval: false,
+            sat_secs,
+            held_for_screen_sharing: false,
+        },
+        ShownTip {
+            text: "Stretch.".to_string(),
+            source: TipSource::Schedule,
+        },
+    );
+    assert!(active_reminder_payload(&state).reason.is_some());
+
+    settle_reminder(&state, true, now);
+
+    let history = reminder_reason::history(&state);
+    assert_eq!(history.len(), 1);
+    assert!(history[0].reason.trigger == reminder_reason::Trigger::FocusEnded);
+    assert_eq!(history[0].reason.sat_secs, 900);
+    assert_eq!(history[0].outcome.as_deref(), Some("stood_up"));
+    assert!(active_reminder_payload(&state).reason.is_none());
+    assert!(
+        reminder_reason::take_trigger(&state, 1800)
+            == (reminder_reason::Trigger::IntervalElapsed, 1800)
+    );
+}
+
+#[test]
+fn bundled_changelog_parses_and_filters_by_version() {
+    let entries = changelog::entries();
+    assert!(!entries.is_empty());
+    let changelog_md = include_str!("../CHANGELOG.md");
+    for entry in &entries {
+        assert!(changelog::parse_version(&entry.version).is_some());
+        assert!(changelog_md.contains(&format!("## [{}]", entry.version)));
+    }
+
+    assert_eq!(changelog::parse_version("v1.2"), Some((1, 2, 0)));
+    assert_eq!(changelog::parse_version("1.2.3-beta.1"), Some((1, 2, 3)));
+    assert_eq!(changelog::parse_version("1.x"), None);
+    assert_eq!(changelog::since("0.9.0").unwrap().len(), entries.len());
+    assert!(changelog::since(env!("CARGO_PKG_VERSION"))
+        .unwrap()
+        .is_empty());
+    assert!(changelog::since("latest").is_err());
+}
+
+#[test]
+fn third_party_notices_cover_the_dependencies() {
+    let notices = about::third_party_notices();
+    let serde = notices
+        .iter()
+        .find(|notice| notice.name == "serde")
+        .expect("serde is listed");
+    assert!(!serde.version.is_empty());
+    assert!(notices.iter().all(|notice| notice.name != "upstand"));
+    assert!(about::THIRD_PARTY_LICENSES.contains("serde "));
+    assert!(about::THIRD_PARTY_LICENSES.contains("Permission is hereby granted"));
+}
+
+#[test]
+fn data_dir_copy_includes_backups_and_tip_packs() {
+    let root = std::env::temp_dir().join(format!("upstand-move-{}", std::process::id()));
+    let (from, to) = (root.join("from"), root.join("to"));
+    fs::create_dir_all(from.join("tip_packs")).unwrap();
+    fs::write(from.join("config.json"), "{}").unwrap();
+    fs::write(from.join("analytics.json.corrupt-20260101-000000"), "x").unwrap();
+    fs::write(from.join("tip_packs/desk.json"), "{}").unwrap();
+    fs::write(from.join("cache.bin"), "not ours").unwrap();
+
+    let entries = data_dir::store_entries(&from);
+    data_dir::copy_verified(&from, &to, &entries).unwrap();
+
+    assert_eq!(entries.len(), 3);
+    assert!(to.join("tip_packs/desk.json").is_file());
+    assert!(to.join("analytics.json.corrupt-20260101-000000").is_file());
+    assert!(!to.join("cache.bin").exists());
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn read_only_mode_blocks_every_listed_write_command() {
+    let described = api::describe().commands;
+    let commands: Vec<&str> = described
+        .as_array()
+        .expect("commands are a list")
+        .iter()
+        .filter_map(|command| command["name"].as_str())
+        .collect();
+    for write in read_only::WRITE_COMMANDS {
+        assert!(commands.contains(write), "{} is not a command", write);
+    }
+    assert!(!read_only::is_write_command("get_analytics"));
+
+    let state = AppState::new(false);
+    assert!(read_only::check(&state).is_ok());
+    let cfg: AppConfigFile =
+        serde_json::from_str(r#"{"interval_minutes": 30, "read_only": true}"#).unwrap();
+    apply_config(&state, cfg);
+    assert_eq!(read_only::check(&state), Err(read_only::ERROR.to_string()));
+}
+
+#[test]
+fn system_language_is_kept_as_a_preference_and_resolved() {
+    let state = AppState::new(false);
+    let cfg: AppConfigFile = serde_json::from_str(r#"{"interval_minutes": 45}"#).unwrap();
+    assert_eq!(cfg.language, "system");
+
+    apply_config(&state, cfg);
+
+    assert_eq!(*state.language.lock().unwrap(), detect_system_language());
+    assert_eq!(config_from_state(&state).language, "system");
+
+    let cfg: AppConfigFile =
+        serde_json::from_str(r#"{"interval_minutes": 45, "language": "zh-CN"}"#).unwrap();
+    apply_config(&state, cfg);
+    assert_eq!(language_preference(&state), "zh-CN");
+}
+
+#[test]
+fn unknown_languages_fall_back_to_english() {
+    assert_eq!(languages::normalize("zh-CN"), "zh-CN");
+    assert_eq!(languages::normalize("fr"), languages::FALLBACK);
+    assert!(!languages::info("ar").rtl);
+    assert!(languages::available()
+        .iter()
+        .all(|language| languages::normalize(&language.code) == language.code));
+}
+
+#[test]
+fn timeline_splits_the_day_into_segments() {
+    use timeline::{Hold, SegmentKind};
+    let holds = [
+        HoldChange {
+            ts: 1_000,
+            hold: Some(Hold::Locked),
+        },
+        HoldChange {
+            ts: 2_000,
+            hold: None,
+        },
+    ];
+    let standups = [2_100];
+    let kinds: Vec<(SegmentKind, i64, i64)> = timeline::segments(0, 3_000, &holds, &standups)
+        .iter()
+        .map(|segment| (segment.kind, segment.start_ts, segment.end_ts))
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            (SegmentKind::Sitting, 0, 1_000),
+            (SegmentKind::Locked, 1_000, 2_000),
+            (SegmentKind::Sitting, 2_000, 2_100),
+            (SegmentKind::Standing, 2_100, 2_400),
+            (SegmentKind::Sitting, 2_400, 3_000),
+        ]
+    );
+}
+
+#[test]
+fn weekday_breakdown_counts_late_nights_toward_the_day_before() {
+    let monday = Local
+        .with_ymd_and_hms(2026, 10, 12, 10, 0, 0)
+        .unwrap()
+        .timestamp();
+    let next_monday = monday + 7 * 24 * 60 * 60;
+    let tuesday_2am = monday + 16 * 60 * 60;
+    let reminders = [
+        ReminderRecord {
+            ts: monday,
+            duration_secs: 3_600,
+        },
+        ReminderRecord {
+            ts: tuesday_2am,
+            duration_secs: 1_800,
+        },
+    ];
+    let standups = [monday + 60, next_monday];
+
+    let days = weekdays::breakdown(&reminders, &standups, 4);
+
+    assert_eq!(days.len(), 7);
+    assert_eq!(days[0].weekday, "monday");
+    assert_eq!(
+        (days[0].standups, days[0].sedentary, days[0].days),
+        (2, 2, 2)
+    );
+    assert_eq!(days[0].compliance, Some(0.5));
+    assert_eq!(days[0].avg_sitting_secs, 2_700);
+    assert_eq!(days[1].compliance, None);
+}
+
+#[test]
+fn sitting_budget_is_clamped_and_counts_down() {
+    let state = AppState::new(false);
+    assert_eq!(budget::remaining_secs(&state), None);
+
+    let cfg: AppConfigFile =
+        serde_json::from_str(r#"{"interval_minutes": 50, "sitting_budget_minutes": 100000}"#)
+            .unwrap();
+    apply_config(&state, cfg);
+
+    assert_eq!(
+        config_from_state(&state).sitting_budget_minutes,
+        budget::MAX_BUDGET_MINUTES
+    );
+    let remaining = budget::remaining_secs(&state).unwrap();
+    assert!(remaining <= budget::MAX_BUDGET_MINUTES * 60);
+}
+
+#[test]
+fn marked_days_drop_out_of_weekly_totals_and_survive_a_dump() {
+    let state = AppState::new(false);
+    let now = now_ts();
+    seed(&state, &[now], &[(now, 600)]);
+    let today = clock::now().date_naive();
+    marked_days::mark(&state, today, DayKind::parse("sick"));
+
+    let weekly = build_analytics_for_period(&state, "weekly");
+    assert_eq!(weekly.record_count, 0);
+    assert_eq!(weekly.marked_days.len(), 1);
+    assert_eq!(build_analytics_for_period(&state, "daily").record_count, 2);
+
+    let dump = raw_events::dump(&state);
+    let restored = AppState::new(false);
+    raw_events::merge(&restored, dump.events);
+    assert!(marked_days::is_marked(
+        &restored.marked_days.lock().unwrap(),
+        today
+    ));
+}
+
+#[test]
+fn blocking_apps_match_by_name_and_pause_the_countdown() {
+    let cfg = activity::sanitize_blocking_apps(BlockingApps {
+        processes: vec!["OBS64.exe".into(), " obs64.exe ".into(), "zoom.us".into()],
+        poll_secs: 0,
+    });
+    assert_eq!(cfg.processes, ["OBS64.exe", "zoom.us"]);
+    assert_eq!(cfg.poll_secs, activity::MIN_BLOCKING_POLL_SECS);
+
+    let running = ["explorer.exe".to_string(), "obs64".to_string()];
+    let found = activity::find_blocking(&cfg.processes, &running);
+    assert_eq!(found.as_deref(), Some("OBS64.exe"));
+
+    let state = AppState::new(false);
+    *state.blocking_app.lock().unwrap() = found;
+    assert!(timers::pause_reason(&state) == Some(timers::PauseReason::BlockingApp));
+}
+
+#[test]
+fn export_file_names_follow_the_template() {
+    use file_names::{render, validate, ExportKind};
+    let now = Local.with_ymd_and_hms(2026, 10, 16, 9, 5, 0).unwrap();
+    assert_eq!(
+        render("", ExportKind::Analytics, "weekly", now, "csv"),
+        "standby_weekly_analytics_20261016_090500.csv"
+    );
+    let template = validate(" {app}-{type}/{period}-{date}T{time} ").unwrap();
+    assert_eq!(
+        render(&template, ExportKind::RawEvents, "all", now, "json"),
+        "upstand-raw_events_all-20261016T090500.json"
+    );
+    assert!(validate("{app}-{user}").is_err());
+    assert!(validate("{app").is_err());
+}
+
+#[test]
+fn clipboard_summary_has_a_line_and_an_optional_table() {
+    let state = AppState::new(false);
+    let now = now_ts();
+    seed(&state, &[now - 60, now - 30, now - 10], &[(now - 20, 1800)]);
+    let analytics = build_analytics_for_period(&state, "daily");
+    let fmt = Formatter::new("en", false);
+
+    let line = clipboard::summary(&analytics, "daily", "en", &fmt, false);
+    assert!(line.starts_with("Today: 3 standups"), "{}", line);
+    assert!(line.contains("1 reminder ignored"), "{}", line);
+    assert!(line.ends_with("75% on time"), "{}", line);
+    assert!(!line.contains('\n'));
+
+    let table = clipboard::summary(&analytics, "daily", "en", &fmt, true);
+    assert!(table.starts_with(&line));
+    assert!(table.contains("| Metric | Value |\n|---|---|\n"));
+    assert!(table.ends_with("| On time | 75% |"));
+}
+
+#[test]
+fn markdown_journal_replaces_a_days_section_in_place() {
+    let state = AppState::new(false);
+    let now = now_ts();
+    seed(&state, &[now - 60, now - 30], &[(now - 20, 1500)]);
+    let today = analytics_date(clock::now(), *state.day_start_hour.lock().unwrap());
+    let entry = markdown_journal::entry(&state, today);
+    let heading = format!("## {}", today.format("%Y-%m-%d"));
+    assert!(entry.starts_with(&format!(
+        "{}\n\n- Standups: 2\n- Sitting: 25min\n- Score: 66/100\n",
+        heading
+    )));
+
+    let notes = "# Journal\n";
+    let once = markdown_journal::merge(notes, today, &entry);
+    assert_eq!(once, format!("# Journal\n\n{}", entry));
+    let later = format!("{}\n## 2099-01-01\n\n- Standups: 9\n", once);
+    let again = markdown_journal::merge(&later, today, "REPLACED\n");
+    assert_eq!(
+        again,
+        "# Journal\n\nREPLACED\n\n## 2099-01-01\n\n- Standups: 9\n"
+    );
+}
+
+#[test]
+fn local_api_needs_a_live_token() {
+    let state = AppState::new(false);
+    let new = api_tokens::generate(&state, "  Obsidian  ").unwrap();
+    assert!(new.token.starts_with("ups_"));
+    assert_eq!(new.info.name, "Obsidian");
+    assert!(new.token.ends_with(&new.info.hint));
+
+    let request = |token: &str| {
+        format!(
+            "GET /v1/today HTTP/1.1\r\nHost: 127.0.0.1\r\nauthorization: Bearer {}\r\n\r\n",
+            token
+        )
+    };
+    let (status, body) = local_api::respond(&state, &request(&new.token));
+    assert_eq!(status, "200 OK");
+    assert!(body.contains("\"standups\":0"));
+    assert!(api_tokens::list(&state)[0].last_used_ts.is_some());
+    assert_eq!(
+        local_api::respond(&state, &request("ups_wrong")).0,
+        "401 Unauthorized"
+    );
+    assert_eq!(
+        local_api::respond(&state, "GET /v1/today HTTP/1.1\r\n\r\n").0,
+        "401 Unauthorized"
+    );
+
+    api_tokens::revoke(&state, &new.info.id).unwrap();
+    assert_eq!(
+        local_api::respond(&state, &request(&new.token)).0,
+        "401 Unauthorized"
+    );
+    assert!(api_tokens::list(&state)[0].revoked_ts.is_some());
+}
+
+#[test]
+fn status_file_reports_todays_standups_and_next_reminder() {
+    let cfg = status_file::sanitize(status_file::StatusFileConfig {
+        enabled: true,
+        path: Some("  ".into()),
+        every_secs: 1,
+    });
+    assert_eq!(cfg.path, None);
+    assert_eq!(cfg.every_secs, status_file::MIN_EVERY_SECS);
+
+    let state = AppState::new(false);
+    let now = now_ts();
+    seed(&state, &[now - 30], &[(now - 20, 900)]);
+    let status = status_file::snapshot(&state);
+    assert_eq!(status.standups_today, 1);
+    assert_eq!(status.score, 50);
+    assert_eq!(status.next_reminder_ts.is_some(), !status.paused);
+}
+
+#[test]
+fn interval_today_overrides_until_the_day_ends() {
+    let state = AppState::new(false);
+    let configured = timers::effective_interval(&state);
+    timers::set_interval_today(&state, Some(90 * 60));
+    assert_eq!(timers::effective_interval(&state), 90 * 60);
+    assert_eq!(
+        timers::timer_status(&state).interval_today_secs,
+        Some(90 * 60)
+    );
+    assert_eq!(*state.interval.lock().unwrap(), configured);
+
+    let yesterday = analytics_date(clock::now(), *state.day_start_hour.lock().unwrap())
+        .pred_opt()
+        .unwrap();
+    state.interval_today.lock().unwrap().as_mut().unwrap().date = yesterday;
+    assert_eq!(timers::effective_interval(&state), configured);
+    assert_eq!(timers::timer_status(&state).interval_today_secs, None);
+}
+
+#[test]
+fn reminder_preview_payload_logs_nothing() {
+    let state = AppState::new(false);
+    *state.reminder_preview.lock().unwrap() = Some(ReminderPreview {
+        text: "Stretch".into(),
+        theme: "day".into(),
+        sound: None,
+    });
+    let payload = active_reminder_payload(&state);
+    assert!(payload.preview && payload.visible);
+    assert_eq!(payload.text, "Stretch");
+    assert!(!*state.reminder_visible.lock().unwrap());
+    assert!(state.standup_events.lock().unwrap().is_empty());
+    assert_eq!(*state.active_reminder_start_ts.lock().unwrap(), None);
+}
+
+#[test]
+fn unknown_themes_resolve_to_the_fallback_tokens() {
+    assert_eq!(themes::resolve("day").name, "day");
+    assert_eq!(themes::resolve("solarized").name, themes::FALLBACK);
+    assert_eq!(normalize_theme("solarized"), themes::FALLBACK);
+    for theme in themes::registry() {
+        assert!(theme.font_scale > 0.0 && (0.0..=1.0).contains(&theme.opacity));
+    }
+
+    let state = AppState::new(false);
+    *state.theme.lock().unwrap() = "day".into();
+    assert_eq!(active_reminder_payload(&state).theme.colors.card, "#ffffff");
+}
+
+#[test]
+fn display_layout_changes_are_noticed_after_the_first_reading() {
+    use reminder_windows::{layout_changed, MonitorLayout};
+    let monitor = |x: i32, width: u32| MonitorLayout {
+        name: None,
+        x,
+        y: 0,
+        width,
+        height: 1040,
+        scale_milli: 1000,
+    };
+    let state = AppState::new(false);
+    let docked = vec![monitor(0, 1920), monitor(1920, 2560)];
+    assert!(!layout_changed(&state, docked.clone()));
+    assert!(!layout_changed(&state, docked));
+    assert!(layout_changed(&state, vec![monitor(0, 1920)]));
+    assert!(layout_changed(&state, vec![monitor(0, 1280)]));
+}
+
+#[test]
+fn reminder_anchors_next_to_the_taskbar() {
+    use reminder_windows::{corner, normalize_anchor, taskbar_edge, Corner, Edge};
+    let screen = (0, 0, 1920, 1080);
+    assert_eq!(taskbar_edge(screen, (0, 0, 1920, 1040)), Edge::Bottom);
+    assert_eq!(taskbar_edge(screen, (0, 40, 1920, 1040)), Edge::Top);
+    assert_eq!(taskbar_edge(screen, (62, 0, 1858, 1080)), Edge::Left);
+    assert_eq!(taskbar_edge(screen, (0, 0, 1858, 1080)), Edge::Right);
+    assert_eq!(taskbar_edge(screen, screen), Edge::Bottom);
+    // A second monitor to the left of the primary.
+    assert_eq!(
+        taskbar_edge((-2560, 0, 2560, 1440), (-2560, 48, 2560, 1392)),
+        Edge::Top
+    );
+
+    assert_eq!(
+        corner("auto", false, Edge::Top),
+        Corner {
+            top: true,
+            left: false
+        }
+    );
+    assert_eq!(
+        corner("auto", false, Edge::Left),
+        Corner {
+            top: false,
+            left: true
+        }
+    );
+    assert_eq!(
+        corner("auto", true, Edge::Bottom),
+        Corner {
+            top: false,
+            left: true
+        }
+    );
+    assert_eq!(
+        corner("top_left", false, Edge::Bottom),
+        Corner {
+            top: true,
+            left: true
+        }
+    );
+    assert_eq!(normalize_anchor("middle"), "auto");
+}
+
+#[test]
+fn interaction_stats_group_by_control() {
+    use interactions::{Control, Interaction};
+    let now = now_ts();
+    let answered = |control, latency_ms, focused, ts| Interaction {
+        ts,
+        reminder_id: 1,
+        control,
+        stood_up: true,
+        latency_ms,
+        focused,
+    };
+    let log = vec![
+        answered(Some(Control::Card), 4_000, true, now - 60),
+        answered(Some(Control::Card), 2_000, false, now - 50),
+        answered(Some(Control::Escape), 9_000, true, now - 40),
+        answered(None, 1_000, false, now - 40 * 86_400),
+    ];
+    let stats = interactions::stats(&log, 7);
+    assert_eq!(stats.count, 3);
+    assert!(stats.by_control[0].control == Some(Control::Card));
+    assert_eq!(stats.by_control[0].count, 2);
+    assert_eq!(stats.by_control[0].median_latency_ms, 4_000);
+    assert_eq!(stats.median_latency_ms, Some(4_000));
+    assert!((stats.focused_share.unwrap() - 2.0 / 3.0).abs() < 1e-6);
+    assert_eq!(Control::parse("Enter").map(|c| c == Control::Enter), Some(true));
+}
+
+#[test]
+fn skip_budget_counts_todays_skips() {
+    let state = AppState::new(false);
+    let now = now_ts();
+    assert!(!skips::exhausted(&state));
+    *state.free_skips_per_day.lock().unwrap() = Some(2);
+    skips::record(&state, now);
+    assert_eq!(skips::budget(&state).remaining_today, Some(1));
+    skips::record(&state, now);
+    assert!(skips::exhausted(&state));
+    let week = [now, now, now - 7 * 86_400];
+    assert_eq!(skips::days_used_up(&week, 2, 0), 1);
+    assert_eq!(skips::days_used_up(&week, 1, 0), 2);
+}
+
+#[test]
+fn desk_mode_alternates_phases_and_totals() {
+    use desk::{DeskChange, DeskPosition};
+    let state = AppState::new(false);
+    assert_eq!(desk::phase_secs(&state), None);
+    state.desk_mode.lock().unwrap().enabled = true;
+    assert!(desk::set_enabled(&state, 1_000, true));
+    assert_eq!(desk::phase_secs(&state), Some(45 * 60));
+    assert!(desk::next_move(&state) == Some(DeskPosition::Standing));
+    state.desk_log.lock().unwrap().push(DeskChange {
+        ts: 3_700,
+        position: Some(DeskPosition::Standing),
+        from_desk: true,
+    });
+    assert!(desk::standing(&state));
+    assert_eq!(timers::effective_interval(&state), 15 * 60);
+    assert!(desk::set_enabled(&state, 4_600, false));
+    let totals = desk::totals(&state.desk_log.lock().unwrap(), 0, 10_000, 10_000, &|_| true);
+    assert_eq!(totals.sitting_secs, 2_700);
+    assert_eq!(totals.standing_secs, 900);
+    assert_eq!(totals.raises, 1);
+}
+
+#[test]
+fn mic_in_use_drops_reminder_sound() {
+    let state = AppState::new(false);
+    *state.active_reminder_sound.lock().unwrap() = Some("chime".to_string());
+    assert_eq!(active_reminder_payload(&state).sound.as_deref(), Some("chime"));
+    *state.mic_in_use.lock().unwrap() = true;
+    let payload = active_reminder_payload(&state);
+    assert!(payload.sound.is_none());
+    assert!(payload.sound_muted);
+}
+
+#[test]
+fn tray_icon_variants_follow_the_mode() {
+    use tray_icons::{TrayIconVariant, TrayMode};
+    let cfg = tray_icons::sanitize(TrayIconConfig {
+        paused: Some(TrayIconVariant::Bundled("faded".to_string())),
+        vacation: Some(TrayIconVariant::Bundled("sparkly".to_string())),
+        ..TrayIconConfig::default()
+    });
+    assert!(cfg.vacation.is_none());
+    assert!(cfg.variant(TrayMode::Normal).is_none());
+    let base = tauri::image::Image::new(&[200, 100, 0, 255], 1, 1);
+    let faded = tray_icons::bundled(&base, "faded");
+    assert_eq!(faded.rgba(), &[200, 100, 0, 127]);
+    let mono = tray_icons::bundled(&base, "mono");
+    assert_eq!(mono.rgba(), &[119, 119, 119, 255]);
+    let state = AppState::new(false);
+    assert!(tray_icons::mode(&state) == TrayMode::Normal);
+}
+
+#[test]
+fn tip_ratings_attach_to_the_shown_tip() {
+    let state = AppState::new(false);
+    let now = now_ts();
+    let tip = |text: &str, pack: &str| ShownTip {
+        text: text.to_string(),
+        source: TipSource::Pack {
+            id: pack.to_string(),
+        },
+    };
+    for (id, shown) in [(1, tip("Walk.", "friends")), (2, tip("Walk.", "office"))] {
+        reminder_reason::fired(
+            &state,
+            id,
+            ReminderReason {
+                trigger: reminder_reason::Trigger::IntervalElapsed,
+                fired_ts: now,
+                interval_secs: 1800,
+                weekend_interval: false,
+                sat_secs: 1800,
+                held_for_screen_sharing: false,
+            },
+            shown,
+        );
+    }
+    assert!(tip_ratings::rate(&state, 9, true, now).is_err());
+    tip_ratings::rate(&state, 1, false, now).unwrap();
+    tip_ratings::rate(&state, 1, true, now).unwrap();
+    tip_ratings::rate(&state, 2, false, now).unwrap();
+    let summary = tip_ratings::summary(&state.tip_ratings.lock().unwrap());
+    assert_eq!(summary.len(), 2);
+    assert!(summary[0].tip == tip("Walk.", "friends"));
+    assert_eq!((summary[0].likes, summary[0].dislikes), (1, 0));
+    assert_eq!((summary[1].likes, summary[1].dislikes), (0, 1));
+}
+
+#[test]
+fn dismissed_reminder_repeats_up_to_the_hourly_cap() {
+    let state = AppState::new(false);
+    let now = now_ts();
+    let interval = timers::effective_interval(&state);
+    assert!(!repeats::schedule(&state, now));
+    *state.repeat_when_dismissed.lock().unwrap() = repeats::sanitize(RepeatConfig {
+        enabled: true,
+        delay_minutes: 5,
+        max_per_hour: 1,
+    });
+    assert!(repeats::schedule(&state, now));
+    assert_eq!(*state.elapsed.lock().unwrap(), interval - 300);
+    let (trigger, sat_secs) = reminder_reason::take_trigger(&state, interval);
+    assert!(trigger == reminder_reason::Trigger::Repeat);
+    assert_eq!(sat_secs, 300);
+    assert!(!repeats::schedule(&state, now + 60));
+    assert!(repeats::schedule(&state, now + 3_700));
+}
+
+#[test]
+fn google_sheets_appends_each_finished_day_once() {
+    let day = |s: &str| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+    let today = day("2026-03-10");
+    assert_eq!(google_sheets::pending_days(None, today), vec![day("2026-03-09")]);
+    assert_eq!(
+        google_sheets::pending_days(Some("2026-03-06"), today),
+        vec![day("2026-03-07"), day("2026-03-08"), day("2026-03-09")]
+    );
+    assert!(google_sheets::pending_days(Some("2026-03-09"), today).is_empty());
+    assert_eq!(google_sheets::pending_days(Some("2025-01-01"), today).len(), 31);
+    let cfg = google_sheets::sanitize(GoogleSheetsConfig {
+        key_file: Some("  ".to_string()),
+        sheet: " ".to_string(),
+        ..GoogleSheetsConfig::default()
+    });
+    assert!(cfg.key_file.is_none());
+    assert_eq!(cfg.sheet, "Upstand");
+}
+
+#[test]
+fn hook_args_fill_known_placeholders_only() {
+    let vars = hooks::variables(
+        hooks::HookEvent::StandupLogged,
+        1_700_000_000,
+        &[("standups_today", "3".to_string())],
+    );
+    assert_eq!(hooks::expand("{event}", &vars), "standup-logged");
+    assert_eq!(
+        hooks::expand("n={standups_today} at {ts}", &vars),
+        "n=3 at 1700000000"
+    );
+    assert_eq!(hooks::expand("{nope} {ts", &vars), "{nope} {ts");
+    assert_eq!(hooks::expand("{{ts}}", &vars), "{1700000000}");
+    let hook = |program: &str, timeout_secs| HookConfig {
+        event: hooks::HookEvent::ReminderFired,
+        program: program.to_string(),
+        args: Vec::new(),
+        timeout_secs,
+        enabled: true,
+    };
+    let kept = hooks::sanitize(vec![
+        hook("  notify-send ", 0),
+        hook(" ", 5),
+        hook("x", 600),
+    ]);
+    assert_eq!(kept.len(), 2);
+    assert_eq!(kept[0].program, "notify-send");
+    assert_eq!(kept[0].timeout_secs, 1);
+    assert_eq!(kept[1].timeout_secs, hooks::MAX_TIMEOUT_SECS);
+}
+
+#[test]
+fn plugin_output_is_trimmed_and_capped() {
+    let long = "x".repeat(300);
+    let output =
+        serde_json::to_vec(&vec!["  Stretch ", "", long.as_str(), "Walk", "Drink"]).unwrap();
+    assert_eq!(
+        plugins::texts(&output, 2).unwrap(),
+        vec!["Stretch".to_string(), "Walk".to_string()]
+    );
+    assert!(plugins::texts(b"{\"tips\": []}", 10).is_err());
+}
+
+#[test]
+fn dev_intervals_unlock_short_intervals_and_guards() {
+    let state = AppState::new(false);
+    assert_eq!(
+        sanitize_interval_minutes(&state, 1),
+        DEFAULT_INTERVAL_MINUTES
+    );
+    assert_eq!(dev_intervals::ack_grace_ms(&state), DEFAULT_ACK_GRACE_MS);
+    assert!(dev_intervals::from_args(&[
+        "upstand".to_string(),
+        "--dev-intervals".to_string()
+    ]));
+    *state.dev_intervals_from_args.lock().unwrap() = true;
+    assert_eq!(sanitize_interval_minutes(&state, 1), 1);
+    assert_eq!(allowed_interval_minutes(&state)[..3], [1, 2, 5]);
+    assert_eq!(
+        dev_intervals::ack_grace_ms(&state),
+        dev_intervals::MAX_ACK_GRACE_MS
+    );
+    assert_eq!(
+        dev_intervals::standup_cooldown_secs(&state),
+        dev_intervals::MAX_STANDUP_COOLDOWN_SECS
+    );
+}
+
+#[test]
+fn tip_translations_fall_back_to_english_per_tip() {
+    let translated = |pairs: &[(&str, &str)]| {
+        tip_packs::PackTip::Translated(
+            pairs
+                .iter()
+                .map(|(language, text)| (language.to_string(), text.to_string()))
+                .collect(),
+        )
+    };
+    let pack = tip_packs::TipPack {
+        id: "walks".to_string(),
+        name: "Walks".to_string(),
+        language: "en".to_string(),
+        author: String::new(),
+        description: String::new(),
+        tips: vec![
+            translated(&[("en", "Walk"), ("zh-CN", "走走")]),
+            tip_packs::PackTip::Text("Stretch".to_string()),
+        ],
+    };
+    let zh: Vec<_> = pack
+        .tips
+        .iter()
+        .filter_map(|tip| tip.resolve(&pack.language, "zh-CN"))
+        .collect();
+    assert_eq!(zh, vec!["走走", "Stretch"]);
+    let coverage = tip_packs::coverage(&pack, "zh-CN");
+    assert_eq!((coverage.total, coverage.translated), (2, 1));
+    assert_eq!(coverage.missing, vec!["Stretch".to_string()]);
+    assert!(tip_packs::coverage(&pack, "en").missing.is_empty());
+}
+
+#[test]
+fn handoff_merges_peer_events_and_takes_over_recent_countdown() {
+    let peer = |id: &str, last_active_ts: i64, elapsed_secs: u64| handoff::HandoffSnapshot {
+        device_id: id.to_string(),
+        device_name: id.to_string(),
+        ts: last_active_ts,
+        last_active_ts,
+        date: "2026-03-10".to_string(),
+        elapsed_secs,
+        standups: vec![100, 300],
+        sitting: vec![handoff::SittingRecord {
+            ts: 200,
+            duration_secs: 1_800,
+        }],
+    };
+    let mut standups = vec![300];
+    let mut sitting = Vec::new();
+    let peers = vec![peer("laptop", 5_000, 600), peer("desktop", 4_000, 60)];
+    assert_eq!(handoff::merge(&mut standups, &mut sitting, &peers), 2);
+    assert_eq!(standups, vec![100, 300]);
+    assert_eq!(sitting.len(), 1);
+    assert_eq!(handoff::merge(&mut standups, &mut sitting, &peers), 0);
+
+    let taken = handoff::pick_takeover(&peers, 4_500, 5_100).map(|p| p.elapsed_secs);
+    assert_eq!(taken, Some(600));
+    assert!(handoff::pick_takeover(&peers, 5_000, 5_100).is_none());
+    assert!(handoff::pick_takeover(&peers, 0, 5_000 + 60 * 60).is_none());
+}
+
+#[test]
+fn challenge_holds_stood_up_until_passed() {
+    let state = AppState::new(false);
+    show_reminder(&state, now_ts());
+    assert!(challenge::check(&state, None).is_ok());
+    *state.challenge.lock().unwrap() = challenge::sanitize(ChallengeConfig {
+        kind: Some(challenge::ChallengeKind::Phrase),
+        wait_secs: 60,
+    });
+    assert_eq!(
+        challenge::check(&state, None),
+        Err("CHALLENGE_PENDING".to_string())
+    );
+    assert!(challenge::status(&state).unwrap().phrase.is_none());
+    *state.active_reminder_shown_at.lock().unwrap() =
+        Some(Instant::now() - Duration::from_secs(61));
+    let phrase = challenge::status(&state).unwrap().phrase.unwrap();
+    assert_eq!(
+        challenge::check(&state, Some("wrong")),
+        Err("CHALLENGE_FAILED".to_string())
+    );
+    let typed = format!("  {}  ", phrase.to_uppercase().replace(' ', "   "));
+    assert!(challenge::check(&state, Some(&typed)).is_ok());
+}
+
+#[test]
+fn network_profile_applies_on_its_wifi() {
+    let netsh = "    Name                   : Wi-Fi\n    SSID                   : Acme Office\n    BSSID                  : 00:11:22:33:44:55\n";
+    assert_eq!(
+        network_info::parse_netsh(netsh),
+        Some("Acme Office".to_string())
+    );
+    assert_eq!(
+        network_info::parse_netsh("    State : disconnected\n"),
+        None
+    );
+
+    let state = AppState::new(false);
+    let profiles = vec![NetworkProfile {
+        name: " Office ".to_string(),
+        ssids: vec!["Acme Office".to_string(), " ".to_string()],
+        interval_minutes: Some(20),
+        free_skips_per_day: Some(0),
+        challenge: Some(challenge::ChallengeKind::Countdown),
+    }];
+    *state.network_profiles.lock().unwrap() = sanitize_network_profiles(&state, profiles);
+    let configured = timers::effective_interval(&state);
+    assert!(network_profiles::active(&state).is_none());
+    assert!(!skips::exhausted(&state));
+
+    *state.ssid.lock().unwrap() = Some("Acme Office".to_string());
+    let status = network_profiles::status(&state);
+    assert_eq!(status.profile.as_deref(), Some("Office"));
+    assert_eq!(timers::effective_interval(&state), 20 * 60);
+    assert!(skips::exhausted(&state));
+    show_reminder(&state, now_ts());
+    assert!(challenge::check(&state, None).is_err());
+
+    *state.ssid.lock().unwrap() = Some("Home".to_string());
+    assert_eq!(timers::effective_interval(&state), configured);
+    assert!(challenge::check(&state, None).is_ok());
+}
+
+#[test]
+fn sit_stand_ratio_judges_after_warmup() {
+    let segment = |kind, start_ts, end_ts| timeline::TimelineSegment {
+        kind,
+        start_ts,
+        end_ts,
+    };
+    let target = ratio::sanitize_target(Some(0.2));
+    assert_eq!(target, Some(ratio::MIN_TARGET));
+    assert_eq!(ratio::sanitize_target(Some(f64::NAN)), None);
+
+    let early = [segment(timeline::SegmentKind::Sitting, 0, 30 * 60)];
+    let early = ratio::of_segments(&early, Some(3.0));
+    assert_eq!(early.ratio, None);
+    assert!(!early.below_target);
+
+    let day = [
+        segment(timeline::SegmentKind::Sitting, 0, 90 * 60),
+        segment(timeline::SegmentKind::Standing, 90 * 60, 100 * 60),
+        segment(timeline::SegmentKind::Idle, 100 * 60, 200 * 60),
+        segment(timeline::SegmentKind::Sitting, 200 * 60, 230 * 60),
+    ];
+    let status = ratio::of_segments(&day, Some(3.0));
+    assert_eq!(status.sitting_secs, 120 * 60);
+    assert_eq!(status.standing_secs, 10 * 60);
+    assert_eq!(status.ratio, Some(12.0));
+    assert!(status.below_target);
+    assert!(!ratio::of_segments(&day, Some(12.0)).below_target);
+    assert!(!ratio::of_segments(&day, None).below_target);
+}
+
+#[test]
+fn overlay_holds_until_escalation() {
+    let state = AppState::new(false);
+    let cfg = overlay::sanitize(PresentationConfig {
+        mode: overlay::Presentation::Overlay,
+        escalate_after_secs: 5,
+    });
+    assert_eq!(cfg.escalate_after_secs, overlay::MIN_ESCALATE_SECS);
+    *state.reminder_presentation.lock().unwrap() = cfg;
+    assert!(overlay::wanted(&state));
+
+    show_reminder(&state, now_ts());
+    assert!(!overlay::holding(&state));
+    *state.reminder_overlay.lock().unwrap() = true;
+    assert!(overlay::holding(&state));
+    *state.active_reminder_shown_at.lock().unwrap() =
+        Some(Instant::now() - Duration::from_secs(overlay::MIN_ESCALATE_SECS));
+    assert!(!overlay::holding(&state));
+}
+
+#[test]
+fn imported_tray_icons_missing_here_are_dropped() {
+    use tray_icons::TrayIconVariant;
+    let missing = std::env::temp_dir()
+        .join("upstand-no-such-icon.png")
+        .to_string_lossy()
+        .into_owned();
+    let mut cfg = TrayIconConfig {
+        normal: Some(TrayIconVariant::Bundled("mono".to_string())),
+        paused: Some(TrayIconVariant::File(missing.clone())),
+        ..Default::default()
+    };
+    assert_eq!(tray_icons::drop_unloadable(&mut cfg), vec![missing]);
+    assert!(cfg.paused.is_none());
+    assert!(cfg.normal.is_some());
+    assert!(tray_icons::drop_unloadable(&mut cfg).is_empty());
+}
+
+#[test]
+fn window_focus_nudges_once_per_stretch() {
+    let state = AppState::new(false);
+    let threshold = 30 * 60;
+    window_focus::focus_changed(&state, "settings", true, 1_000);
+    assert!(window_focus::due_nudge(&state, 1_000 + threshold - 1).is_none());
+    assert_eq!(
+        window_focus::due_nudge(&state, 1_000 + threshold),
+        Some(("settings".to_string(), threshold as u64))
+    );
+    assert!(window_focus::due_nudge(&state, 1_000 + threshold + 60).is_none());
+
+    window_focus::focus_changed(&state, "settings", false, 3_000);
+    window_focus::focus_changed(&state, "settings", true, 4_000);
+    let info = window_focus::summary(&state, 4_100);
+    assert_eq!(info[0].focused_secs, Some(100));
+    assert_eq!(info[0].total_secs, 2_100);
+
+    *state.window_nudge_minutes.lock().unwrap() = 0;
+    assert!(window_focus::due_nudge(&state, 4_000 + threshold).is_none());
+}
+
+#[test]
+fn config_history_logs_changed_keys_only() {
+    let state = AppState::new(false);
+    let save = |ts| config_history::record(&state, &config_from_state(&state), ts);
+    assert!(!save(100));
+
+    *state.interval.lock().unwrap() = 20 * 60;
+    config_history::set_source(&state, "sanitizer");
+    assert!(save(200));
+    let history = config_history::list(&state, 10);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].key, "interval_minutes");
+    assert_eq!(history[0].source, "sanitizer");
+    assert_eq!(history[0].old, serde_json::json!(DEFAULT_INTERVAL_MINUTES));
+    assert_eq!(history[0].new, serde_json::json!(20));
+
+    state
+        .window_prefs
+        .lock()
+        .unwrap()
+        .insert("settings".to_string(), WindowPrefs::default());
+    *state.last_rollover_date.lock().unwrap() = Some("2026-10-16".to_string());
+    assert!(!save(300));
+
+    *state.pre_warning_minutes.lock().unwrap() = 2;
+    assert!(save(400));
+    let history = config_history::list(&state, 10);
+    assert_eq!(history[0].id, 2);
+    assert_eq!(history[0].source, "settings");
+}
+
+#[test]
+fn analytics_filter_narrows_by_profile_and_outcome() {
+    let state = AppState::new(false);
+    let now = now_ts();
+    seed(&state, &[now - 300], &[(now - 290, 600)]);
+    network_profiles::log_profile(&state, now - 200, Some("Office".to_string()));
+    seed(&state, &[now - 100], &[(now - 90, 900)]);
+    skips::record(&state, now - 80);
+
+    let filter = AnalyticsFilter {
+        outcomes: vec![OutcomeClass::Ignored],
+        profiles: vec![Some("Office".to_string())],
+        ..AnalyticsFilter::default()
+    };
+    let analytics = build_analytics_filtered(&state, 0, i64::MAX, &|_| true, &filter);
+    assert_eq!(analytics.sedentary_sessions, 1);
+    assert_eq!(analytics.total_sitting_secs, 900);
+    assert_eq!(analytics.standup_sessions, 0);
+    assert_eq!(analytics.skips, 0);
+
+    let filter = AnalyticsFilter {
+        profiles: vec![None],
+        tags: vec![None],
+        ..AnalyticsFilter::default()
+    };
+    let analytics = build_analytics_filtered(&state, 0, i64::MAX, &|_| true, &filter);
+    assert_eq!(analytics.standup_sessions, 1);
+    assert_eq!(analytics.total_sitting_secs, 600);
+
+    let filter = AnalyticsFilter {
+        hours: Some(analytics_filter::HourRange { from: 9, to: 9 }),
+        ..AnalyticsFilter::default()
+    };
+    assert!(filter.validate().is_err());
+}
+
+#[test]
+fn sleep_gap_reconciles_the_countdown_by_policy() {
+    let state = AppState::new(false);
+    let now = now_ts();
+    assert_eq!(sleep_drift::gap(30, 29, 30), None);
+    assert_eq!(sleep_drift::gap(630, 30, 30), Some(600));
+    // Windows: the monotonic clock counted the suspend too.
+    assert_eq!(sleep_drift::gap(630, 630, 30), Some(600));
+
+    *state.elapsed.lock().unwrap() = 1500;
+    *state.sleep_policy.lock().unwrap() = SleepPolicy::SubtractGap;
+    assert!(!sleep_drift::reconcile(&state, 600));
+    assert_eq!(*state.elapsed.lock().unwrap(), 900);
+
+    *state.sleep_policy.lock().unwrap() = SleepPolicy::CountAsStanding;
+    show_reminder(&state, now);
+    assert!(!sleep_drift::reconcile(&state, 600));
+    assert_eq!(*state.elapsed.lock().unwrap(), 900);
+
+    *state.reminder_visible.lock().unwrap() = false;
+    assert!(sleep_drift::reconcile(&state, 600));
+    assert_eq!(*state.elapsed.lock().unwrap(), 0);
+}
+
+#[test]
+fn reminder_language_adds_packs_written_in_it() {
+    let pack = |id: &str, language: &str| tip_packs::TipPack {
+        id: id.to_string(),
+        name: id.to_string(),
+        language: language.to_string(),
+        author: String::new(),
+        description: String::new(),
+        tips: vec![tip_packs::PackTip::Text("tip".to_string())],
+    };
+    let packs = vec![
+        pack(tip_packs::BUILTIN_PACK_ID, "en"),
+        pack("desk-yoga", "en"),
+        pack("zhan", "zh-CN"),
+    ];
+    let enabled = tip_packs::default_enabled_packs();
+
+    assert_eq!(
+        tip_packs::packs_for_language(&packs, &enabled, "zh-CN"),
+        Some(vec!["zhan".to_string()])
+    );
+    assert_eq!(
+        tip_packs::packs_for_language(&packs, &["zhan".to_string()], "en"),
+        Some(vec![tip_packs::BUILTIN_PACK_ID.to_string()])
+    );
+    // Switching back finds the user's own packs still enabled.
+    let both = vec!["desk-yoga".to_string(), "zhan".to_string()];
+    assert_eq!(
+        tip_packs::packs_for_language(&packs, &both, "en"),
+        Some(Vec::new())
+    );
+    assert_eq!(tip_packs::packs_for_language(&packs, &both, "de"), None);
+}
+
+#[test]
+fn export_diff_compares_totals_with_the_last_export() {
+    let snapshot = |records, sitting_secs| export_diff::ExportSnapshot {
+        exported_ts: now_ts(),
+        period_start_ts: 1_000,
+        records,
+        standups: records / 2,
+        sedentary: records - records / 2,
+        sitting_secs,
+        skips: 0,
+    };
+    let csv = export_diff::diff_csv(
+        &snapshot(4, 3_600),
+        &snapshot(7, 3_000),
+        &Formatter::new("en", true),
+    );
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows[0], "metric,previous,current,change");
+    assert_eq!(rows[1], "records,4,7,+3");
+    assert_eq!(rows[4], "skips,0,0,0");
+    assert_eq!(rows[5], "total_sitting_minutes,60,50,-10");
+    assert_eq!(rows[6], "same_period,true,,");
+}
+
+#[test]
+fn unknown_reminder_focus_modes_are_rejected() {
+    assert!(parse_reminder_focus_mode("show-without-focus").is_ok());
+    assert!(parse_reminder_focus_mode("show-without-foucs").is_err());
+    assert_eq!(normalize_reminder_focus_mode("bogus"), "steal");
+}
+
+#[test]
+fn standup_cooldown_only_holds_back_reminders_the_countdown_missed() {
+    let state = AppState::new(false);
+    let now = now_ts();
+    seed(&state, &[now - 30], &[]);
+    assert_eq!(recent_standup_age(&state, now, 30 * 60), Some(30));
+    // A standup that restarted the countdown is a whole interval old.
+    assert_eq!(recent_standup_age(&state, now + 30 * 60, 30 * 60), None);
+    // Never more than half the interval, even with a long cooldown.
+    *state.standup_cooldown_secs.lock().unwrap() = 25 * 60;
+    assert_eq!(
+        recent_standup_age(&state, now + 14 * 60, 30 * 60),
+        Some(14 * 60 + 30)
+    );
+    assert_eq!(recent_standup_age(&state, now + 15 * 60, 30 * 60), None);
+}
+
+#[test]
+fn schedule_drops_sounds_the_reminder_page_cannot_play() {
+    let mut slots = schedule::default_schedule();
+    slots[0].sound = Some(" energetic ".to_string());
+    slots[1].sound = Some("airhorn".to_string());
+    let slots = schedule::sanitize_schedule(slots);
+    assert_eq!(slots[0].sound.as_deref(), Some("energetic"));
+    assert_eq!(slots[1].sound, None);
+    // Sounds are opt-in.
+    assert_eq!(slots[2].sound, None);
+}
+
+#[test]
+fn scheduled_export_formats_are_deduplicated() {
+    let cfg = scheduled_export::sanitize(scheduled_export::ExportScheduleConfig {
+        formats: vec!["csv".to_string(), "json".to_string(), "csv".to_string()],
+        ..Default::default()
+    });
+    assert_eq!(cfg.formats, vec!["csv".to_string(), "json".to_string()]);
+}
+
+#[test]
+fn scheduled_export_catches_up_on_a_missed_slot() {
+    let at = |date: &str, hour: u32| {
+        let day = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        Local
+            .from_local_datetime(&day.and_hms_opt(hour, 0, 0).unwrap())
+            .unwrap()
+    };
+    // Sundays at 21:00; 2026-03-01 is a Sunday.
+    let mut cfg = scheduled_export::ExportScheduleConfig {
+        enabled: true,
+        folder: Some("/tmp".to_string()),
+        last_run_date: Some("2026-02-22".to_string()),
+        ..Default::default()
+    };
+    assert!(!scheduled_export::is_due(&cfg, at("2026-03-01", 20), false));
+    assert!(scheduled_export::is_due(&cfg, at("2026-03-01", 21), false));
+    // Closed all Sunday evening, or on battery saver: the next check runs it.
+    assert!(!scheduled_export::is_due(&cfg, at("2026-03-03", 9), true));
+    assert!(scheduled_export::is_due(&cfg, at("2026-03-03", 9), false));
+
+    cfg.last_run_date = Some("2026-03-03".to_string());
+    assert!(!scheduled_export::is_due(&cfg, at("2026-03-07", 23), false));
+    assert!(scheduled_export::is_due(&cfg, at("2026-03-08", 21), false));
+}
+
+#[test]
+fn data_dir_writes_stop_while_another_process_holds_the_store() {
+    let path = std::env::temp_dir().join(format!("upstand-lock-{}.json", std::process::id()));
+    let state = AppState::new(false);
+    *state.store_lock_error.lock().unwrap() = Some("locked".to_string());
+    assert!(data_dir::write(&state, &path, "{}").is_err());
+    assert!(!path.exists());
+
+    *state.store_lock_error.lock().unwrap() = None;
+    data_dir::write(&state, &path, "{}").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "{}");
+    let _ = fs::remove_file(path);
+}
+
+#[test]
+fn writes_are_refused_in_read_only_mode() {
+    let dir = std::env::temp_dir().join(format!("upstand-read-only-{}", std::process::id()));
+    let state = AppState::new(false);
+    *state.read_only.lock().unwrap() = true;
+    let err = data_dir::write(&state, &dir.join("config.json"), "{}").unwrap_err();
+    assert_eq!(err.to_string(), read_only::ERROR);
+    assert_eq!(
+        write_export(&state, &dir.join("export.csv"), "a,b"),
+        Err(read_only::ERROR.to_string())
+    );
+    assert!(!dir.exists());
+}
+
+#[test]
+fn reminder_payload_carries_the_ack_grace_period() {
+    let state = AppState::new(false);
+    *state.ack_grace_ms.lock().unwrap() = 2_500;
+    assert_eq!(active_reminder_payload(&state).ack_grace_ms, 2_500);
+}
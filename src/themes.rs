@@ -0,0 +1,96 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Theme used for a name the registry doesn't know.
+pub const FALLBACK: &str = "night";
+
+/// CSS colours for the reminder card, as the page's custom properties.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct ThemeColors {
+    pub card: String,
+    pub text: String,
+    pub muted: String,
+    pub line: String,
+    pub hover: String,
+    pub shadow: String,
+}
+
+/// Everything the reminder popup needs to draw a theme, so the page holds no
+/// per-theme styles of its own.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct ThemeTokens {
+    pub name: String,
+    pub colors: ThemeColors,
+    /// Multiplies the page's base font sizes.
+    pub font_scale: f32,
+    /// Card corner radius in CSS pixels.
+    pub corner_radius: u32,
+    /// Card opacity, 0.0-1.0.
+    pub opacity: f32,
+}
+
+fn colors(
+    card: &str,
+    text: &str,
+    muted: &str,
+    line: &str,
+    hover: &str,
+    shadow: &str,
+) -> ThemeColors {
+    ThemeColors {
+        card: card.to_string(),
+        text: text.to_string(),
+        muted: muted.to_string(),
+        line: line.to_string(),
+        hover: hover.to_string(),
+        shadow: shadow.to_string(),
+    }
+}
+
+/// Every theme the app can draw; `set_theme` names one of these.
+pub fn registry() -> Vec<ThemeTokens> {
+    vec![
+        ThemeTokens {
+            name: "day".to_string(),
+            colors: colors(
+                "#ffffff",
+                "#0f172a",
+                "#64748b",
+                "rgba(15, 23, 42, 0.12)",
+                "rgba(15, 23, 42, 0.06)",
+                "0 14px 30px rgba(15, 23, 42, 0.18)",
+            ),
+            font_scale: 1.0,
+            corner_radius: 12,
+            opacity: 1.0,
+        },
+        ThemeTokens {
+            name: "night".to_string(),
+            colors: colors(
+                "#0d1727",
+                "#eaf1fb",
+                "#9fb0c8",
+                "rgba(234, 241, 251, 0.14)",
+                "rgba(234, 241, 251, 0.08)",
+                "0 18px 34px rgba(0, 0, 0, 0.45)",
+            ),
+            font_scale: 1.0,
+            corner_radius: 12,
+            opacity: 1.0,
+        },
+    ]
+}
+
+/// Tokens for `name`, or for [`FALLBACK`] if there is no such theme.
+pub fn resolve(name: &str) -> ThemeTokens {
+    let themes = registry();
+    let found = themes
+        .iter()
+        .position(|theme| theme.name == name)
+        .or_else(|| themes.iter().position(|theme| theme.name == FALLBACK))
+        .unwrap_or(0);
+    themes
+        .into_iter()
+        .nth(found)
+        .expect("the registry is not empty")
+}
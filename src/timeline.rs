@@ -0,0 +1,170 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    analytics_date, clock, day_start_ts, journal, now_ts, prune_changes, timers, AppState,
+};
+
+/// No keyboard or mouse input for this long counts as idle. Needs the opt-in
+/// input sampler; without it sitting time is never split into idle.
+pub const IDLE_AFTER_SECS: u64 = 5 * 60;
+/// A standup is logged as a moment; the timeline draws it as a break this
+/// long, cut short by whatever is logged next.
+pub const STANDING_BREAK_SECS: i64 = 5 * 60;
+
+/// Why sitting time wasn't being counted.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Hold {
+    /// No input for [`IDLE_AFTER_SECS`].
+    Idle,
+    /// The displays were off or the session locked.
+    Locked,
+    /// Reminders were off for the day (holiday, weekend off) or held for a
+    /// blocking app.
+    Paused,
+}
+
+/// The hold changed at `ts`; `None` means back to sitting.
+#[derive(Clone, Copy, Serialize, Deserialize, JsonSchema)]
+pub struct HoldChange {
+    pub ts: i64,
+    pub hold: Option<Hold>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentKind {
+    Sitting,
+    Standing,
+    Idle,
+    Locked,
+    Paused,
+}
+
+/// One stretch of the day, `[start_ts, end_ts)`.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct TimelineSegment {
+    pub kind: SegmentKind,
+    pub start_ts: i64,
+    pub end_ts: i64,
+}
+
+fn current_hold(state: &AppState) -> Option<Hold> {
+    if state.power.lock().unwrap().display_off {
+        Some(Hold::Locked)
+    } else if timers::day_off(state) || timers::blocking_app_running(state) {
+        Some(Hold::Paused)
+    } else if *state.input_idle.lock().unwrap() {
+        Some(Hold::Idle)
+    } else {
+        None
+    }
+}
+
+/// Log the hold if it changed since the last call. Run from the timer loop,
+/// which is woken by the power and input changes that move it.
+pub fn observe(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let hold = current_hold(&state);
+    let logged = {
+        let mut log = state.hold_log.lock().unwrap();
+        let unchanged = log.last().map(|change| change.hold).unwrap_or(None) == hold;
+        if !unchanged {
+            log.push(HoldChange { ts: now_ts(), hold });
+        }
+        !unchanged
+    };
+    if logged {
+        journal::compact_soon(app);
+    }
+}
+
+pub fn hold_at(log: &[HoldChange], ts: i64) -> Option<Hold> {
+    let after = log.partition_point(|change| change.ts <= ts);
+    after.checked_sub(1).and_then(|i| log[i].hold)
+}
+
+pub fn prune(log: &mut Vec<HoldChange>, cutoff: i64) {
+    prune_changes(log, cutoff, |change| change.ts);
+}
+
+fn kind_at(holds: &[HoldChange], standups: &[i64], ts: i64) -> SegmentKind {
+    // Walking away after a standup reads as the break it was, not as idle.
+    if standups
+        .iter()
+        .any(|s| *s <= ts && ts < s + STANDING_BREAK_SECS)
+    {
+        return SegmentKind::Standing;
+    }
+    match hold_at(holds, ts) {
+        Some(Hold::Idle) => SegmentKind::Idle,
+        Some(Hold::Locked) => SegmentKind::Locked,
+        Some(Hold::Paused) => SegmentKind::Paused,
+        None => SegmentKind::Sitting,
+    }
+}
+
+/// Split `[start_ts, end_ts)` into segments, oldest first, with adjacent
+/// stretches of the same kind merged. A standing break ends early at the
+/// next hold change or standup.
+pub fn segments(
+    start_ts: i64,
+    end_ts: i64,
+    holds: &[HoldChange],
+    standups: &[i64],
+) -> Vec<TimelineSegment> {
+    let mut bounds = vec![start_ts, end_ts];
+    bounds.extend(holds.iter().map(|change| change.ts));
+    for ts in standups {
+        bounds.push(*ts);
+        bounds.push(ts + STANDING_BREAK_SECS);
+    }
+    bounds.retain(|ts| (start_ts..=end_ts).contains(ts));
+    bounds.sort_unstable();
+    bounds.dedup();
+
+    let mut out: Vec<TimelineSegment> = Vec::new();
+    for pair in bounds.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        let kind = kind_at(holds, standups, from);
+        match out.last_mut() {
+            Some(last) if last.kind == kind => last.end_ts = to,
+            _ => out.push(TimelineSegment {
+                kind,
+                start_ts: from,
+                end_ts: to,
+            }),
+        }
+    }
+    out
+}
+
+/// Today's segments up to now. The day is drawn from the launch, or from the
+/// first event logged today if that came earlier; time the app wasn't
+/// running in between can't be told apart and shows as whatever came before.
+pub fn today(state: &AppState) -> Vec<TimelineSegment> {
+    let day_start_hour = *state.day_start_hour.lock().unwrap();
+    let day_start = day_start_ts(analytics_date(clock::now(), day_start_hour), day_start_hour);
+    let now = now_ts();
+    let holds = state.hold_log.lock().unwrap().clone();
+    let standups: Vec<i64> = state
+        .standup_events
+        .lock()
+        .unwrap()
+        .iter()
+        .copied()
+        .filter(|ts| (day_start..now).contains(ts))
+        .collect();
+    let first_event = holds
+        .iter()
+        .map(|change| change.ts)
+        .filter(|ts| *ts >= day_start)
+        .chain(standups.iter().copied())
+        .min();
+    let start = first_event
+        .map_or(state.launched_ts, |ts| ts.min(state.launched_ts))
+        .max(day_start);
+    segments(start, now, &holds, &standups)
+}
@@ -0,0 +1,795 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::ratio::{self, SitStandRatio};
+use crate::schedule::{self, WeekendOverride};
+use crate::tip_ratings::{ShownTip, TipSource};
+use crate::{
+    analytics_date, budget, clock, delivery, desk, events, holidays, journal, network_profiles,
+    notify, notify_silent, now_ts, overlay, raw_events, recent_standup_age, refresh_tray_menu,
+    reminder_reason, reminder_windows, repeats, select_reminder_tip, sleep_drift, timeline,
+    window_focus, AppState, ReminderRecord,
+};
+use reminder_reason::ReminderReason;
+
+pub const TICK_SECS: u64 = 1;
+/// Coarser step used while the OS battery saver is on.
+pub const POWER_SAVER_TICK_SECS: u64 = 5;
+/// Longest adaptive step; kept under `WATCHDOG_STALE_SECS` so a sleeping loop
+/// never looks hung.
+pub const MAX_COARSE_TICK_SECS: u64 = 20;
+const DEFAULT_COARSE_TICK_SECS: u64 = 15;
+const DEFAULT_FINE_WINDOW_SECS: u64 = 60;
+pub const MAX_FINE_WINDOW_SECS: u64 = 600;
+pub const DEFAULT_TICK_EVENT_SECS: u64 = 1;
+pub const MAX_TICK_EVENT_SECS: u64 = 60;
+pub const DEFAULT_FOCUS_MINUTES: u64 = 25;
+pub const MAX_FOCUS_MINUTES: u64 = 180;
+/// After an interval change the reminder can't fire for this long, so flipping
+/// through options never triggers (or repeats) a reminder on the spot.
+pub const INTERVAL_CHANGE_GRACE_SECS: u64 = 60;
+pub const MAX_PRE_WARNING_MINUTES: u64 = 15;
+/// Bounds for `set_interval_today`, which isn't limited to the preset list.
+pub const MIN_INTERVAL_TODAY_MINUTES: u64 = 5;
+pub const MAX_INTERVAL_TODAY_MINUTES: u64 = 180;
+/// The timer loop counts as hung when it hasn't ticked for this long.
+pub const WATCHDOG_STALE_SECS: u64 = 30;
+const WATCHDOG_POLL_SECS: u64 = 5;
+const WATCHDOG_RESTART_DELAY_SECS: u64 = 2;
+/// Longest sleep while nothing is due (paused, day off, displays off).
+/// Anything that could bring a deadline closer wakes the loop early.
+const MAX_IDLE_SLEEP_SECS: u64 = 60 * 60;
+
+/// How often the timer loop wakes. Adaptive ticks sleep up to `coarse_secs`
+/// while the next deadline (reminder, pre-warning, focus end) is further off
+/// than `fine_window_secs`, then step finely so it still lands on time.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TickResolution {
+    #[serde(default = "default_adaptive")]
+    pub adaptive: bool,
+    #[serde(default = "default_coarse_secs")]
+    pub coarse_secs: u64,
+    #[serde(default = "default_fine_window_secs")]
+    pub fine_window_secs: u64,
+}
+
+impl Default for TickResolution {
+    fn default() -> Self {
+        Self {
+            adaptive: default_adaptive(),
+            coarse_secs: default_coarse_secs(),
+            fine_window_secs: default_fine_window_secs(),
+        }
+    }
+}
+
+fn default_adaptive() -> bool {
+    true
+}
+
+fn default_coarse_secs() -> u64 {
+    DEFAULT_COARSE_TICK_SECS
+}
+
+fn default_fine_window_secs() -> u64 {
+    DEFAULT_FINE_WINDOW_SECS
+}
+
+pub fn sanitize_tick_resolution(mut cfg: TickResolution) -> TickResolution {
+    cfg.coarse_secs = cfg.coarse_secs.clamp(TICK_SECS, MAX_COARSE_TICK_SECS);
+    cfg.fine_window_secs = cfg.fine_window_secs.min(MAX_FINE_WINDOW_SECS);
+    cfg
+}
+
+/// A running deep-work session.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct FocusSession {
+    pub started_ts: i64,
+    pub planned_secs: u64,
+    pub elapsed_secs: u64,
+}
+
+/// A finished (or stopped) deep-work session as stored in analytics.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FocusRecord {
+    pub ts: i64,
+    pub duration_secs: u64,
+    pub completed: bool,
+}
+
+/// Why the countdown is held.
+#[derive(Clone, Copy, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseReason {
+    /// A reminder is waiting for an answer.
+    ReminderVisible,
+    /// The displays are off.
+    DisplayOff,
+    /// A holiday, or a weekend with reminders off.
+    DayOff,
+    /// One of the configured blocking apps is running.
+    BlockingApp,
+}
+
+/// Countdown snapshot streamed to the frontend as the `tick` event.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct TickPayload {
+    pub remaining_secs: u64,
+    pub elapsed_secs: u64,
+    pub interval_secs: u64,
+    /// The countdown is held, e.g. while a reminder is waiting for an answer.
+    pub paused: bool,
+    pub pause_reason: Option<PauseReason>,
+}
+
+/// Full timer snapshot returned by `get_timer_status`.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct TimerStatus {
+    pub interval_secs: u64,
+    /// Set when `interval_secs` comes from `set_interval_today`; the
+    /// configured interval returns when the day ends.
+    pub interval_today_secs: Option<u64>,
+    pub elapsed_secs: u64,
+    pub paused: bool,
+    pub pause_reason: Option<PauseReason>,
+    /// The running app behind a `blocking_app` pause.
+    pub blocking_app: Option<String>,
+    pub reminder_active: bool,
+    /// There is no snooze yet, so this is always `None`.
+    pub snoozed_until: Option<i64>,
+    /// `None` while the countdown is held.
+    pub next_fire_ts: Option<i64>,
+    /// Battery saver is on and background work is throttled.
+    pub power_saving: bool,
+    /// What is left of today's sitting budget; `None` when no budget is set.
+    pub sitting_budget_remaining_secs: Option<u64>,
+    /// Today's sitting against standing, and the target if one is set.
+    pub sit_stand: SitStandRatio,
+}
+
+/// Why the standup countdown is held instead of advancing, if it is.
+pub fn pause_reason(state: &AppState) -> Option<PauseReason> {
+    if *state.reminder_visible.lock().unwrap() {
+        Some(PauseReason::ReminderVisible)
+    } else if user_away(state) {
+        Some(PauseReason::DisplayOff)
+    } else if day_off(state) {
+        Some(PauseReason::DayOff)
+    } else if blocking_app_running(state) {
+        Some(PauseReason::BlockingApp)
+    } else {
+        None
+    }
+}
+
+pub fn countdown_paused(state: &AppState) -> bool {
+    pause_reason(state).is_some()
+}
+
+pub fn blocking_app_running(state: &AppState) -> bool {
+    state.blocking_app.lock().unwrap().is_some()
+}
+
+/// Today is a holiday and reminders are off for holidays.
+fn holiday_pause(state: &AppState) -> bool {
+    let cfg = state.holidays.lock().unwrap();
+    cfg.pause_reminders && holidays::holiday_on(&cfg, clock::now().date_naive()).is_some()
+}
+
+/// The weekend override, if it is enabled and today is Saturday or Sunday.
+pub fn active_weekend_override(state: &AppState) -> Option<WeekendOverride> {
+    let cfg = state.weekend.lock().unwrap();
+    (cfg.enabled && schedule::is_weekend(clock::now().date_naive())).then(|| cfg.clone())
+}
+
+/// No reminders today: a holiday, or a weekend with reminders switched off.
+pub fn day_off(state: &AppState) -> bool {
+    holiday_pause(state)
+        || active_weekend_override(state).is_some_and(|weekend| weekend.reminders_off)
+}
+
+/// An interval for the rest of one day, set by `set_interval_today` and
+/// never written to the config.
+#[derive(Clone, Copy)]
+pub struct IntervalToday {
+    pub date: chrono::NaiveDate,
+    pub secs: u64,
+}
+
+/// Today's override in seconds; one left from an earlier day has lapsed.
+pub fn interval_today(state: &AppState) -> Option<u64> {
+    let today = analytics_date(clock::now(), *state.day_start_hour.lock().unwrap());
+    state
+        .interval_today
+        .lock()
+        .unwrap()
+        .filter(|o| o.date == today)
+        .map(|o| o.secs)
+}
+
+/// Set or clear (`None`) the override for the rest of today. Like any
+/// interval change it starts the grace period.
+pub fn set_interval_today(state: &AppState, secs: Option<u64>) {
+    let date = analytics_date(clock::now(), *state.day_start_hour.lock().unwrap());
+    *state.interval_today.lock().unwrap() = secs.map(|secs| IntervalToday { date, secs });
+    *state.last_interval_change.lock().unwrap() = Instant::now();
+    wake(state);
+}
+
+/// The interval in effect today: the override for today, else the current
+/// desk-mode phase, else the weekend value on weekends, else the configured one.
+pub fn effective_interval(state: &AppState) -> u64 {
+    if let Some(secs) = interval_today(state).or_else(|| desk::phase_secs(state)) {
+        return secs;
+    }
+    network_profiles::active(state)
+        .and_then(|profile| profile.interval_minutes)
+        .or_else(|| active_weekend_override(state).and_then(|weekend| weekend.interval_minutes))
+        .map(|minutes| minutes * 60)
+        .unwrap_or_else(|| *state.interval.lock().unwrap())
+}
+
+/// The displays are off (lid closed, no external monitor), so nobody is sitting here.
+fn user_away(state: &AppState) -> bool {
+    state.power.lock().unwrap().display_off
+}
+
+fn screen_sharing(state: &AppState) -> bool {
+    *state.screen_sharing.lock().unwrap()
+}
+
+/// Seconds left of the grace period that follows an interval change.
+fn interval_change_grace(state: &AppState) -> u64 {
+    let since = state
+        .last_interval_change
+        .lock()
+        .unwrap()
+        .elapsed()
+        .as_secs();
+    INTERVAL_CHANGE_GRACE_SECS.saturating_sub(since)
+}
+
+/// Seconds until the next reminder, counting the post-change grace period.
+fn remaining_secs(state: &AppState, interval_secs: u64, elapsed_secs: u64) -> u64 {
+    interval_secs
+        .saturating_sub(elapsed_secs)
+        .max(interval_change_grace(state))
+}
+
+/// Switch to a new interval, keeping the sitting time already accumulated.
+/// A reminder on screen is left alone: it belongs to the old interval and the
+/// countdown restarts with the new one once it is answered. Returns false if
+/// the interval didn't actually change.
+pub fn change_interval(state: &AppState, interval_secs: u64) -> bool {
+    {
+        let mut interval = state.interval.lock().unwrap();
+        if *interval == interval_secs {
+            return false;
+        }
+        *interval = interval_secs;
+    }
+    *state.last_interval_change.lock().unwrap() = Instant::now();
+    true
+}
+
+pub fn tick_payload(state: &AppState) -> TickPayload {
+    let interval_secs = effective_interval(state);
+    let elapsed_secs = *state.elapsed.lock().unwrap();
+    TickPayload {
+        remaining_secs: remaining_secs(state, interval_secs, elapsed_secs),
+        elapsed_secs,
+        interval_secs,
+        paused: countdown_paused(state),
+        pause_reason: pause_reason(state),
+    }
+}
+
+pub fn timer_status(state: &AppState) -> TimerStatus {
+    let interval_secs = effective_interval(state);
+    let elapsed_secs = *state.elapsed.lock().unwrap();
+    let pause_reason = pause_reason(state);
+    let paused = pause_reason.is_some();
+    TimerStatus {
+        interval_secs,
+        interval_today_secs: interval_today(state),
+        elapsed_secs,
+        paused,
+        pause_reason,
+        blocking_app: state.blocking_app.lock().unwrap().clone(),
+        reminder_active: *state.reminder_visible.lock().unwrap(),
+        snoozed_until: None,
+        next_fire_ts: (!paused)
+            .then(|| now_ts() + remaining_secs(state, interval_secs, elapsed_secs) as i64),
+        power_saving: state.power.lock().unwrap().battery_saver,
+        sitting_budget_remaining_secs: budget::remaining_secs(state),
+        sit_stand: ratio::today(state),
+    }
+}
+
+/// Seconds until the nearest thing the loop has to act on, if any.
+fn next_deadline_secs(state: &AppState) -> Option<u64> {
+    let mut deadlines = Vec::new();
+    if !countdown_paused(state) {
+        let interval_secs = effective_interval(state);
+        let elapsed_secs = *state.elapsed.lock().unwrap();
+        let remaining = remaining_secs(state, interval_secs, elapsed_secs);
+        deadlines.push(remaining);
+        let warning_secs = *state.pre_warning_minutes.lock().unwrap() * 60;
+        if warning_secs > 0 && remaining > warning_secs {
+            deadlines.push(remaining - warning_secs);
+        }
+        if let Some(budget_left) = budget::remaining_secs(state).filter(|secs| *secs > 0) {
+            deadlines.push(budget_left);
+        }
+    }
+    if let Some(session) = state.focus_session.lock().unwrap().as_ref() {
+        deadlines.push(session.planned_secs.saturating_sub(session.elapsed_secs));
+    }
+    deadlines.into_iter().min()
+}
+
+/// A visible (non-reminder) window shows the live countdown, so it caps the
+/// step at the `tick` event cadence it asked for.
+fn countdown_on_screen(app: &AppHandle) -> bool {
+    app.webview_windows().iter().any(|(label, window)| {
+        !reminder_windows::is_reminder_label(label) && window.is_visible().unwrap_or(false)
+    })
+}
+
+/// How far the next tick advances the timers, and how long the loop sleeps.
+fn next_step(app: &AppHandle) -> u64 {
+    let state = app.state::<AppState>();
+    let fine = if state.power.lock().unwrap().battery_saver {
+        POWER_SAVER_TICK_SECS
+    } else {
+        TICK_SECS
+    };
+    let resolution = state.tick_resolution.lock().unwrap().clone();
+    // A showing reminder is re-checked every step (sitting threshold,
+    // vanished window), so it always gets the fine step.
+    if !resolution.adaptive || *state.reminder_visible.lock().unwrap() {
+        return fine;
+    }
+    let mut step = match next_deadline_secs(&state) {
+        Some(secs) if secs <= resolution.fine_window_secs => fine,
+        Some(secs) => resolution
+            .coarse_secs
+            .min(secs - resolution.fine_window_secs),
+        // Nothing counts down: sleep until the date changes (holidays and
+        // weekends are per date) unless woken first.
+        None => secs_until_tomorrow().min(MAX_IDLE_SLEEP_SECS),
+    };
+    let tick_event_secs = *state.tick_event_secs.lock().unwrap();
+    if tick_event_secs < step && countdown_on_screen(app) {
+        step = tick_event_secs;
+    }
+    step.max(fine)
+}
+
+fn secs_until_tomorrow() -> u64 {
+    let now = clock::now();
+    now.date_naive()
+        .succ_opt()
+        .and_then(|tomorrow| tomorrow.and_hms_opt(0, 0, 1))
+        .and_then(|naive| naive.and_local_timezone(chrono::Local).earliest())
+        .map(|next| (next - now).num_seconds().max(1) as u64)
+        .unwrap_or(MAX_IDLE_SLEEP_SECS)
+}
+
+/// Cut the current sleep short so a change (a logged standup, new settings,
+/// a power event) takes effect now instead of at the next planned tick.
+pub fn wake(state: &AppState) {
+    state.timer_wake.notify_one();
+}
+
+/// How far past its planned wakeup the loop is; zero while it's asleep on
+/// schedule, however long that sleep is.
+pub fn overdue(state: &AppState) -> Duration {
+    Instant::now().saturating_duration_since(*state.timer_next_wake.lock().unwrap())
+}
+
+/// Drive every timer from a single task so they share one wakeup.
+pub async fn run(app: AppHandle) {
+    let mut since_tick_event = 0u64;
+    // Clock time slept but not yet handed to the timers, so early wakeups
+    // don't lose the fractions of a second they cut off.
+    let mut carry = Duration::ZERO;
+    loop {
+        let planned = next_step(&app);
+        let state = app.state::<AppState>();
+        let started = Instant::now();
+        let started_ts = now_ts();
+        *state.timer_next_wake.lock().unwrap() =
+            started + Duration::from_secs(planned).div_f64(clock::scale());
+        tokio::select! {
+            _ = clock::sleep(Duration::from_secs(planned)) => {}
+            _ = state.timer_wake.notified() => {}
+        }
+        timeline::observe(&app);
+        let mut slept = started.elapsed().mul_f64(clock::scale());
+        if let Some(gap) = sleep_drift::gap(now_ts() - started_ts, slept.as_secs(), planned) {
+            sleep_drift::resumed(&app, gap);
+            // Where the monotonic clock ran on through the suspend, the
+            // policy has already dealt with the time slept past the plan.
+            slept = slept.min(Duration::from_secs(planned));
+        }
+        carry += slept;
+        let step = carry.as_secs();
+        carry -= Duration::from_secs(step);
+        if step == 0 {
+            *state.timer_heartbeat.lock().unwrap() = Instant::now();
+            continue;
+        }
+        standup_tick(&app, step);
+        focus_tick(&app, step);
+        budget::tick(&app);
+        ratio::tick(&app);
+        window_focus::tick(&app);
+
+        since_tick_event += step;
+        *state.timer_heartbeat.lock().unwrap() = Instant::now();
+        if since_tick_event >= *state.tick_event_secs.lock().unwrap() {
+            since_tick_event = 0;
+            let _ = app.emit("tick", tick_payload(&state));
+        }
+    }
+}
+
+/// Why the supervised timer loop had to be replaced.
+async fn watch(app: &AppHandle, mut task: tauri::async_runtime::JoinHandle<()>) -> String {
+    loop {
+        let polled = Instant::now();
+        tokio::select! {
+            result = &mut task => {
+                return match result {
+                    Err(e) => format!("died: {}", e),
+                    Ok(()) => "exited".to_string(),
+                };
+            }
+            _ = tokio::time::sleep(Duration::from_secs(WATCHDOG_POLL_SECS)) => {}
+        }
+        // A poll that overslept means the machine was suspended, not that the
+        // loop hung; give it a fresh window to tick again.
+        if polled.elapsed() > Duration::from_secs(WATCHDOG_STALE_SECS) {
+            let state = app.state::<AppState>();
+            *state.timer_heartbeat.lock().unwrap() = Instant::now();
+            *state.timer_next_wake.lock().unwrap() = Instant::now();
+            continue;
+        }
+        if overdue(&app.state::<AppState>()) > Duration::from_secs(WATCHDOG_STALE_SECS) {
+            task.abort();
+            return "stalled".to_string();
+        }
+    }
+}
+
+/// Run the timer loop and restart it whenever it panics or stops ticking.
+/// The user is told once per launch; later restarts are only logged.
+pub async fn supervise(app: AppHandle) {
+    loop {
+        {
+            let state = app.state::<AppState>();
+            *state.timer_heartbeat.lock().unwrap() = Instant::now();
+            *state.timer_next_wake.lock().unwrap() = Instant::now();
+        }
+        let task = tauri::async_runtime::spawn(run(app.clone()));
+        let reason = watch(&app, task).await;
+        eprintln!("upstand: reminder loop {}, restarting", reason);
+
+        let first_restart = {
+            let state = app.state::<AppState>();
+            let mut restarts = state.timer_restarts.lock().unwrap();
+            *restarts += 1;
+            *restarts == 1
+        };
+        if first_restart {
+            notify(
+                &app,
+                "Upstand",
+                "Reminders stopped unexpectedly and were restarted.",
+            );
+        }
+        tokio::time::sleep(Duration::from_secs(WATCHDOG_RESTART_DELAY_SECS)).await;
+    }
+}
+
+/// Start a session, ending any running one as not completed first so it
+/// still lands in the history.
+pub fn start_focus(app: &AppHandle, minutes: u64) -> FocusSession {
+    finish_focus(app, false);
+    let state = app.state::<AppState>();
+    let session = FocusSession {
+        started_ts: now_ts(),
+        planned_secs: minutes.clamp(1, MAX_FOCUS_MINUTES) * 60,
+        elapsed_secs: 0,
+    };
+    *state.focus_session.lock().unwrap() = Some(session.clone());
+    wake(&state);
+    let _ = app.emit("focus-session-started", session.clone());
+    refresh_tray_menu(app);
+    session
+}
+
+/// End the running session, storing it with `completed` set accordingly.
+pub fn finish_focus(app: &AppHandle, completed: bool) -> Option<FocusRecord> {
+    let state = app.state::<AppState>();
+    let session = state.focus_session.lock().unwrap().take()?;
+    let record = FocusRecord {
+        ts: session.started_ts,
+        duration_secs: session.elapsed_secs.min(session.planned_secs),
+        completed,
+    };
+    state.focus_events.lock().unwrap().push(record.clone());
+    journal::record(app, raw_events::focus(&record));
+    let _ = app.emit("focus-session-ended", record.clone());
+    events::analytics_updated(app);
+    refresh_tray_menu(app);
+    Some(record)
+}
+
+fn focus_tick(app: &AppHandle, step_secs: u64) {
+    let state = app.state::<AppState>();
+    let done = {
+        let mut session = state.focus_session.lock().unwrap();
+        let Some(session) = session.as_mut() else {
+            return;
+        };
+        session.elapsed_secs += step_secs;
+        session.elapsed_secs >= session.planned_secs
+    };
+    if !done {
+        return;
+    }
+    finish_focus(app, true);
+    notify(
+        app,
+        "Focus session complete",
+        "Nice work. Stand up and stretch before the next one.",
+    );
+    // Pull the stand reminder forward so the break happens now.
+    if !*state.reminder_visible.lock().unwrap() {
+        let limit = effective_interval(&state);
+        let sat_secs = std::mem::replace(&mut *state.elapsed.lock().unwrap(), limit);
+        *state.focus_break_due.lock().unwrap() = Some(sat_secs);
+    }
+}
+
+/// Post the "break in N min" heads-up once per countdown when it enters the
+/// warning window. The sent flag clears itself whenever the countdown is
+/// outside the window again (new cycle, interval change, logged standup).
+fn pre_warning_tick(app: &AppHandle, state: &AppState, interval_secs: u64, remaining_secs: u64) {
+    // A follow-up to a dismissal comes soon by design; don't announce it.
+    let window_secs = if repeats::pending(state) {
+        0
+    } else {
+        *state.pre_warning_minutes.lock().unwrap() * 60
+    };
+    let mut sent = state.pre_warning_sent.lock().unwrap();
+    if window_secs == 0 || window_secs >= interval_secs || remaining_secs > window_secs {
+        *sent = false;
+        return;
+    }
+    if *sent || remaining_secs == 0 {
+        return;
+    }
+    *sent = true;
+    drop(sent);
+
+    let minutes = remaining_secs.div_ceil(60);
+    let body = if *state.language.lock().unwrap() == "zh-CN" {
+        format!("{} 分钟后站起来休息，先收个尾吧", minutes)
+    } else {
+        format!("Stand break in {} min — wrap up", minutes)
+    };
+    notify_silent(app, "Upstand", &body);
+    let _ = app.emit("pre-reminder-warning", remaining_secs);
+}
+
+/// One step of the standup interval timer: keeps a visible reminder alive and
+/// auto-logs ignored ones, or advances the countdown and fires when it runs out.
+pub fn standup_tick(app: &AppHandle, step_secs: u64) {
+    let state = app.state::<AppState>();
+    let held = day_off(&state) || blocking_app_running(&state);
+    if user_away(&state) || (held && !*state.reminder_visible.lock().unwrap()) {
+        return;
+    }
+    if *state.reminder_visible.lock().unwrap() {
+        let reminder_id = *state.active_reminder_id.lock().unwrap();
+        let mut windows = reminder_windows::existing(app);
+        let primary_alive = windows
+            .first()
+            .is_some_and(|w| w.label() == reminder_windows::PRIMARY_LABEL);
+        if !primary_alive {
+            // The webview was closed or crashed: rebuild it (hidden) so the
+            // check below brings the pending reminder back.
+            windows = reminder_windows::prepare(app, reminder_id);
+        }
+        let Some(primary) = windows.first() else {
+            delivery::window_failed(
+                app,
+                Some(reminder_id),
+                "the window is gone and couldn't be rebuilt",
+            );
+            reminder_reason::settled(&state, reminder_id, "failed", now_ts());
+            *state.reminder_visible.lock().unwrap() = false;
+            *state.active_reminder_start_ts.lock().unwrap() = None;
+            *state.active_reminder_shown_at.lock().unwrap() = None;
+            return;
+        };
+        if overlay::holding(&state) {
+            if !overlay::visible(app) && !screen_sharing(&state) {
+                overlay::show(app);
+            }
+        } else if let Ok(false) = primary.is_visible() {
+            if screen_sharing(&state) {
+                return;
+            }
+            // Ignored on the banner for long enough: escalate to the window.
+            overlay::hide(app);
+            match reminder_windows::present_all(app, &windows) {
+                Ok(()) => delivery::record(&state, delivery::Outcome::Shown(reminder_id)),
+                Err(e) => delivery::window_failed(app, Some(reminder_id), &e),
+            }
+            events::emit(app, "refresh_tip", reminder_id);
+        }
+
+        let threshold = *state.sedentary_threshold_secs.lock().unwrap();
+        // Standing at the desk is not sitting, however late it is lowered.
+        let standing = desk::standing(&state);
+        let maybe_new_sedentary = {
+            let start_opt = *state.active_reminder_start_ts.lock().unwrap();
+            let mut logged = state.active_reminder_logged_sedentary.lock().unwrap();
+            if let Some(start) = start_opt {
+                let lag = (now_ts() - start).max(0) as u64;
+                if !*logged && !standing && lag >= threshold {
+                    *logged = true;
+                    Some((start, lag))
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        };
+        if let Some((start, _lag)) = maybe_new_sedentary {
+            let interval_secs = *state.active_reminder_interval_secs.lock().unwrap();
+            let record = ReminderRecord {
+                ts: start,
+                duration_secs: interval_secs,
+            };
+            state.reminder_events.lock().unwrap().push(record.clone());
+            journal::record(app, raw_events::sedentary(&record));
+            events::reminder_ignored(app, reminder_id);
+            events::analytics_updated(app);
+        }
+        return;
+    }
+    let current_limit = effective_interval(&state);
+    let mut elapsed = state.elapsed.lock().unwrap();
+    *elapsed += step_secs;
+    pre_warning_tick(
+        app,
+        &state,
+        current_limit,
+        current_limit.saturating_sub(*elapsed),
+    );
+
+    if *elapsed >= current_limit {
+        // The interval was just changed: hold the countdown at its limit until
+        // the grace period is over instead of firing mid-adjustment.
+        if interval_change_grace(&state) > 0 {
+            *elapsed = current_limit;
+            return;
+        }
+        delivery::record(&state, delivery::Outcome::Scheduled);
+        // The user moved moments ago without the countdown knowing: restart
+        // the interval from that standup instead of nagging right after it.
+        if let Some(age) = recent_standup_age(&state, now_ts(), current_limit) {
+            delivery::record(
+                &state,
+                delivery::Outcome::Suppressed(delivery::Suppression::RecentStandup),
+            );
+            *elapsed = age;
+            return;
+        }
+        // Only taken once the reminder really fires, so a suppressed one
+        // leaves a pending repeat or focus break for the next.
+        let (trigger, sat_secs) = reminder_reason::take_trigger(&state, *elapsed);
+        // Building a window round-trips through the main thread, where sync
+        // commands also take the countdown lock, so release it first.
+        *elapsed = 0;
+        drop(elapsed);
+        let next_id = *state.active_reminder_id.lock().unwrap() + 1;
+        let windows = reminder_windows::prepare(app, next_id);
+        if !windows.is_empty() {
+            let reminder_id = {
+                let mut id = state.active_reminder_id.lock().unwrap();
+                *id += 1;
+                *id
+            };
+            let (tip, sound) = select_reminder_tip(&state);
+            let tip = desk::reminder_text(&state)
+                .map(|text| ShownTip {
+                    text,
+                    source: TipSource::Desk,
+                })
+                .unwrap_or(tip);
+            reminder_windows::resize(app, &windows, reminder_windows::estimated_height(&tip.text));
+            {
+                let mut tip_slot = state.active_reminder_tip.lock().unwrap();
+                *tip_slot = tip.text.clone();
+            }
+            *state.active_reminder_sound.lock().unwrap() = sound;
+            {
+                let mut start = state.active_reminder_start_ts.lock().unwrap();
+                *start = Some(now_ts());
+            }
+            {
+                let mut shown_at = state.active_reminder_shown_at.lock().unwrap();
+                *shown_at = Some(Instant::now());
+            }
+            *state.challenge_phrase.lock().unwrap() = None;
+            {
+                let mut interval_secs = state.active_reminder_interval_secs.lock().unwrap();
+                *interval_secs = current_limit;
+            }
+            {
+                let mut logged = state.active_reminder_logged_sedentary.lock().unwrap();
+                *logged = false;
+            }
+            if let Some(to) = desk::next_move(&state) {
+                // A desk integration may move the desk itself.
+                events::emit(app, "desk-move-due", to);
+            }
+            reminder_reason::fired(
+                &state,
+                reminder_id,
+                ReminderReason {
+                    trigger,
+                    fired_ts: now_ts(),
+                    interval_secs: current_limit,
+                    weekend_interval: active_weekend_override(&state)
+                        .is_some_and(|weekend| weekend.interval_minutes.is_some()),
+                    sat_secs,
+                    held_for_screen_sharing: false,
+                },
+                tip,
+            );
+
+            *state.reminder_visible.lock().unwrap() = true;
+            // The real thing takes over the windows from a settings preview.
+            state.reminder_preview.lock().unwrap().take();
+            if screen_sharing(&state) {
+                // Held until sharing ends; the visible-reminder branch above shows it then.
+                reminder_reason::held_for_screen_sharing(&state, reminder_id);
+                delivery::record(
+                    &state,
+                    delivery::Outcome::Suppressed(delivery::Suppression::ScreenSharing),
+                );
+                notify_silent(
+                    app,
+                    "Time to stand up",
+                    "Your reminder will appear when screen sharing ends.",
+                );
+            } else if overlay::wanted(&state) && overlay::show(app) {
+                delivery::record(&state, delivery::Outcome::Shown(reminder_id));
+                events::emit(app, "refresh_tip", reminder_id);
+            } else {
+                match reminder_windows::present_all(app, &windows) {
+                    Ok(()) => delivery::record(&state, delivery::Outcome::Shown(reminder_id)),
+                    Err(e) => delivery::window_failed(app, Some(reminder_id), &e),
+                }
+                events::emit(app, "refresh_tip", reminder_id);
+                for win in &windows {
+                    let _ =
+                        win.eval("window.__standbyReminderSync && window.__standbyReminderSync();");
+                }
+            }
+        } else {
+            delivery::window_failed(app, None, "the window couldn't be built");
+        }
+        events::reminder_fired(app);
+    }
+}
@@ -0,0 +1,394 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, io::Read, path::PathBuf, time::Duration};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::tip_ratings::{ShownTip, TipSource};
+use crate::{data_dir, AppState, REMINDER_TIPS_EN};
+
+/// The pack compiled into the app; it can be disabled but not removed.
+pub const BUILTIN_PACK_ID: &str = "friends";
+const PACKS_DIR: &str = "tip_packs";
+const DOWNLOAD_TIMEOUT_SECS: u64 = 15;
+const MAX_PACK_BYTES: u64 = 256 * 1024;
+/// Tips missing a translation fall back to this one.
+const FALLBACK_LANGUAGE: &str = "en";
+
+/// On-disk format of `tip_packs/<id>.json`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TipPack {
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_pack_language")]
+    pub language: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub description: String,
+    pub tips: Vec<PackTip>,
+}
+
+/// One tip of a pack: a plain string in the pack's `language`, or an object
+/// of language code to text, e.g. `{"en": "Stand up!", "zh-CN": "站起来！"}`.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PackTip {
+    Text(String),
+    Translated(BTreeMap<String, String>),
+}
+
+impl PackTip {
+    /// The text in `language`, if the tip has it.
+    pub fn text_in<'a>(&'a self, pack_language: &str, language: &str) -> Option<&'a str> {
+        match self {
+            PackTip::Text(text) => (pack_language == language).then_some(text.as_str()),
+            PackTip::Translated(texts) => texts.get(language).map(String::as_str),
+        }
+    }
+
+    /// Its text in `language`, else in English.
+    pub fn resolve<'a>(&'a self, pack_language: &str, language: &str) -> Option<&'a str> {
+        self.text_in(pack_language, language)
+            .or_else(|| self.text_in(pack_language, FALLBACK_LANGUAGE))
+    }
+
+    /// Some text, for when no wanted language is available at all.
+    fn any_text<'a>(&'a self, pack_language: &str) -> Option<&'a str> {
+        match self {
+            PackTip::Text(text) => Some(text),
+            PackTip::Translated(texts) => self
+                .resolve(pack_language, pack_language)
+                .or_else(|| texts.values().next().map(String::as_str)),
+        }
+    }
+
+    /// Trimmed, with empty translations dropped; `None` if nothing is left.
+    fn sanitize(self) -> Option<PackTip> {
+        match self {
+            PackTip::Text(text) => {
+                let text = text.trim().to_string();
+                (!text.is_empty()).then_some(PackTip::Text(text))
+            }
+            PackTip::Translated(texts) => {
+                let texts: BTreeMap<String, String> = texts
+                    .into_iter()
+                    .map(|(language, text)| (language.trim().to_string(), text.trim().to_string()))
+                    .filter(|(language, text)| !language.is_empty() && !text.is_empty())
+                    .collect();
+                (!texts.is_empty()).then_some(PackTip::Translated(texts))
+            }
+        }
+    }
+}
+
+/// How much of a pack is written in one language.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct TipPackCoverage {
+    pub id: String,
+    pub name: String,
+    pub language: String,
+    pub total: usize,
+    pub translated: usize,
+    /// Tips without a translation, in the language they fall back to.
+    pub missing: Vec<String>,
+}
+
+/// What switching the reminder language did to the enabled packs.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct TipLanguageMatch {
+    pub language: String,
+    /// Some enabled pack has tips in `language`; false means the reminders
+    /// carry on in English.
+    pub supported: bool,
+    /// Packs switched on for the language.
+    pub enabled: Vec<String>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct TipPackInfo {
+    pub id: String,
+    pub name: String,
+    pub language: String,
+    pub author: String,
+    pub description: String,
+    pub tip_count: usize,
+    pub enabled: bool,
+    pub builtin: bool,
+}
+
+fn default_pack_language() -> String {
+    "en".to_string()
+}
+
+fn shown(pack: &TipPack, text: &str) -> ShownTip {
+    ShownTip {
+        text: text.to_string(),
+        source: TipSource::Pack {
+            id: pack.id.clone(),
+        },
+    }
+}
+
+/// The pack's tips in `language`, each falling back to English on its own.
+fn tips_in<'a>(pack: &'a TipPack, language: &'a str) -> impl Iterator<Item = ShownTip> + 'a {
+    pack.tips
+        .iter()
+        .filter_map(move |tip| tip.resolve(&pack.language, language))
+        .map(move |text| shown(pack, text))
+}
+
+fn tips_of(pack: &TipPack) -> impl Iterator<Item = ShownTip> + '_ {
+    pack.tips
+        .iter()
+        .filter_map(|tip| tip.any_text(&pack.language))
+        .map(|text| shown(pack, text))
+}
+
+pub fn coverage(pack: &TipPack, language: &str) -> TipPackCoverage {
+    let missing: Vec<String> = pack
+        .tips
+        .iter()
+        .filter(|tip| tip.text_in(&pack.language, language).is_none())
+        .filter_map(|tip| {
+            tip.resolve(&pack.language, language)
+                .or_else(|| tip.any_text(&pack.language))
+        })
+        .map(str::to_string)
+        .collect();
+    TipPackCoverage {
+        id: pack.id.clone(),
+        name: pack.name.clone(),
+        language: language.to_string(),
+        total: pack.tips.len(),
+        translated: pack.tips.len() - missing.len(),
+        missing,
+    }
+}
+
+fn has_language(pack: &TipPack, language: &str) -> bool {
+    pack.tips
+        .iter()
+        .any(|tip| tip.text_in(&pack.language, language).is_some())
+}
+
+/// The packs to enable for `language` on top of `enabled`: none if an
+/// enabled pack has tips in it, else the built-in pack if it does, else
+/// every pack that does. `None` when no pack has any.
+pub fn packs_for_language(
+    packs: &[TipPack],
+    enabled: &[String],
+    language: &str,
+) -> Option<Vec<String>> {
+    let matching: Vec<&TipPack> = packs
+        .iter()
+        .filter(|pack| has_language(pack, language))
+        .collect();
+    if matching.iter().any(|pack| enabled.contains(&pack.id)) {
+        return Some(Vec::new());
+    }
+    if matching.iter().any(|pack| pack.id == BUILTIN_PACK_ID) {
+        return Some(vec![BUILTIN_PACK_ID.to_string()]);
+    }
+    let ids: Vec<String> = matching.iter().map(|pack| pack.id.clone()).collect();
+    (!ids.is_empty()).then_some(ids)
+}
+
+/// Enable packs written in the new reminder language, so the reminders don't
+/// quietly stay in English. The user's packs stay enabled: the pool only
+/// draws on tips in the reminder language, so they are back in play as soon
+/// as it switches back. Warns with `reminder-language-unsupported` when no
+/// installed pack has the language.
+pub fn select_for_language(app: &AppHandle, language: &str) -> TipLanguageMatch {
+    let state = app.state::<AppState>();
+    let packs = load_packs(app);
+    let mut enabled = state.enabled_tip_packs.lock().unwrap();
+    let Some(added) = packs_for_language(&packs, &enabled, language) else {
+        let _ = app.emit("reminder-language-unsupported", language);
+        return TipLanguageMatch {
+            language: language.to_string(),
+            supported: false,
+            enabled: Vec::new(),
+        };
+    };
+    enabled.extend(added.iter().cloned());
+    TipLanguageMatch {
+        language: language.to_string(),
+        supported: true,
+        enabled: added,
+    }
+}
+
+pub fn default_enabled_packs() -> Vec<String> {
+    vec![BUILTIN_PACK_ID.to_string()]
+}
+
+fn builtin_pack() -> TipPack {
+    TipPack {
+        id: BUILTIN_PACK_ID.to_string(),
+        name: "Friends".to_string(),
+        language: "en".to_string(),
+        author: "Upstand".to_string(),
+        description: "The original sitcom-flavoured nudges.".to_string(),
+        tips: REMINDER_TIPS_EN
+            .iter()
+            .map(|tip| PackTip::Text(tip.to_string()))
+            .collect(),
+    }
+}
+
+pub fn packs_dir(app: &AppHandle) -> Option<PathBuf> {
+    data_dir::dir(app).map(|dir| dir.join(PACKS_DIR))
+}
+
+/// Ids become file names, so keep them to a safe character set.
+fn valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 64
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn validate(mut pack: TipPack) -> Result<TipPack, String> {
+    pack.id = pack.id.trim().to_string();
+    if !valid_id(&pack.id) {
+        return Err("tip pack id must be 1-64 letters, digits, '-' or '_'".to_string());
+    }
+    if pack.id == BUILTIN_PACK_ID {
+        return Err("tip pack id is reserved".to_string());
+    }
+    pack.tips = pack
+        .tips
+        .into_iter()
+        .filter_map(PackTip::sanitize)
+        .collect();
+    if pack.tips.is_empty() {
+        return Err("tip pack has no tips".to_string());
+    }
+    if pack.name.trim().is_empty() {
+        pack.name = pack.id.clone();
+    }
+    Ok(pack)
+}
+
+/// The built-in pack followed by every valid pack in the app-data directory.
+pub fn load_packs(app: &AppHandle) -> Vec<TipPack> {
+    let mut packs = vec![builtin_pack()];
+    let Some(entries) = packs_dir(app).and_then(|dir| fs::read_dir(dir).ok()) else {
+        return packs;
+    };
+    let mut installed: Vec<TipPack> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("json"))
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .filter_map(|contents| serde_json::from_str::<TipPack>(&contents).ok())
+        .filter_map(|pack| validate(pack).ok())
+        .collect();
+    installed.sort_by(|a, b| a.id.cmp(&b.id));
+    installed.dedup_by(|a, b| a.id == b.id);
+    packs.extend(installed);
+    packs
+}
+
+pub fn list(app: &AppHandle) -> Vec<TipPackInfo> {
+    let enabled = app
+        .state::<AppState>()
+        .enabled_tip_packs
+        .lock()
+        .unwrap()
+        .clone();
+    load_packs(app)
+        .into_iter()
+        .map(|pack| TipPackInfo {
+            enabled: enabled.contains(&pack.id),
+            builtin: pack.id == BUILTIN_PACK_ID,
+            tip_count: pack.tips.len(),
+            id: pack.id,
+            name: pack.name,
+            language: pack.language,
+            author: pack.author,
+            description: pack.description,
+        })
+        .collect()
+}
+
+/// Merge the tips of every enabled pack in the reminder language, each tip
+/// falling back to English where its translation is missing. Falls back to
+/// every enabled tip in any language, then to the built-in pack, so the
+/// selector never ends up with an empty pool. Tips from plugins come on top.
+pub fn rebuild_pool(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let enabled = state.enabled_tip_packs.lock().unwrap().clone();
+    let language = state.reminder_language.lock().unwrap().clone();
+    let packs: Vec<TipPack> = load_packs(app)
+        .into_iter()
+        .filter(|pack| enabled.contains(&pack.id))
+        .collect();
+
+    let mut pool: Vec<ShownTip> = packs
+        .iter()
+        .flat_map(|pack| tips_in(pack, &language))
+        .collect();
+    if pool.is_empty() {
+        pool = packs.iter().flat_map(tips_of).collect();
+    }
+    if pool.is_empty() {
+        pool = tips_of(&builtin_pack()).collect();
+    }
+    pool.extend(state.plugin_tips.lock().unwrap().iter().cloned());
+    *state.tip_pool.lock().unwrap() = pool;
+}
+
+fn install(app: &AppHandle, contents: &str) -> Result<TipPackInfo, String> {
+    let pack = serde_json::from_str::<TipPack>(contents)
+        .map_err(|e| format!("invalid tip pack: {}", e))?;
+    install_pack(app, pack)
+}
+
+/// Validate and store a pack, replacing any pack with the same id.
+pub fn install_pack(app: &AppHandle, pack: TipPack) -> Result<TipPackInfo, String> {
+    let pack = validate(pack)?;
+    let dir = packs_dir(app).ok_or_else(|| "app data directory unavailable".to_string())?;
+    fs::create_dir_all(&dir).map_err(|e| format!("create folder failed: {}", e))?;
+    let json =
+        serde_json::to_string_pretty(&pack).map_err(|e| format!("serialize failed: {}", e))?;
+    data_dir::write(
+        &app.state::<AppState>(),
+        &dir.join(format!("{}.json", pack.id)),
+        json,
+    )
+    .map_err(|e| format!("write failed: {}", e))?;
+    rebuild_pool(app);
+    list(app)
+        .into_iter()
+        .find(|info| info.id == pack.id)
+        .ok_or_else(|| "installed pack not found".to_string())
+}
+
+/// Copy a pack file into the tip pack directory, replacing any pack with the same id.
+pub fn import_file(app: &AppHandle, path: &str) -> Result<TipPackInfo, String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("read failed: {}", e))?;
+    install(app, &contents)
+}
+
+/// Fetch a pack over HTTP(S). Blocking; call it off the async runtime.
+pub fn download(app: &AppHandle, url: &str) -> Result<TipPackInfo, String> {
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return Err("tip pack URL must be http(s)".to_string());
+    }
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(DOWNLOAD_TIMEOUT_SECS))
+        .build();
+    let response = agent
+        .get(url)
+        .call()
+        .map_err(|e| format!("download failed: {}", e))?;
+    let mut contents = String::new();
+    response
+        .into_reader()
+        .take(MAX_PACK_BYTES)
+        .read_to_string(&mut contents)
+        .map_err(|e| format!("download failed: {}", e))?;
+    install(app, &contents)
+}
@@ -0,0 +1,94 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::AppState;
+
+/// Where a reminder's text came from.
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TipSource {
+    /// An enabled tip pack, the built-in one included.
+    Pack { id: String },
+    /// The tips of the schedule slot covering the hour it fired.
+    Schedule,
+    /// The raise/lower text of desk mode.
+    Desk,
+    /// A plugin with the `tips` capability.
+    Plugin { id: String },
+}
+
+/// The text a reminder showed, kept with the reminder so a rating lands on
+/// exactly that tip.
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ShownTip {
+    pub text: String,
+    pub source: TipSource,
+}
+
+/// "That tip was great / annoying", given on the reminder itself.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TipRating {
+    pub ts: i64,
+    pub reminder_id: u64,
+    pub tip: ShownTip,
+    pub liked: bool,
+}
+
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct TipRatingSummary {
+    pub tip: ShownTip,
+    pub likes: u32,
+    pub dislikes: u32,
+}
+
+/// Rate the tip reminder `reminder_id` showed. Rating the same reminder
+/// again replaces the earlier rating.
+pub fn rate(state: &AppState, reminder_id: u64, liked: bool, ts: i64) -> Result<TipRating, String> {
+    let tip = state
+        .reminder_history
+        .lock()
+        .unwrap()
+        .iter()
+        .rfind(|entry| entry.id == reminder_id)
+        .map(|entry| entry.tip.clone())
+        .ok_or_else(|| "UNKNOWN_REMINDER".to_string())?;
+    let rating = TipRating {
+        ts,
+        reminder_id,
+        tip,
+        liked,
+    };
+    let mut ratings = state.tip_ratings.lock().unwrap();
+    ratings.retain(|r| r.reminder_id != reminder_id);
+    ratings.push(rating.clone());
+    Ok(rating)
+}
+
+pub fn prune(ratings: &mut Vec<TipRating>, cutoff: i64) {
+    ratings.retain(|r| r.ts >= cutoff);
+}
+
+/// Totals per tip, best liked first.
+pub fn summary(ratings: &[TipRating]) -> Vec<TipRatingSummary> {
+    let mut tips: Vec<TipRatingSummary> = Vec::new();
+    for rating in ratings {
+        let index = match tips.iter().position(|t| t.tip == rating.tip) {
+            Some(index) => index,
+            None => {
+                tips.push(TipRatingSummary {
+                    tip: rating.tip.clone(),
+                    likes: 0,
+                    dislikes: 0,
+                });
+                tips.len() - 1
+            }
+        };
+        if rating.liked {
+            tips[index].likes += 1;
+        } else {
+            tips[index].dislikes += 1;
+        }
+    }
+    tips.sort_by_key(|t| std::cmp::Reverse(i64::from(t.likes) - i64::from(t.dislikes)));
+    tips
+}
@@ -0,0 +1,203 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{image::Image, AppHandle, Manager};
+
+use crate::{health, timers, AppState, TRAY_ID};
+
+/// How often the tray icon service looks at the app state. Mode changes come
+/// from many places (focus, holidays, blocking apps), so it polls instead of
+/// being told.
+const TRAY_POLL_SECS: u64 = 2;
+
+/// Variants drawn from the normal icon, so they need no extra files.
+pub const BUNDLED: &[&str] = &["default", "faded", "mono", "dot"];
+
+/// What the app is doing, as far as the tray icon is concerned.
+#[derive(Clone, Copy, PartialEq)]
+pub enum TrayMode {
+    Normal,
+    /// A blocking app holds the countdown.
+    Paused,
+    /// A holiday, or a weekend with reminders off.
+    Vacation,
+    /// A focus session is running.
+    Focus,
+}
+
+/// One of [`BUNDLED`], or a PNG the user picked.
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayIconVariant {
+    Bundled(String),
+    File(String),
+}
+
+/// The icon per mode; `None` shows the normal icon.
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TrayIconConfig {
+    #[serde(default)]
+    pub normal: Option<TrayIconVariant>,
+    #[serde(default)]
+    pub paused: Option<TrayIconVariant>,
+    #[serde(default)]
+    pub vacation: Option<TrayIconVariant>,
+    #[serde(default)]
+    pub focus: Option<TrayIconVariant>,
+}
+
+impl TrayIconConfig {
+    pub fn variant(&self, mode: TrayMode) -> Option<&TrayIconVariant> {
+        match mode {
+            TrayMode::Normal => self.normal.as_ref(),
+            TrayMode::Paused => self.paused.as_ref(),
+            TrayMode::Vacation => self.vacation.as_ref(),
+            TrayMode::Focus => self.focus.as_ref(),
+        }
+    }
+}
+
+/// Unknown bundled names are dropped; user files are checked when set.
+pub fn sanitize(mut cfg: TrayIconConfig) -> TrayIconConfig {
+    for slot in [
+        &mut cfg.normal,
+        &mut cfg.paused,
+        &mut cfg.vacation,
+        &mut cfg.focus,
+    ] {
+        if matches!(slot, Some(TrayIconVariant::Bundled(name)) if !BUNDLED.contains(&name.as_str()))
+        {
+            *slot = None;
+        }
+    }
+    cfg
+}
+
+/// Every user-supplied file in `cfg` must load as an image.
+pub fn validate(cfg: &TrayIconConfig) -> Result<(), String> {
+    for mode in [
+        TrayMode::Normal,
+        TrayMode::Paused,
+        TrayMode::Vacation,
+        TrayMode::Focus,
+    ] {
+        if let Some(TrayIconVariant::File(path)) = cfg.variant(mode) {
+            Image::from_path(path).map_err(|e| format!("can't load {}: {}", path, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Reset every user file in `cfg` that doesn't load here to the normal icon,
+/// e.g. a path from another machine. Returns the paths dropped.
+pub fn drop_unloadable(cfg: &mut TrayIconConfig) -> Vec<String> {
+    let mut dropped = Vec::new();
+    for slot in [
+        &mut cfg.normal,
+        &mut cfg.paused,
+        &mut cfg.vacation,
+        &mut cfg.focus,
+    ] {
+        if let Some(TrayIconVariant::File(path)) = slot {
+            if Image::from_path(&*path).is_err() {
+                dropped.push(std::mem::take(path));
+                *slot = None;
+            }
+        }
+    }
+    dropped
+}
+
+pub fn mode(state: &AppState) -> TrayMode {
+    if state.focus_session.lock().unwrap().is_some() {
+        TrayMode::Focus
+    } else if timers::day_off(state) {
+        TrayMode::Vacation
+    } else if timers::blocking_app_running(state) {
+        TrayMode::Paused
+    } else {
+        TrayMode::Normal
+    }
+}
+
+/// Apply `f` to every pixel's RGBA.
+fn map_pixels(base: &Image<'_>, f: impl Fn(&mut [u8])) -> Image<'static> {
+    let mut rgba = base.rgba().to_vec();
+    rgba.chunks_exact_mut(4).for_each(f);
+    Image::new(&rgba, base.width(), base.height()).to_owned()
+}
+
+/// A bundled variant of `base`; unknown names give `base` itself.
+pub fn bundled(base: &Image<'_>, name: &str) -> Image<'static> {
+    match name {
+        "faded" => map_pixels(base, |px| px[3] /= 2),
+        "mono" => map_pixels(base, |px| {
+            let luma =
+                (u32::from(px[0]) * 30 + u32::from(px[1]) * 59 + u32::from(px[2]) * 11) / 100;
+            px[..3].fill(luma as u8);
+        }),
+        "dot" => {
+            let (width, height) = (base.width(), base.height());
+            let mut rgba = base.rgba().to_vec();
+            let radius = width.min(height) as f32 / 4.0;
+            let (cx, cy) = (width as f32 - radius, radius);
+            for y in 0..height {
+                for x in 0..width {
+                    let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+                    if dx * dx + dy * dy <= radius * radius {
+                        let i = ((y * width + x) * 4) as usize;
+                        rgba[i..i + 4].copy_from_slice(&[0x3b, 0x82, 0xf6, 0xff]);
+                    }
+                }
+            }
+            Image::new(&rgba, width, height).to_owned()
+        }
+        _ => base.clone().to_owned(),
+    }
+}
+
+/// The icon for `variant`. A file that no longer loads falls back to the
+/// normal icon rather than leaving the tray blank.
+fn load(base: &Image<'static>, variant: Option<&TrayIconVariant>) -> Image<'static> {
+    match variant {
+        None => base.clone(),
+        Some(TrayIconVariant::Bundled(name)) => bundled(base, name),
+        Some(TrayIconVariant::File(path)) => Image::from_path(path)
+            .map(Image::to_owned)
+            .unwrap_or_else(|e| {
+                eprintln!("tray icon {}: {}", path, e);
+                base.clone()
+            }),
+    }
+}
+
+/// Swap the tray icon as the mode or its configured variant changes. While
+/// the timer heartbeat is stale the icon carries the health warning badge
+/// on top, so a running app whose reminders stopped is visible at a glance.
+pub async fn run(app: AppHandle, base: Image<'static>) {
+    let mut shown: Option<(Option<TrayIconVariant>, bool)> = None;
+    loop {
+        let wanted = {
+            let state = app.state::<AppState>();
+            let stale = timers::overdue(&state).as_secs() > timers::WATCHDOG_STALE_SECS;
+            let variant = state
+                .tray_icons
+                .lock()
+                .unwrap()
+                .variant(mode(&state))
+                .cloned();
+            (variant, stale)
+        };
+        if shown.as_ref() != Some(&wanted) {
+            let mut icon = load(&base, wanted.0.as_ref());
+            if wanted.1 {
+                icon = health::warning_icon(&icon);
+            }
+            if let Some(tray) = app.tray_by_id(TRAY_ID) {
+                let _ = tray.set_icon(Some(icon));
+            }
+            shown = Some(wanted);
+        }
+        tokio::time::sleep(Duration::from_secs(TRAY_POLL_SECS)).await;
+    }
+}
@@ -0,0 +1,96 @@
+use chrono::{Datelike, Local, NaiveDate, TimeZone, Weekday};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::{analytics_date, ReminderRecord};
+
+/// Totals for one day of the week across an analytics period.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WeekdayBreakdown {
+    /// "monday" through "sunday".
+    pub weekday: String,
+    pub standups: u32,
+    pub sedentary: u32,
+    pub sitting_secs: u64,
+    /// Share of reminders answered by standing up; `None` without any.
+    pub compliance: Option<f32>,
+    /// Days of this weekday with at least one event; the average divides by it.
+    pub days: u32,
+    pub avg_sitting_secs: u64,
+}
+
+const WEEK: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+fn weekday_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "monday",
+        Weekday::Tue => "tuesday",
+        Weekday::Wed => "wednesday",
+        Weekday::Thu => "thursday",
+        Weekday::Fri => "friday",
+        Weekday::Sat => "saturday",
+        Weekday::Sun => "sunday",
+    }
+}
+
+/// The analytics day an event belongs to, so late-night events count toward
+/// the day they started with `day_start_hour` set.
+fn date_of(ts: i64, day_start_hour: u32) -> Option<NaiveDate> {
+    let dt = Local.timestamp_opt(ts, 0).single()?;
+    Some(analytics_date(dt, day_start_hour))
+}
+
+/// Group already-filtered events by weekday, Monday first, all seven present.
+pub fn breakdown(
+    reminders: &[ReminderRecord],
+    standups: &[i64],
+    day_start_hour: u32,
+) -> Vec<WeekdayBreakdown> {
+    let mut groups: Vec<WeekdayBreakdown> = WEEK
+        .iter()
+        .map(|day| WeekdayBreakdown {
+            weekday: weekday_name(*day).to_string(),
+            standups: 0,
+            sedentary: 0,
+            sitting_secs: 0,
+            compliance: None,
+            days: 0,
+            avg_sitting_secs: 0,
+        })
+        .collect();
+    let mut dates = HashSet::new();
+    for ts in standups {
+        if let Some(date) = date_of(*ts, day_start_hour) {
+            groups[date.weekday().num_days_from_monday() as usize].standups += 1;
+            dates.insert(date);
+        }
+    }
+    for record in reminders {
+        if let Some(date) = date_of(record.ts, day_start_hour) {
+            let group = &mut groups[date.weekday().num_days_from_monday() as usize];
+            group.sedentary += 1;
+            group.sitting_secs += record.duration_secs;
+            dates.insert(date);
+        }
+    }
+    for date in dates {
+        groups[date.weekday().num_days_from_monday() as usize].days += 1;
+    }
+    for group in groups.iter_mut() {
+        let answered = group.standups + group.sedentary;
+        group.compliance = (answered > 0).then(|| group.standups as f32 / answered as f32);
+        if group.days > 0 {
+            group.avg_sitting_secs = group.sitting_secs / u64::from(group.days);
+        }
+    }
+    groups
+}
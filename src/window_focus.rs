@@ -0,0 +1,136 @@
+use rand::Rng;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::window_manager::{SETTINGS_LABEL, STATS_LABEL};
+use crate::{notify_silent, now_ts, AppState};
+
+/// Upper bound for `set_window_nudge_minutes`.
+pub const MAX_NUDGE_MINUTES: u64 = 4 * 60;
+
+const NUDGES_EN: [&str; 4] = [
+    "You've spent a while studying your sitting stats. Sitting down, no doubt.",
+    "Fun fact: reading about sitting also counts as sitting.",
+    "The charts will still be here after a quick stretch.",
+    "Plot twist: the best way to improve these numbers is to close this window.",
+];
+const NUDGES_ZH: [&str; 4] = [
+    "你已经坐着研究久坐统计很久了。",
+    "小知识：看久坐数据的时候，也是在久坐。",
+    "伸个懒腰回来，图表还在这里。",
+    "改善这些数字的最好办法，是先关掉这个窗口。",
+];
+
+/// Focus bookkeeping for one window.
+#[derive(Clone, Default)]
+pub struct WindowFocus {
+    /// When the current focused stretch began.
+    pub focused_since: Option<i64>,
+    /// Focused time since launch, not counting the current stretch.
+    pub total_secs: u64,
+    /// The current stretch has been nudged about.
+    pub nudged: bool,
+}
+
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct WindowFocusInfo {
+    pub label: String,
+    /// Length of the current focused stretch; `None` while unfocused.
+    pub focused_secs: Option<u64>,
+    /// Focused time since launch, the current stretch included.
+    pub total_secs: u64,
+}
+
+/// Payload of `window-focus-nudge`.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct WindowFocusNudge {
+    pub label: String,
+    pub focused_secs: u64,
+    pub message: String,
+}
+
+/// Record a window gaining or losing focus at `now`.
+pub fn focus_changed(state: &AppState, label: &str, focused: bool, now: i64) {
+    let mut windows = state.window_focus.lock().unwrap();
+    let entry = windows.entry(label.to_string()).or_default();
+    match (focused, entry.focused_since) {
+        (true, None) => {
+            entry.focused_since = Some(now);
+            entry.nudged = false;
+        }
+        (false, Some(since)) => {
+            entry.total_secs += (now - since).max(0) as u64;
+            entry.focused_since = None;
+        }
+        _ => {}
+    }
+}
+
+/// How long `label` has been focused without a break, if it is.
+pub fn focused_secs(windows: &HashMap<String, WindowFocus>, label: &str, now: i64) -> Option<u64> {
+    windows
+        .get(label)
+        .and_then(|entry| entry.focused_since)
+        .map(|since| (now - since).max(0) as u64)
+}
+
+pub fn summary(state: &AppState, now: i64) -> Vec<WindowFocusInfo> {
+    let windows = state.window_focus.lock().unwrap();
+    let mut infos: Vec<WindowFocusInfo> = windows
+        .iter()
+        .map(|(label, entry)| {
+            let focused_secs = focused_secs(&windows, label, now);
+            WindowFocusInfo {
+                label: label.clone(),
+                focused_secs,
+                total_secs: entry.total_secs + focused_secs.unwrap_or(0),
+            }
+        })
+        .collect();
+    infos.sort_by(|a, b| a.label.cmp(&b.label));
+    infos
+}
+
+/// The settings or stats window whose focused stretch just passed the
+/// nudge threshold, marking it nudged.
+pub fn due_nudge(state: &AppState, now: i64) -> Option<(String, u64)> {
+    let threshold = *state.window_nudge_minutes.lock().unwrap() * 60;
+    if threshold == 0 {
+        return None;
+    }
+    let mut windows = state.window_focus.lock().unwrap();
+    for label in [SETTINGS_LABEL, STATS_LABEL] {
+        let Some(secs) = focused_secs(&windows, label, now) else {
+            continue;
+        };
+        if let Some(entry) = windows.get_mut(label).filter(|entry| !entry.nudged) {
+            if secs >= threshold {
+                entry.nudged = true;
+                return Some((label.to_string(), secs));
+            }
+        }
+    }
+    None
+}
+
+/// Tease the user, once per stretch, for sitting in front of the dashboard.
+pub fn tick(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let Some((label, focused_secs)) = due_nudge(&state, now_ts()) else {
+        return;
+    };
+    let zh = *state.language.lock().unwrap() == "zh-CN";
+    let nudges: &[&str] = if zh { &NUDGES_ZH } else { &NUDGES_EN };
+    let message = nudges[rand::thread_rng().gen_range(0..nudges.len())].to_string();
+    notify_silent(app, "Upstand", &message);
+    let _ = app.emit(
+        "window-focus-nudge",
+        WindowFocusNudge {
+            label,
+            focused_secs,
+            message,
+        },
+    );
+}
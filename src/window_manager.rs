@@ -0,0 +1,235 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{
+    window::Color, AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindow,
+    WebviewWindowBuilder,
+};
+
+use crate::{save_config, timers, AppState};
+
+pub const SPLASH_LABEL: &str = "main";
+pub const SETTINGS_LABEL: &str = "settings";
+pub const STATS_LABEL: &str = "stats";
+
+/// Move/resize events arrive in bursts while dragging; write the config at most this often.
+const GEOMETRY_SAVE_DELAY_MS: u64 = 750;
+
+/// Remembered geometry and behaviour for a user-facing window, keyed by label.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct WindowPrefs {
+    #[serde(default)]
+    pub x: Option<i32>,
+    #[serde(default)]
+    pub y: Option<i32>,
+    #[serde(default)]
+    pub width: Option<u32>,
+    #[serde(default)]
+    pub height: Option<u32>,
+    #[serde(default)]
+    pub always_on_top: bool,
+    #[serde(default)]
+    pub maximized: bool,
+}
+
+/// Labels whose preferences are persisted; the splash and reminder windows
+/// are positioned by the app itself.
+pub fn is_managed(label: &str) -> bool {
+    label == SETTINGS_LABEL || label == STATS_LABEL
+}
+
+fn position_on_screen(win: &WebviewWindow, x: i32, y: i32) -> bool {
+    win.available_monitors()
+        .map(|monitors| {
+            monitors.iter().any(|m| {
+                let pos = m.position();
+                let size = m.size();
+                x >= pos.x
+                    && y >= pos.y
+                    && x < pos.x + size.width as i32
+                    && y < pos.y + size.height as i32
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// Apply stored preferences to a freshly created (or first shown) window.
+/// A saved position is ignored when it no longer lands on any monitor.
+pub fn restore_window(app: &AppHandle, win: &WebviewWindow) {
+    let prefs = {
+        let state = app.state::<AppState>();
+        let all = state.window_prefs.lock().unwrap();
+        all.get(win.label()).cloned()
+    };
+    let Some(prefs) = prefs else {
+        return;
+    };
+    if let (Some(width), Some(height)) = (prefs.width, prefs.height) {
+        let _ = win.set_size(PhysicalSize::new(width, height));
+    }
+    if let (Some(x), Some(y)) = (prefs.x, prefs.y) {
+        if position_on_screen(win, x, y) {
+            let _ = win.set_position(PhysicalPosition::new(x, y));
+        }
+    }
+    let _ = win.set_always_on_top(prefs.always_on_top);
+    if prefs.maximized {
+        let _ = win.maximize();
+    }
+}
+
+/// Copy the window's current geometry into the in-memory prefs. While the
+/// window is maximized only the flag changes, so un-maximizing after the next
+/// launch returns to the last normal bounds. Returns false if nothing was read.
+fn capture_geometry(app: &AppHandle, win: &WebviewWindow) -> bool {
+    if !is_managed(win.label()) || win.is_minimized().unwrap_or(false) {
+        return false;
+    }
+    let maximized = win.is_maximized().unwrap_or(false);
+    let (Ok(pos), Ok(size)) = (win.outer_position(), win.inner_size()) else {
+        return false;
+    };
+    if size.width == 0 || size.height == 0 {
+        return false;
+    }
+    let state = app.state::<AppState>();
+    let mut all = state.window_prefs.lock().unwrap();
+    let prefs = all.entry(win.label().to_string()).or_default();
+    prefs.maximized = maximized;
+    if !maximized {
+        prefs.x = Some(pos.x);
+        prefs.y = Some(pos.y);
+        prefs.width = Some(size.width);
+        prefs.height = Some(size.height);
+    }
+    true
+}
+
+/// Record the current geometry of a managed window and persist it right away.
+pub fn remember_geometry(app: &AppHandle, win: &WebviewWindow) {
+    if capture_geometry(app, win) {
+        let _ = save_config(app, &app.state::<AppState>());
+    }
+}
+
+/// Called from the move/resize window events: track the geometry immediately
+/// and coalesce the config write.
+pub fn geometry_changed(app: &AppHandle, label: &str) {
+    let Some(win) = app.get_webview_window(label) else {
+        return;
+    };
+    if !capture_geometry(app, &win) {
+        return;
+    }
+    let state = app.state::<AppState>();
+    {
+        let mut pending = state.window_prefs_save_pending.lock().unwrap();
+        if *pending {
+            return;
+        }
+        *pending = true;
+    }
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(GEOMETRY_SAVE_DELAY_MS)).await;
+        let state = handle.state::<AppState>();
+        *state.window_prefs_save_pending.lock().unwrap() = false;
+        let _ = save_config(&handle, &state);
+    });
+}
+
+pub fn set_always_on_top(app: &AppHandle, label: &str, on_top: bool) -> Result<(), String> {
+    let win = app
+        .get_webview_window(label)
+        .ok_or_else(|| "window not found".to_string())?;
+    win.set_always_on_top(on_top)
+        .map_err(|e| format!("set always-on-top failed: {}", e))?;
+    if is_managed(label) {
+        let state = app.state::<AppState>();
+        state
+            .window_prefs
+            .lock()
+            .unwrap()
+            .entry(label.to_string())
+            .or_default()
+            .always_on_top = on_top;
+        save_config(app, &state)?;
+    }
+    Ok(())
+}
+
+/// The startup splash is built here rather than in tauri.conf.json so it can be
+/// skipped entirely when `show_splash` is off.
+pub fn show_splash_window(app: &AppHandle) {
+    let _ = WebviewWindowBuilder::new(app, SPLASH_LABEL, WebviewUrl::App("index.html".into()))
+        .title("Upstand Startup")
+        .inner_size(300.0, 160.0)
+        .decorations(false)
+        .transparent(false)
+        .background_color(Color(0x0f, 0x17, 0x2a, 0xff))
+        .center()
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .build();
+}
+
+fn focus_existing(app: &AppHandle, label: &str) -> bool {
+    if let Some(win) = app.get_webview_window(label) {
+        let _ = win.unminimize();
+        let _ = win.show();
+        let _ = win.set_focus();
+        return true;
+    }
+    false
+}
+
+pub fn show_or_create_settings_window(app: &AppHandle) {
+    // The dashboard shows the live countdown; stop any long timer sleep.
+    timers::wake(&app.state::<AppState>());
+    if focus_existing(app, SETTINGS_LABEL) {
+        return;
+    }
+
+    let created =
+        WebviewWindowBuilder::new(app, SETTINGS_LABEL, WebviewUrl::App("settings.html".into()))
+            .title("Upstand Dashboard")
+            .inner_size(980.0, 700.0)
+            .decorations(false)
+            .transparent(false)
+            .center()
+            .build();
+
+    if let Ok(win) = created {
+        restore_window(app, &win);
+        let _ = win.show();
+        let _ = win.set_focus();
+    }
+}
+
+/// The stats window is a standalone, resizable copy of the dashboard view.
+/// Unlike settings it is destroyed on close and rebuilt on the next open.
+pub fn show_or_create_stats_window(app: &AppHandle) -> Result<(), String> {
+    timers::wake(&app.state::<AppState>());
+    if focus_existing(app, STATS_LABEL) {
+        return Ok(());
+    }
+
+    let win = WebviewWindowBuilder::new(
+        app,
+        STATS_LABEL,
+        WebviewUrl::App("settings.html?view=stats".into()),
+    )
+    .title("Upstand Stats")
+    .inner_size(760.0, 560.0)
+    .min_inner_size(480.0, 360.0)
+    .resizable(true)
+    .decorations(false)
+    .transparent(false)
+    .center()
+    .build()
+    .map_err(|e| format!("create stats window failed: {}", e))?;
+
+    restore_window(app, &win);
+    let _ = win.show();
+    let _ = win.set_focus();
+    Ok(())
+}